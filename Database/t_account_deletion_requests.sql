@@ -0,0 +1,59 @@
+-- アカウント削除リクエスト管理テーブル
+-- 論理名: アカウント削除リクエスト管理テーブル
+-- 物理名: t_account_deletion_requests
+CREATE TABLE t_account_deletion_requests (
+    -- 論理名: リクエストID
+    -- 物理名: request_id
+    request_id UUID NOT NULL DEFAULT uuid_generate_v4(),
+
+    -- 論理名: ログインID
+    -- 物理名: login_id
+    login_id UUID NOT NULL,
+
+    -- 論理名: ステータス
+    -- 物理名: status
+    -- pending: 猶予期間中, cancelled: 取り消し, completed: 削除済み
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+
+    -- 論理名: 削除予定日時
+    -- 物理名: scheduled_datetime
+    -- 猶予期間の終了日時。これ以降にバッチで削除する
+    scheduled_datetime TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    -- 論理名: 取り消し日時
+    -- 物理名: cancelled_datetime
+    cancelled_datetime TIMESTAMP WITH TIME ZONE,
+
+    -- 論理名: 削除完了日時
+    -- 物理名: completed_datetime
+    completed_datetime TIMESTAMP WITH TIME ZONE,
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    -- 論理名: 更新日時
+    -- 物理名: updated_datetime
+    updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_account_deletion_requests PRIMARY KEY (request_id),
+    CONSTRAINT check_t_account_deletion_requests_status CHECK (status IN ('pending', 'cancelled', 'completed'))
+);
+
+-- テーブルコメント
+COMMENT ON TABLE t_account_deletion_requests IS 'アカウント削除（個人情報保護法に基づく利用停止・消去）のリクエストを管理するテーブル';
+
+-- カラムコメント
+COMMENT ON COLUMN t_account_deletion_requests.request_id IS 'リクエストの一意識別子（UUID v4）';
+COMMENT ON COLUMN t_account_deletion_requests.login_id IS '削除対象のログインID';
+COMMENT ON COLUMN t_account_deletion_requests.status IS 'ステータス（pending: 猶予期間中, cancelled: 取り消し, completed: 削除済み）';
+COMMENT ON COLUMN t_account_deletion_requests.scheduled_datetime IS '削除予定日時（猶予期間の終了日時）';
+COMMENT ON COLUMN t_account_deletion_requests.cancelled_datetime IS 'リクエストの取り消し日時';
+COMMENT ON COLUMN t_account_deletion_requests.completed_datetime IS '削除の完了日時';
+COMMENT ON COLUMN t_account_deletion_requests.created_datetime IS 'レコードの作成日時';
+COMMENT ON COLUMN t_account_deletion_requests.updated_datetime IS 'レコードの最終更新日時';
+
+-- インデックス作成
+CREATE INDEX idx_t_account_deletion_requests_login_id ON t_account_deletion_requests(login_id);
+CREATE UNIQUE INDEX uq_t_account_deletion_requests_pending ON t_account_deletion_requests(login_id) WHERE status = 'pending';
+CREATE INDEX idx_t_account_deletion_requests_scheduled ON t_account_deletion_requests(scheduled_datetime) WHERE status = 'pending';
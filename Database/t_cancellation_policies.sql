@@ -0,0 +1,50 @@
+-- キャンセルポリシーテーブル
+-- 論理名: キャンセルポリシーテーブル
+-- 物理名: t_cancellation_policies
+-- 駐車場ごとのキャンセル料の規定（未登録の駐車場は利用開始まで無料）
+CREATE TABLE IF NOT EXISTS t_cancellation_policies (
+    -- 論理名: 駐車場ID
+    -- 物理名: parking_lot_id
+    parking_lot_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: キャンセル料規定
+    -- 物理名: rules
+    -- 例: [{"hours_before": 24, "fee_percent": 0}, {"hours_before": 1, "fee_percent": 50}]
+    -- 利用開始のhours_before時間前までのキャンセルはfee_percent%。どの規定にも当たらない場合は100%
+    rules JSONB NOT NULL,
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    -- 論理名: 更新日時
+    -- 物理名: updated_datetime
+    updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_cancellation_policies PRIMARY KEY (parking_lot_id),
+    CONSTRAINT fk_t_cancellation_policies_parking_lot_id FOREIGN KEY (parking_lot_id)
+        REFERENCES t_parking_lots(parking_lot_id) ON DELETE CASCADE
+);
+
+-- テーブルコメント
+COMMENT ON TABLE t_cancellation_policies IS '駐車場ごとのキャンセル料の規定（利用開始までの時間とキャンセル料率）';
+
+-- カラムコメント
+COMMENT ON COLUMN t_cancellation_policies.parking_lot_id IS '対象の駐車場ID';
+COMMENT ON COLUMN t_cancellation_policies.rules IS 'キャンセル料規定の配列（hours_before: 利用開始の何時間前まで, fee_percent: キャンセル料率）';
+COMMENT ON COLUMN t_cancellation_policies.created_datetime IS 'レコードの作成日時';
+COMMENT ON COLUMN t_cancellation_policies.updated_datetime IS 'レコードの最終更新日時';
+
+
+-- 予約テーブルへのキャンセル精算結果の追加
+ALTER TABLE t_reservations ADD COLUMN IF NOT EXISTS cancellation_fee BIGINT;
+ALTER TABLE t_reservations ADD COLUMN IF NOT EXISTS refund_amount BIGINT;
+ALTER TABLE t_reservations ADD COLUMN IF NOT EXISTS cancellation_fee_percent INTEGER;
+ALTER TABLE t_reservations ADD COLUMN IF NOT EXISTS cancellation_policy JSONB;
+ALTER TABLE t_reservations ADD COLUMN IF NOT EXISTS cancelled_datetime TIMESTAMP WITH TIME ZONE;
+
+COMMENT ON COLUMN t_reservations.cancellation_fee IS 'キャンセル料（円、キャンセル時に適用したポリシーで算出）';
+COMMENT ON COLUMN t_reservations.refund_amount IS '返金額（円、キャンセルの精算で実際に返金された額。精算前はNULL）';
+COMMENT ON COLUMN t_reservations.cancellation_fee_percent IS '適用したキャンセル料率（%）';
+COMMENT ON COLUMN t_reservations.cancellation_policy IS 'キャンセル時点のキャンセルポリシー（適用時の規定を保存）';
+COMMENT ON COLUMN t_reservations.cancelled_datetime IS 'キャンセル日時';
@@ -0,0 +1,69 @@
+-- 連絡先変更リクエスト管理テーブル
+-- 論理名: 連絡先変更リクエスト管理テーブル
+-- 物理名: t_contact_change_requests
+CREATE TABLE t_contact_change_requests (
+    -- 論理名: リクエストID
+    -- 物理名: request_id
+    request_id UUID NOT NULL DEFAULT uuid_generate_v4(),
+
+    -- 論理名: ログインID
+    -- 物理名: login_id
+    login_id UUID NOT NULL,
+
+    -- 論理名: 変更種別
+    -- 物理名: change_type
+    -- email: メールアドレス, phone: 電話番号
+    change_type VARCHAR(10) NOT NULL,
+
+    -- 論理名: 変更後の値
+    -- 物理名: new_value
+    new_value VARCHAR(255) NOT NULL,
+
+    -- 論理名: 認証コードハッシュ
+    -- 物理名: code_hash
+    -- 認証コードのHMAC-SHA256（サーバーの秘密鍵を使用、平文は保存しない）
+    code_hash VARCHAR(64) NOT NULL,
+
+    -- 論理名: 認証試行回数
+    -- 物理名: attempt_count
+    attempt_count INTEGER NOT NULL DEFAULT 0,
+
+    -- 論理名: 有効期限
+    -- 物理名: expires_at
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    -- 論理名: 使用フラグ
+    -- 物理名: is_used
+    -- 0: 未使用, 1: 使用済み
+    is_used VARCHAR(1) NOT NULL DEFAULT '0',
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    -- 論理名: 更新日時
+    -- 物理名: updated_datetime
+    updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_contact_change_requests PRIMARY KEY (request_id),
+    CONSTRAINT check_t_contact_change_requests_change_type CHECK (change_type IN ('email', 'phone'))
+);
+
+-- テーブルコメント
+COMMENT ON TABLE t_contact_change_requests IS 'メールアドレス・電話番号変更時の再認証コードを管理するテーブル';
+
+-- カラムコメント
+COMMENT ON COLUMN t_contact_change_requests.request_id IS 'リクエストの一意識別子（UUID v4）';
+COMMENT ON COLUMN t_contact_change_requests.login_id IS '変更対象のログインID';
+COMMENT ON COLUMN t_contact_change_requests.change_type IS '変更種別（email: メールアドレス, phone: 電話番号）';
+COMMENT ON COLUMN t_contact_change_requests.new_value IS '変更後のメールアドレスまたは電話番号';
+COMMENT ON COLUMN t_contact_change_requests.code_hash IS '認証コードのHMAC-SHA256';
+COMMENT ON COLUMN t_contact_change_requests.attempt_count IS '認証コードの試行回数';
+COMMENT ON COLUMN t_contact_change_requests.expires_at IS '認証コードの有効期限';
+COMMENT ON COLUMN t_contact_change_requests.is_used IS '使用フラグ（0: 未使用, 1: 使用済み）';
+COMMENT ON COLUMN t_contact_change_requests.created_datetime IS 'レコードの作成日時';
+COMMENT ON COLUMN t_contact_change_requests.updated_datetime IS 'レコードの最終更新日時';
+
+-- インデックス作成
+CREATE INDEX idx_t_contact_change_requests_login_id ON t_contact_change_requests(login_id, change_type);
+CREATE INDEX idx_t_contact_change_requests_expires_at ON t_contact_change_requests(expires_at);
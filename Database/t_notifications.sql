@@ -0,0 +1,57 @@
+-- アプリ内通知テーブル
+-- 論理名: アプリ内通知テーブル
+-- 物理名: t_notifications
+CREATE TABLE IF NOT EXISTS t_notifications (
+    -- 論理名: 通知ID
+    -- 物理名: notification_id
+    notification_id UUID NOT NULL DEFAULT uuid_generate_v4(),
+
+    -- 論理名: ユーザーID
+    -- 物理名: user_id
+    user_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: 通知種別
+    -- 物理名: notification_type
+    -- saved_search_match: 保存検索に一致する駐車場
+    notification_type VARCHAR(30) NOT NULL,
+
+    -- 論理名: タイトル
+    -- 物理名: title
+    title VARCHAR(200) NOT NULL,
+
+    -- 論理名: 本文
+    -- 物理名: body
+    body TEXT NOT NULL,
+
+    -- 論理名: 関連データ
+    -- 物理名: payload
+    -- 通知種別ごとの付加情報（保存検索ID、駐車場IDなど）
+    payload JSONB,
+
+    -- 論理名: 既読日時
+    -- 物理名: read_datetime
+    read_datetime TIMESTAMP WITH TIME ZONE,
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_notifications PRIMARY KEY (notification_id)
+);
+
+-- テーブルコメント
+COMMENT ON TABLE t_notifications IS 'ユーザーへのアプリ内通知を管理するテーブル';
+
+-- カラムコメント
+COMMENT ON COLUMN t_notifications.notification_id IS '通知の一意識別子（UUID v4）';
+COMMENT ON COLUMN t_notifications.user_id IS '通知先のユーザーID（m_users.user_id）';
+COMMENT ON COLUMN t_notifications.notification_type IS '通知種別（saved_search_match など）';
+COMMENT ON COLUMN t_notifications.title IS '通知のタイトル';
+COMMENT ON COLUMN t_notifications.body IS '通知の本文';
+COMMENT ON COLUMN t_notifications.payload IS '通知種別ごとの付加情報';
+COMMENT ON COLUMN t_notifications.read_datetime IS '既読にした日時（未読はNULL）';
+COMMENT ON COLUMN t_notifications.created_datetime IS 'レコードの作成日時';
+
+-- インデックス作成
+CREATE INDEX IF NOT EXISTS idx_t_notifications_user_id ON t_notifications(user_id, created_datetime DESC);
+CREATE INDEX IF NOT EXISTS idx_t_notifications_unread ON t_notifications(user_id) WHERE read_datetime IS NULL;
@@ -0,0 +1,91 @@
+-- 駐車場検索履歴テーブル
+-- 論理名: 駐車場検索履歴テーブル
+-- 物理名: t_parking_search_history
+CREATE TABLE IF NOT EXISTS t_parking_search_history (
+    -- 論理名: 検索ID
+    -- 物理名: search_id
+    search_id VARCHAR(37) NOT NULL DEFAULT uuid_generate_v4(),
+
+    -- 論理名: ユーザーID
+    -- 物理名: user_id
+    user_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: 駐車場ID
+    -- 物理名: parking_lot_id
+    -- 検索結果から駐車場を選択した場合のみ
+    parking_lot_id VARCHAR(37),
+
+    -- 論理名: フリーワード条件
+    -- 物理名: condition_keyword_free
+    -- 住所または駅名
+    condition_keyword_free VARCHAR(255),
+
+    -- 論理名: 利用開始日時条件
+    -- 物理名: condition_use_date_start
+    -- 表示用（例: 2025/05/04 10:00、日本時間）
+    condition_use_date_start VARCHAR(20),
+
+    -- 論理名: 利用終了日時条件
+    -- 物理名: condition_use_date_end
+    condition_use_date_end VARCHAR(20),
+
+    -- 論理名: 車種条件
+    -- 物理名: condition_vehicle_type_id
+    condition_vehicle_type_id VARCHAR(37),
+
+    -- 論理名: 貸出タイプ条件
+    -- 物理名: condition_rental_type_id
+    condition_rental_type_id VARCHAR(37),
+
+    -- 論理名: 検索条件
+    -- 物理名: condition_json
+    -- 正規化済みの検索条件（再検索に使用）
+    condition_json JSONB,
+
+    -- 論理名: 検索条件ハッシュ
+    -- 物理名: condition_hash
+    -- condition_json のSHA-256（同一条件の重複排除用）
+    condition_hash VARCHAR(64),
+
+    -- 論理名: 検索回数
+    -- 物理名: search_count
+    search_count INTEGER NOT NULL DEFAULT 1,
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    -- 論理名: 更新日時
+    -- 物理名: updated_datetime
+    -- 同一条件で再検索した日時
+    updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_parking_search_history PRIMARY KEY (search_id)
+);
+
+-- 既存環境向けのカラム追加
+ALTER TABLE t_parking_search_history ADD COLUMN IF NOT EXISTS condition_json JSONB;
+ALTER TABLE t_parking_search_history ADD COLUMN IF NOT EXISTS condition_hash VARCHAR(64);
+ALTER TABLE t_parking_search_history ADD COLUMN IF NOT EXISTS search_count INTEGER NOT NULL DEFAULT 1;
+
+-- テーブルコメント
+COMMENT ON TABLE t_parking_search_history IS 'ユーザーの駐車場検索条件の履歴を管理するテーブル';
+
+-- カラムコメント
+COMMENT ON COLUMN t_parking_search_history.search_id IS '検索履歴の一意識別子（UUID v4）';
+COMMENT ON COLUMN t_parking_search_history.user_id IS '検索したユーザーのID（m_users.user_id）';
+COMMENT ON COLUMN t_parking_search_history.parking_lot_id IS '検索結果から選択した駐車場ID（可NULL）';
+COMMENT ON COLUMN t_parking_search_history.condition_keyword_free IS 'フリーワード条件（住所または駅名）';
+COMMENT ON COLUMN t_parking_search_history.condition_use_date_start IS '利用開始日時条件（表示用、日本時間）';
+COMMENT ON COLUMN t_parking_search_history.condition_use_date_end IS '利用終了日時条件（表示用、日本時間）';
+COMMENT ON COLUMN t_parking_search_history.condition_vehicle_type_id IS '車種条件（m_parking_vehicle_types.vehicle_type_id）';
+COMMENT ON COLUMN t_parking_search_history.condition_rental_type_id IS '貸出タイプ条件（t_parking_rental_types.rental_type_id）';
+COMMENT ON COLUMN t_parking_search_history.condition_json IS '正規化済みの検索条件（再検索に使用）';
+COMMENT ON COLUMN t_parking_search_history.condition_hash IS '検索条件のSHA-256ハッシュ（同一条件の重複排除用）';
+COMMENT ON COLUMN t_parking_search_history.search_count IS '同一条件での検索回数';
+COMMENT ON COLUMN t_parking_search_history.created_datetime IS 'レコードの作成日時';
+COMMENT ON COLUMN t_parking_search_history.updated_datetime IS '同一条件で最後に検索した日時';
+
+-- インデックス作成
+CREATE INDEX IF NOT EXISTS idx_t_parking_search_history_user_id ON t_parking_search_history(user_id, updated_datetime DESC);
+CREATE UNIQUE INDEX IF NOT EXISTS uq_t_parking_search_history_condition ON t_parking_search_history(user_id, condition_hash);
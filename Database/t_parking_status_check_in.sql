@@ -0,0 +1,21 @@
+-- 入出庫状況テーブルへの入出庫の確認方法の追加
+-- 論理名: 入出庫状況テーブル
+-- 物理名: t_parking_status
+-- 入出庫は駐車場のスキャナーでの署名付きQRコードの読み取り、または利用者の位置情報で確認したものだけを記録する
+ALTER TABLE t_parking_status ADD COLUMN IF NOT EXISTS entry_method VARCHAR(20);
+ALTER TABLE t_parking_status ADD COLUMN IF NOT EXISTS exit_method VARCHAR(20);
+-- 入庫に使ったQRコードは出庫に使えない
+ALTER TABLE t_parking_status ADD COLUMN IF NOT EXISTS entry_code_id VARCHAR(32);
+ALTER TABLE t_parking_status ADD COLUMN IF NOT EXISTS exit_code_id VARCHAR(32);
+
+ALTER TABLE t_parking_status DROP CONSTRAINT IF EXISTS check_t_parking_status_entry_method;
+ALTER TABLE t_parking_status ADD CONSTRAINT check_t_parking_status_entry_method
+    CHECK (entry_method IN ('qr_scan', 'geofence'));
+ALTER TABLE t_parking_status DROP CONSTRAINT IF EXISTS check_t_parking_status_exit_method;
+ALTER TABLE t_parking_status ADD CONSTRAINT check_t_parking_status_exit_method
+    CHECK (exit_method IN ('qr_scan', 'geofence'));
+
+COMMENT ON COLUMN t_parking_status.entry_method IS '入庫の確認方法（qr_scan: QRコードの読み取り, geofence: 位置情報）';
+COMMENT ON COLUMN t_parking_status.exit_method IS '出庫の確認方法（qr_scan: QRコードの読み取り, geofence: 位置情報）';
+COMMENT ON COLUMN t_parking_status.entry_code_id IS '入庫に使ったQRコードのID（位置情報の場合はNULL）';
+COMMENT ON COLUMN t_parking_status.exit_code_id IS '出庫に使ったQRコードのID（位置情報の場合はNULL）';
@@ -0,0 +1,35 @@
+-- パスワード履歴テーブル
+-- 論理名: パスワード履歴テーブル
+-- 物理名: t_password_history
+CREATE TABLE t_password_history (
+    -- 論理名: 履歴ID
+    -- 物理名: history_id
+    history_id UUID NOT NULL DEFAULT uuid_generate_v4(),
+
+    -- 論理名: ログインID
+    -- 物理名: login_id
+    login_id UUID NOT NULL,
+
+    -- 論理名: パスワード
+    -- 物理名: pass_word
+    -- 過去に使用したパスワードのハッシュ
+    pass_word TEXT NOT NULL,
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_password_history PRIMARY KEY (history_id)
+);
+
+-- テーブルコメント
+COMMENT ON TABLE t_password_history IS 'パスワード再利用を防ぐため、過去のパスワードハッシュを保持するテーブル';
+
+-- カラムコメント
+COMMENT ON COLUMN t_password_history.history_id IS '履歴の一意識別子（UUID v4）';
+COMMENT ON COLUMN t_password_history.login_id IS '対象のログインID';
+COMMENT ON COLUMN t_password_history.pass_word IS '過去に使用したパスワードのハッシュ';
+COMMENT ON COLUMN t_password_history.created_datetime IS 'パスワードを変更した日時';
+
+-- インデックス作成
+CREATE INDEX idx_t_password_history_login_id ON t_password_history(login_id, created_datetime DESC);
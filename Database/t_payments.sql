@@ -0,0 +1,140 @@
+-- 決済テーブル
+-- 論理名: 決済テーブル
+-- 物理名: t_payments
+-- 予約ごとの決済（与信・売上確定・返金・取消）の状態を管理する
+CREATE TABLE IF NOT EXISTS t_payments (
+    -- 論理名: 決済ID
+    -- 物理名: payment_id
+    payment_id UUID NOT NULL DEFAULT uuid_generate_v4(),
+
+    -- 論理名: 予約ID
+    -- 物理名: reservation_id
+    reservation_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: ユーザーID
+    -- 物理名: user_id
+    user_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: 決済プロバイダー
+    -- 物理名: provider
+    provider VARCHAR(30) NOT NULL,
+
+    -- 論理名: プロバイダー決済ID
+    -- 物理名: provider_payment_id
+    -- 与信前はNULL
+    provider_payment_id VARCHAR(100),
+
+    -- 論理名: ステータス
+    -- 物理名: status
+    -- pending: 与信待ち / authorized: 与信済み / captured: 売上確定 / refunded: 全額返金 / voided: 与信取消 / failed: 失敗
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+
+    -- 論理名: 与信金額（円）
+    -- 物理名: amount
+    amount BIGINT NOT NULL,
+
+    -- 論理名: 売上確定金額（円）
+    -- 物理名: captured_amount
+    captured_amount BIGINT NOT NULL DEFAULT 0,
+
+    -- 論理名: 返金済み金額（円）
+    -- 物理名: refunded_amount
+    refunded_amount BIGINT NOT NULL DEFAULT 0,
+
+    -- 論理名: 通貨
+    -- 物理名: currency
+    currency VARCHAR(3) NOT NULL DEFAULT 'JPY',
+
+    -- 論理名: 与信試行回数
+    -- 物理名: attempt_count
+    -- 与信失敗後の再試行ごとに加算し、プロバイダーへの冪等キーに使用する
+    attempt_count INTEGER NOT NULL DEFAULT 1,
+
+    -- 論理名: 失敗理由
+    -- 物理名: failure_reason
+    failure_reason TEXT,
+
+    -- 論理名: 与信日時
+    -- 物理名: authorized_datetime
+    authorized_datetime TIMESTAMP WITH TIME ZONE,
+
+    -- 論理名: 売上確定日時
+    -- 物理名: captured_datetime
+    captured_datetime TIMESTAMP WITH TIME ZONE,
+
+    -- 論理名: 最終返金日時
+    -- 物理名: refunded_datetime
+    refunded_datetime TIMESTAMP WITH TIME ZONE,
+
+    -- 論理名: 与信取消日時
+    -- 物理名: voided_datetime
+    voided_datetime TIMESTAMP WITH TIME ZONE,
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    -- 論理名: 更新日時
+    -- 物理名: updated_datetime
+    updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_payments PRIMARY KEY (payment_id),
+    CONSTRAINT uq_t_payments_reservation_id UNIQUE (reservation_id),
+    CONSTRAINT uq_t_payments_provider_payment_id UNIQUE (provider, provider_payment_id),
+    CONSTRAINT check_t_payments_status CHECK (
+        status IN ('pending', 'authorized', 'captured', 'refunded', 'voided', 'failed')
+    ),
+    CONSTRAINT check_t_payments_amounts CHECK (
+        amount >= 0 AND captured_amount >= 0 AND refunded_amount >= 0 AND refunded_amount <= captured_amount
+    )
+);
+
+-- テーブルコメント
+COMMENT ON TABLE t_payments IS '予約ごとの決済（与信・売上確定・返金・取消）の状態を管理するテーブル';
+
+-- カラムコメント
+COMMENT ON COLUMN t_payments.payment_id IS '決済の一意識別子（UUID v4）';
+COMMENT ON COLUMN t_payments.reservation_id IS '対象の予約ID（t_reservations.reservation_id、予約ごとに1件）';
+COMMENT ON COLUMN t_payments.user_id IS '支払うユーザーのID（m_users.user_id）';
+COMMENT ON COLUMN t_payments.provider IS '決済プロバイダー名（fake等）';
+COMMENT ON COLUMN t_payments.provider_payment_id IS 'プロバイダー側の決済ID';
+COMMENT ON COLUMN t_payments.status IS 'ステータス（pending, authorized, captured, refunded, voided, failed）';
+COMMENT ON COLUMN t_payments.amount IS '与信金額（円）';
+COMMENT ON COLUMN t_payments.captured_amount IS '売上確定金額（円）';
+COMMENT ON COLUMN t_payments.refunded_amount IS '返金済み金額（円、一部返金の場合はステータスはcapturedのまま）';
+COMMENT ON COLUMN t_payments.currency IS '通貨コード（ISO 4217）';
+COMMENT ON COLUMN t_payments.attempt_count IS '与信の試行回数';
+COMMENT ON COLUMN t_payments.failure_reason IS '与信・売上確定に失敗した理由';
+COMMENT ON COLUMN t_payments.created_datetime IS 'レコードの作成日時';
+COMMENT ON COLUMN t_payments.updated_datetime IS 'レコードの最終更新日時';
+
+-- インデックス作成
+CREATE INDEX IF NOT EXISTS idx_t_payments_user_id ON t_payments(user_id);
+CREATE INDEX IF NOT EXISTS idx_t_payments_status ON t_payments(status);
+
+
+-- 決済Webhookイベントテーブル
+-- 論理名: 決済Webhookイベントテーブル
+-- 物理名: t_payment_events
+-- プロバイダーからの通知を記録し、同じイベントの重複処理を防ぐ
+CREATE TABLE IF NOT EXISTS t_payment_events (
+    provider VARCHAR(30) NOT NULL,
+    event_id VARCHAR(100) NOT NULL,
+    -- payment.authorized / payment.captured / payment.refunded / payment.voided / payment.failed
+    event_type VARCHAR(50) NOT NULL,
+    provider_payment_id VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL,
+    received_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    processed_datetime TIMESTAMP WITH TIME ZONE,
+    CONSTRAINT pk_t_payment_events PRIMARY KEY (provider, event_id)
+);
+
+COMMENT ON TABLE t_payment_events IS '決済プロバイダーから受信したWebhookイベント（重複受信の検知に使用）';
+COMMENT ON COLUMN t_payment_events.provider IS '決済プロバイダー名';
+COMMENT ON COLUMN t_payment_events.event_id IS 'プロバイダー側のイベントID';
+COMMENT ON COLUMN t_payment_events.event_type IS 'イベント種別（payment.authorized, payment.captured, payment.refunded, payment.voided, payment.failed）';
+COMMENT ON COLUMN t_payment_events.provider_payment_id IS '対象のプロバイダー決済ID';
+COMMENT ON COLUMN t_payment_events.payload IS '受信したイベント本文';
+COMMENT ON COLUMN t_payment_events.processed_datetime IS '決済状態へ反映した日時';
+
+CREATE INDEX IF NOT EXISTS idx_t_payment_events_provider_payment_id ON t_payment_events(provider, provider_payment_id);
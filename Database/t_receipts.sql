@@ -0,0 +1,143 @@
+-- 領収書テーブル
+-- 論理名: 領収書テーブル
+-- 物理名: t_receipts
+-- 利用完了した予約の領収書（適格簡易請求書）。発行時点の内容を保存し、再出力しても内容は変わらない
+CREATE TABLE IF NOT EXISTS t_receipts (
+    -- 論理名: 領収書ID
+    -- 物理名: receipt_id
+    receipt_id UUID NOT NULL DEFAULT uuid_generate_v4(),
+
+    -- 論理名: 領収書番号
+    -- 物理名: receipt_number
+    -- 例: R2026-000001（発行年ごとの連番）
+    receipt_number VARCHAR(20) NOT NULL,
+
+    -- 論理名: 予約ID
+    -- 物理名: reservation_id
+    reservation_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: ユーザーID
+    -- 物理名: user_id
+    user_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: オーナーID
+    -- 物理名: owner_id
+    owner_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: 発行者名
+    -- 物理名: issuer_name
+    issuer_name VARCHAR(100) NOT NULL,
+
+    -- 論理名: 登録番号
+    -- 物理名: invoice_registration_number
+    -- 発行時点のオーナーの適格請求書発行事業者登録番号（未登録の場合はNULL）
+    invoice_registration_number VARCHAR(14),
+
+    -- 論理名: 宛名
+    -- 物理名: recipient_name
+    recipient_name VARCHAR(100),
+
+    -- 論理名: 駐車場名
+    -- 物理名: parking_lot_name
+    parking_lot_name VARCHAR(100) NOT NULL,
+
+    -- 論理名: 利用開始日時
+    -- 物理名: usage_start_datetime
+    usage_start_datetime TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    -- 論理名: 利用終了日時
+    -- 物理名: usage_end_datetime
+    usage_end_datetime TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    -- 論理名: 合計金額（円、税込）
+    -- 物理名: total_amount
+    total_amount BIGINT NOT NULL,
+
+    -- 論理名: 税率（%）
+    -- 物理名: tax_rate
+    tax_rate INTEGER NOT NULL,
+
+    -- 論理名: 消費税額（円）
+    -- 物理名: tax_amount
+    tax_amount BIGINT NOT NULL,
+
+    -- 論理名: 発行日時
+    -- 物理名: issued_datetime
+    issued_datetime TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    -- 論理名: 更新日時
+    -- 物理名: updated_datetime
+    updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_receipts PRIMARY KEY (receipt_id),
+    CONSTRAINT uq_t_receipts_receipt_number UNIQUE (receipt_number),
+    CONSTRAINT uq_t_receipts_reservation_id UNIQUE (reservation_id),
+    CONSTRAINT fk_t_receipts_reservation_id FOREIGN KEY (reservation_id)
+        REFERENCES t_reservations(reservation_id),
+    CONSTRAINT check_t_receipts_amount CHECK (total_amount >= 0 AND tax_amount >= 0 AND tax_amount <= total_amount)
+);
+
+-- インデックス
+CREATE INDEX IF NOT EXISTS idx_t_receipts_user_id ON t_receipts(user_id);
+CREATE INDEX IF NOT EXISTS idx_t_receipts_owner_id ON t_receipts(owner_id);
+
+-- テーブルコメント
+COMMENT ON TABLE t_receipts IS '利用完了した予約の領収書（適格簡易請求書）。発行時点の内容を保存する';
+
+-- カラムコメント
+COMMENT ON COLUMN t_receipts.receipt_id IS '領収書の一意識別子';
+COMMENT ON COLUMN t_receipts.receipt_number IS '領収書番号（発行年ごとの連番、例: R2026-000001）';
+COMMENT ON COLUMN t_receipts.reservation_id IS '対象の予約ID（1予約につき1件）';
+COMMENT ON COLUMN t_receipts.user_id IS '予約したユーザーのID';
+COMMENT ON COLUMN t_receipts.owner_id IS '発行者（駐車場のオーナー）のID';
+COMMENT ON COLUMN t_receipts.issuer_name IS '発行時点の発行者名';
+COMMENT ON COLUMN t_receipts.invoice_registration_number IS '発行時点の適格請求書発行事業者登録番号（T+13桁、未登録の場合はNULL）';
+COMMENT ON COLUMN t_receipts.recipient_name IS '宛名';
+COMMENT ON COLUMN t_receipts.parking_lot_name IS '発行時点の駐車場名';
+COMMENT ON COLUMN t_receipts.usage_start_datetime IS '利用開始日時（入庫日時、未記録の場合は予約開始日時）';
+COMMENT ON COLUMN t_receipts.usage_end_datetime IS '利用終了日時（出庫日時、未記録の場合は予約終了日時）';
+COMMENT ON COLUMN t_receipts.total_amount IS '合計金額（円、税込）';
+COMMENT ON COLUMN t_receipts.tax_rate IS '適用税率（%）';
+COMMENT ON COLUMN t_receipts.tax_amount IS '消費税額（円、税込金額から1円未満切り捨てで算出）';
+COMMENT ON COLUMN t_receipts.issued_datetime IS '発行日時';
+COMMENT ON COLUMN t_receipts.created_datetime IS 'レコードの作成日時';
+COMMENT ON COLUMN t_receipts.updated_datetime IS 'レコードの最終更新日時';
+
+
+-- 領収書番号採番テーブル
+-- 論理名: 領収書番号採番テーブル
+-- 物理名: t_receipt_sequences
+-- 発行年ごとの最終番号。領収書の登録と同じトランザクションで更新し、欠番を出さない
+CREATE TABLE IF NOT EXISTS t_receipt_sequences (
+    -- 論理名: 発行年
+    -- 物理名: issue_year
+    issue_year INTEGER NOT NULL,
+
+    -- 論理名: 最終番号
+    -- 物理名: last_number
+    last_number BIGINT NOT NULL,
+
+    -- 論理名: 更新日時
+    -- 物理名: updated_datetime
+    updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_receipt_sequences PRIMARY KEY (issue_year)
+);
+
+COMMENT ON TABLE t_receipt_sequences IS '領収書番号の発行年ごとの最終番号';
+COMMENT ON COLUMN t_receipt_sequences.issue_year IS '発行年（日本時間）';
+COMMENT ON COLUMN t_receipt_sequences.last_number IS '発行済みの最終番号';
+COMMENT ON COLUMN t_receipt_sequences.updated_datetime IS 'レコードの最終更新日時';
+
+
+-- オーナーテーブルへの登録番号の追加
+ALTER TABLE m_owners ADD COLUMN IF NOT EXISTS invoice_registration_number VARCHAR(14);
+ALTER TABLE m_owners DROP CONSTRAINT IF EXISTS check_m_owners_invoice_registration_number;
+ALTER TABLE m_owners ADD CONSTRAINT check_m_owners_invoice_registration_number
+    CHECK (invoice_registration_number ~ '^T[0-9]{13}$');
+
+COMMENT ON COLUMN m_owners.invoice_registration_number IS '適格請求書発行事業者登録番号（T+13桁、未登録の場合はNULL）';
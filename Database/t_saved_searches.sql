@@ -0,0 +1,167 @@
+-- 保存検索テーブル
+-- 論理名: 保存検索テーブル
+-- 物理名: t_saved_searches
+CREATE TABLE IF NOT EXISTS t_saved_searches (
+    -- 論理名: 保存検索ID
+    -- 物理名: saved_search_id
+    saved_search_id UUID NOT NULL DEFAULT uuid_generate_v4(),
+
+    -- 論理名: ユーザーID
+    -- 物理名: user_id
+    user_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: 名称
+    -- 物理名: search_name
+    search_name VARCHAR(100) NOT NULL,
+
+    -- 論理名: 検索条件
+    -- 物理名: condition_json
+    -- 正規化済みの検索条件（検索履歴と同じ形式）
+    condition_json JSONB NOT NULL,
+
+    -- 論理名: メール通知
+    -- 物理名: notify_email
+    notify_email BOOLEAN NOT NULL DEFAULT TRUE,
+
+    -- 論理名: アプリ内通知
+    -- 物理名: notify_in_app
+    notify_in_app BOOLEAN NOT NULL DEFAULT TRUE,
+
+    -- 論理名: 通知休止期間（分）
+    -- 物理名: quiet_period_minutes
+    -- 通知後、この期間は同じ保存検索の通知を送らない
+    quiet_period_minutes INTEGER NOT NULL DEFAULT 60,
+
+    -- 論理名: 有効フラグ
+    -- 物理名: is_active
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    -- 論理名: 最終通知日時
+    -- 物理名: last_notified_datetime
+    last_notified_datetime TIMESTAMP WITH TIME ZONE,
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    -- 論理名: 更新日時
+    -- 物理名: updated_datetime
+    updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_saved_searches PRIMARY KEY (saved_search_id),
+    CONSTRAINT uq_t_saved_searches_name UNIQUE (user_id, search_name),
+    CONSTRAINT check_t_saved_searches_quiet_period CHECK (quiet_period_minutes >= 0)
+);
+
+-- テーブルコメント
+COMMENT ON TABLE t_saved_searches IS 'ユーザーが名前を付けて保存した検索条件と通知設定を管理するテーブル';
+
+-- カラムコメント
+COMMENT ON COLUMN t_saved_searches.saved_search_id IS '保存検索の一意識別子（UUID v4）';
+COMMENT ON COLUMN t_saved_searches.user_id IS '保存したユーザーのID（m_users.user_id）';
+COMMENT ON COLUMN t_saved_searches.search_name IS '保存検索の名称（ユーザーごとに一意）';
+COMMENT ON COLUMN t_saved_searches.condition_json IS '正規化済みの検索条件（検索履歴と同じ形式）';
+COMMENT ON COLUMN t_saved_searches.notify_email IS 'メールで通知するか';
+COMMENT ON COLUMN t_saved_searches.notify_in_app IS 'アプリ内通知を作成するか';
+COMMENT ON COLUMN t_saved_searches.quiet_period_minutes IS '通知後に次の通知を送らない期間（分）';
+COMMENT ON COLUMN t_saved_searches.is_active IS '通知の有効・停止';
+COMMENT ON COLUMN t_saved_searches.last_notified_datetime IS '最後に通知した日時';
+COMMENT ON COLUMN t_saved_searches.created_datetime IS 'レコードの作成日時';
+COMMENT ON COLUMN t_saved_searches.updated_datetime IS 'レコードの最終更新日時';
+
+-- インデックス作成
+CREATE INDEX IF NOT EXISTS idx_t_saved_searches_user_id ON t_saved_searches(user_id, created_datetime);
+CREATE INDEX IF NOT EXISTS idx_t_saved_searches_active ON t_saved_searches(is_active);
+
+
+-- 保存検索通知済み駐車場テーブル
+-- 論理名: 保存検索通知済み駐車場テーブル
+-- 物理名: t_saved_search_matches
+-- 同じ駐車場を同じ保存検索で繰り返し通知しないために使用する
+CREATE TABLE IF NOT EXISTS t_saved_search_matches (
+    saved_search_id UUID NOT NULL,
+    parking_lot_id VARCHAR(37) NOT NULL,
+    notified_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT pk_t_saved_search_matches PRIMARY KEY (saved_search_id, parking_lot_id),
+    CONSTRAINT fk_t_saved_search_matches_saved_search_id FOREIGN KEY (saved_search_id)
+        REFERENCES t_saved_searches(saved_search_id) ON DELETE CASCADE
+);
+
+COMMENT ON TABLE t_saved_search_matches IS '保存検索ごとに通知済みの駐車場を管理するテーブル';
+COMMENT ON COLUMN t_saved_search_matches.saved_search_id IS '保存検索ID';
+COMMENT ON COLUMN t_saved_search_matches.parking_lot_id IS '通知した駐車場ID';
+COMMENT ON COLUMN t_saved_search_matches.notified_datetime IS '通知した日時';
+
+
+-- 保存検索評価イベントテーブル
+-- 論理名: 保存検索評価イベントテーブル
+-- 物理名: t_saved_search_events
+-- 駐車場の新規登録・予約のキャンセルをトリガーで記録し、バックグラウンド処理で保存検索を再評価する
+CREATE TABLE IF NOT EXISTS t_saved_search_events (
+    event_id BIGSERIAL NOT NULL,
+    -- lot_created: 駐車場の新規登録 / reservation_cancelled: 予約のキャンセル
+    event_type VARCHAR(30) NOT NULL,
+    parking_lot_id VARCHAR(37) NOT NULL,
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    processed_datetime TIMESTAMP WITH TIME ZONE,
+    CONSTRAINT pk_t_saved_search_events PRIMARY KEY (event_id)
+);
+
+COMMENT ON TABLE t_saved_search_events IS '保存検索の再評価が必要なイベント（駐車場の新規登録・予約のキャンセル）';
+COMMENT ON COLUMN t_saved_search_events.event_type IS 'イベント種別（lot_created, reservation_cancelled）';
+COMMENT ON COLUMN t_saved_search_events.parking_lot_id IS '対象の駐車場ID';
+COMMENT ON COLUMN t_saved_search_events.processed_datetime IS '評価済みの日時（未処理はNULL）';
+
+-- 複数のインスタンスで同じイベントを処理しないよう、処理中のイベントに取得日時を記録する
+ALTER TABLE t_saved_search_events ADD COLUMN IF NOT EXISTS claimed_datetime TIMESTAMP WITH TIME ZONE;
+COMMENT ON COLUMN t_saved_search_events.claimed_datetime IS '処理を開始した日時（一定時間を過ぎても未処理の場合は再処理する）';
+
+CREATE INDEX IF NOT EXISTS idx_t_saved_search_events_pending ON t_saved_search_events(event_id) WHERE processed_datetime IS NULL;
+
+-- 保存検索の保留駐車場テーブル
+-- 論理名: 保存検索の保留駐車場テーブル
+-- 物理名: t_saved_search_pending_lots
+-- 通知休止期間中に再評価イベントがあった駐車場を保存検索ごとに保留し、休止明けに評価する
+CREATE TABLE IF NOT EXISTS t_saved_search_pending_lots (
+    saved_search_id UUID NOT NULL,
+    parking_lot_id VARCHAR(37) NOT NULL,
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT pk_t_saved_search_pending_lots PRIMARY KEY (saved_search_id, parking_lot_id),
+    CONSTRAINT fk_t_saved_search_pending_lots_saved_search_id FOREIGN KEY (saved_search_id)
+        REFERENCES t_saved_searches(saved_search_id) ON DELETE CASCADE
+);
+
+COMMENT ON TABLE t_saved_search_pending_lots IS '通知休止期間中の保存検索で、休止明けに評価する駐車場';
+COMMENT ON COLUMN t_saved_search_pending_lots.saved_search_id IS '保存検索ID';
+COMMENT ON COLUMN t_saved_search_pending_lots.parking_lot_id IS '評価を保留した駐車場ID';
+COMMENT ON COLUMN t_saved_search_pending_lots.created_datetime IS '保留した日時';
+
+-- 駐車場の新規登録時にイベントを記録
+CREATE OR REPLACE FUNCTION enqueue_saved_search_lot_created() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO t_saved_search_events (event_type, parking_lot_id)
+    VALUES ('lot_created', NEW.parking_lot_id);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trigger_saved_search_lot_created ON t_parking_lots;
+CREATE TRIGGER trigger_saved_search_lot_created
+    AFTER INSERT ON t_parking_lots
+    FOR EACH ROW EXECUTE FUNCTION enqueue_saved_search_lot_created();
+
+-- 予約がキャンセル（status = '3'）になった時にイベントを記録
+CREATE OR REPLACE FUNCTION enqueue_saved_search_reservation_cancelled() RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.status = '3' AND OLD.status IS DISTINCT FROM '3' THEN
+        INSERT INTO t_saved_search_events (event_type, parking_lot_id)
+        VALUES ('reservation_cancelled', NEW.parking_lot_id);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trigger_saved_search_reservation_cancelled ON t_reservations;
+CREATE TRIGGER trigger_saved_search_reservation_cancelled
+    AFTER UPDATE OF status ON t_reservations
+    FOR EACH ROW EXECUTE FUNCTION enqueue_saved_search_reservation_cancelled();
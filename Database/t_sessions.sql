@@ -0,0 +1,47 @@
+-- セッション管理テーブル
+-- 論理名: セッション管理テーブル
+-- 物理名: t_sessions
+DROP TABLE IF EXISTS t_sessions;
+CREATE TABLE t_sessions (
+    -- 論理名: セッションID
+    -- 物理名: session_id
+    session_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: ユーザーID
+    -- 物理名: user_id
+    user_id VARCHAR(37),
+
+    -- 論理名: セッションデータ
+    -- 物理名: session_data
+    session_data JSONB NOT NULL DEFAULT '{}'::jsonb,
+
+    -- 論理名: 有効期限
+    -- 物理名: expires_datetime
+    expires_datetime TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    -- 論理名: 更新日時
+    -- 物理名: updated_datetime
+    updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    -- 主キー制約
+    CONSTRAINT pk_t_sessions PRIMARY KEY (session_id)
+);
+
+-- テーブルコメント
+COMMENT ON TABLE t_sessions IS 'サーバーセッションを管理するテーブル。全ワーカー・再起動後もセッションを共有するために使用';
+
+-- カラムコメント
+COMMENT ON COLUMN t_sessions.session_id IS 'セッションの一意識別子（UUID v4、クッキーの値）';
+COMMENT ON COLUMN t_sessions.user_id IS 'セッションに紐づくユーザーID（未ログインの場合はNULL）';
+COMMENT ON COLUMN t_sessions.session_data IS 'セッションに保存するキー・値データ';
+COMMENT ON COLUMN t_sessions.expires_datetime IS 'セッションの有効期限（スライディング有効期限の場合はアクセス毎に延長）';
+COMMENT ON COLUMN t_sessions.created_datetime IS 'セッションの作成日時';
+COMMENT ON COLUMN t_sessions.updated_datetime IS 'セッションの最終更新日時';
+
+-- インデックス作成（期限切れセッションの定期削除用）
+CREATE INDEX idx_t_sessions_expires_datetime ON t_sessions(expires_datetime);
+CREATE INDEX idx_t_sessions_user_id ON t_sessions(user_id);
@@ -0,0 +1,82 @@
+-- 登録車両テーブル
+-- 論理名: 登録車両テーブル
+-- 物理名: t_user_vehicles
+CREATE TABLE t_user_vehicles (
+    -- 論理名: 車両ID
+    -- 物理名: vehicle_id
+    vehicle_id UUID NOT NULL DEFAULT uuid_generate_v4(),
+
+    -- 論理名: ユーザーID
+    -- 物理名: user_id
+    user_id VARCHAR(37) NOT NULL,
+
+    -- 論理名: 車両名
+    -- 物理名: vehicle_name
+    vehicle_name VARCHAR(100) NOT NULL,
+
+    -- 論理名: 車種ID
+    -- 物理名: vehicle_type_id
+    -- m_parking_vehicle_types.vehicle_type_id
+    vehicle_type_id VARCHAR(37),
+
+    -- 論理名: ナンバー
+    -- 物理名: license_plate
+    -- 正規化済みの表記（例: 品川 300 あ 12-34）
+    license_plate VARCHAR(20) NOT NULL,
+
+    -- 論理名: 全長（cm）
+    -- 物理名: length_cm
+    length_cm INTEGER,
+
+    -- 論理名: 全幅（cm）
+    -- 物理名: width_cm
+    width_cm INTEGER,
+
+    -- 論理名: 全高（cm）
+    -- 物理名: height_cm
+    height_cm INTEGER,
+
+    -- 論理名: 車両重量（kg）
+    -- 物理名: weight_kg
+    weight_kg INTEGER,
+
+    -- 論理名: デフォルト車両フラグ
+    -- 物理名: is_default
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+
+    -- 論理名: 作成日時
+    -- 物理名: created_datetime
+    created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    -- 論理名: 更新日時
+    -- 物理名: updated_datetime
+    updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT pk_t_user_vehicles PRIMARY KEY (vehicle_id),
+    CONSTRAINT unique_t_user_vehicles_user_id_license_plate UNIQUE (user_id, license_plate),
+    CONSTRAINT check_t_user_vehicles_length_cm CHECK (length_cm IS NULL OR length_cm BETWEEN 1 AND 2000),
+    CONSTRAINT check_t_user_vehicles_width_cm CHECK (width_cm IS NULL OR width_cm BETWEEN 1 AND 500),
+    CONSTRAINT check_t_user_vehicles_height_cm CHECK (height_cm IS NULL OR height_cm BETWEEN 1 AND 500),
+    CONSTRAINT check_t_user_vehicles_weight_kg CHECK (weight_kg IS NULL OR weight_kg BETWEEN 1 AND 30000)
+);
+
+-- テーブルコメント
+COMMENT ON TABLE t_user_vehicles IS 'ユーザーが登録した車両を管理するテーブル。デフォルト車両は予約・検索条件の初期値に使用する';
+
+-- カラムコメント
+COMMENT ON COLUMN t_user_vehicles.vehicle_id IS '車両の一意識別子（UUID v4）';
+COMMENT ON COLUMN t_user_vehicles.user_id IS '車両を登録したユーザーID';
+COMMENT ON COLUMN t_user_vehicles.vehicle_name IS '車両名（例: トヨタプリウス）';
+COMMENT ON COLUMN t_user_vehicles.vehicle_type_id IS '車種ID（m_parking_vehicle_typesと関連）';
+COMMENT ON COLUMN t_user_vehicles.license_plate IS 'ナンバープレート（例: 品川 300 あ 12-34）';
+COMMENT ON COLUMN t_user_vehicles.length_cm IS '全長（cm）';
+COMMENT ON COLUMN t_user_vehicles.width_cm IS '全幅（cm）';
+COMMENT ON COLUMN t_user_vehicles.height_cm IS '全高（cm）';
+COMMENT ON COLUMN t_user_vehicles.weight_kg IS '車両重量（kg）';
+COMMENT ON COLUMN t_user_vehicles.is_default IS 'デフォルト車両フラグ（ユーザーごとに1台まで）';
+COMMENT ON COLUMN t_user_vehicles.created_datetime IS 'レコードの作成日時';
+COMMENT ON COLUMN t_user_vehicles.updated_datetime IS 'レコードの最終更新日時';
+
+-- インデックス作成
+CREATE INDEX idx_t_user_vehicles_user_id ON t_user_vehicles(user_id);
+CREATE UNIQUE INDEX unique_t_user_vehicles_default ON t_user_vehicles(user_id) WHERE is_default;
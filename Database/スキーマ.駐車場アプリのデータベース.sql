@@ -225,6 +225,10 @@ CREATE TABLE m_login (
     -- 論理名: ログイン失敗回数リセット日時
     -- 物理名: login_failed_reset_datetime
     login_failed_reset_datetime TIMESTAMP WITH TIME ZONE,
+    -- 論理名: パスワード変更日時
+    -- 物理名: password_changed_datetime
+    -- この日時より前に発行されたリフレッシュトークンは無効
+    password_changed_datetime TIMESTAMP WITH TIME ZONE,
     -- 論理名: 作成日時
     -- 物理名: created_datetime
     created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
@@ -258,6 +262,7 @@ COMMENT ON COLUMN m_login.login_failed_datetime IS 'ログイン失敗日時';
 COMMENT ON COLUMN m_login.login_failed_flag IS 'ログイン失敗フラグ（1: ログイン失敗, 0: ログイン成功）';
 COMMENT ON COLUMN m_login.login_failed_reason IS 'ログイン失敗理由（例: パスワード不一致, アカウントロック）';
 COMMENT ON COLUMN m_login.login_failed_reason_detail IS 'ログイン失敗理由詳細（例: パスワード不一致, アカウントロック）';
+COMMENT ON COLUMN m_login.password_changed_datetime IS 'パスワード変更日時（これより前に発行されたリフレッシュトークンは無効）';
 COMMENT ON COLUMN m_login.created_datetime IS 'レコードの作成日時';
 COMMENT ON COLUMN m_login.updated_datetime IS 'レコードの最終更新日時';
 -- インデックス作成
@@ -278,6 +283,9 @@ CREATE TABLE m_users (
     -- 論理名: 氏名
     -- 物理名: full_name
     full_name VARCHAR(100) NOT NULL,
+    -- 論理名: 氏名（カナ）
+    -- 物理名: full_name_kana
+    full_name_kana VARCHAR(100),
     -- 論理名: 生年月日
     -- 物理名: birthday
     birthday DATE,
@@ -287,6 +295,10 @@ CREATE TABLE m_users (
     -- 論理名: 電話番号
     -- 物理名: phone_number
     phone_number VARCHAR(50) NOT NULL,
+    -- 論理名: 郵便番号
+    -- 物理名: postal_code
+    -- 例: 123-4567
+    postal_code VARCHAR(20),
     -- 論理名: 住所
     -- 物理名: address
     address TEXT NOT NULL,
@@ -318,6 +330,8 @@ COMMENT ON COLUMN m_users.user_id IS 'ユーザーの一意識別子（形式: u
 -- 修正：login_idを追加
 COMMENT ON COLUMN m_users.login_id IS '関連するログインID';
 COMMENT ON COLUMN m_users.full_name IS 'ユーザーの氏名';
+COMMENT ON COLUMN m_users.full_name_kana IS 'ユーザーの氏名（カタカナ、可NULL）';
+COMMENT ON COLUMN m_users.postal_code IS 'ユーザーの郵便番号（例: 123-4567、可NULL）';
 COMMENT ON COLUMN m_users.phone_number IS 'ユーザーの電話番号（一意、可NULL）';
 COMMENT ON COLUMN m_users.address IS 'ユーザーの住所';
 COMMENT ON COLUMN m_users.promotional_email_opt IS 'プロモーションメールの受信設定（1: 受け取る, 0: 受け取らない）';
@@ -515,6 +529,9 @@ CREATE TABLE t_parking_lots (
     -- 論理名: 最近車站
     -- 物理名: nearest_station
     nearest_station VARCHAR(100),
+    -- 論理名: 最寄り駅ID
+    -- 物理名: nearest_station_id
+    nearest_station_id VARCHAR(20),
     -- 論理名: 状態
     -- 物理名: status
     status VARCHAR(20) NOT NULL,
@@ -601,6 +618,7 @@ COMMENT ON COLUMN t_parking_lots.car_height_limit IS '車高制限（選択肢
 COMMENT ON COLUMN t_parking_lots.tire_width_limit IS 'タイヤ幅制限（選択肢：制限なし）';
 COMMENT ON COLUMN t_parking_lots.vehicle_type IS '駐車場の主要な車種（m_parking_vehicle_typesと関連）';
 COMMENT ON COLUMN t_parking_lots.nearest_station IS '最寄り駅（例: 東京駅）';
+COMMENT ON COLUMN t_parking_lots.nearest_station_id IS '最寄り駅ID（駅マスタのstation_id、可NULL）';
 COMMENT ON COLUMN t_parking_lots.status IS '駐車場の状態（アクティブまたは停止中）';
 COMMENT ON COLUMN t_parking_lots.start_date IS '駐車場の利用開始日';
 COMMENT ON COLUMN t_parking_lots.end_date IS '駐車場の利用停止日';
@@ -683,6 +701,7 @@ COMMENT ON COLUMN t_parking_google_maps.created_datetime IS 'レコードの作
 COMMENT ON COLUMN t_parking_google_maps.updated_datetime IS 'レコードの最終更新日時';
 -- インデックス作成
 CREATE INDEX idx_t_parking_google_maps_parking_lot_id ON t_parking_google_maps(parking_lot_id);
+CREATE INDEX idx_t_parking_google_maps_latitude_longitude ON t_parking_google_maps(latitude, longitude);
 
 -- 3. 駐車場貸出タイプテーブル
 -- 論理名: 駐車場貸出タイプテーブル
@@ -970,6 +989,15 @@ CREATE TABLE t_favorites (
     -- 論理名: 駐車場ID
     -- 物理名: parking_lot_id
     parking_lot_id VARCHAR(37) NOT NULL,
+    -- 論理名: メモ
+    -- 物理名: memo
+    memo VARCHAR(500),
+    -- 論理名: 表示順
+    -- 物理名: sort_order
+    sort_order INTEGER,
+    -- 論理名: ラベル
+    -- 物理名: labels
+    labels TEXT[] NOT NULL DEFAULT '{}',
     -- 論理名: 作成日時
     -- 物理名: created_datetime
     created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
@@ -987,11 +1015,16 @@ COMMENT ON TABLE t_favorites IS 'ユーザーがお気に入りに登録した
 COMMENT ON COLUMN t_favorites.favorite_id IS 'お気に入りの一意識別子（UUID v4）';
 COMMENT ON COLUMN t_favorites.user_id IS 'お気に入りに登録したユーザーのID（形式: user_XXXXXX）';
 COMMENT ON COLUMN t_favorites.parking_lot_id IS 'お気に入りに登録した駐車場ID（形式: P-XXXXXX）';
+COMMENT ON COLUMN t_favorites.memo IS 'ユーザーが駐車場ごとに残すメモ（最大500文字）';
+COMMENT ON COLUMN t_favorites.sort_order IS 'ユーザーが並べ替えた表示順（小さいほど上位、未設定は末尾）';
+COMMENT ON COLUMN t_favorites.labels IS 'お気に入りの分類ラベル（例: 自宅、職場）';
 COMMENT ON COLUMN t_favorites.created_datetime IS 'レコードの作成日時';
 COMMENT ON COLUMN t_favorites.updated_datetime IS 'レコードの最終更新日時';
 -- インデックス作成
 CREATE INDEX idx_t_favorites_user_id ON t_favorites(user_id);
 CREATE INDEX idx_t_favorites_parking_lot_id ON t_favorites(parking_lot_id);
+CREATE INDEX idx_t_favorites_user_id_sort_order ON t_favorites(user_id, sort_order);
+CREATE INDEX idx_t_favorites_labels ON t_favorites USING GIN (labels);
 
 -- 5. 入出庫状況テーブル
 -- 論理名: 入出庫状況テーブル
@@ -129,8 +129,18 @@ CREATE TABLE t_parking_images (
   id SERIAL PRIMARY KEY,
   parking_lot_id VARCHAR(37) REFERENCES t_parking_lots(parking_lot_id) ON DELETE CASCADE,
   image_url TEXT NOT NULL,
+  medium_url TEXT,
+  thumbnail_url TEXT,
+  content_hash VARCHAR(64),
+  width INTEGER,
+  height INTEGER,
+  file_size INTEGER,
+  sort_order INTEGER NOT NULL DEFAULT 0,
+  caption VARCHAR(200),
+  is_cover BOOLEAN NOT NULL DEFAULT FALSE,
   created_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
-  updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+  updated_datetime TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT unique_t_parking_images_parking_lot_id_content_hash UNIQUE (parking_lot_id, content_hash)
 );
 
 COMMENT ON TABLE t_parking_images IS '駐車場に紐づく画像情報を格納するテーブル';
@@ -138,5 +148,17 @@ COMMENT ON TABLE t_parking_images IS '駐車場に紐づく画像情報を格納
 COMMENT ON COLUMN t_parking_images.id IS 'レコードID（連番）';
 COMMENT ON COLUMN t_parking_images.parking_lot_id IS '画像の対象となる駐車場ID（外部キー）';
 COMMENT ON COLUMN t_parking_images.image_url IS '画像の保存先パスまたはURL';
+COMMENT ON COLUMN t_parking_images.medium_url IS '中サイズ画像の保存先パス';
+COMMENT ON COLUMN t_parking_images.thumbnail_url IS 'サムネイル画像の保存先パス';
+COMMENT ON COLUMN t_parking_images.content_hash IS '画像のSHA-256ハッシュ（ファイル名にも使用）';
+COMMENT ON COLUMN t_parking_images.width IS '画像の幅（ピクセル）';
+COMMENT ON COLUMN t_parking_images.height IS '画像の高さ（ピクセル）';
+COMMENT ON COLUMN t_parking_images.file_size IS '画像のファイルサイズ（バイト）';
+COMMENT ON COLUMN t_parking_images.sort_order IS '表示順（昇順）';
+COMMENT ON COLUMN t_parking_images.caption IS '画像の説明文';
+COMMENT ON COLUMN t_parking_images.is_cover IS 'カバー画像フラグ（駐車場ごとに1枚）';
 COMMENT ON COLUMN t_parking_images.created_datetime IS 'レコード作成日時';
 COMMENT ON COLUMN t_parking_images.updated_datetime IS 'レコード更新日時';
+
+CREATE INDEX idx_t_parking_images_parking_lot_id_sort_order ON t_parking_images(parking_lot_id, sort_order);
+CREATE UNIQUE INDEX uq_t_parking_images_cover ON t_parking_images(parking_lot_id) WHERE is_cover;
@@ -0,0 +1,143 @@
+// src/bin/blob_migrate.rs
+//! ファイルストレージ移行コマンド
+//!
+//! 保存済みファイルをストレージバックエンド間でコピー（移動）する。
+//!
+//! ```text
+//! cargo run --bin blob_migrate -- --from local --to s3 [--prefix parking_lot_images/] [--overwrite] [--delete-source] [--dry-run]
+//! ```
+
+use std::process::ExitCode;
+
+use parking_app_backend::config::logging::{init_logger, LogConfig};
+use parking_app_backend::storage::{content_type_for_key, create_blob_store, BlobStoreConfig};
+use tracing::{error, info, warn};
+
+/// コマンドライン引数
+#[derive(Debug, Default)]
+struct MigrateOptions {
+    from: String,
+    to: String,
+    prefix: String,
+    overwrite: bool,
+    delete_source: bool,
+    dry_run: bool,
+}
+
+const USAGE: &str = "使い方: blob_migrate --from <local|s3> --to <local|s3> [--prefix <接頭辞>] [--overwrite] [--delete-source] [--dry-run]";
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<MigrateOptions, String> {
+    let mut options = MigrateOptions::default();
+    let mut args = args.peekable();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => options.from = args.next().ok_or("--from の値がありません")?,
+            "--to" => options.to = args.next().ok_or("--to の値がありません")?,
+            "--prefix" => options.prefix = args.next().ok_or("--prefix の値がありません")?,
+            "--overwrite" => options.overwrite = true,
+            "--delete-source" => options.delete_source = true,
+            "--dry-run" => options.dry_run = true,
+            other => return Err(format!("不明な引数です: {}", other)),
+        }
+    }
+
+    if options.from.is_empty() || options.to.is_empty() {
+        return Err("--from と --to は必須です".to_string());
+    }
+    if options.from == options.to {
+        return Err("移行元と移行先が同じです".to_string());
+    }
+    Ok(options)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    dotenv::dotenv().ok();
+    let _guard = init_logger(LogConfig::from_env());
+
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let config = BlobStoreConfig::from_env();
+    let (source, destination) = match (
+        create_blob_store(&options.from, &config),
+        create_blob_store(&options.to, &config),
+    ) {
+        (Ok(source), Ok(destination)) => (source, destination),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("ストレージの初期化に失敗しました: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let keys = match source.list(&options.prefix).await {
+        Ok(keys) => keys,
+        Err(e) => {
+            error!("移行元のファイル一覧取得に失敗しました: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    info!(
+        "移行を開始します: {} → {}（対象: {}件, prefix='{}', dry_run={}）",
+        source.backend_name(),
+        destination.backend_name(),
+        keys.len(),
+        options.prefix,
+        options.dry_run
+    );
+
+    let (mut copied, mut skipped, mut failed) = (0usize, 0usize, 0usize);
+
+    for key in &keys {
+        if !options.overwrite {
+            match destination.exists(key).await {
+                Ok(true) => {
+                    skipped += 1;
+                    continue;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("移行先の存在確認に失敗: {} - {}", key, e);
+                }
+            }
+        }
+
+        if options.dry_run {
+            info!("[dry-run] コピー対象: {}", key);
+            copied += 1;
+            continue;
+        }
+
+        let result = async {
+            let bytes = source.get(key).await?;
+            destination.put(key, bytes, content_type_for_key(key)).await?;
+            if options.delete_source {
+                source.delete(key).await?;
+            }
+            Ok::<(), parking_app_backend::storage::StorageError>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => copied += 1,
+            Err(e) => {
+                error!("ファイルの移行に失敗: {} - {}", key, e);
+                failed += 1;
+            }
+        }
+    }
+
+    info!("移行が完了しました: コピー={}件, スキップ={}件, 失敗={}件", copied, skipped, failed);
+
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
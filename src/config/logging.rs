@@ -1,13 +1,22 @@
 use chrono::{DateTime, Local, NaiveDate, Utc};
-use std::{env, io, path::Path};
-use tracing::{info, Level};
+use std::{
+    env,
+    future::Future,
+    io,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tracing::{info, instrument::WithSubscriber, Event, Level, Subscriber};
 use tracing_appender::{
     non_blocking::WorkerGuard,
     rolling::{RollingFileAppender, Rotation},
 };
 use tracing_subscriber::{
     fmt::{format::FmtSpan, format::Writer, time::FormatTime},
-    layer::SubscriberExt,
+    layer::{Context, SubscriberExt},
     util::SubscriberInitExt,
     EnvFilter, Layer,
 };
@@ -407,6 +416,31 @@ pub fn log_sql_query(query: &str, params: &[SqlParam], execution_time_ms: Option
     }
 }
 
+/// sqlxがクエリを実行するたびに出力するイベントのターゲット
+const SQLX_QUERY_TARGET: &str = "sqlx::query";
+
+/// 実行されたSQLクエリを数えるレイヤー
+#[derive(Clone, Default)]
+struct SqlQueryCounter(Arc<AtomicUsize>);
+
+impl<S: Subscriber> Layer<S> for SqlQueryCounter {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() == SQLX_QUERY_TARGET {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// 処理中にデータベースで実行されたSQLクエリ数を数える（sqlxのクエリ実行イベントの件数）
+///
+/// N+1クエリの回帰テストなどで、件数に対してクエリ数が一定であることの確認に使う
+pub async fn count_sql_queries<F: Future>(future: F) -> (F::Output, usize) {
+    let counter = SqlQueryCounter::default();
+    let subscriber = tracing_subscriber::registry().with(counter.clone());
+    let output = future.with_subscriber(subscriber).await;
+    (output, counter.0.load(Ordering::Relaxed))
+}
+
 /// SQLクエリエラーをログ出力
 pub fn log_sql_error(query: &str, params: &[SqlParam], error: &str) {
     let formatted_sql = format_sql_query(query, params);
@@ -438,3 +472,25 @@ macro_rules! log_sql_error {
         $crate::config::logging::log_sql_error($query, $params, $error)
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_count_sql_queries() {
+        let ((), count) = count_sql_queries(async {
+            tracing::debug!(target: "sqlx::query", "SELECT 1");
+            tracing::debug!(target: "sqlx::query", "SELECT 2");
+            // アプリケーションのSQLログは数えない
+            log_sql_query("SELECT 3", &[], None);
+        })
+        .await;
+        assert_eq!(count, 2);
+
+        // 計測範囲外の実行は数えない
+        tracing::debug!(target: "sqlx::query", "SELECT 4");
+        let ((), count) = count_sql_queries(async {}).await;
+        assert_eq!(count, 0);
+    }
+}
@@ -0,0 +1,94 @@
+use actix_web::{delete, get, http::StatusCode, post, web::{Data, Json}, Responder, ResponseError};
+use tracing::{error, instrument, warn};
+
+use crate::{
+    controllers::ApiResponse,
+    middlewares::identity_middleware::UserIdentity,
+    models::account_model::AccountDeletionRequest,
+    services::AccountService,
+};
+
+/// 個人データのエクスポート（ログイン情報・プロフィール・車両・予約・お気に入り・検索履歴）
+#[get("/export")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn export_account_data_controller(
+    service: Data<AccountService>,
+    identity: UserIdentity,
+) -> impl Responder {
+    match service.export_data(&identity).await {
+        Ok(export) => ApiResponse::success(
+            export,
+            Some(StatusCode::OK.as_u16()),
+            Some("個人データのエクスポートに成功しました"),
+            None,
+        ),
+        Err(e) => {
+            error!("（account_controller.rs）個人データのエクスポートに失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// アカウント削除の申請（パスワードによる再認証が必要）
+#[post("/deletion")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn request_account_deletion_controller(
+    service: Data<AccountService>,
+    identity: UserIdentity,
+    req: Json<AccountDeletionRequest>,
+) -> impl Responder {
+    match service.request_deletion(&identity, &req).await {
+        Ok(result) => ApiResponse::success(
+            result,
+            Some(StatusCode::ACCEPTED.as_u16()),
+            Some("アカウント削除を受け付けました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（account_controller.rs）アカウント削除の申請に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// アカウント削除の申請状況
+#[get("/deletion")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn get_account_deletion_controller(
+    service: Data<AccountService>,
+    identity: UserIdentity,
+) -> impl Responder {
+    match service.get_deletion_status(&identity).await {
+        Ok(result) => ApiResponse::success(
+            result,
+            Some(StatusCode::OK.as_u16()),
+            Some("アカウント削除の申請状況を取得しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（account_controller.rs）アカウント削除の申請状況の取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// アカウント削除の取り消し（猶予期間中のみ）
+#[delete("/deletion")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn cancel_account_deletion_controller(
+    service: Data<AccountService>,
+    identity: UserIdentity,
+) -> impl Responder {
+    match service.cancel_deletion(&identity).await {
+        Ok(()) => ApiResponse::success(
+            (),
+            Some(StatusCode::OK.as_u16()),
+            Some("アカウント削除を取り消しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（account_controller.rs）アカウント削除の取り消しに失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
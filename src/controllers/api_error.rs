@@ -42,6 +42,12 @@ pub enum ApiError {
 
     #[display(fmt = "重複エラー: {}", _0)]
     DuplicateError(String),
+
+    #[display(fmt = "リクエストサイズ超過: {}", _0)]
+    PayloadTooLarge(String),
+
+    #[display(fmt = "サポートされていない形式: {}", _0)]
+    UnsupportedMediaType(String),
     
     #[display(fmt = "レート制限エラー: {}", _0)]
     RateLimitError(String),
@@ -99,7 +105,9 @@ impl ResponseError for ApiError {
             },
             ApiError::ValidationError(msg) | 
             ApiError::BadRequestError(msg) |
-            ApiError::BadRequest(msg) => {
+            ApiError::BadRequest(msg) |
+            ApiError::PayloadTooLarge(msg) |
+            ApiError::UnsupportedMediaType(msg) => {
                 debug!(request_id = %request_id, error = %msg, "Client error");
             },
             ApiError::NotFoundError(msg) => {
@@ -150,6 +158,8 @@ impl ResponseError for ApiError {
             ApiError::BadRequestError(_) => StatusCode::BAD_REQUEST,
             ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
             ApiError::DuplicateError(_) => StatusCode::CONFLICT,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             ApiError::RateLimitError(_) => StatusCode::TOO_MANY_REQUESTS,
             ApiError::ServiceUnavailableError(_) => StatusCode::SERVICE_UNAVAILABLE,
             ApiError::SessionError(_) => StatusCode::UNAUTHORIZED,
@@ -10,11 +10,12 @@
 //! - 密码重置完成 (POST /api/auth/password-reset/complete)
 //! - 获取当前用户信息 (GET /api/auth/me)
 //! - 用户登出 (POST /api/auth/signout)
+//! - 修改密码 (POST /api/auth/password/change)
 
 use actix_web::{
     get, post, 
     web::{Data, Json},
-    Responder, ResponseError,
+    HttpMessage, HttpRequest, Responder, ResponseError,
 };
 use tracing::{debug, error, info, instrument, warn};
 
@@ -23,11 +24,14 @@ use crate::{
         api_error::ApiError,
         api_response::ApiResponse,
     },
-    middlewares::identity_middleware::UserIdentity,
+    middlewares::{
+        identity_middleware::UserIdentity,
+        session_middleware::{SessionBackend, SessionData},
+    },
     models::auth_signin_model::{
         AuthSigninRequest,
         PasswordResetRequest, PasswordResetVerification, PasswordResetCompletion,
-        RefreshTokenRequest,
+        PasswordChangeRequest, RefreshTokenRequest,
     },
     services::auth_signin_service::AuthSigninService,
 
@@ -61,10 +65,11 @@ use crate::{
 /// - 422: 输入验证失败
 /// - 500: 服务器内部错误
 #[post("/signin")]
-#[instrument(skip(service, request), fields(email = %request.email))]
+#[instrument(skip(service, request, http_request), fields(email = %request.email))]
 pub async fn signin(
     service: Data<AuthSigninService>,
     request: Json<AuthSigninRequest>,
+    http_request: HttpRequest,
 ) -> impl Responder {
     info!("收到用户登录请求: {}", request.email);
     
@@ -82,6 +87,11 @@ pub async fn signin(
             info!("用户登录成功: {} (类型: {})", 
                 signin_response.email, if signin_response.is_owner { "owner" } else { "user" });
             
+            // 将会话绑定到登录ID（修改密码时用于使其他会话失效）
+            if let Some(session) = http_request.extensions_mut().get_mut::<SessionData>() {
+                session.user_id = Some(signin_response.login_id.clone());
+            }
+            
             ApiResponse::success(
                 signin_response,
                 Some(200),
@@ -369,9 +379,68 @@ pub async fn refresh_token(
             warn!("令牌刷新失败: {}", msg);
             ApiError::AuthenticationError(msg).error_response()
         }
+        Err(ApiError::TokenError(msg)) => {
+            warn!("令牌刷新失败: {}", msg);
+            ApiError::TokenError(msg).error_response()
+        }
         Err(err) => {
             error!("令牌刷新处理错误: {:?}", err);
             ApiError::InternalServerError.error_response()
         }
     }
-}
\ No newline at end of file
+}
+// ================================
+// 修改密码端点
+// ================================
+
+/// 修改密码端点
+/// 
+/// 验证当前密码后更新密码，当前会话以外的会话全部失效，
+/// 并返回新的令牌对（旧的刷新令牌不可再使用）
+/// 
+/// # 端点
+/// `POST /api/auth/password/change`
+/// 
+/// # 请求头
+/// - Authorization: Bearer <jwt_token>
+/// 
+/// # 响应
+/// - 200: 修改成功，返回新的令牌
+/// - 401: 当前密码错误
+/// - 422: 不符合密码策略或使用了最近的密码
+#[post("/password/change")]
+#[instrument(skip(service, session_backend, request, http_request), fields(user_id = %claims.user_id))]
+pub async fn change_password(
+    service: Data<AuthSigninService>,
+    session_backend: Data<dyn SessionBackend>,
+    claims: UserIdentity,
+    request: Json<PasswordChangeRequest>,
+    http_request: HttpRequest,
+) -> impl Responder {
+    info!("收到修改密码请求: user_id={}", claims.user_id);
+    
+    // 当前会话以外的会话失效
+    let current_session_id = http_request.extensions().get::<SessionData>().map(|session| session.id.clone());
+    let response = match service
+        .change_password(&claims.user_id, &request, session_backend.get_ref(), current_session_id.as_deref())
+        .await
+    {
+        Ok(response) => response,
+        Err(err) => {
+            warn!("修改密码失败: {}", err);
+            return err.error_response();
+        }
+    };
+    
+    // 当前会话重新绑定到登录ID
+    if let Some(session) = http_request.extensions_mut().get_mut::<SessionData>() {
+        session.user_id = Some(claims.user_id.clone());
+    }
+    
+    ApiResponse::success(
+        response,
+        Some(200),
+        Some("パスワードを変更しました"),
+        None,
+    )
+}
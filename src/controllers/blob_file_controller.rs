@@ -0,0 +1,72 @@
+use actix_web::{
+    get,
+    http::header::{CacheControl, CacheDirective, ETag, EntityTag, Header, IfNoneMatch},
+    web::{self, Data},
+    HttpRequest, HttpResponse, Responder, ResponseError,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use tracing::{debug, instrument, warn};
+
+use crate::{
+    controllers::ApiError,
+    storage::{content_type_for_key, BlobStore},
+    utils::image_processing::content_hash,
+};
+
+/// 署名済みURLのクエリパラメータ
+#[derive(Debug, Deserialize)]
+pub struct SignedUrlQuery {
+    pub expires: i64,
+    pub signature: String,
+}
+
+/// 署名済みURLによるファイル配信（ローカルストレージ用）
+///
+/// 内容のハッシュをETagとして返し、If-None-Match一致時は304を返す。
+/// キャッシュ期間はURLの有効期限までとする。
+#[get("/{key:.*}")]
+#[instrument(skip(blob_store, query, req))]
+pub async fn get_blob_file_controller(
+    req: HttpRequest,
+    key: web::Path<String>,
+    query: web::Query<SignedUrlQuery>,
+    blob_store: Data<dyn BlobStore>,
+) -> impl Responder {
+    let key = key.into_inner();
+
+    if !blob_store.verify_signature(&key, query.expires, &query.signature) {
+        warn!("署名済みURLの検証に失敗: {}", key);
+        return ApiError::AuthorizationError("URLが無効か有効期限が切れています".to_string()).error_response();
+    }
+
+    let bytes = match blob_store.get(&key).await {
+        Ok(bytes) => bytes,
+        Err(e) => return ApiError::from(e).error_response(),
+    };
+
+    let etag = EntityTag::new_strong(content_hash(&bytes));
+    let max_age = (query.expires - Utc::now().timestamp()).max(0) as u32;
+    let cache_control = CacheControl(vec![CacheDirective::Private, CacheDirective::MaxAge(max_age)]);
+
+    let not_modified = match <IfNoneMatch as Header>::parse(&req) {
+        Ok(IfNoneMatch::Any) => true,
+        Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+        Err(_) => false,
+    };
+
+    if not_modified {
+        debug!("ファイル未変更（304）: {}", key);
+        return HttpResponse::NotModified()
+            .insert_header(ETag(etag))
+            .insert_header(cache_control)
+            .finish();
+    }
+
+    debug!("ファイル配信: {} ({}バイト)", key, bytes.len());
+    HttpResponse::Ok()
+        .content_type(content_type_for_key(&key))
+        .insert_header(ETag(etag))
+        .insert_header(cache_control)
+        .body(bytes)
+}
@@ -0,0 +1,169 @@
+use actix_web::{
+    delete, get,
+    http::StatusCode,
+    post, put,
+    web::{Data, Json, Path},
+    Responder, ResponseError,
+};
+use tracing::{instrument, warn};
+
+use crate::{
+    controllers::ApiResponse,
+    middlewares::identity_middleware::UserIdentity,
+    models::cancellation_policy_model::{CancelReservationRequest, CancellationPolicy},
+    services::{CancellationPolicyService, ParkingLotsService},
+};
+
+/// 駐車場のキャンセルポリシー（利用者向け、未設定の場合は利用開始まで無料）
+#[get("/details/{parking_lot_id}/cancellation-policy")]
+#[instrument(skip(service))]
+pub async fn get_parking_lot_cancellation_policy_controller(
+    service: Data<CancellationPolicyService>,
+    parking_lot_id: Path<String>,
+) -> impl Responder {
+    match service.get_policy(&parking_lot_id).await {
+        Ok(policy) => ApiResponse::success(
+            policy,
+            Some(StatusCode::OK.as_u16()),
+            Some("キャンセルポリシーを取得しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（cancellation_policy_controller.rs）キャンセルポリシーの取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 駐車場のキャンセルポリシー（オーナー向け）
+#[get("/parking-lots/{parking_lot_id}/cancellation-policy")]
+#[instrument(skip(service, parking_lots_service), fields(user_id = %identity.user_id))]
+pub async fn get_owner_cancellation_policy_controller(
+    service: Data<CancellationPolicyService>,
+    parking_lots_service: Data<ParkingLotsService>,
+    identity: UserIdentity,
+    parking_lot_id: Path<String>,
+) -> impl Responder {
+    if let Err(e) = parking_lots_service.authorize_parking_lot_owner(&parking_lot_id, &identity).await {
+        warn!("（cancellation_policy_controller.rs）キャンセルポリシー参照の権限確認に失敗: {}", e);
+        return e.error_response();
+    }
+
+    match service.get_policy(&parking_lot_id).await {
+        Ok(policy) => ApiResponse::success(
+            policy,
+            Some(StatusCode::OK.as_u16()),
+            Some("キャンセルポリシーを取得しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（cancellation_policy_controller.rs）キャンセルポリシーの取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 駐車場のキャンセルポリシーの登録・更新
+///
+/// 例: `{"rules": [{"hours_before": 24, "fee_percent": 0}, {"hours_before": 1, "fee_percent": 50}]}`
+#[put("/parking-lots/{parking_lot_id}/cancellation-policy")]
+#[instrument(skip(service, parking_lots_service, req), fields(user_id = %identity.user_id))]
+pub async fn update_cancellation_policy_controller(
+    service: Data<CancellationPolicyService>,
+    parking_lots_service: Data<ParkingLotsService>,
+    identity: UserIdentity,
+    parking_lot_id: Path<String>,
+    req: Json<CancellationPolicy>,
+) -> impl Responder {
+    if let Err(e) = parking_lots_service.authorize_parking_lot_owner(&parking_lot_id, &identity).await {
+        warn!("（cancellation_policy_controller.rs）キャンセルポリシー更新の権限確認に失敗: {}", e);
+        return e.error_response();
+    }
+
+    match service.update_policy(&parking_lot_id, req.into_inner()).await {
+        Ok(policy) => ApiResponse::success(
+            policy,
+            Some(StatusCode::OK.as_u16()),
+            Some("キャンセルポリシーを更新しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（cancellation_policy_controller.rs）キャンセルポリシーの更新に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 駐車場のキャンセルポリシーの削除（利用開始まで無料に戻す）
+#[delete("/parking-lots/{parking_lot_id}/cancellation-policy")]
+#[instrument(skip(service, parking_lots_service), fields(user_id = %identity.user_id))]
+pub async fn delete_cancellation_policy_controller(
+    service: Data<CancellationPolicyService>,
+    parking_lots_service: Data<ParkingLotsService>,
+    identity: UserIdentity,
+    parking_lot_id: Path<String>,
+) -> impl Responder {
+    if let Err(e) = parking_lots_service.authorize_parking_lot_owner(&parking_lot_id, &identity).await {
+        warn!("（cancellation_policy_controller.rs）キャンセルポリシー削除の権限確認に失敗: {}", e);
+        return e.error_response();
+    }
+
+    match service.delete_policy(&parking_lot_id).await {
+        Ok(policy) => ApiResponse::success(
+            policy,
+            Some(StatusCode::OK.as_u16()),
+            Some("キャンセルポリシーを削除しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（cancellation_policy_controller.rs）キャンセルポリシーの削除に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 現時点でキャンセルした場合のキャンセル料の試算
+#[get("/{reservation_id}/cancellation-fee")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn get_cancellation_fee_controller(
+    service: Data<CancellationPolicyService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+) -> impl Responder {
+    match service.quote(&identity, &reservation_id).await {
+        Ok(quote) => ApiResponse::success(
+            quote,
+            Some(StatusCode::OK.as_u16()),
+            Some("キャンセル料を試算しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（cancellation_policy_controller.rs）キャンセル料の試算に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 予約のキャンセル（キャンセル料を差し引いて自動返金）
+#[post("/{reservation_id}/cancel")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn cancel_reservation_controller(
+    service: Data<CancellationPolicyService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+    req: Option<Json<CancelReservationRequest>>,
+) -> impl Responder {
+    let req = req.map(Json::into_inner).unwrap_or_default();
+    match service.cancel_reservation(&identity, &reservation_id, req).await {
+        Ok(result) => ApiResponse::success(
+            result,
+            Some(StatusCode::OK.as_u16()),
+            Some("予約をキャンセルしました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（cancellation_policy_controller.rs）予約のキャンセルに失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
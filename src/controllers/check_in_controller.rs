@@ -0,0 +1,97 @@
+use actix_web::{
+    get,
+    http::StatusCode,
+    post,
+    web::{Data, Json, Path},
+    HttpResponse, Responder, ResponseError,
+};
+use tracing::{instrument, warn};
+
+use crate::{
+    controllers::ApiResponse,
+    middlewares::identity_middleware::UserIdentity,
+    models::check_in_model::{CheckInResponse, LocationCheckInRequest, ScanCheckInRequest},
+    services::{CheckInService, ParkingLotsService},
+};
+
+/// 入出庫用QRコードの発行（予約した本人のみ、有効期限が切れる前に取得し直す）
+#[get("/{reservation_id}/check-in-code")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn get_check_in_code_controller(
+    service: Data<CheckInService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+) -> impl Responder {
+    match service.issue_code(&identity, &reservation_id).await {
+        Ok(code) => ApiResponse::success(
+            code,
+            Some(StatusCode::OK.as_u16()),
+            Some("QRコードを発行しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（check_in_controller.rs）QRコードの発行に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 位置情報による入出庫
+///
+/// 例: `{"action": "check_in", "latitude": 35.681236, "longitude": 139.767125, "accuracy_meters": 20}`
+#[post("/{reservation_id}/location-check")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn location_check_in_controller(
+    service: Data<CheckInService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+    req: Json<LocationCheckInRequest>,
+) -> impl Responder {
+    match service
+        .check_by_location(&identity, &reservation_id, req.into_inner(), None)
+        .await
+    {
+        Ok(result) => check_in_response(result),
+        Err(e) => {
+            warn!("（check_in_controller.rs）位置情報による入出庫に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 駐車場のスキャナーでのQRコード読み取り（駐車場のオーナーのみ）
+///
+/// 例: `{"code": "...", "action": "check_out"}`（actionを省略した場合は入庫前なら入庫、入庫後なら出庫）
+#[post("/parking-lots/{parking_lot_id}/check-in-scan")]
+#[instrument(skip(service, parking_lots_service, req), fields(user_id = %identity.user_id))]
+pub async fn scan_check_in_code_controller(
+    service: Data<CheckInService>,
+    parking_lots_service: Data<ParkingLotsService>,
+    identity: UserIdentity,
+    parking_lot_id: Path<String>,
+    req: Json<ScanCheckInRequest>,
+) -> impl Responder {
+    if let Err(e) = parking_lots_service.authorize_parking_lot_owner(&parking_lot_id, &identity).await {
+        warn!("（check_in_controller.rs）QRコード読み取りの権限確認に失敗: {}", e);
+        return e.error_response();
+    }
+
+    match service.scan(&parking_lot_id, req.into_inner()).await {
+        Ok(result) => check_in_response(result),
+        Err(e) => {
+            warn!("（check_in_controller.rs）QRコードによる入出庫に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 入出庫の結果（受け付けなかった場合は422で理由を返す）
+pub fn check_in_response(result: CheckInResponse) -> HttpResponse {
+    let code = if result.accepted {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+    let message = result.message.clone();
+    ApiResponse::success(result, Some(code.as_u16()), Some(&message), None)
+}
@@ -30,9 +30,21 @@ pub mod auth_signup_controller;
 pub mod parking_search_controller;
 pub mod parking_lots_controller;
 pub mod parking_lots_image_controller;
+pub mod blob_file_controller;
 
 pub mod user_home_controller;
 pub mod use_history_controller;
+pub mod profile_controller;
+pub mod account_controller;
+pub mod vehicle_controller;
+pub mod station_controller;
+pub mod saved_search_controller;
+pub mod notification_controller;
+pub mod owner_analytics_controller;
+pub mod payment_controller;
+pub mod cancellation_policy_controller;
+pub mod receipt_controller;
+pub mod check_in_controller;
 
 /// Initialize controllers if needed
 pub fn init() {
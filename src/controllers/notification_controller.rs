@@ -0,0 +1,75 @@
+use actix_web::{get, http::StatusCode, put, web::{Data, Path, Query}, Responder, ResponseError};
+use tracing::{instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    controllers::ApiResponse,
+    middlewares::identity_middleware::UserIdentity,
+    models::notification_model::NotificationListQuery,
+    services::NotificationService,
+};
+
+/// 通知一覧
+#[get("")]
+#[instrument(skip(service, query), fields(user_id = %identity.user_id))]
+pub async fn list_notifications_controller(
+    service: Data<NotificationService>,
+    identity: UserIdentity,
+    query: Query<NotificationListQuery>,
+) -> impl Responder {
+    match service.list_notifications(&identity, query.into_inner()).await {
+        Ok(result) => ApiResponse::success(
+            result,
+            Some(StatusCode::OK.as_u16()),
+            Some("通知の取得に成功しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（notification_controller.rs）通知一覧の取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// すべての通知を既読にする
+#[put("/read")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn mark_all_notifications_read_controller(
+    service: Data<NotificationService>,
+    identity: UserIdentity,
+) -> impl Responder {
+    match service.mark_all_read(&identity).await {
+        Ok(updated_count) => ApiResponse::success(
+            serde_json::json!({ "updated_count": updated_count }),
+            Some(StatusCode::OK.as_u16()),
+            Some("すべての通知を既読にしました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（notification_controller.rs）通知の一括既読に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 通知を既読にする
+#[put("/{notification_id}/read")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn mark_notification_read_controller(
+    service: Data<NotificationService>,
+    identity: UserIdentity,
+    notification_id: Path<Uuid>,
+) -> impl Responder {
+    match service.mark_read(&identity, notification_id.into_inner()).await {
+        Ok(()) => ApiResponse::success(
+            (),
+            Some(StatusCode::OK.as_u16()),
+            Some("通知を既読にしました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（notification_controller.rs）通知の既読に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use actix_web::{
+    get,
+    http::{header::ContentDisposition, StatusCode},
+    web::{Data, Query},
+    HttpResponse, Responder, ResponseError,
+};
+use tracing::{instrument, warn};
+
+use crate::{
+    controllers::ApiResponse,
+    middlewares::identity_middleware::UserIdentity,
+    models::owner_analytics_model::OwnerAnalyticsQuery,
+    services::OwnerAnalyticsService,
+};
+
+/// 駐車場ごとの売上・稼働分析
+///
+/// `GET /api/owner/analytics?from=2026-03-01&to=2026-03-31&granularity=week&parking_lot_id=...`
+#[get("/analytics")]
+#[instrument(skip(service, query), fields(user_id = %identity.user_id))]
+pub async fn get_owner_analytics_controller(
+    service: Data<OwnerAnalyticsService>,
+    identity: UserIdentity,
+    query: Query<OwnerAnalyticsQuery>,
+) -> impl Responder {
+    match service.get_analytics(&identity, &query).await {
+        Ok(analytics) => ApiResponse::success(
+            analytics,
+            Some(StatusCode::OK.as_u16()),
+            Some("売上・稼働分析を取得しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（owner_analytics_controller.rs）売上・稼働分析の取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 売上・稼働分析のCSV出力（駐車場・期間ごと）
+#[get("/analytics/export")]
+#[instrument(skip(service, query), fields(user_id = %identity.user_id))]
+pub async fn export_owner_analytics_controller(
+    service: Data<OwnerAnalyticsService>,
+    identity: UserIdentity,
+    query: Query<OwnerAnalyticsQuery>,
+) -> impl Responder {
+    match service.get_analytics(&identity, &query).await {
+        Ok(analytics) => {
+            let filename = format!("parking_analytics_{}_{}.csv", analytics.from, analytics.to);
+            HttpResponse::Ok()
+                .content_type("text/csv; charset=utf-8")
+                .insert_header(ContentDisposition::attachment(filename))
+                .body(analytics.to_csv())
+        }
+        Err(e) => {
+            warn!("（owner_analytics_controller.rs）売上・稼働分析のCSV出力に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
@@ -10,7 +10,7 @@ pub async fn add_parking_space_controller(
     // req: web::Json<Value>, // ここを serde_json::Value に変更
     service: Data<ParkingLotsService>,
 ) -> impl Responder {
-    let request_data = req.into_inner();
+    let mut request_data = req.into_inner();
     // let request_data: ParkingLotRequest = match parse_json_with_error_log(req.into_inner()) {
     //     Ok(data) => data,
     //     Err(resp) => return resp,  // 解析失败，直接返回 400 响应
@@ -20,6 +20,18 @@ pub async fn add_parking_space_controller(
         request_data.parking_lot_name, request_data.owner_id
     );
 
+    // 郵便番号による都道府県・市区町村の検証と補完
+    if let Err(e) = service.fill_address(&mut request_data).await {
+        warn!("駐車場登録の住所検証に失敗: {}", e);
+        return e.error_response();
+    }
+
+    // 最寄り駅を駅マスタに紐付け
+    if let Err(e) = service.link_nearest_station(&mut request_data) {
+        warn!("駐車場登録の最寄り駅検証に失敗: {}", e);
+        return e.error_response();
+    }
+
     if let Err(validation_error) = request_data.validate() {
         warn!("駐車場登録の入力検証に失敗: {}", validation_error);
         return ApiError::ValidationError(validation_error).error_response();
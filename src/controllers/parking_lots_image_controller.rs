@@ -1,28 +1,32 @@
-use actix_web::{http::StatusCode, post, web::{ Data}, Responder, ResponseError};
-use actix_multipart::Multipart;
+use actix_web::{delete, get, http::StatusCode, patch, post, put, web::{Data, Json, Path}, Responder, ResponseError};
+use actix_multipart::{Field, Multipart};
 use futures_util::TryStreamExt;
-use std::{fs, io::Write};
-use uuid::Uuid;
 use tracing::{debug, error, instrument, warn};
 
 use crate::{
     controllers::{ApiError, ApiResponse},
-    models::ParkingImageRequest,
+    middlewares::identity_middleware::UserIdentity,
+    models::parking_lots_model::{ParkingImageOrderRequest, ParkingImageUpdateRequest},
     services::ParkingLotsService,
 };
 
+/// parking_lot_idフィールドの最大バイト数
+const MAX_ID_FIELD_SIZE: usize = 64;
+
 #[post("/parking-lot-images/upload")]
-#[instrument(skip(service, payload))]
+#[instrument(skip(service, payload), fields(user_id = %identity.user_id))]
 pub async fn upload_parking_lot_images_controller(
     service: Data<ParkingLotsService>,
+    identity: UserIdentity,
     mut payload: Multipart,
 ) -> impl Responder {
     debug!("（parking_lots_image_controller.rs）画像アップロードリクエストを受信");
 
+    let config = service.image_config().clone();
     let mut parking_lot_id: Option<String> = None;
-    let mut image_records: Vec<ParkingImageRequest> = vec![];
+    let mut uploads: Vec<Vec<u8>> = vec![];
 
-    // 1. 解析 multipart 字段
+    // 1. Multipartフィールドを解析
     while let Some(mut field) = match payload.try_next().await {
         Ok(Some(f)) => Some(f),
         Ok(None) => None,
@@ -31,95 +35,219 @@ pub async fn upload_parking_lot_images_controller(
             return ApiError::BadRequest("リクエスト形式が正しくありません".to_string()).error_response();
         }
     } {
-        let content_disposition = field.content_disposition();
-        let field_name = match content_disposition.and_then(|cd| cd.get_name()) {
-            Some(name) => name,
+        let field_name = match field.content_disposition().and_then(|cd| cd.get_name()) {
+            Some(name) => name.to_string(),
             None => {
                 warn!("（parking_lots_image_controller.rs）フィールド名なしのMultipartフィールドをスキップ");
                 continue;
             }
         };
-        debug!("parking_lots_image_controller.rs）multipart field name: {}", field_name);
+        debug!("（parking_lots_image_controller.rs）multipart field name: {}", field_name);
 
         if field_name == "parking_lot_id" {
-            // 读取 parking_lot_id
-            let mut id_bytes = Vec::new();
-            while let Some(chunk) = field.try_next().await.unwrap_or(None) {
-                id_bytes.extend_from_slice(&chunk);
+            let id_bytes = match read_field_limited(&mut field, MAX_ID_FIELD_SIZE).await {
+                Ok(bytes) => bytes,
+                Err(e) => return e.error_response(),
+            };
+            let lot_id = match String::from_utf8(id_bytes) {
+                Ok(id) if !id.trim().is_empty() => id.trim().to_string(),
+                _ => {
+                    return ApiError::BadRequest("parking_lot_id が正しくありません".to_string()).error_response();
+                }
+            };
+
+            // 2. 駐車場の存在とオーナー権限を画像受信前に確認
+            if let Err(e) = service.authorize_parking_lot_owner(&lot_id, &identity).await {
+                warn!("（parking_lots_image_controller.rs）画像アップロードの権限確認に失敗: {}", e);
+                return e.error_response();
             }
-            parking_lot_id = Some(String::from_utf8(id_bytes).unwrap_or_default());
+            parking_lot_id = Some(lot_id);
 
         } else if field_name == "images" {
-            // 2. 先确保有 parking_lot_id
-            let lot_id = match &parking_lot_id {
-                Some(id) => id,
-                None => {
-                    warn!("parking_lots_image_controller.rs）parking_lot_id が存在しません");
-                    return ApiError::BadRequest("parking_lot_id が必要です".to_string()).error_response();
-                }
-            };
+            if parking_lot_id.is_none() {
+                warn!("（parking_lots_image_controller.rs）parking_lot_id が存在しません");
+                return ApiError::BadRequest("parking_lot_id が必要です".to_string()).error_response();
+            }
 
-            let base_dir = format!("./uploadfolder/parking_lot_images/{}", lot_id);
-            if let Err(e) = fs::create_dir_all(&base_dir) {
-                error!("parking_lots_image_controller.rs）ディレクトリ作成失敗: {}", e);
-                return ApiError::InternalServerError.error_response();
+            if uploads.len() >= config.max_files_per_request {
+                return ApiError::ValidationError(format!(
+                    "一度にアップロードできる画像は{}枚までです",
+                    config.max_files_per_request
+                ))
+                .error_response();
             }
 
-            let filename = content_disposition
-                .and_then(|cd| cd.get_filename())
-                .map(sanitize_filename::sanitize)
-                .unwrap_or_else(|| format!("upload_{}.jpg", Uuid::new_v4()));
-
-            let filepath = format!("{}/{}", base_dir, filename);
-
-            // 3. 文件写入
-            match fs::File::create(&filepath) {
-                Ok(mut file) => {
-                    while let Some(chunk) = field.try_next().await.unwrap_or(None) {
-                        if let Err(e) = file.write_all(&chunk) {
-                            error!("parking_lots_image_controller.rs）画像の書き込みに失敗: {}", e);
-                            return ApiError::InternalServerError.error_response();
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("parking_lots_image_controller.rs）画像ファイルの作成に失敗: {}", e);
-                    return ApiError::InternalServerError.error_response();
+            // 3. 上限サイズまで読み込み（形式はサービス側でマジックバイトにより判定）
+            match read_field_limited(&mut field, config.max_file_size).await {
+                Ok(bytes) if bytes.is_empty() => {
+                    warn!("（parking_lots_image_controller.rs）空の画像ファイルをスキップ");
                 }
+                Ok(bytes) => uploads.push(bytes),
+                Err(e) => return e.error_response(),
             }
-
-            image_records.push(ParkingImageRequest {
-                parking_lot_id: lot_id.clone(),
-                image_url: format!("uploadfolder/parking_lot_images/{}/{}", lot_id, filename),
-            });
         }
     }
 
-    if parking_lot_id.is_none() {
+    let Some(lot_id) = parking_lot_id else {
         return ApiError::BadRequest("parking_lot_id が指定されていません".to_string()).error_response();
-    }
+    };
 
-    if image_records.is_empty() {
+    if uploads.is_empty() {
         return ApiError::BadRequest("画像ファイルがありません".to_string()).error_response();
     }
 
-    // 4. 调用 service 保存记录
-    match service.upload_parking_lot_images(&image_records).await {
-        Ok(_) => {
-            debug!("parking_lots_image_controller.rs）画像アップロード完了 - 枚数: {}", image_records.len());
+    // 4. 画像の変換・保存とDB登録
+    match service.store_parking_lot_images(&lot_id, uploads).await {
+        Ok(result) => {
+            debug!("（parking_lots_image_controller.rs）画像アップロード完了 - 枚数: {}", result.uploaded);
             ApiResponse::success(
-                serde_json::json!({
-                    "uploaded": image_records.len(),
-                    "images": image_records.iter().map(|i| i.image_url.clone()).collect::<Vec<_>>(),
-                }),
+                result,
                 Some(StatusCode::OK.as_u16()),
                 Some("画像アップロードに成功しました"),
                 None,
             )
         }
         Err(e) => {
-            error!("parking_lots_image_controller.rs）DBへの保存に失敗: {}", e);
-            ApiError::InternalServerError.error_response()
+            error!("（parking_lots_image_controller.rs）画像の保存に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 駐車場画像一覧（表示順、サムネイルURL付き）
+#[get("/details/{parking_lot_id}/images")]
+#[instrument(skip(service))]
+pub async fn get_parking_lot_images_controller(
+    service: Data<ParkingLotsService>,
+    parking_lot_id: Path<String>,
+) -> impl Responder {
+    let parking_lot_id = parking_lot_id.into_inner();
+
+    match service.list_parking_lot_images(&parking_lot_id).await {
+        Ok(images) => ApiResponse::success(
+            images,
+            Some(StatusCode::OK.as_u16()),
+            Some("駐車場画像の取得に成功しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（parking_lots_image_controller.rs）駐車場画像の取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 駐車場画像の削除（ファイルも削除）
+#[delete("/parking-lots/{parking_lot_id}/images/{image_id}")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn delete_parking_lot_image_controller(
+    service: Data<ParkingLotsService>,
+    identity: UserIdentity,
+    path: Path<(String, i32)>,
+) -> impl Responder {
+    let (parking_lot_id, image_id) = path.into_inner();
+
+    if let Err(e) = service.authorize_parking_lot_owner(&parking_lot_id, &identity).await {
+        warn!("（parking_lots_image_controller.rs）画像削除の権限確認に失敗: {}", e);
+        return e.error_response();
+    }
+
+    match service.delete_parking_lot_image(&parking_lot_id, image_id).await {
+        Ok(images) => ApiResponse::success(
+            images,
+            Some(StatusCode::OK.as_u16()),
+            Some("駐車場画像を削除しました"),
+            None,
+        ),
+        Err(e) => {
+            error!("（parking_lots_image_controller.rs）駐車場画像の削除に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 駐車場画像の並び替え
+#[put("/parking-lots/{parking_lot_id}/images/order")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn reorder_parking_lot_images_controller(
+    service: Data<ParkingLotsService>,
+    identity: UserIdentity,
+    parking_lot_id: Path<String>,
+    req: Json<ParkingImageOrderRequest>,
+) -> impl Responder {
+    let parking_lot_id = parking_lot_id.into_inner();
+
+    if let Err(e) = service.authorize_parking_lot_owner(&parking_lot_id, &identity).await {
+        warn!("（parking_lots_image_controller.rs）画像並び替えの権限確認に失敗: {}", e);
+        return e.error_response();
+    }
+
+    match service.reorder_parking_lot_images(&parking_lot_id, &req).await {
+        Ok(images) => ApiResponse::success(
+            images,
+            Some(StatusCode::OK.as_u16()),
+            Some("駐車場画像の表示順を更新しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（parking_lots_image_controller.rs）駐車場画像の並び替えに失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 駐車場画像の説明文・カバー設定の更新
+#[patch("/parking-lots/{parking_lot_id}/images/{image_id}")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn update_parking_lot_image_controller(
+    service: Data<ParkingLotsService>,
+    identity: UserIdentity,
+    path: Path<(String, i32)>,
+    req: Json<ParkingImageUpdateRequest>,
+) -> impl Responder {
+    let (parking_lot_id, image_id) = path.into_inner();
+
+    if let Err(e) = service.authorize_parking_lot_owner(&parking_lot_id, &identity).await {
+        warn!("（parking_lots_image_controller.rs）画像更新の権限確認に失敗: {}", e);
+        return e.error_response();
+    }
+
+    match service.update_parking_lot_image(&parking_lot_id, image_id, &req).await {
+        Ok(image) => ApiResponse::success(
+            image,
+            Some(StatusCode::OK.as_u16()),
+            Some("駐車場画像を更新しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（parking_lots_image_controller.rs）駐車場画像の更新に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// Multipartフィールドを上限バイト数まで読み込む
+///
+/// 読み取りエラーは握りつぶさずBadRequest、上限超過はPayloadTooLargeとして返す
+pub(crate) async fn read_field_limited(field: &mut Field, limit: usize) -> Result<Vec<u8>, ApiError> {
+    let mut bytes = Vec::new();
+    loop {
+        match field.try_next().await {
+            Ok(Some(chunk)) => {
+                if bytes.len() + chunk.len() > limit {
+                    warn!("（parking_lots_image_controller.rs）フィールドサイズが上限を超過: 上限={}バイト", limit);
+                    return Err(ApiError::PayloadTooLarge(format!(
+                        "ファイルサイズは{}KBまでです",
+                        limit / 1024
+                    )));
+                }
+                bytes.extend_from_slice(&chunk);
+            }
+            Ok(None) => return Ok(bytes),
+            Err(e) => {
+                error!("（parking_lots_image_controller.rs）Multipartフィールドの読み取りに失敗: {}", e);
+                return Err(ApiError::BadRequest("ファイルの読み取りに失敗しました".to_string()));
+            }
         }
     }
 }
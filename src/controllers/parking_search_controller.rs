@@ -1,15 +1,18 @@
 use actix_web::http::StatusCode;
 use actix_web::{
-    HttpRequest, Responder, ResponseError, post, get,
+    HttpRequest, Responder, ResponseError, post, get, delete, put,
     web::{Data, Json, Query, Path},
 };
 use tracing::{debug, error, instrument, warn, info};
 
 use crate::controllers::api_error::ApiError;
 use crate::controllers::api_response::ApiResponse;
+use crate::middlewares::identity_middleware::UserIdentity;
 use crate::models::parking_search_model::{
-    ParkingSearchRequest, FavoriteOperationRequest,
+    ParkingSearchRequest, FavoriteOperationRequest, FavoriteUpdateRequest, FavoriteOrderRequest,
+    SearchFilterCatalogueQuery, NearbyParkingQuery, ParkingStatsQuery, MapViewportQuery, PageQuery,
 };
+use crate::models::parking_search_history_model::SearchHistoryRerunQuery;
 use crate::services::{ParkingSearchService, parking_search_service::ParkingSearchFilters};
 
 /// 駐車場検索エンドポイント
@@ -31,10 +34,11 @@ use crate::services::{ParkingSearchService, parking_search_service::ParkingSearc
 /// - `422 Unprocessable Entity`: 検証エラー
 /// - `500 Internal Server Error`: サーバー処理エラー
 #[post("/search")]
-#[instrument(skip(service), fields(latitude = ?req.latitude, longitude = ?req.longitude))]
+#[instrument(skip(service, identity), fields(latitude = ?req.latitude, longitude = ?req.longitude))]
 pub async fn search_parking_lots_controller(
     req: Json<ParkingSearchRequest>,
     service: Data<ParkingSearchService>,
+    identity: Option<UserIdentity>,
     http_req: HttpRequest,
 ) -> impl Responder {
     let request_data = req.into_inner();
@@ -53,7 +57,16 @@ pub async fn search_parking_lots_controller(
     }
 
     // 認証されたユーザーIDの取得（オプション）
-    let user_id = extract_user_id_from_request(&http_req);
+    let user_id = match identity {
+        Some(identity) => match service.find_user_id(&identity).await {
+            Ok(user_id) => user_id,
+            Err(api_err) => {
+                warn!("駐車場検索でユーザーの取得に失敗 - IP: {}: {}", client_ip, api_err);
+                return api_err.error_response();
+            }
+        },
+        None => None,
+    };
 
     // 駐車場検索処理の実行
     match service.search_parking_lots(request_data, user_id).await {
@@ -108,10 +121,11 @@ pub async fn search_parking_lots_controller(
 /// - `403 Forbidden`: アクセス権限なし
 /// - `500 Internal Server Error`: サーバー処理エラー
 #[get("/favorites")]
-#[instrument(skip(service))]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
 pub async fn get_favorite_parking_lots_controller(
     service: Data<ParkingSearchService>,
     query: Query<ParkingSearchFilters>,
+    identity: UserIdentity,
     http_req: HttpRequest,
 ) -> impl Responder {
     let filters = query.into_inner();
@@ -121,13 +135,11 @@ pub async fn get_favorite_parking_lots_controller(
     debug!("お気に入り駐車場検索リクエストを受信 - IP: {}", client_ip);
 
     // 認証されたユーザーIDの取得（必須）
-    let user_id = match extract_user_id_from_request(&http_req) {
-        Some(uid) => uid,
-        None => {
-            warn!("お気に入り検索で認証情報が不足 - IP: {}", client_ip);
-            return ApiError::AuthenticationError(
-                "お気に入り検索には認証が必要です".to_string()
-            ).error_response();
+    let user_id = match service.load_user_id(&identity).await {
+        Ok(uid) => uid,
+        Err(api_err) => {
+            warn!("お気に入り検索でユーザーの取得に失敗 - IP: {}: {}", client_ip, api_err);
+            return api_err.error_response();
         }
     };
 
@@ -185,10 +197,11 @@ pub async fn get_favorite_parking_lots_controller(
 /// - `404 Not Found`: 指定された駐車場が見つからない
 /// - `500 Internal Server Error`: サーバー処理エラー
 #[post("/favorites")]
-#[instrument(skip(service), fields(parking_lot_id = %req.parking_lot_id, operation = %req.operation))]
+#[instrument(skip(service), fields(parking_lot_id = %req.parking_lot_id, operation = %req.operation, user_id = %identity.user_id))]
 pub async fn manage_favorite_parking_lot_controller(
     service: Data<ParkingSearchService>,
     req: Json<FavoriteOperationRequest>,
+    identity: UserIdentity,
     http_req: HttpRequest,
 ) -> impl Responder {
     let request_data = req.into_inner();
@@ -207,13 +220,11 @@ pub async fn manage_favorite_parking_lot_controller(
     }
 
     // 認証されたユーザーIDの取得（必須）
-    let user_id = match extract_user_id_from_request(&http_req) {
-        Some(uid) => uid,
-        None => {
-            warn!("お気に入り操作で認証情報が不足 - IP: {}", client_ip);
-            return ApiError::AuthenticationError(
-                "お気に入り操作には認証が必要です".to_string()
-            ).error_response();
+    let user_id = match service.load_user_id(&identity).await {
+        Ok(uid) => uid,
+        Err(api_err) => {
+            warn!("お気に入り操作でユーザーの取得に失敗 - IP: {}: {}", client_ip, api_err);
+            return api_err.error_response();
         }
     };
 
@@ -263,6 +274,227 @@ pub async fn manage_favorite_parking_lot_controller(
     }
 }
 
+/// お気に入りの並べ替えエンドポイント
+///
+/// お気に入り全件の駐車場IDを表示順に指定します。
+///
+/// # エンドポイント
+/// `PUT /api/parking/favorites/order`
+#[put("/favorites/order")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn reorder_favorites_controller(
+    service: Data<ParkingSearchService>,
+    req: Json<FavoriteOrderRequest>,
+    identity: UserIdentity,
+) -> impl Responder {
+    let user_id = match service.load_user_id(&identity).await {
+        Ok(user_id) => user_id,
+        Err(api_err) => {
+            warn!("お気に入りの並べ替えでユーザーの取得に失敗: {}", api_err);
+            return api_err.error_response();
+        }
+    };
+
+    match service.reorder_favorites(&user_id, req.into_inner()).await {
+        Ok(parking_lot_ids) => ApiResponse::success(
+            serde_json::json!({ "parking_lot_ids": parking_lot_ids }),
+            Some(StatusCode::OK.as_u16()),
+            Some("お気に入りを並べ替えました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("お気に入りの並べ替えに失敗: {}", api_err);
+            api_err.error_response()
+        }
+    }
+}
+
+/// お気に入りラベル一覧エンドポイント
+///
+/// # エンドポイント
+/// `GET /api/parking/favorites/labels`
+#[get("/favorites/labels")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn get_favorite_labels_controller(
+    service: Data<ParkingSearchService>,
+    identity: UserIdentity,
+) -> impl Responder {
+    let user_id = match service.load_user_id(&identity).await {
+        Ok(user_id) => user_id,
+        Err(api_err) => {
+            warn!("お気に入りラベル取得でユーザーの取得に失敗: {}", api_err);
+            return api_err.error_response();
+        }
+    };
+
+    match service.get_favorite_labels(&user_id).await {
+        Ok(labels) => ApiResponse::success(
+            labels,
+            Some(StatusCode::OK.as_u16()),
+            Some("お気に入りラベルの取得に成功しました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("お気に入りラベルの取得に失敗: {}", api_err);
+            api_err.error_response()
+        }
+    }
+}
+
+/// お気に入りのメモ・ラベル更新エンドポイント
+///
+/// # エンドポイント
+/// `PUT /api/parking/favorites/{parking_lot_id}`
+///
+/// # レスポンス
+/// - `200 OK`: 更新成功、お気に入り情報を返す
+/// - `404 Not Found`: 指定された駐車場がお気に入りに登録されていない
+#[put("/favorites/{parking_lot_id}")]
+#[instrument(skip(service, req), fields(parking_lot_id = %parking_lot_id, user_id = %identity.user_id))]
+pub async fn update_favorite_controller(
+    service: Data<ParkingSearchService>,
+    parking_lot_id: Path<String>,
+    req: Json<FavoriteUpdateRequest>,
+    identity: UserIdentity,
+) -> impl Responder {
+    let user_id = match service.load_user_id(&identity).await {
+        Ok(user_id) => user_id,
+        Err(api_err) => {
+            warn!("お気に入りの更新でユーザーの取得に失敗: {}", api_err);
+            return api_err.error_response();
+        }
+    };
+
+    match service.update_favorite(&user_id, &parking_lot_id, req.into_inner()).await {
+        Ok(favorite_info) => ApiResponse::success(
+            favorite_info,
+            Some(StatusCode::OK.as_u16()),
+            Some("お気に入りを更新しました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("お気に入りの更新に失敗 - 駐車場ID: {}: {}", parking_lot_id, api_err);
+            api_err.error_response()
+        }
+    }
+}
+
+/// 検索フィルター一覧エンドポイント
+///
+/// 車種・設備・貸出タイプ・料金帯の選択肢と該当駐車場数を返します。
+///
+/// # エンドポイント
+/// `GET /api/parking/filters?latitude=&longitude=&radius_km=`
+///
+/// # レスポンス
+/// - `200 OK`: 取得成功、フィルター一覧を返す
+/// - `400 Bad Request`: 無効な地点・検索半径
+#[get("/filters")]
+#[instrument(skip(service))]
+pub async fn get_search_filters_controller(
+    service: Data<ParkingSearchService>,
+    query: Query<SearchFilterCatalogueQuery>,
+) -> impl Responder {
+    match service.get_filter_catalogue(query.into_inner()).await {
+        Ok(catalogue) => ApiResponse::success(
+            catalogue,
+            Some(StatusCode::OK.as_u16()),
+            Some("検索フィルターの取得に成功しました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("検索フィルターの取得に失敗: {}", api_err);
+            api_err.error_response()
+        }
+    }
+}
+
+/// 近隣駐車場検索エンドポイント
+///
+/// 地図のピン表示用に、指定地点から近い順に駐車場の位置・空き状況・料金を返します。
+///
+/// # エンドポイント
+/// `GET /api/parking/nearby?latitude=&longitude=&radius_km=&limit=`
+///
+/// # レスポンス
+/// - `200 OK`: 検索成功、駐車場ピンのリストを返す
+/// - `400 Bad Request`: 無効な地点・検索半径・件数
+#[get("/nearby")]
+#[instrument(skip(service))]
+pub async fn get_nearby_parking_lots_controller(
+    service: Data<ParkingSearchService>,
+    query: Query<NearbyParkingQuery>,
+) -> impl Responder {
+    match service.get_nearby_parking_lots(query.into_inner()).await {
+        Ok(response) => ApiResponse::success(
+            response,
+            Some(StatusCode::OK.as_u16()),
+            Some("近隣駐車場検索が正常に完了しました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("近隣駐車場検索に失敗: {}", api_err);
+            api_err.error_response()
+        }
+    }
+}
+
+/// 地図表示範囲検索エンドポイント
+///
+/// 表示範囲内の駐車場を、ズームレベルに応じてピンまたはクラスタで返します。
+///
+/// # エンドポイント
+/// `GET /api/parking/map?min_lat=&max_lat=&min_lng=&max_lng=&zoom=`
+///
+/// # レスポンス
+/// - `200 OK`: 検索成功、ピンまたはクラスタを返す
+/// - `400 Bad Request`: 無効な表示範囲・ズームレベル
+#[get("/map")]
+#[instrument(skip(service))]
+pub async fn search_map_viewport_controller(
+    service: Data<ParkingSearchService>,
+    query: Query<MapViewportQuery>,
+) -> impl Responder {
+    match service.search_map_viewport(query.into_inner()).await {
+        Ok(response) => ApiResponse::success(
+            response,
+            Some(StatusCode::OK.as_u16()),
+            Some("地図検索が正常に完了しました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("地図検索に失敗: {}", api_err);
+            api_err.error_response()
+        }
+    }
+}
+
+/// 駐車場統計エンドポイント
+///
+/// 都道府県・市区町村別の駐車場数、平均料金、現在の稼働率を返します。
+///
+/// # エンドポイント
+/// `GET /api/parking/stats?group_by=prefecture|city&prefecture=`
+#[get("/stats")]
+#[instrument(skip(service))]
+pub async fn get_parking_stats_controller(
+    service: Data<ParkingSearchService>,
+    query: Query<ParkingStatsQuery>,
+) -> impl Responder {
+    match service.get_parking_stats(query.into_inner()).await {
+        Ok(response) => ApiResponse::success(
+            response,
+            Some(StatusCode::OK.as_u16()),
+            Some("駐車場統計の取得に成功しました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("駐車場統計の取得に失敗: {}", api_err);
+            api_err.error_response()
+        }
+    }
+}
+
 /// 駐車場詳細取得エンドポイント
 ///
 /// 指定された駐車場の詳細情報を取得します。
@@ -279,10 +511,11 @@ pub async fn manage_favorite_parking_lot_controller(
 /// - `404 Not Found`: 指定された駐車場が見つからない
 /// - `500 Internal Server Error`: サーバー処理エラー
 #[get("/details/{parking_lot_id}")]
-#[instrument(skip(service), fields(parking_lot_id = %parking_lot_id))]
+#[instrument(skip(service, identity), fields(parking_lot_id = %parking_lot_id))]
 pub async fn get_parking_lot_detail_controller(
     service: Data<ParkingSearchService>,
     parking_lot_id: Path<String>,
+    identity: Option<UserIdentity>,
     http_req: HttpRequest,
 ) -> impl Responder {
     let parking_lot_id = parking_lot_id.into_inner();
@@ -292,7 +525,16 @@ pub async fn get_parking_lot_detail_controller(
     debug!("駐車場詳細取得リクエストを受信 - 駐車場ID: {}, IP: {}", parking_lot_id, client_ip);
 
     // 認証されたユーザーIDの取得（オプション）
-    let user_id = extract_user_id_from_request(&http_req);
+    let user_id = match identity {
+        Some(identity) => match service.find_user_id(&identity).await {
+            Ok(user_id) => user_id,
+            Err(api_err) => {
+                warn!("駐車場詳細取得でユーザーの取得に失敗 - IP: {}: {}", client_ip, api_err);
+                return api_err.error_response();
+            }
+        },
+        None => None,
+    };
 
     // 駐車場詳細取得処理の実行
     match service.get_parking_lot_detail(&parking_lot_id, user_id).await {
@@ -333,17 +575,145 @@ pub async fn get_parking_lot_detail_controller(
     }
 }
 
-// プライベートヘルパーメソッド
-
-/// HTTPリクエストからユーザーIDを抽出
-/// 
-/// JWTトークンから認証されたユーザーのIDを取得します。
-/// 認証が失敗した場合やトークンが無効な場合はNoneを返します。
-fn extract_user_id_from_request(req: &HttpRequest) -> Option<String> {
-    // TODO: JWTミドルウェアと連携してユーザーIDを抽出する実装
-    // 現在は仮の実装として、ヘッダーから直接取得する例を示す
-    req.headers()
-        .get("X-User-ID")
-        .and_then(|header| header.to_str().ok())
-        .map(|s| s.to_string())
+/// 検索履歴一覧エンドポイント
+///
+/// 新しい順に返します。2ページ目以降は`page`の代わりに前回レスポンスの
+/// `pagination.next_cursor`を`cursor`に指定できます。
+///
+/// # エンドポイント
+/// `GET /api/parking/search-history?page_size=20&cursor=...`
+#[get("/search-history")]
+#[instrument(skip(service, query), fields(user_id = %identity.user_id))]
+pub async fn list_search_history_controller(
+    service: Data<ParkingSearchService>,
+    query: Query<PageQuery>,
+    identity: UserIdentity,
+) -> impl Responder {
+    let user_id = match service.load_user_id(&identity).await {
+        Ok(user_id) => user_id,
+        Err(api_err) => {
+            warn!("検索履歴一覧の取得でユーザーの取得に失敗: {}", api_err);
+            return api_err.error_response();
+        }
+    };
+
+    match service.list_search_history(&user_id, query.into_inner()).await {
+        Ok(history) => ApiResponse::success(
+            history,
+            Some(StatusCode::OK.as_u16()),
+            Some("検索履歴を取得しました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("検索履歴一覧の取得に失敗: {}", api_err);
+            api_err.error_response()
+        }
+    }
+}
+
+/// 検索履歴からの再検索エンドポイント
+///
+/// 保存済みの検索条件で駐車場を再検索します。
+///
+/// # エンドポイント
+/// `POST /api/parking/search-history/{search_id}/search`
+///
+/// # レスポンス
+/// - `200 OK`: 検索成功、駐車場リストを返す
+/// - `401 Unauthorized`: 認証が必要
+/// - `404 Not Found`: 指定された検索履歴が見つからない
+#[post("/search-history/{search_id}/search")]
+#[instrument(skip(service), fields(search_id = %search_id, user_id = %identity.user_id))]
+pub async fn rerun_search_history_controller(
+    service: Data<ParkingSearchService>,
+    search_id: Path<String>,
+    query: Query<SearchHistoryRerunQuery>,
+    identity: UserIdentity,
+) -> impl Responder {
+    let user_id = match service.load_user_id(&identity).await {
+        Ok(user_id) => user_id,
+        Err(api_err) => {
+            warn!("検索履歴からの再検索でユーザーの取得に失敗: {}", api_err);
+            return api_err.error_response();
+        }
+    };
+
+    let query = query.into_inner();
+    match service.rerun_search_history(&user_id, &search_id, query.page, query.page_size, query.cursor).await {
+        Ok(search_response) => ApiResponse::success(
+            search_response,
+            Some(StatusCode::OK.as_u16()),
+            Some("駐車場検索が正常に完了しました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("検索履歴からの再検索に失敗 - 検索ID: {}: {}", search_id, api_err);
+            api_err.error_response()
+        }
+    }
+}
+
+/// 検索履歴の削除エンドポイント
+///
+/// # エンドポイント
+/// `DELETE /api/parking/search-history/{search_id}`
+#[delete("/search-history/{search_id}")]
+#[instrument(skip(service), fields(search_id = %search_id, user_id = %identity.user_id))]
+pub async fn delete_search_history_controller(
+    service: Data<ParkingSearchService>,
+    search_id: Path<String>,
+    identity: UserIdentity,
+) -> impl Responder {
+    let user_id = match service.load_user_id(&identity).await {
+        Ok(user_id) => user_id,
+        Err(api_err) => {
+            warn!("検索履歴の削除でユーザーの取得に失敗: {}", api_err);
+            return api_err.error_response();
+        }
+    };
+
+    match service.delete_search_history(&user_id, &search_id).await {
+        Ok(()) => ApiResponse::success(
+            (),
+            Some(StatusCode::OK.as_u16()),
+            Some("検索履歴を削除しました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("検索履歴の削除に失敗 - 検索ID: {}: {}", search_id, api_err);
+            api_err.error_response()
+        }
+    }
+}
+
+/// 検索履歴の全件削除エンドポイント
+///
+/// # エンドポイント
+/// `DELETE /api/parking/search-history`
+#[delete("/search-history")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn clear_search_history_controller(
+    service: Data<ParkingSearchService>,
+    identity: UserIdentity,
+) -> impl Responder {
+    let user_id = match service.load_user_id(&identity).await {
+        Ok(user_id) => user_id,
+        Err(api_err) => {
+            warn!("検索履歴の全件削除でユーザーの取得に失敗: {}", api_err);
+            return api_err.error_response();
+        }
+    };
+
+    match service.clear_search_history(&user_id).await {
+        Ok(deleted_count) => ApiResponse::success(
+            serde_json::json!({ "deleted_count": deleted_count }),
+            Some(StatusCode::OK.as_u16()),
+            Some("検索履歴をすべて削除しました"),
+            None,
+        ),
+        Err(api_err) => {
+            warn!("検索履歴の全件削除に失敗: {}", api_err);
+            api_err.error_response()
+        }
+    }
 }
@@ -0,0 +1,158 @@
+use actix_web::{
+    get,
+    http::StatusCode,
+    post,
+    web::{Bytes, Data, Json, Path},
+    HttpRequest, Responder, ResponseError,
+};
+use tracing::{instrument, warn};
+
+use crate::{
+    controllers::ApiResponse,
+    middlewares::identity_middleware::UserIdentity,
+    models::payment_model::{AuthorizePaymentRequest, RefundPaymentRequest, SimulatePaymentWebhookRequest},
+    payments::WEBHOOK_SIGNATURE_HEADER,
+    services::PaymentService,
+};
+
+/// 予約の決済情報
+#[get("/reservations/{reservation_id}")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn get_reservation_payment_controller(
+    service: Data<PaymentService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+) -> impl Responder {
+    match service.get_payment(&identity, &reservation_id).await {
+        Ok(payment) => ApiResponse::success(
+            payment,
+            Some(StatusCode::OK.as_u16()),
+            Some("決済情報を取得しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（payment_controller.rs）決済情報の取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 予約の与信（同じ予約への再送は既存の決済を返す）
+#[post("/reservations/{reservation_id}/authorize")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn authorize_reservation_payment_controller(
+    service: Data<PaymentService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+    req: Option<Json<AuthorizePaymentRequest>>,
+) -> impl Responder {
+    let req = req.map(Json::into_inner).unwrap_or_default();
+    match service.authorize_payment(&identity, &reservation_id, &req).await {
+        Ok(payment) => ApiResponse::success(
+            payment,
+            Some(StatusCode::OK.as_u16()),
+            Some("与信が完了しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（payment_controller.rs）与信に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 返金（駐車場のオーナー・管理者のみ）
+#[post("/reservations/{reservation_id}/refund")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn refund_reservation_payment_controller(
+    service: Data<PaymentService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+    req: Option<Json<RefundPaymentRequest>>,
+) -> impl Responder {
+    let req = req.map(Json::into_inner).unwrap_or_default();
+    match service.refund_payment(&identity, &reservation_id, &req).await {
+        Ok(payment) => ApiResponse::success(
+            payment,
+            Some(StatusCode::OK.as_u16()),
+            Some("返金しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（payment_controller.rs）返金に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 与信の取消
+#[post("/reservations/{reservation_id}/void")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn void_reservation_payment_controller(
+    service: Data<PaymentService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+) -> impl Responder {
+    match service.void_payment(&identity, &reservation_id).await {
+        Ok(payment) => ApiResponse::success(
+            payment,
+            Some(StatusCode::OK.as_u16()),
+            Some("与信を取消しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（payment_controller.rs）与信の取消に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 決済プロバイダーからのWebhook（認証不要、署名で検証）
+#[post("/webhook")]
+#[instrument(skip(service, request, body))]
+pub async fn payment_webhook_controller(
+    service: Data<PaymentService>,
+    request: HttpRequest,
+    body: Bytes,
+) -> impl Responder {
+    let signature = request
+        .headers()
+        .get(WEBHOOK_SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    match service.handle_webhook(&body, signature).await {
+        Ok(result) => ApiResponse::success(
+            result,
+            Some(StatusCode::OK.as_u16()),
+            Some("Webhookを受信しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（payment_controller.rs）Webhookの処理に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// Webhookの模擬送信（開発環境のテスト用決済プロバイダーのみ登録、管理者のみ）
+#[post("/simulate-webhook")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn simulate_payment_webhook_controller(
+    service: Data<PaymentService>,
+    identity: UserIdentity,
+    req: Json<SimulatePaymentWebhookRequest>,
+) -> impl Responder {
+    match service.simulate_webhook(&identity, &req).await {
+        Ok(result) => ApiResponse::success(
+            result,
+            Some(StatusCode::OK.as_u16()),
+            Some("Webhookを模擬送信しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（payment_controller.rs）Webhookの模擬送信に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use actix_multipart::Multipart;
+use actix_web::{get, http::StatusCode, post, put, web::{Data, Json}, Responder, ResponseError};
+use futures_util::TryStreamExt;
+use tracing::{debug, error, instrument, warn};
+
+use crate::{
+    controllers::{parking_lots_image_controller::read_field_limited, ApiError, ApiResponse},
+    middlewares::identity_middleware::UserIdentity,
+    models::profile_model::{ContactChangeRequest, ContactChangeVerifyRequest, ProfileUpdateRequest},
+    services::ProfileService,
+};
+
+/// プロフィール取得
+#[get("")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn get_profile_controller(service: Data<ProfileService>, identity: UserIdentity) -> impl Responder {
+    match service.get_profile(&identity).await {
+        Ok(profile) => ApiResponse::success(
+            profile,
+            Some(StatusCode::OK.as_u16()),
+            Some("プロフィールの取得に成功しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（profile_controller.rs）プロフィールの取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// プロフィール更新（氏名・カナ・生年月日・性別・郵便番号・住所）
+#[put("")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn update_profile_controller(
+    service: Data<ProfileService>,
+    identity: UserIdentity,
+    req: Json<ProfileUpdateRequest>,
+) -> impl Responder {
+    match service.update_profile(&identity, req.into_inner()).await {
+        Ok(profile) => ApiResponse::success(
+            profile,
+            Some(StatusCode::OK.as_u16()),
+            Some("プロフィールを更新しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（profile_controller.rs）プロフィールの更新に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// プロフィール写真のアップロード・差し替え（multipartの photo フィールド）
+#[post("/photo")]
+#[instrument(skip(service, payload), fields(user_id = %identity.user_id))]
+pub async fn upload_profile_photo_controller(
+    service: Data<ProfileService>,
+    identity: UserIdentity,
+    mut payload: Multipart,
+) -> impl Responder {
+    let max_file_size = service.image_config().max_file_size;
+    let mut photo: Option<Vec<u8>> = None;
+
+    while let Some(mut field) = match payload.try_next().await {
+        Ok(Some(f)) => Some(f),
+        Ok(None) => None,
+        Err(e) => {
+            error!("（profile_controller.rs）Multipartの読み取りに失敗: {}", e);
+            return ApiError::BadRequest("リクエスト形式が正しくありません".to_string()).error_response();
+        }
+    } {
+        let is_photo = field
+            .content_disposition()
+            .and_then(|cd| cd.get_name())
+            .is_some_and(|name| name == "photo");
+        if !is_photo {
+            debug!("（profile_controller.rs）photo以外のフィールドをスキップ");
+            continue;
+        }
+        if photo.is_some() {
+            return ApiError::ValidationError("プロフィール写真は1枚のみ指定してください".to_string())
+                .error_response();
+        }
+
+        match read_field_limited(&mut field, max_file_size).await {
+            Ok(bytes) if !bytes.is_empty() => photo = Some(bytes),
+            Ok(_) => {}
+            Err(e) => return e.error_response(),
+        }
+    }
+
+    let Some(photo) = photo else {
+        return ApiError::BadRequest("画像ファイルがありません".to_string()).error_response();
+    };
+
+    match service.upload_profile_photo(&identity, photo).await {
+        Ok(profile) => ApiResponse::success(
+            profile,
+            Some(StatusCode::OK.as_u16()),
+            Some("プロフィール写真を更新しました"),
+            None,
+        ),
+        Err(e) => {
+            error!("（profile_controller.rs）プロフィール写真の保存に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// メールアドレス・電話番号変更の認証コード送信
+#[post("/contact-change")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn request_contact_change_controller(
+    service: Data<ProfileService>,
+    identity: UserIdentity,
+    req: Json<ContactChangeRequest>,
+) -> impl Responder {
+    match service.request_contact_change(&identity, req.into_inner()).await {
+        Ok(result) => ApiResponse::success(
+            result,
+            Some(StatusCode::OK.as_u16()),
+            Some("認証コードを送信しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（profile_controller.rs）連絡先変更の受付に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 認証コードを確認してメールアドレス・電話番号を変更
+#[post("/contact-change/verify")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn verify_contact_change_controller(
+    service: Data<ProfileService>,
+    identity: UserIdentity,
+    req: Json<ContactChangeVerifyRequest>,
+) -> impl Responder {
+    match service.verify_contact_change(&identity, req.into_inner()).await {
+        Ok(profile) => ApiResponse::success(
+            profile,
+            Some(StatusCode::OK.as_u16()),
+            Some("連絡先を変更しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（profile_controller.rs）連絡先の変更に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
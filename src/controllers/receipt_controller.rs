@@ -0,0 +1,103 @@
+use actix_web::{
+    get,
+    http::{header::ContentDisposition, StatusCode},
+    post,
+    web::{Data, Json, Path},
+    HttpResponse, Responder, ResponseError,
+};
+use tracing::{instrument, warn};
+
+use crate::{
+    controllers::ApiResponse,
+    middlewares::identity_middleware::UserIdentity,
+    models::receipt_model::IssueReceiptRequest,
+    services::ReceiptService,
+};
+
+/// 領収書の発行（利用完了した予約のみ、発行済みの場合は既存の領収書を返す）
+#[post("/{reservation_id}/receipt")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn issue_receipt_controller(
+    service: Data<ReceiptService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+    req: Option<Json<IssueReceiptRequest>>,
+) -> impl Responder {
+    let req = req.map(Json::into_inner).unwrap_or_default();
+    match service.issue_receipt(&identity, &reservation_id, req).await {
+        Ok(receipt) => ApiResponse::success(
+            receipt,
+            Some(StatusCode::OK.as_u16()),
+            Some("領収書を発行しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（receipt_controller.rs）領収書の発行に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 発行済みの領収書
+#[get("/{reservation_id}/receipt")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn get_receipt_controller(
+    service: Data<ReceiptService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+) -> impl Responder {
+    match service.get_receipt(&identity, &reservation_id).await {
+        Ok(receipt) => ApiResponse::success(
+            receipt,
+            Some(StatusCode::OK.as_u16()),
+            Some("領収書を取得しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（receipt_controller.rs）領収書の取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 発行済みの領収書のPDFダウンロード
+#[get("/{reservation_id}/receipt/pdf")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn download_receipt_pdf_controller(
+    service: Data<ReceiptService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+) -> impl Responder {
+    match service.get_receipt(&identity, &reservation_id).await {
+        Ok(receipt) => HttpResponse::Ok()
+            .content_type("application/pdf")
+            .insert_header(ContentDisposition::attachment(receipt.pdf_filename()))
+            .body(receipt.to_pdf()),
+        Err(e) => {
+            warn!("（receipt_controller.rs）領収書のPDF出力に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 発行済みの領収書をアカウントのメールアドレスにPDF添付で送信
+#[post("/{reservation_id}/receipt/email")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn email_receipt_controller(
+    service: Data<ReceiptService>,
+    identity: UserIdentity,
+    reservation_id: Path<String>,
+) -> impl Responder {
+    match service.email_receipt(&identity, &reservation_id).await {
+        Ok(result) => ApiResponse::success(
+            result,
+            Some(StatusCode::OK.as_u16()),
+            Some("領収書をメールで送信しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（receipt_controller.rs）領収書のメール送信に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
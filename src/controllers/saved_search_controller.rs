@@ -0,0 +1,117 @@
+use actix_web::{delete, get, http::StatusCode, post, put, web::{Data, Json, Path}, Responder, ResponseError};
+use tracing::{instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    controllers::ApiResponse,
+    middlewares::identity_middleware::UserIdentity,
+    models::saved_search_model::SavedSearchRequest,
+    services::SavedSearchService,
+};
+
+/// 保存検索一覧
+#[get("")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn list_saved_searches_controller(service: Data<SavedSearchService>, identity: UserIdentity) -> impl Responder {
+    match service.list_saved_searches(&identity).await {
+        Ok(saved_searches) => ApiResponse::success(
+            saved_searches,
+            Some(StatusCode::OK.as_u16()),
+            Some("保存検索の取得に成功しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（saved_search_controller.rs）保存検索一覧の取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 保存検索の登録
+#[post("")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn create_saved_search_controller(
+    service: Data<SavedSearchService>,
+    identity: UserIdentity,
+    req: Json<SavedSearchRequest>,
+) -> impl Responder {
+    match service.create_saved_search(&identity, req.into_inner()).await {
+        Ok(saved_search) => ApiResponse::success(
+            saved_search,
+            Some(StatusCode::CREATED.as_u16()),
+            Some("検索条件を保存しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（saved_search_controller.rs）保存検索の登録に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 保存検索の取得
+#[get("/{saved_search_id}")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn get_saved_search_controller(
+    service: Data<SavedSearchService>,
+    identity: UserIdentity,
+    saved_search_id: Path<Uuid>,
+) -> impl Responder {
+    match service.get_saved_search(&identity, saved_search_id.into_inner()).await {
+        Ok(saved_search) => ApiResponse::success(
+            saved_search,
+            Some(StatusCode::OK.as_u16()),
+            Some("保存検索の取得に成功しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（saved_search_controller.rs）保存検索の取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 保存検索の更新
+#[put("/{saved_search_id}")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn update_saved_search_controller(
+    service: Data<SavedSearchService>,
+    identity: UserIdentity,
+    saved_search_id: Path<Uuid>,
+    req: Json<SavedSearchRequest>,
+) -> impl Responder {
+    match service.update_saved_search(&identity, saved_search_id.into_inner(), req.into_inner()).await {
+        Ok(saved_search) => ApiResponse::success(
+            saved_search,
+            Some(StatusCode::OK.as_u16()),
+            Some("保存検索を更新しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（saved_search_controller.rs）保存検索の更新に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 保存検索の削除
+#[delete("/{saved_search_id}")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn delete_saved_search_controller(
+    service: Data<SavedSearchService>,
+    identity: UserIdentity,
+    saved_search_id: Path<Uuid>,
+) -> impl Responder {
+    match service.delete_saved_search(&identity, saved_search_id.into_inner()).await {
+        Ok(()) => ApiResponse::success(
+            (),
+            Some(StatusCode::OK.as_u16()),
+            Some("保存検索を削除しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（saved_search_controller.rs）保存検索の削除に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use actix_web::{get, http::StatusCode, web::{Data, Path, Query}, Responder, ResponseError};
+use serde::Deserialize;
+use tracing::{instrument, warn};
+
+use crate::{
+    controllers::{ApiError, ApiResponse},
+    geocoding::{Station, StationMaster},
+};
+
+/// 駅検索の上限件数
+const MAX_STATION_RESULTS: usize = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct StationSearchQuery {
+    /// 駅名または読み（前方一致）
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+/// 駅名・読みによる駅検索（入力補完用）
+#[get("")]
+#[instrument(skip(stations))]
+pub async fn search_stations_controller(
+    stations: Data<StationMaster>,
+    query: Query<StationSearchQuery>,
+) -> impl Responder {
+    if query.q.trim().is_empty() {
+        return ApiError::ValidationError("検索する駅名を入力してください".to_string()).error_response();
+    }
+
+    let limit = query.limit.unwrap_or(10).clamp(1, MAX_STATION_RESULTS);
+    let results: Vec<Station> = stations.search(&query.q, limit).into_iter().cloned().collect();
+    ApiResponse::success(
+        results,
+        Some(StatusCode::OK.as_u16()),
+        Some("駅の検索に成功しました"),
+        None,
+    )
+}
+
+/// 駅情報の取得
+#[get("/{station_id}")]
+#[instrument(skip(stations))]
+pub async fn get_station_controller(stations: Data<StationMaster>, station_id: Path<String>) -> impl Responder {
+    match stations.find_by_id(&station_id) {
+        Some(station) => ApiResponse::success(
+            station.clone(),
+            Some(StatusCode::OK.as_u16()),
+            Some("駅情報の取得に成功しました"),
+            None,
+        ),
+        None => {
+            warn!("（station_controller.rs）駅が見つかりません: {}", station_id);
+            ApiError::NotFoundError("駅が見つかりません".to_string()).error_response()
+        }
+    }
+}
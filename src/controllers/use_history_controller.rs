@@ -1,10 +1,12 @@
-use tracing::{ error, info};
+use tracing::{ error, info, warn};
 use crate::controllers::api_response::{success_response, error_response};
+use crate::middlewares::identity_middleware::UserIdentity;
 use crate::services::UseHistoryService;
-use actix_web::{post, web, Responder};
+use actix_web::{get, post, web, Responder, ResponseError};
 use crate::models::{
      parking_use_history_model::{ParkingUseHistoryRequest, ParkingUseHistoryDetailRequest},
     parking_feature_model::{ParkingFeatureRequest},
+    parking_search_model::PageQuery,
     };
 use crate::config::postgresql_database::PostgresDatabase;
 
@@ -30,6 +32,29 @@ pub async fn get_parking_use_history(
     }
 }
 
+/// 駐車場利用履歴一覧の取得（ページ番号またはカーソルで取得）
+#[get("/use-history")]
+pub async fn list_parking_use_history(
+    db: web::Data<PostgresDatabase>,
+    query: web::Query<PageQuery>,
+    identity: UserIdentity,
+) -> impl Responder {
+    let user_id = &identity.user_id;
+    info!("Processing list_parking_use_history request for user_id: {}", user_id);
+    let use_history_service = UseHistoryService::new(db.pool().clone());
+
+    match use_history_service.list_parking_use_history(&identity, query.into_inner()).await {
+        Ok(res) => {
+            info!("Processing list_parking_use_history successfully: user_id: {}", user_id);
+            success_response(res, None, None, None)
+        },
+        Err(e) => {
+            warn!("Processing list_parking_use_history failed for user_id: {} : {}", user_id, e);
+            e.error_response()
+        },
+    }
+}
+
 /// 駐車場検索履歴詳細の取得
 #[post("/use-history-detail")]
 pub async fn get_parking_use_history_detail(
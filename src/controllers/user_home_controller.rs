@@ -1,8 +1,11 @@
 use tracing::{ error, info};
 use crate::controllers::api_response::{success_response, error_response};
-use crate::services::UserHomeService;
-use actix_web::{post, web, Responder};
+use crate::controllers::check_in_controller::check_in_response;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::services::{CheckInService, UserHomeService};
+use actix_web::{post, web, Responder, ResponseError};
 use crate::models::{
+    check_in_model::{CheckInAction, LocationCheckInRequest},
     parking_status_model::{ParkingStatusRequest, UpdateParkingStatusRequest},
     parking_search_history_model::{ParkingSearchHistoryRequest},
     favorites_model:: {FavoritesRequest}
@@ -31,39 +34,32 @@ pub async fn get_parking_status(
     }
 }
 
-/// 入出庫状況の更新
+/// 入出庫状況の更新（位置情報が駐車場の範囲内の場合のみ記録する）
 #[post("/update-status")]
 pub async fn update_parking_status(
-    db: web::Data<PostgresDatabase>,
+    check_in_service: web::Data<CheckInService>,
+    identity: UserIdentity,
     req: web::Json<UpdateParkingStatusRequest>,
 ) -> impl Responder {
     info!("Processing update_parking_status for status_id: {} reservation_id: {} check_inout_kbn: {}", &req.status_id, &req.reservation_id, &req.check_inout_kbn);
-    let user_home_service = UserHomeService::new(db.pool().clone());
-    
-    if req.check_inout_kbn == "1" {
-            match user_home_service.update_parking_status_checkin(&req).await {
-            Ok(_) => {
-                // シンプルな成功レスポンス
-                success_response(true, None, None, None)
-            },
-            Err(e) => {
-                error!("Processing  update_parking_status failed status_id: {}  reservation_id: {} check_inout_kbn: {} {}", &req.status_id, &req.reservation_id,  &req.check_inout_kbn, e);
-                error_response(400, &e.to_string())
-            },
-        } 
-    } else {
-          match user_home_service.update_parking_status_checkout(&req).await {
-            Ok(_) => {
-                // シンプルな成功レスポンス
-                success_response(true, None, None, None)
-            },
-            Err(e) => {
-                error!("Processing  update_parking_status failed status_id: {} reservation_id: {} check_inout_kbn: {} {}", &req.status_id, &req.reservation_id, &req.check_inout_kbn, e);
-                error_response(400, &e.to_string())
-            },
-        } 
-    }
+    let Some(action) = CheckInAction::from_kbn(&req.check_inout_kbn) else {
+        error!("Processing update_parking_status failed: invalid check_inout_kbn: {}", &req.check_inout_kbn);
+        return error_response(400, "入出庫区分は1（入庫）または2（出庫）を指定してください");
+    };
+    let location = LocationCheckInRequest {
+        action: Some(action),
+        latitude: req.latitude,
+        longitude: req.longitude,
+        accuracy_meters: req.accuracy_meters,
+    };
 
+    match check_in_service.check_by_location(&identity, &req.reservation_id, location, Some(&req.status_id)).await {
+        Ok(result) => check_in_response(result),
+        Err(e) => {
+            error!("Processing  update_parking_status failed status_id: {} reservation_id: {} check_inout_kbn: {} {}", &req.status_id, &req.reservation_id, &req.check_inout_kbn, e);
+            e.error_response()
+        },
+    }
 }
 
 /// 駐車場検索履歴の取得
@@ -0,0 +1,135 @@
+use actix_web::{delete, get, http::StatusCode, post, put, web::{Data, Json, Path}, Responder, ResponseError};
+use tracing::{instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    controllers::ApiResponse,
+    middlewares::identity_middleware::UserIdentity,
+    models::vehicle_model::UserVehicleRequest,
+    services::VehicleService,
+};
+
+/// 登録車両一覧
+#[get("")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn list_vehicles_controller(service: Data<VehicleService>, identity: UserIdentity) -> impl Responder {
+    match service.list_vehicles(&identity).await {
+        Ok(vehicles) => ApiResponse::success(
+            vehicles,
+            Some(StatusCode::OK.as_u16()),
+            Some("登録車両の取得に成功しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（vehicle_controller.rs）登録車両の取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 車両の登録
+#[post("")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn create_vehicle_controller(
+    service: Data<VehicleService>,
+    identity: UserIdentity,
+    req: Json<UserVehicleRequest>,
+) -> impl Responder {
+    match service.create_vehicle(&identity, req.into_inner()).await {
+        Ok(vehicle) => ApiResponse::success(
+            vehicle,
+            Some(StatusCode::CREATED.as_u16()),
+            Some("車両を登録しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（vehicle_controller.rs）車両の登録に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// デフォルト車両と予約・検索条件の初期値
+#[get("/default")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn get_default_vehicle_controller(service: Data<VehicleService>, identity: UserIdentity) -> impl Responder {
+    match service.get_default_vehicle(&identity).await {
+        Ok(result) => ApiResponse::success(
+            result,
+            Some(StatusCode::OK.as_u16()),
+            Some("デフォルト車両の取得に成功しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（vehicle_controller.rs）デフォルト車両の取得に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 車両情報の更新
+#[put("/{vehicle_id}")]
+#[instrument(skip(service, req), fields(user_id = %identity.user_id))]
+pub async fn update_vehicle_controller(
+    service: Data<VehicleService>,
+    identity: UserIdentity,
+    vehicle_id: Path<Uuid>,
+    req: Json<UserVehicleRequest>,
+) -> impl Responder {
+    match service.update_vehicle(&identity, vehicle_id.into_inner(), req.into_inner()).await {
+        Ok(vehicle) => ApiResponse::success(
+            vehicle,
+            Some(StatusCode::OK.as_u16()),
+            Some("車両情報を更新しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（vehicle_controller.rs）車両情報の更新に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// デフォルト車両に設定
+#[put("/{vehicle_id}/default")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn set_default_vehicle_controller(
+    service: Data<VehicleService>,
+    identity: UserIdentity,
+    vehicle_id: Path<Uuid>,
+) -> impl Responder {
+    match service.set_default_vehicle(&identity, vehicle_id.into_inner()).await {
+        Ok(vehicle) => ApiResponse::success(
+            vehicle,
+            Some(StatusCode::OK.as_u16()),
+            Some("デフォルト車両を設定しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（vehicle_controller.rs）デフォルト車両の設定に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
+
+/// 車両の削除
+#[delete("/{vehicle_id}")]
+#[instrument(skip(service), fields(user_id = %identity.user_id))]
+pub async fn delete_vehicle_controller(
+    service: Data<VehicleService>,
+    identity: UserIdentity,
+    vehicle_id: Path<Uuid>,
+) -> impl Responder {
+    match service.delete_vehicle(&identity, vehicle_id.into_inner()).await {
+        Ok(()) => ApiResponse::success(
+            (),
+            Some(StatusCode::OK.as_u16()),
+            Some("車両を削除しました"),
+            None,
+        ),
+        Err(e) => {
+            warn!("（vehicle_controller.rs）車両の削除に失敗: {}", e);
+            e.error_response()
+        }
+    }
+}
@@ -0,0 +1,231 @@
+// src/geocoding/mod.rs
+//! 住所・郵便番号のジオコーディング
+//!
+//! `Geocoder`トレイトで実装を抽象化し、郵便番号・町域データセット（CSV）を
+//! 読み込むオフライン実装を提供する。駐車場検索の住所指定と、駐車場登録時の
+//! 都道府県・市区町村の検証・補完に使用する。
+//! 駅指定の検索と駐車場の最寄り駅には駅マスタ（`StationMaster`）を使用する。
+
+use std::env;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use thiserror::Error;
+use tracing::info;
+
+use crate::controllers::ApiError;
+
+pub mod offline_geocoder;
+pub mod station_master;
+
+pub use offline_geocoder::OfflineGeocoder;
+pub use station_master::{describe_candidates, Station, StationMaster, StationMatch};
+
+/// 都道府県一覧
+pub const PREFECTURES: [&str; 47] = [
+    "北海道", "青森県", "岩手県", "宮城県", "秋田県", "山形県", "福島県",
+    "茨城県", "栃木県", "群馬県", "埼玉県", "千葉県", "東京都", "神奈川県",
+    "新潟県", "富山県", "石川県", "福井県", "山梨県", "長野県", "岐阜県",
+    "静岡県", "愛知県", "三重県", "滋賀県", "京都府", "大阪府", "兵庫県",
+    "奈良県", "和歌山県", "鳥取県", "島根県", "岡山県", "広島県", "山口県",
+    "徳島県", "香川県", "愛媛県", "高知県", "福岡県", "佐賀県", "長崎県",
+    "熊本県", "大分県", "宮崎県", "鹿児島県", "沖縄県",
+];
+
+lazy_static! {
+    /// 郵便番号（〒・ハイフンは任意）
+    static ref POSTAL_CODE_REGEX: Regex = Regex::new(r"^〒?(\d{3})-?(\d{4})$").unwrap();
+
+    /// 先頭の郵便番号（「〒150-0002 渋谷区…」形式）
+    static ref LEADING_POSTAL_CODE_REGEX: Regex = Regex::new(r"^〒?(\d{3}-?\d{4})(.*)$").unwrap();
+
+    /// 漢数字の丁目（例: 二丁目、十二丁目）
+    static ref KANJI_CHOME_REGEX: Regex = Regex::new(r"([一二三四五六七八九十]+)丁目").unwrap();
+}
+
+#[derive(Error, Debug)]
+pub enum GeocodingError {
+    #[error("ジオコーディングデータの読み込みに失敗: {0}")]
+    Dataset(String),
+
+    #[error("ジオコーディング設定エラー: {0}")]
+    Config(String),
+}
+
+impl From<GeocodingError> for ApiError {
+    fn from(err: GeocodingError) -> Self {
+        tracing::error!("ジオコーディングエラー: {}", err);
+        ApiError::ServiceUnavailableError("住所検索を利用できません".to_string())
+    }
+}
+
+/// 座標の精度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeocodePrecision {
+    /// 町域（丁目）単位
+    Town,
+    /// 郵便番号単位（複数町域の中心）
+    PostalCode,
+    /// 市区町村単位（町域の中心）
+    City,
+}
+
+/// ジオコーディング結果
+#[derive(Debug, Clone, Serialize)]
+pub struct GeocodeResult {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// 郵便番号（例: 150-0002）
+    pub postal_code: Option<String>,
+    pub prefecture: String,
+    pub city: String,
+    pub town: Option<String>,
+    pub precision: GeocodePrecision,
+}
+
+impl GeocodeResult {
+    /// 表示用の地名（例: 東京都渋谷区渋谷二丁目）
+    pub fn display_name(&self) -> String {
+        format!("{}{}{}", self.prefecture, self.city, self.town.as_deref().unwrap_or(""))
+    }
+}
+
+/// ジオコーダー
+#[async_trait]
+pub trait Geocoder: Send + Sync {
+    /// バックエンド名（ログ用）
+    fn backend_name(&self) -> &'static str;
+
+    /// 郵便番号または住所から座標を取得（該当なしの場合はNone）
+    async fn geocode(&self, query: &str) -> Result<Option<GeocodeResult>, GeocodingError>;
+
+    /// 郵便番号から所在地を取得（該当なしの場合はNone）
+    async fn lookup_postal_code(&self, postal_code: &str) -> Result<Option<GeocodeResult>, GeocodingError>;
+}
+
+/// ジオコーディング設定
+#[derive(Debug, Clone)]
+pub struct GeocodingConfig {
+    /// バックエンド種別 (offline)
+    pub backend: String,
+    /// 郵便番号・町域データセットのパス
+    pub dataset_path: String,
+    /// 駅マスタデータセットのパス
+    pub station_dataset_path: String,
+}
+
+impl GeocodingConfig {
+    /// 環境変数からジオコーディング設定を読み込む
+    pub fn from_env() -> Self {
+        Self {
+            backend: env::var("GEOCODING_BACKEND")
+                .unwrap_or_else(|_| "offline".to_string())
+                .to_lowercase(),
+            dataset_path: env::var("GEOCODING_DATASET_PATH")
+                .unwrap_or_else(|_| "Database/geocoding/jp_postal_towns.csv".to_string()),
+            station_dataset_path: env::var("STATION_DATASET_PATH")
+                .unwrap_or_else(|_| "Database/geocoding/jp_stations.csv".to_string()),
+        }
+    }
+}
+
+/// 設定に応じたジオコーダーを生成
+pub fn create_geocoder(config: &GeocodingConfig) -> Result<Arc<dyn Geocoder>, GeocodingError> {
+    match config.backend.as_str() {
+        "offline" => {
+            let geocoder = OfflineGeocoder::from_csv_file(&config.dataset_path)?;
+            info!(
+                "ジオコーディング: オフライン ({}, {}件)",
+                config.dataset_path,
+                geocoder.len()
+            );
+            Ok(Arc::new(geocoder))
+        }
+        other => Err(GeocodingError::Config(format!("不明なジオコーディングバックエンドです: {}", other))),
+    }
+}
+
+/// 駅マスタを読み込む
+pub fn load_station_master(config: &GeocodingConfig) -> Result<Arc<StationMaster>, GeocodingError> {
+    let master = StationMaster::from_csv_file(&config.station_dataset_path)?;
+    info!("駅マスタ: {} ({}件)", config.station_dataset_path, master.len());
+    Ok(Arc::new(master))
+}
+
+/// 郵便番号を「123-4567」形式に正規化（郵便番号でない場合はNone）
+pub fn normalize_postal_code(value: &str) -> Option<String> {
+    let normalized = normalize_address(value);
+    POSTAL_CODE_REGEX
+        .captures(&normalized)
+        .map(|caps| format!("{}-{}", &caps[1], &caps[2]))
+}
+
+/// 先頭に郵便番号が付いた住所を郵便番号と残りの住所に分割
+pub fn split_leading_postal_code(value: &str) -> (Option<String>, String) {
+    let normalized = normalize_address(value);
+    match LEADING_POSTAL_CODE_REGEX.captures(&normalized) {
+        Some(caps) => (normalize_postal_code(&caps[1]), caps[2].to_string()),
+        None => (None, normalized),
+    }
+}
+
+/// 住所の表記ゆれを正規化
+///
+/// 全角英数字・ハイフンを半角に揃え、空白を除去し、漢数字の丁目を算用数字にする
+pub fn normalize_address(value: &str) -> String {
+    let converted: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            '－' | '‐' | '−' | '–' | '―' => '-',
+            'ヶ' => 'ケ',
+            _ => c,
+        })
+        .collect();
+
+    KANJI_CHOME_REGEX
+        .replace_all(&converted, |caps: &regex::Captures| {
+            match kanji_to_number(&caps[1]) {
+                Some(n) => format!("{}丁目", n),
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// 漢数字（99まで）を数値に変換
+fn kanji_to_number(kanji: &str) -> Option<u32> {
+    let digit = |c: char| "一二三四五六七八九".chars().position(|d| d == c).map(|p| p as u32 + 1);
+    let chars: Vec<char> = kanji.chars().collect();
+    match chars.as_slice() {
+        ['十'] => Some(10),
+        ['十', ones] => Some(10 + digit(*ones)?),
+        [tens, '十'] => Some(digit(*tens)? * 10),
+        [tens, '十', ones] => Some(digit(*tens)? * 10 + digit(*ones)?),
+        [ones] => digit(*ones),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_address_and_postal_code() {
+        assert_eq!(normalize_address("渋谷区渋谷二丁目２１－１"), "渋谷区渋谷2丁目21-1");
+        assert_eq!(normalize_address("港区六本木十二丁目"), "港区六本木12丁目");
+        assert_eq!(normalize_postal_code("〒150-0002"), Some("150-0002".to_string()));
+        assert_eq!(normalize_postal_code("１５００００２"), Some("150-0002".to_string()));
+        assert_eq!(normalize_postal_code("渋谷区"), None);
+        assert_eq!(
+            split_leading_postal_code("〒150-0002 東京都渋谷区渋谷2丁目"),
+            (Some("150-0002".to_string()), "東京都渋谷区渋谷2丁目".to_string())
+        );
+    }
+}
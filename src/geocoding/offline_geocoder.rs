@@ -0,0 +1,248 @@
+// src/geocoding/offline_geocoder.rs
+//! 郵便番号・町域データセットによるオフラインジオコーダー
+//!
+//! データセットはCSV（`postal_code,prefecture,city,town,latitude,longitude`）。
+//! `#`で始まる行とヘッダー行は読み飛ばす。町域の座標は代表点とする。
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use tracing::debug;
+
+use super::{
+    normalize_address, normalize_postal_code, split_leading_postal_code, GeocodePrecision, GeocodeResult, Geocoder,
+    GeocodingError, PREFECTURES,
+};
+
+/// 町域データ
+#[derive(Debug, Clone)]
+struct TownEntry {
+    postal_code: String,
+    prefecture: String,
+    city: String,
+    town: String,
+    latitude: f64,
+    longitude: f64,
+    /// 正規化済みの市区町村名
+    city_key: String,
+    /// 正規化済みの町域名
+    town_key: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OfflineGeocoder {
+    entries: Vec<TownEntry>,
+    /// 郵便番号 -> entriesの添字
+    postal_index: HashMap<String, Vec<usize>>,
+}
+
+impl OfflineGeocoder {
+    /// CSVファイルから読み込む
+    pub fn from_csv_file(path: &str) -> Result<Self, GeocodingError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| GeocodingError::Dataset(format!("{}: {}", path, e)))?;
+        Self::from_csv_str(&content)
+    }
+
+    /// CSV文字列から読み込む
+    pub fn from_csv_str(content: &str) -> Result<Self, GeocodingError> {
+        let mut geocoder = Self::default();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim().trim_start_matches('\u{feff}');
+            if line.is_empty() || line.starts_with('#') || line.starts_with("postal_code,") {
+                continue;
+            }
+
+            let entry = parse_line(line)
+                .ok_or_else(|| GeocodingError::Dataset(format!("{}行目の形式が正しくありません", index + 1)))?;
+            geocoder
+                .postal_index
+                .entry(entry.postal_code.clone())
+                .or_default()
+                .push(geocoder.entries.len());
+            geocoder.entries.push(entry);
+        }
+
+        if geocoder.entries.is_empty() {
+            return Err(GeocodingError::Dataset("データが1件もありません".to_string()));
+        }
+        Ok(geocoder)
+    }
+
+    /// 登録町域数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn find_by_postal_code(&self, postal_code: &str) -> Option<GeocodeResult> {
+        let indexes = self.postal_index.get(postal_code)?;
+        let entries: Vec<&TownEntry> = indexes.iter().map(|&i| &self.entries[i]).collect();
+        Some(centroid(&entries, GeocodePrecision::PostalCode))
+    }
+
+    /// 住所（都道府県は省略可）に最も長く一致する町域を探す
+    fn find_by_address(&self, address: &str, postal_code: Option<&str>) -> Option<GeocodeResult> {
+        let (prefecture, rest) = match PREFECTURES.iter().find(|p| address.starts_with(**p)) {
+            Some(p) => (Some(*p), &address[p.len()..]),
+            None => (None, address),
+        };
+        if rest.is_empty() {
+            return None;
+        }
+
+        let candidates: Vec<&TownEntry> = self
+            .entries
+            .iter()
+            .filter(|e| prefecture.is_none_or(|p| e.prefecture == p))
+            .filter(|e| rest.starts_with(&e.city_key))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        // 町域まで一致したもの（郵便番号の指定があれば一致するものを優先）
+        let town_matches: Vec<&TownEntry> = candidates
+            .iter()
+            .copied()
+            .filter(|e| !e.town_key.is_empty() && town_matches(&rest[e.city_key.len()..], &e.town_key))
+            .collect();
+        let best = town_matches
+            .iter()
+            .copied()
+            .filter(|e| postal_code.is_none_or(|code| e.postal_code == code))
+            .max_by_key(|e| e.city_key.len() + e.town_key.len())
+            .or_else(|| town_matches.iter().copied().max_by_key(|e| e.city_key.len() + e.town_key.len()));
+        if let Some(entry) = best {
+            return Some(centroid(&[entry], GeocodePrecision::Town));
+        }
+
+        // 市区町村のみ一致した場合は町域の中心
+        let longest_city = candidates.iter().map(|e| e.city_key.len()).max()?;
+        let city_entries: Vec<&TownEntry> = candidates
+            .into_iter()
+            .filter(|e| e.city_key.len() == longest_city)
+            .collect();
+        Some(centroid(&city_entries, GeocodePrecision::City))
+    }
+}
+
+#[async_trait]
+impl Geocoder for OfflineGeocoder {
+    fn backend_name(&self) -> &'static str {
+        "offline"
+    }
+
+    async fn geocode(&self, query: &str) -> Result<Option<GeocodeResult>, GeocodingError> {
+        let (postal_code, address) = split_leading_postal_code(query);
+
+        let result = match (&postal_code, address.is_empty()) {
+            (Some(code), true) => self.find_by_postal_code(code),
+            (code, false) => self
+                .find_by_address(&address, code.as_deref())
+                .or_else(|| code.as_deref().and_then(|c| self.find_by_postal_code(c))),
+            (None, true) => None,
+        };
+
+        debug!("ジオコーディング: query={}, found={}", query, result.is_some());
+        Ok(result)
+    }
+
+    async fn lookup_postal_code(&self, postal_code: &str) -> Result<Option<GeocodeResult>, GeocodingError> {
+        Ok(normalize_postal_code(postal_code).and_then(|code| self.find_by_postal_code(&code)))
+    }
+}
+
+fn parse_line(line: &str) -> Option<TownEntry> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [postal_code, prefecture, city, town, latitude, longitude] = fields.as_slice() else {
+        return None;
+    };
+
+    let postal_code = normalize_postal_code(postal_code)?;
+    if !PREFECTURES.contains(prefecture) || city.is_empty() {
+        return None;
+    }
+    let latitude: f64 = latitude.parse().ok().filter(|v| (-90.0..=90.0).contains(v))?;
+    let longitude: f64 = longitude.parse().ok().filter(|v| (-180.0..=180.0).contains(v))?;
+
+    Some(TownEntry {
+        postal_code,
+        prefecture: prefecture.to_string(),
+        city: city.to_string(),
+        town: town.to_string(),
+        latitude,
+        longitude,
+        city_key: normalize_address(city),
+        town_key: normalize_address(town),
+    })
+}
+
+/// 町域名が一致するか（「渋谷1丁目」が「渋谷10丁目」に一致しないよう直後の数字を確認）
+fn town_matches(rest: &str, town_key: &str) -> bool {
+    rest.strip_prefix(town_key)
+        .is_some_and(|after| !(town_key.ends_with(|c: char| c.is_ascii_digit()) && after.starts_with(|c: char| c.is_ascii_digit())))
+}
+
+/// 町域の代表点の平均を結果にする
+fn centroid(entries: &[&TownEntry], precision: GeocodePrecision) -> GeocodeResult {
+    let first = entries[0];
+    let count = entries.len() as f64;
+    let same = |f: fn(&TownEntry) -> &str| entries.iter().all(|e| f(e) == f(first));
+
+    GeocodeResult {
+        latitude: entries.iter().map(|e| e.latitude).sum::<f64>() / count,
+        longitude: entries.iter().map(|e| e.longitude).sum::<f64>() / count,
+        postal_code: same(|e| &e.postal_code).then(|| first.postal_code.clone()),
+        prefecture: first.prefecture.clone(),
+        city: first.city.clone(),
+        town: (precision != GeocodePrecision::City && same(|e| &e.town)).then(|| first.town.clone()),
+        precision,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATASET: &str = "\
+# テスト用データ
+postal_code,prefecture,city,town,latitude,longitude
+150-0002,東京都,渋谷区,渋谷一丁目,35.6627,139.7050
+150-0002,東京都,渋谷区,渋谷二丁目,35.6595,139.7075
+150-0043,東京都,渋谷区,道玄坂一丁目,35.6580,139.6980
+100-0005,東京都,千代田区,丸の内一丁目,35.6812,139.7671
+";
+
+    #[tokio::test]
+    async fn test_geocode() {
+        let geocoder = OfflineGeocoder::from_csv_str(DATASET).unwrap();
+        assert_eq!(geocoder.len(), 4);
+
+        let postal = geocoder.geocode("〒150-0002").await.unwrap().unwrap();
+        assert_eq!(postal.precision, GeocodePrecision::PostalCode);
+        assert_eq!(postal.city, "渋谷区");
+        assert!(postal.town.is_none());
+        assert!((postal.latitude - 35.6611).abs() < 1e-6);
+
+        let town = geocoder.geocode("渋谷区渋谷2丁目21-1").await.unwrap().unwrap();
+        assert_eq!(town.precision, GeocodePrecision::Town);
+        assert_eq!(town.town.as_deref(), Some("渋谷二丁目"));
+        assert_eq!(town.postal_code.as_deref(), Some("150-0002"));
+
+        let with_pref = geocoder.geocode("東京都千代田区丸の内１丁目").await.unwrap().unwrap();
+        assert_eq!(with_pref.postal_code.as_deref(), Some("100-0005"));
+
+        let city = geocoder.geocode("渋谷区神南").await.unwrap().unwrap();
+        assert_eq!(city.precision, GeocodePrecision::City);
+        assert!(city.town.is_none());
+
+        assert!(geocoder.geocode("大阪府大阪市北区梅田").await.unwrap().is_none());
+        assert!(geocoder.geocode("渋谷区渋谷10丁目").await.unwrap().unwrap().precision == GeocodePrecision::City);
+        assert!(OfflineGeocoder::from_csv_str("150-0002,東京都,渋谷区,渋谷,abc,139.7").is_err());
+    }
+}
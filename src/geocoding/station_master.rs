@@ -0,0 +1,206 @@
+// src/geocoding/station_master.rs
+//! 駅マスタ
+//!
+//! データセットはCSV（`station_id,station_name,station_kana,lines,prefecture,latitude,longitude`）。
+//! 路線は`|`区切り。`#`で始まる行とヘッダー行は読み飛ばす。
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::{GeocodingError, PREFECTURES};
+
+/// 駅
+#[derive(Debug, Clone, Serialize)]
+pub struct Station {
+    pub station_id: String,
+    /// 駅名（「駅」は付けない。例: 新宿）
+    pub station_name: String,
+    /// 読み（ひらがな）
+    pub station_kana: String,
+    pub lines: Vec<String>,
+    pub prefecture: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Station {
+    /// 表示名（例: 新宿駅）
+    pub fn display_name(&self) -> String {
+        format!("{}駅", self.station_name)
+    }
+}
+
+/// 駅名の解決結果
+#[derive(Debug)]
+pub enum StationMatch<'a> {
+    Found(&'a Station),
+    /// 同名の駅が複数ある場合
+    Ambiguous(Vec<&'a Station>),
+    NotFound,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StationMaster {
+    stations: Vec<Station>,
+    /// 駅ID -> stationsの添字
+    id_index: HashMap<String, usize>,
+}
+
+impl StationMaster {
+    /// CSVファイルから読み込む
+    pub fn from_csv_file(path: &str) -> Result<Self, GeocodingError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| GeocodingError::Dataset(format!("{}: {}", path, e)))?;
+        Self::from_csv_str(&content)
+    }
+
+    /// CSV文字列から読み込む
+    pub fn from_csv_str(content: &str) -> Result<Self, GeocodingError> {
+        let mut master = Self::default();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim().trim_start_matches('\u{feff}');
+            if line.is_empty() || line.starts_with('#') || line.starts_with("station_id,") {
+                continue;
+            }
+
+            let station = parse_line(line)
+                .ok_or_else(|| GeocodingError::Dataset(format!("駅データ{}行目の形式が正しくありません", index + 1)))?;
+            if master.id_index.contains_key(&station.station_id) {
+                return Err(GeocodingError::Dataset(format!("駅IDが重複しています: {}", station.station_id)));
+            }
+            master.id_index.insert(station.station_id.clone(), master.stations.len());
+            master.stations.push(station);
+        }
+
+        if master.stations.is_empty() {
+            return Err(GeocodingError::Dataset("駅データが1件もありません".to_string()));
+        }
+        Ok(master)
+    }
+
+    /// 登録駅数
+    pub fn len(&self) -> usize {
+        self.stations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stations.is_empty()
+    }
+
+    /// 駅IDで取得
+    pub fn find_by_id(&self, station_id: &str) -> Option<&Station> {
+        self.id_index.get(station_id.trim()).map(|&i| &self.stations[i])
+    }
+
+    /// 駅名（「駅」の有無は問わない）または読みに完全一致する駅を解決
+    pub fn resolve_name(&self, name: &str) -> StationMatch<'_> {
+        let key = station_key(name);
+        if key.is_empty() {
+            return StationMatch::NotFound;
+        }
+
+        let matches: Vec<&Station> = self
+            .stations
+            .iter()
+            .filter(|s| s.station_name == key || s.station_kana == key)
+            .collect();
+        match matches.as_slice() {
+            [] => StationMatch::NotFound,
+            [station] => StationMatch::Found(station),
+            _ => StationMatch::Ambiguous(matches),
+        }
+    }
+
+    /// 駅名・読みの前方一致検索（完全一致を先頭に、読み順）
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&Station> {
+        let key = station_key(query);
+        if key.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<&Station> = self
+            .stations
+            .iter()
+            .filter(|s| s.station_name.starts_with(&key) || s.station_kana.starts_with(&key))
+            .collect();
+        matches.sort_by(|a, b| {
+            let partial = |s: &Station| s.station_name != key && s.station_kana != key;
+            partial(a).cmp(&partial(b)).then_with(|| a.station_kana.cmp(&b.station_kana))
+        });
+        matches.truncate(limit);
+        matches
+    }
+}
+
+/// 同名駅の候補一覧（エラーメッセージ用。例: ST-0022: 府中駅（東京都）、…）
+pub fn describe_candidates(candidates: &[&Station]) -> String {
+    candidates
+        .iter()
+        .map(|s| format!("{}: {}（{}）", s.station_id, s.display_name(), s.prefecture))
+        .collect::<Vec<_>>()
+        .join("、")
+}
+
+/// 駅名の比較用キー（空白と末尾の「駅」を除去）
+fn station_key(name: &str) -> String {
+    let compact: String = name.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.strip_suffix('駅').unwrap_or(&compact).to_string()
+}
+
+fn parse_line(line: &str) -> Option<Station> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [station_id, station_name, station_kana, lines, prefecture, latitude, longitude] = fields.as_slice() else {
+        return None;
+    };
+
+    if station_id.is_empty() || station_name.is_empty() || !PREFECTURES.contains(prefecture) {
+        return None;
+    }
+    let latitude: f64 = latitude.parse().ok().filter(|v| (-90.0..=90.0).contains(v))?;
+    let longitude: f64 = longitude.parse().ok().filter(|v| (-180.0..=180.0).contains(v))?;
+
+    Some(Station {
+        station_id: station_id.to_string(),
+        station_name: station_key(station_name),
+        station_kana: station_kana.to_string(),
+        lines: lines
+            .split('|')
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect(),
+        prefecture: prefecture.to_string(),
+        latitude,
+        longitude,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATASET: &str = "\
+station_id,station_name,station_kana,lines,prefecture,latitude,longitude
+ST-0001,新宿,しんじゅく,JR山手線|京王線,東京都,35.6896,139.7006
+ST-0002,新宿三丁目,しんじゅくさんちょうめ,東京メトロ丸ノ内線,東京都,35.6905,139.7048
+ST-0003,府中,ふちゅう,京王線,東京都,35.6722,139.4800
+ST-0004,府中,ふちゅう,JR福塩線,広島県,34.5683,133.2363
+";
+
+    #[test]
+    fn test_resolve_and_search() {
+        let master = StationMaster::from_csv_str(DATASET).unwrap();
+        assert_eq!(master.len(), 4);
+        assert_eq!(master.find_by_id("ST-0002").unwrap().lines, vec!["東京メトロ丸ノ内線"]);
+
+        assert!(matches!(master.resolve_name("新宿駅"), StationMatch::Found(s) if s.station_id == "ST-0001"));
+        assert!(matches!(master.resolve_name("府中"), StationMatch::Ambiguous(s) if s.len() == 2));
+        assert!(matches!(master.resolve_name("渋谷"), StationMatch::NotFound));
+
+        let found: Vec<&str> = master.search("しんじゅく", 10).iter().map(|s| s.station_id.as_str()).collect();
+        assert_eq!(found, vec!["ST-0001", "ST-0002"]);
+        assert!(StationMaster::from_csv_str("ST-0001,新宿,しんじゅく,JR,東京都,35.6,139.7\nST-0001,新宿,しんじゅく,JR,東京都,35.6,139.7").is_err());
+    }
+}
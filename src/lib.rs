@@ -7,12 +7,15 @@
 
 pub mod config;
 pub mod controllers;
+pub mod geocoding;
 pub mod middlewares;
 pub mod models;
+pub mod payments;
 pub mod repositories;
 pub mod routes;
 pub mod server;
 pub mod services;
+pub mod storage;
 pub mod utils;
 
 
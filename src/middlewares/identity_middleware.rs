@@ -12,7 +12,11 @@ use std::{
 };
 use tracing::{debug, error, info, instrument, warn};
 
-use crate::{controllers::api_error::ApiError, middlewares::jwt::verify_jwt};
+use crate::{
+    controllers::api_error::ApiError,
+    middlewares::jwt::{verify_jwt, TokenClaims},
+    repositories::AuthSigninRepository,
+};
 
 /// ユーザー認証情報を表す構造体
 ///
@@ -22,7 +26,7 @@ use crate::{controllers::api_error::ApiError, middlewares::jwt::verify_jwt};
 pub struct UserIdentity {
     /// ユーザーID (例: user_000001, owner_000001)
     pub user_id: String,
-    /// ユーザータイプ (0: 一般ユーザー, 1: オーナー, 2: 管理者)
+    /// ユーザータイプ (0/user: 一般ユーザー, 1/owner: オーナー, 2/admin: 管理者)
     pub user_type: String,
     /// ログインID (オプション)
     pub login_id: Option<String>,
@@ -40,17 +44,17 @@ impl UserIdentity {
 
     /// オーナーかどうかを判定
     pub fn is_owner(&self) -> bool {
-        self.user_type == "1"
+        self.user_type == "1" || self.user_type == "owner"
     }
 
     /// 一般ユーザーかどうかを判定
     pub fn is_user(&self) -> bool {
-        self.user_type == "0"
+        self.user_type == "0" || self.user_type == "user"
     }
 
     /// 管理者かどうかを判定
     pub fn is_admin(&self) -> bool {
-        self.user_type == "2"
+        self.user_type == "2" || self.user_type == "admin"
     }
 }
 
@@ -77,15 +81,18 @@ impl FromRequest for UserIdentity {
 /// JWT認証ミドルウェア
 ///
 /// リクエストのAuthorizationヘッダーからJWTトークンを取得し、
-/// 有効性を検証してユーザー情報をリクエストに挿入する
+/// 有効性を検証してユーザー情報をリクエストに挿入する。
+/// パスワード変更前に発行されたトークンは拒否する
 pub struct JwtAuthMiddleware {
     /// 認証をスキップするかどうか（デバッグ用）
     pub skip_auth: bool,
+    /// パスワード変更日時の取得用
+    repository: AuthSigninRepository,
 }
 
 impl JwtAuthMiddleware {
     /// 新しいJwtAuthMiddlewareインスタンスを作成
-    pub fn new() -> Self {
+    pub fn new(repository: AuthSigninRepository) -> Self {
         let skip_auth = is_debug_mode();
         if skip_auth {
             info!("デバッグモードが有効です。JWT認証をスキップします");
@@ -93,13 +100,7 @@ impl JwtAuthMiddleware {
             info!("本番モードです。JWT認証を実行します");
         }
 
-        Self { skip_auth }
-    }
-}
-
-impl Default for JwtAuthMiddleware {
-    fn default() -> Self {
-        Self::new()
+        Self { skip_auth, repository }
     }
 }
 
@@ -129,6 +130,8 @@ pub fn is_public_path(path: &str) -> bool {
         "/api/auth/password-reset/complete",
         "/api/auth/refresh",
         "/health",
+        "/v1/api/files/",
+        "/v1/api/payments/webhook",
         "/docs",
         "/api-docs",
     ];
@@ -155,6 +158,7 @@ where
         ready(Ok(JwtAuthMiddlewareService {
             service: Rc::new(service),
             skip_auth: self.skip_auth,
+            repository: self.repository.clone(),
         }))
     }
 }
@@ -163,6 +167,7 @@ where
 pub struct JwtAuthMiddlewareService<S> {
     service: Rc<S>,
     skip_auth: bool,
+    repository: AuthSigninRepository,
 }
 
 impl<S, B> Service<ServiceRequest> for JwtAuthMiddlewareService<S>
@@ -182,6 +187,7 @@ where
         let skip_auth = self.skip_auth;
         let path = req.path().to_string();
         let service = self.service.clone(); // Clone the Rc, not the service
+        let repository = self.repository.clone();
 
         Box::pin(async move {
             // パブリックパスをチェック
@@ -214,6 +220,17 @@ where
 
             // JWTトークンを検証してユーザー情報を設定
             match verify_jwt(&token) {
+                Ok(claims) if issued_before_password_change(&repository, &claims).await? => {
+                    warn!("パスワード変更前に発行されたトークン: user_id={}", claims.sub);
+                    Err(actix_web::error::ErrorUnauthorized(
+                        serde_json::json!({
+                            "code": 401,
+                            "message": "トークンが無効です。再度ログインしてください",
+                            "data": Option::<()>::None
+                        })
+                        .to_string(),
+                    ))
+                }
                 Ok(claims) => {
                     info!(
                         "JWT検証成功: user_id={}, user_type={}",
@@ -245,6 +262,28 @@ where
     }
 }
 
+/// パスワード変更前に発行されたトークンかどうか
+///
+/// 発行時刻は秒単位のため、変更日時も秒単位で比較する（リフレッシュトークンと同じ判定）
+async fn issued_before_password_change(repository: &AuthSigninRepository, claims: &TokenClaims) -> Result<bool, Error> {
+    match repository.get_password_changed_datetime(&claims.sub).await {
+        Ok(changed) => Ok(changed.is_some_and(|changed| (claims.iat as i64) < changed.timestamp())),
+        // ログインIDの形式でないトークンは無効
+        Err(ApiError::ValidationError(_)) => Ok(true),
+        Err(e) => {
+            error!("パスワード変更日時の取得に失敗: {}", e);
+            Err(actix_web::error::ErrorInternalServerError(
+                serde_json::json!({
+                    "code": 500,
+                    "message": "認証情報の確認に失敗しました",
+                    "data": Option::<()>::None
+                })
+                .to_string(),
+            ))
+        }
+    }
+}
+
 /// リクエストからBearerトークンを抽出する
 ///
 /// # Errors
@@ -331,5 +370,7 @@ mod tests {
         assert!(is_public_path("/health"));
         assert!(!is_public_path("/api/users"));
         assert!(!is_public_path("/api/parking"));
+        assert!(is_public_path("/v1/api/payments/webhook"));
+        assert!(!is_public_path("/v1/api/payments/simulate-webhook"));
     }
 }
@@ -1,15 +1,139 @@
 use actix_web::{
+    cookie::{Cookie, SameSite},
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
     Error, HttpMessage,
 };
+use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 use futures::future::{ready, LocalBoxFuture, Ready};
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
 use std::collections::HashMap;
+use std::env;
+use std::rc::Rc;
 use std::sync::{Arc, Mutex};
-use tracing::{debug, info};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::utils::env::{env_flag_or, parse_env_or};
+
+/// セッションクッキー名（デフォルト）
+const DEFAULT_SESSION_COOKIE_NAME: &str = "session_id";
+
+/// スライディング有効期限の延長を保存する最短間隔（秒）
+const SESSION_TOUCH_INTERVAL_SECS: i64 = 60;
+
+/// セッション設定
+///
+/// 環境変数から読み込み、バックエンド種別・有効期限・クッキー属性を決定する
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    /// セッションバックエンド種別 (memory / postgres)
+    pub backend: String,
+    /// セッション有効期間（分）
+    pub ttl_minutes: i64,
+    /// スライディング有効期限（アクセス毎に有効期限を延長するか）
+    pub sliding_expiration: bool,
+    /// 期限切れセッションの削除間隔（秒）
+    pub cleanup_interval_secs: u64,
+    /// クッキー名
+    pub cookie_name: String,
+    /// クッキーのパス
+    pub cookie_path: String,
+    /// クッキーのドメイン（未設定の場合は付与しない）
+    pub cookie_domain: Option<String>,
+    /// Secure属性
+    pub cookie_secure: bool,
+    /// SameSite属性
+    pub cookie_same_site: SameSite,
+}
+
+impl SessionConfig {
+    /// 環境変数からセッション設定を読み込む
+    ///
+    /// ENVIRONMENTがdevelopment以外の場合、Secure属性とPostgreSQLバックエンドを既定値とする
+    pub fn from_env() -> Self {
+        let is_development = env::var("ENVIRONMENT")
+            .unwrap_or_else(|_| "development".to_string())
+            .to_lowercase()
+            == "development";
+
+        let default_backend = if is_development { "memory" } else { "postgres" };
+
+        let cookie_same_site = match env::var("SESSION_COOKIE_SAMESITE")
+            .unwrap_or_else(|_| "strict".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "lax" => SameSite::Lax,
+            "none" => SameSite::None,
+            "strict" => SameSite::Strict,
+            other => {
+                warn!("SESSION_COOKIE_SAMESITEの値が無効です: {}。Strictを使用します", other);
+                SameSite::Strict
+            }
+        };
+
+        let mut cookie_secure = env::var("SESSION_COOKIE_SECURE")
+            .or_else(|_| env::var("SECURE_COOKIES"))
+            .map(|v| v.to_lowercase() == "true")
+            .unwrap_or(!is_development);
+
+        // SameSite=NoneはSecure属性が必須
+        if cookie_same_site == SameSite::None && !cookie_secure {
+            warn!("SameSite=NoneのためSecure属性を有効化します");
+            cookie_secure = true;
+        }
+
+        Self {
+            backend: env::var("SESSION_BACKEND")
+                .unwrap_or_else(|_| default_backend.to_string())
+                .to_lowercase(),
+            ttl_minutes: parse_env_or("SESSION_TTL_MINUTES", 60 * 24),
+            sliding_expiration: env_flag_or("SESSION_SLIDING_EXPIRATION", true),
+            cleanup_interval_secs: parse_env_or("SESSION_CLEANUP_INTERVAL_SECS", 300),
+            cookie_name: env::var("SESSION_COOKIE_NAME")
+                .unwrap_or_else(|_| DEFAULT_SESSION_COOKIE_NAME.to_string()),
+            cookie_path: env::var("SESSION_COOKIE_PATH").unwrap_or_else(|_| "/".to_string()),
+            cookie_domain: env::var("SESSION_COOKIE_DOMAIN")
+                .ok()
+                .filter(|d| !d.trim().is_empty()),
+            cookie_secure,
+            cookie_same_site,
+        }
+    }
+
+    /// セッション有効期間
+    pub fn ttl(&self) -> Duration {
+        Duration::minutes(self.ttl_minutes)
+    }
+
+    /// セッションクッキーを構築
+    pub fn build_cookie(&self, session: &SessionData) -> Cookie<'static> {
+        let max_age = (session.expires_at - Utc::now()).num_seconds().max(0);
+
+        let mut builder = Cookie::build(self.cookie_name.clone(), session.id.clone())
+            .path(self.cookie_path.clone())
+            .http_only(true)
+            .secure(self.cookie_secure)
+            .same_site(self.cookie_same_site)
+            .max_age(actix_web::cookie::time::Duration::seconds(max_age));
+
+        if let Some(domain) = &self.cookie_domain {
+            builder = builder.domain(domain.clone());
+        }
+
+        builder.finish()
+    }
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
 // Session data structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionData {
@@ -21,80 +145,269 @@ pub struct SessionData {
 }
 
 impl SessionData {
-    pub fn new(user_id: Option<String>) -> Self {
+    pub fn new(user_id: Option<String>, ttl: Duration) -> Self {
         let now = Utc::now();
-        let session_duration = Duration::hours(24); // 24 hours session duration
-        
+
         Self {
             id: Uuid::new_v4().to_string(),
             user_id,
             created_at: now,
-            expires_at: now + session_duration,
+            expires_at: now + ttl,
             data: HashMap::new(),
         }
     }
-    
+
     pub fn is_expired(&self) -> bool {
         Utc::now() > self.expires_at
     }
-    
-    pub fn extend(&mut self) {
-        let session_duration = Duration::hours(24);
-        self.expires_at = Utc::now() + session_duration;
+
+    pub fn extend(&mut self, ttl: Duration) {
+        self.expires_at = Utc::now() + ttl;
     }
 }
 
-// In-memory session store (could be replaced with Redis or another store in production)
-#[derive(Debug, Clone)]
-pub struct SessionStore {
+/// セッションバックエンド
+///
+/// セッションの永続化先を抽象化する。ワーカー間・再起動後もセッションを共有する場合は
+/// PostgreSQLバックエンドを使用する
+#[async_trait]
+pub trait SessionBackend: Send + Sync {
+    /// セッションを取得
+    async fn get(&self, session_id: &str) -> Result<Option<SessionData>, DatabaseError>;
+
+    /// セッションを保存（存在する場合は更新）
+    async fn set(&self, session: &SessionData) -> Result<(), DatabaseError>;
+
+    /// セッションを削除
+    async fn remove(&self, session_id: &str) -> Result<(), DatabaseError>;
+
+    /// 指定ユーザーのセッションを削除し、削除件数を返す（except_session_idは残す）
+    async fn remove_by_user(&self, user_id: &str, except_session_id: Option<&str>) -> Result<u64, DatabaseError>;
+
+    /// 期限切れセッションを削除し、削除件数を返す
+    async fn cleanup_expired(&self) -> Result<u64, DatabaseError>;
+}
+
+/// インメモリセッションバックエンド（開発・テスト用）
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySessionBackend {
     sessions: Arc<Mutex<HashMap<String, SessionData>>>,
 }
 
-impl SessionStore {
+impl InMemorySessionBackend {
     pub fn new() -> Self {
-        Self {
-            sessions: Arc::new(Mutex::new(HashMap::new())),
-        }
+        Self::default()
     }
-    
-    pub fn get(&self, session_id: &str) -> Option<SessionData> {
+}
+
+#[async_trait]
+impl SessionBackend for InMemorySessionBackend {
+    async fn get(&self, session_id: &str) -> Result<Option<SessionData>, DatabaseError> {
         let sessions = self.sessions.lock().unwrap();
-        sessions.get(session_id).cloned()
+        Ok(sessions.get(session_id).cloned())
     }
-    
-    pub fn set(&self, session: SessionData) {
+
+    async fn set(&self, session: &SessionData) -> Result<(), DatabaseError> {
         let mut sessions = self.sessions.lock().unwrap();
-        sessions.insert(session.id.clone(), session);
+        sessions.insert(session.id.clone(), session.clone());
+        Ok(())
     }
-    
-    pub fn remove(&self, session_id: &str) {
+
+    async fn remove(&self, session_id: &str) -> Result<(), DatabaseError> {
         let mut sessions = self.sessions.lock().unwrap();
         sessions.remove(session_id);
+        Ok(())
     }
-    
-    pub fn cleanup_expired(&self) {
+
+    async fn remove_by_user(&self, user_id: &str, except_session_id: Option<&str>) -> Result<u64, DatabaseError> {
         let mut sessions = self.sessions.lock().unwrap();
+        let before = sessions.len();
+        sessions.retain(|id, session| {
+            session.user_id.as_deref() != Some(user_id) || Some(id.as_str()) == except_session_id
+        });
+        Ok((before - sessions.len()) as u64)
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, DatabaseError> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let before = sessions.len();
         sessions.retain(|_, session| !session.is_expired());
+        Ok((before - sessions.len()) as u64)
+    }
+}
+
+/// t_sessionsテーブルの行
+#[derive(Debug, FromRow)]
+struct SessionRow {
+    session_id: String,
+    user_id: Option<String>,
+    session_data: sqlx::types::Json<HashMap<String, String>>,
+    created_datetime: DateTime<Utc>,
+    expires_datetime: DateTime<Utc>,
+}
+
+impl From<SessionRow> for SessionData {
+    fn from(row: SessionRow) -> Self {
+        Self {
+            id: row.session_id,
+            user_id: row.user_id,
+            created_at: row.created_datetime,
+            expires_at: row.expires_datetime,
+            data: row.session_data.0,
+        }
     }
 }
 
+/// PostgreSQLセッションバックエンド（t_sessionsテーブル）
+#[derive(Debug, Clone)]
+pub struct PostgresSessionBackend {
+    db: PostgresDatabase,
+}
+
+impl PostgresSessionBackend {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl SessionBackend for PostgresSessionBackend {
+    async fn get(&self, session_id: &str) -> Result<Option<SessionData>, DatabaseError> {
+        let query = r#"
+            SELECT session_id, user_id, session_data, created_datetime, expires_datetime
+            FROM t_sessions
+            WHERE session_id = $1
+        "#;
+
+        sqlx::query_as::<_, SessionRow>(query)
+            .bind(session_id)
+            .fetch_optional(self.db.pool())
+            .await
+            .map(|row| row.map(SessionData::from))
+            .map_err(|e| DatabaseError::QueryError(format!("セッション取得に失敗: {}", e)))
+    }
+
+    async fn set(&self, session: &SessionData) -> Result<(), DatabaseError> {
+        let query = r#"
+            INSERT INTO t_sessions (
+                session_id, user_id, session_data, created_datetime, expires_datetime, updated_datetime
+            ) VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP)
+            ON CONFLICT (session_id) DO UPDATE SET
+                user_id = EXCLUDED.user_id,
+                session_data = EXCLUDED.session_data,
+                expires_datetime = EXCLUDED.expires_datetime,
+                updated_datetime = CURRENT_TIMESTAMP
+        "#;
+
+        sqlx::query(query)
+            .bind(&session.id)
+            .bind(&session.user_id)
+            .bind(sqlx::types::Json(&session.data))
+            .bind(session.created_at)
+            .bind(session.expires_at)
+            .execute(self.db.pool())
+            .await
+            .map(|_| ())
+            .map_err(|e| DatabaseError::QueryError(format!("セッション保存に失敗: {}", e)))
+    }
+
+    async fn remove(&self, session_id: &str) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM t_sessions WHERE session_id = $1")
+            .bind(session_id)
+            .execute(self.db.pool())
+            .await
+            .map(|_| ())
+            .map_err(|e| DatabaseError::QueryError(format!("セッション削除に失敗: {}", e)))
+    }
+
+    async fn remove_by_user(&self, user_id: &str, except_session_id: Option<&str>) -> Result<u64, DatabaseError> {
+        let query = r#"
+            DELETE FROM t_sessions
+            WHERE user_id = $1
+              AND ($2::text IS NULL OR session_id <> $2)
+        "#;
+
+        sqlx::query(query)
+            .bind(user_id)
+            .bind(except_session_id)
+            .execute(self.db.pool())
+            .await
+            .map(|result| result.rows_affected())
+            .map_err(|e| DatabaseError::QueryError(format!("ユーザーセッション削除に失敗: {}", e)))
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, DatabaseError> {
+        sqlx::query("DELETE FROM t_sessions WHERE expires_datetime < CURRENT_TIMESTAMP")
+            .execute(self.db.pool())
+            .await
+            .map(|result| result.rows_affected())
+            .map_err(|e| {
+                DatabaseError::QueryError(format!("期限切れセッション削除に失敗: {}", e))
+            })
+    }
+}
+
+/// 設定に応じたセッションバックエンドを生成
+pub fn create_session_backend(
+    config: &SessionConfig,
+    db: &PostgresDatabase,
+) -> Arc<dyn SessionBackend> {
+    match config.backend.as_str() {
+        "postgres" | "postgresql" => {
+            info!("セッションバックエンド: PostgreSQL");
+            Arc::new(PostgresSessionBackend::new(db.clone()))
+        }
+        "memory" => {
+            info!("セッションバックエンド: インメモリ");
+            Arc::new(InMemorySessionBackend::new())
+        }
+        other => {
+            warn!("不明なSESSION_BACKENDです: {}。インメモリを使用します", other);
+            Arc::new(InMemorySessionBackend::new())
+        }
+    }
+}
+
+/// 期限切れセッションを定期的に削除するバックグラウンドタスクを起動
+pub fn spawn_session_sweeper(
+    backend: Arc<dyn SessionBackend>,
+    interval_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    let period = std::time::Duration::from_secs(interval_secs.max(1));
+    info!("セッションスイーパーを起動します（間隔: {}秒）", period.as_secs());
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match backend.cleanup_expired().await {
+                Ok(0) => {}
+                Ok(count) => info!("期限切れセッションを削除しました: {}件", count),
+                Err(e) => error!("期限切れセッションの削除に失敗しました: {}", e),
+            }
+        }
+    })
+}
+
 // Session middleware
 pub struct SessionMiddleware {
-    store: SessionStore,
+    backend: Arc<dyn SessionBackend>,
+    config: Rc<SessionConfig>,
 }
 
 impl SessionMiddleware {
-    pub fn new() -> Self {
+    pub fn new(backend: Arc<dyn SessionBackend>, config: SessionConfig) -> Self {
         debug!("Initializing session middleware");
         Self {
-            store: SessionStore::new(),
+            backend,
+            config: Rc::new(config),
         }
     }
 }
 
 impl<S, B> Transform<S, ServiceRequest> for SessionMiddleware
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -105,24 +418,80 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        // Clean up expired sessions periodically
-        self.store.cleanup_expired();
-        
         ready(Ok(SessionMiddlewareService {
-            service,
-            store: self.store.clone(),
+            service: Rc::new(service),
+            backend: self.backend.clone(),
+            config: self.config.clone(),
         }))
     }
 }
 
 pub struct SessionMiddlewareService<S> {
-    service: S,
-    store: SessionStore,
+    service: Rc<S>,
+    backend: Arc<dyn SessionBackend>,
+    config: Rc<SessionConfig>,
+}
+
+/// クッキーのセッションIDからセッションを復元（無効な場合は新規作成）
+///
+/// 2つ目の値は保存済みのセッション（新規作成した場合はNone）で、保存要否の判定に使う
+async fn load_session(
+    backend: &dyn SessionBackend,
+    config: &SessionConfig,
+    session_id: Option<String>,
+) -> (SessionData, Option<SessionData>) {
+    let Some(id) = session_id else {
+        debug!("No session cookie, creating new session");
+        return (SessionData::new(None, config.ttl()), None);
+    };
+
+    match backend.get(&id).await {
+        Ok(Some(mut session)) => {
+            if session.is_expired() {
+                debug!("Session expired, creating new one");
+                if let Err(e) = backend.remove(&id).await {
+                    warn!("期限切れセッションの削除に失敗しました: {}", e);
+                }
+                (SessionData::new(None, config.ttl()), None)
+            } else {
+                debug!("Using existing session: {}", id);
+                let stored = session.clone();
+                if config.sliding_expiration {
+                    session.extend(config.ttl());
+                }
+                (session, Some(stored))
+            }
+        }
+        Ok(None) => {
+            debug!("Session not found, creating new one");
+            (SessionData::new(None, config.ttl()), None)
+        }
+        Err(e) => {
+            error!("セッションの取得に失敗しました: {}", e);
+            (SessionData::new(None, config.ttl()), None)
+        }
+    }
+}
+
+/// セッションの保存が必要か
+///
+/// 新規セッションは内容がある場合のみ保存し（匿名のリクエストごとに作成しない）、
+/// 保存済みのセッションは内容の変更時と有効期限の延長時（最短間隔ごと）のみ保存する
+fn needs_save(stored: Option<&SessionData>, session: &SessionData) -> bool {
+    match stored {
+        None => session.user_id.is_some() || !session.data.is_empty(),
+        Some(stored) => {
+            stored.id != session.id
+                || stored.user_id != session.user_id
+                || stored.data != session.data
+                || session.expires_at - stored.expires_at >= Duration::seconds(SESSION_TOUCH_INTERVAL_SECS)
+        }
+    }
 }
 
 impl<S, B> Service<ServiceRequest> for SessionMiddlewareService<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -134,75 +503,134 @@ where
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
         debug!("Session middleware processing request: {}", req.path());
-        
+
+        let service = self.service.clone();
+        let backend = self.backend.clone();
+        let config = self.config.clone();
+
         // Extract session ID from cookie
-        let session_id = req.cookie("session_id")
+        let session_id = req
+            .cookie(&config.cookie_name)
             .map(|cookie| cookie.value().to_string());
-        
-        let session = match session_id {
-            Some(id) => {
-                match self.store.get(&id) {
-                    Some(session) => {
-                        if session.is_expired() {
-                            debug!("Session expired, creating new one");
-                            // Create a new session if expired
-                            SessionData::new(None)
-                        } else {
-                            debug!("Using existing session: {}", id);
-                            // Extend existing session
-                            let mut session = session;
-                            session.extend();
-                            session
-                        }
-                    }
-                    None => {
-                        debug!("Session not found, creating new one");
-                        // Create a new session if not found
-                        SessionData::new(None)
-                    }
-                }
-            }
-            None => {
-                debug!("No session cookie, creating new session");
-                // Create a new session if no cookie
-                SessionData::new(None)
-            }
-        };
-        
-        // Store the session
-        self.store.set(session.clone());
-        
-        // Add session to request extensions
-        req.extensions_mut().insert(session);
-        
-        let store = self.store.clone();
-        let fut = self.service.call(req);
-        
+
         Box::pin(async move {
-            let mut res = fut.await?;
-            
+            let (session, stored) = load_session(backend.as_ref(), &config, session_id).await;
+
+            // Add session to request extensions
+            req.extensions_mut().insert(session);
+
+            let mut res = service.call(req).await?;
+
             // Get the potentially modified session from response extensions
             let session_option = res.request().extensions().get::<SessionData>().cloned();
-            
-            if let Some(session) = session_option {
-                // Update the session in the store
-                store.set(session.clone());
-                
-                // Set the session cookie
-                let cookie = format!(
-                    "session_id={}; Path=/; HttpOnly; SameSite=Strict; Max-Age=86400",
-                    session.id
-                );
-                
-                res.headers_mut().append(
-                    actix_web::http::header::SET_COOKIE,
-                    actix_web::http::header::HeaderValue::from_str(&cookie).unwrap(),
-                );
-                
-                info!("Session updated: {}", session.id);
+
+            if let Some(session) = session_option.filter(|session| needs_save(stored.as_ref(), session)) {
+                // Update the session in the backend
+                if let Err(e) = backend.set(&session).await {
+                    error!("セッションの保存に失敗しました: {}", e);
+                    return Ok(res);
+                }
+
+                let cookie = config.build_cookie(&session);
+                match actix_web::http::header::HeaderValue::from_str(&cookie.to_string()) {
+                    Ok(value) => {
+                        res.headers_mut()
+                            .append(actix_web::http::header::SET_COOKIE, value);
+                    }
+                    Err(e) => error!("セッションクッキーの設定に失敗しました: {}", e),
+                }
+
+                debug!("Session updated: {}", session.id);
             }
-            
+
             Ok(res)
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_memory_backend_cleanup_expired() {
+        let backend = InMemorySessionBackend::new();
+
+        let active = SessionData::new(None, Duration::minutes(30));
+        let mut expired = SessionData::new(None, Duration::minutes(30));
+        expired.expires_at = Utc::now() - Duration::minutes(1);
+
+        backend.set(&active).await.unwrap();
+        backend.set(&expired).await.unwrap();
+
+        assert_eq!(backend.cleanup_expired().await.unwrap(), 1);
+        assert!(backend.get(&active.id).await.unwrap().is_some());
+        assert!(backend.get(&expired.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_backend_remove_by_user() {
+        let backend = InMemorySessionBackend::new();
+
+        let current = SessionData::new(Some("login-1".to_string()), Duration::minutes(30));
+        let other = SessionData::new(Some("login-1".to_string()), Duration::minutes(30));
+        let another_user = SessionData::new(Some("login-2".to_string()), Duration::minutes(30));
+        for session in [&current, &other, &another_user] {
+            backend.set(session).await.unwrap();
+        }
+
+        assert_eq!(backend.remove_by_user("login-1", Some(&current.id)).await.unwrap(), 1);
+        assert!(backend.get(&current.id).await.unwrap().is_some());
+        assert!(backend.get(&other.id).await.unwrap().is_none());
+        assert!(backend.get(&another_user.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_load_session_sliding_expiration() {
+        let backend = InMemorySessionBackend::new();
+        let config = SessionConfig {
+            backend: "memory".to_string(),
+            ttl_minutes: 60,
+            sliding_expiration: true,
+            cleanup_interval_secs: 60,
+            cookie_name: DEFAULT_SESSION_COOKIE_NAME.to_string(),
+            cookie_path: "/".to_string(),
+            cookie_domain: None,
+            cookie_secure: true,
+            cookie_same_site: SameSite::Strict,
+        };
+
+        let mut session = SessionData::new(None, Duration::minutes(5));
+        let original_expiry = session.expires_at;
+        session.data.insert("key".to_string(), "value".to_string());
+        backend.set(&session).await.unwrap();
+
+        let (loaded, stored) = load_session(&backend, &config, Some(session.id.clone())).await;
+        assert_eq!(loaded.id, session.id);
+        assert!(loaded.expires_at > original_expiry);
+        assert!(needs_save(stored.as_ref(), &loaded));
+
+        let cookie = config.build_cookie(&loaded);
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+    }
+
+    #[test]
+    fn test_needs_save_only_when_changed() {
+        let anonymous = SessionData::new(None, Duration::minutes(30));
+        assert!(!needs_save(None, &anonymous));
+
+        let stored = SessionData::new(Some("login-1".to_string()), Duration::minutes(30));
+        assert!(needs_save(None, &stored));
+        assert!(!needs_save(Some(&stored), &stored.clone()));
+
+        let mut extended = stored.clone();
+        extended.expires_at += Duration::seconds(SESSION_TOUCH_INTERVAL_SECS);
+        assert!(needs_save(Some(&stored), &extended));
+
+        let mut changed = stored.clone();
+        changed.data.insert("key".to_string(), "value".to_string());
+        assert!(needs_save(Some(&stored), &changed));
+    }
+}
@@ -0,0 +1,163 @@
+// 論理名: アカウント管理モデル（個人データのエクスポート・アカウント削除）
+// m_login / t_account_deletion_requests に対応
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// 削除リクエストのステータス: 猶予期間中
+pub const DELETION_STATUS_PENDING: &str = "pending";
+/// 削除リクエストのステータス: 取り消し
+pub const DELETION_STATUS_CANCELLED: &str = "cancelled";
+/// 削除リクエストのステータス: 削除済み
+pub const DELETION_STATUS_COMPLETED: &str = "completed";
+
+/// 削除後に予約・駐車場へ残す匿名化ID
+pub const DELETED_ACCOUNT_ID: &str = "deleted";
+
+/// 削除・エクスポート対象アカウント（m_login + m_users/m_owners）
+#[derive(Debug, Clone, FromRow)]
+pub struct AccountRow {
+    pub login_id: String,
+    pub email: String,
+    pub pass_word: String,
+    pub is_owner: bool,
+    /// user_id または owner_id
+    pub account_id: Option<String>,
+}
+
+/// 個人データのエクスポート
+///
+/// 各項目はテーブルの行をそのままJSON化したもの（パスワード・トークンは含めない）
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountExport {
+    pub exported_at: DateTime<Utc>,
+    /// user または owner
+    pub user_type: String,
+    pub login: Value,
+    pub profile: Value,
+    pub profile_photos: Value,
+    pub vehicles: Value,
+    pub reservations: Value,
+    pub favorites: Value,
+    pub search_history: Value,
+    /// 保存検索（通知済みの駐車場を含む）
+    pub saved_searches: Value,
+    pub notifications: Value,
+    /// 所有駐車場（オーナーのみ）
+    pub parking_lots: Value,
+}
+
+/// 削除予定日時（申請日時に猶予期間を加えたもの。負の猶予期間は0日として扱う）
+pub fn deletion_scheduled_at(requested_at: DateTime<Utc>, cool_off_days: i64) -> DateTime<Utc> {
+    requested_at + Duration::days(cool_off_days.max(0))
+}
+
+/// アカウント削除リクエスト（再認証のため現在のパスワードが必須）
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountDeletionRequest {
+    pub password: String,
+}
+
+/// t_account_deletion_requests の行
+#[derive(Debug, Clone, FromRow)]
+pub struct AccountDeletionRow {
+    pub request_id: Uuid,
+    pub login_id: Uuid,
+    pub status: String,
+    pub scheduled_datetime: DateTime<Utc>,
+    pub created_datetime: DateTime<Utc>,
+}
+
+/// アカウント削除リクエストのレスポンス
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountDeletionResponse {
+    pub request_id: String,
+    pub status: String,
+    pub requested_at: DateTime<Utc>,
+    /// 削除予定日時（この日時までは取り消し可能）
+    pub scheduled_at: DateTime<Utc>,
+}
+
+impl AccountDeletionRow {
+    /// 猶予期間を過ぎて削除を実行できるか（取り消し・削除済みの申請は対象外）
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == DELETION_STATUS_PENDING && self.scheduled_datetime <= now
+    }
+}
+
+impl From<AccountDeletionRow> for AccountDeletionResponse {
+    fn from(row: AccountDeletionRow) -> Self {
+        Self {
+            request_id: row.request_id.to_string(),
+            status: row.status,
+            requested_at: row.created_datetime,
+            scheduled_at: row.scheduled_datetime,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn deletion_row(status: &str, scheduled_datetime: DateTime<Utc>) -> AccountDeletionRow {
+        AccountDeletionRow {
+            request_id: Uuid::new_v4(),
+            login_id: Uuid::new_v4(),
+            status: status.to_string(),
+            scheduled_datetime,
+            created_datetime: scheduled_datetime - Duration::days(14),
+        }
+    }
+
+    #[test]
+    fn test_deletion_cool_off_and_due_date() {
+        let requested_at = DateTime::parse_from_rfc3339("2026-10-01T09:00:00Z").unwrap().with_timezone(&Utc);
+        let scheduled_at = deletion_scheduled_at(requested_at, 14);
+        assert_eq!(scheduled_at, requested_at + Duration::days(14));
+        assert_eq!(deletion_scheduled_at(requested_at, -3), requested_at);
+
+        let pending = deletion_row(DELETION_STATUS_PENDING, scheduled_at);
+        assert!(!pending.is_due(scheduled_at - Duration::seconds(1)));
+        assert!(pending.is_due(scheduled_at));
+        assert!(!deletion_row(DELETION_STATUS_CANCELLED, scheduled_at).is_due(scheduled_at + Duration::days(1)));
+        assert!(!deletion_row(DELETION_STATUS_COMPLETED, scheduled_at).is_due(scheduled_at + Duration::days(1)));
+
+        let response = AccountDeletionResponse::from(pending.clone());
+        assert_eq!(response.request_id, pending.request_id.to_string());
+        assert_eq!((response.requested_at, response.scheduled_at), (pending.created_datetime, scheduled_at));
+    }
+
+    #[test]
+    fn test_account_export_shape() {
+        let export = AccountExport {
+            exported_at: Utc::now(),
+            user_type: "user".to_string(),
+            login: json!({"login_id": "L1", "email": "user@example.com"}),
+            profile: json!({}),
+            profile_photos: json!([]),
+            vehicles: json!([]),
+            reservations: json!([]),
+            favorites: json!([]),
+            search_history: json!([]),
+            saved_searches: json!([{"search_id": "S1", "notified_parking_lots": []}]),
+            notifications: json!([]),
+            parking_lots: json!([]),
+        };
+
+        let value = serde_json::to_value(&export).unwrap();
+        let mut keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            [
+                "exported_at", "favorites", "login", "notifications", "parking_lots", "profile", "profile_photos",
+                "reservations", "saved_searches", "search_history", "user_type", "vehicles",
+            ]
+        );
+        assert_eq!(value["saved_searches"][0]["notified_parking_lots"], json!([]));
+    }
+}
@@ -66,6 +66,10 @@ pub struct AuthSigninResponse {
     /// 刷新令牌 - 长期令牌，用于更新访问令牌
     #[serde(rename = "refresh_token")]
     pub refresh_token: String,
+    
+    /// 登录ID - m_login.login_id（会话绑定用，不返回给客户端）
+    #[serde(skip)]
+    pub login_id: String,
 }
 
 /// 当前用户信息响应模型
@@ -141,6 +145,39 @@ pub struct PasswordResetCompletion {
     pub new_password: String,
 }
 
+/// 密码修改请求模型
+/// 
+/// 已登录用户修改密码，需要提供当前密码
+#[derive(Debug, Deserialize)]
+pub struct PasswordChangeRequest {
+    /// 当前密码
+    pub current_password: String,
+    
+    /// 新密码
+    pub new_password: String,
+    
+    /// 新密码（确认）
+    pub confirm_password: String,
+}
+
+/// 密码修改响应模型
+/// 
+/// 修改后其他会话失效，返回新的令牌对
+#[derive(Debug, Serialize, Clone)]
+pub struct PasswordChangeResponse {
+    /// 新的访问令牌
+    pub access_token: String,
+    
+    /// 新的刷新令牌
+    pub refresh_token: String,
+    
+    /// 令牌过期时间（秒）
+    pub expires_in: i64,
+    
+    /// 已失效的其他会话数
+    pub revoked_sessions: u64,
+}
+
 /// 令牌刷新完成响应模型
 #[derive(Debug, Serialize)]
 pub struct RefreshTokenCompletion {
@@ -205,6 +242,7 @@ impl AuthSigninResponse {
             is_owner: false, // 普通用户
             token: access_token,
             refresh_token,
+            login_id: login.login_id.clone(),
         }
     }
     
@@ -229,6 +267,7 @@ impl AuthSigninResponse {
             is_owner: true, // 停车场业主
             token: access_token,
             refresh_token,
+            login_id: login.login_id.clone(),
         }
     }
 }
@@ -397,6 +436,29 @@ impl PasswordResetRequest {
     }
 }
 
+impl PasswordChangeRequest {
+    /// 验证密码修改请求（密码策略在服务层检查）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.current_password.is_empty() {
+            return Err("現在のパスワードは必須です".to_string());
+        }
+        
+        if self.new_password.is_empty() {
+            return Err("新しいパスワードは必須です".to_string());
+        }
+        
+        if self.new_password != self.confirm_password {
+            return Err("新しいパスワードと確認用パスワードが一致しません".to_string());
+        }
+        
+        if self.new_password == self.current_password {
+            return Err("新しいパスワードは現在のパスワードと異なるものを指定してください".to_string());
+        }
+        
+        Ok(())
+    }
+}
+
 impl PasswordResetCompletion {
     /// 验证密码重置完成请求
     pub fn validate(&self) -> Result<(), String> {
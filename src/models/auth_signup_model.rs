@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use crate::models::m_login_model::MLoginModel;
 use crate::models::m_owners_model::MOwnersModel;
 use crate::models::m_users_model::MUsersModel;
+use crate::models::receipt_model::normalize_invoice_registration_number;
 /// User signup request model - matches Flutter AuthSignupModel
 #[derive(Debug, Deserialize, Clone)]
 pub struct UserSignupRequest {
@@ -38,6 +39,8 @@ pub struct OwnerSignupRequest {
     pub remarks: Option<String>,
     pub promotional_email_opt_in: Option<bool>,
     pub service_email_opt_in: Option<bool>,
+    /// 適格請求書発行事業者登録番号（T+13桁、未登録の場合は省略）
+    pub invoice_registration_number: Option<String>,
 }
 
 /// Unified signup response model for both users and owners
@@ -251,7 +254,7 @@ impl UserSignupRequest {
             }
         }
 
-        // Full name kana validation (not in DB schema but keep for compatibility)
+        // Full name kana validation - VARCHAR(100) in m_users
         if let Some(ref full_name_kana) = self.full_name_kana {
             if full_name_kana.len() > 100 {
                 return Err("氏名（カナ）は100文字以内で入力してください".to_string());
@@ -399,9 +402,21 @@ impl OwnerSignupRequest {
             }
         }
 
+        // Invoice registration number validation - VARCHAR(14) in m_owners (T + 13 digits with check digit)
+        if let Some(number) = self.invoice_registration_number.as_deref().filter(|n| !n.trim().is_empty()) {
+            normalize_invoice_registration_number(number)?;
+        }
+
         Ok(())
     }
 
+    /// 正規化した登録番号（未指定・空文字の場合はNone）
+    pub fn normalized_invoice_registration_number(&self) -> Option<String> {
+        self.invoice_registration_number
+            .as_deref()
+            .and_then(|number| normalize_invoice_registration_number(number).ok())
+    }
+
     fn is_valid_email(&self) -> bool {
         let email_regex = Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
         email_regex.is_match(&self.email)
@@ -0,0 +1,284 @@
+// 論理名: キャンセルポリシーモデル
+// t_cancellation_policies / t_reservations（キャンセル精算）に対応
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+use crate::models::payment_model::PaymentResponse;
+
+/// 1駐車場に設定できる規定数の上限
+pub const MAX_CANCELLATION_RULES: usize = 10;
+/// 規定に指定できる時間の上限（1年）
+pub const MAX_HOURS_BEFORE: i32 = 24 * 365;
+/// キャンセル理由の最大文字数
+pub const MAX_CANCEL_REASON_LENGTH: usize = 500;
+
+/// キャンセル料の規定（利用開始のhours_before時間前までのキャンセルはfee_percent%）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CancellationRule {
+    pub hours_before: i32,
+    pub fee_percent: i32,
+}
+
+/// キャンセルポリシー
+///
+/// 規定は利用開始までの時間が長い順に並べ、最初に当てはまる規定を適用する。
+/// どの規定にも当てはまらない場合（直前・利用開始後）はキャンセル料100%
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CancellationPolicy {
+    pub rules: Vec<CancellationRule>,
+}
+
+impl Default for CancellationPolicy {
+    /// 未設定の駐車場は利用開始まで無料
+    fn default() -> Self {
+        Self {
+            rules: vec![CancellationRule { hours_before: 0, fee_percent: 0 }],
+        }
+    }
+}
+
+impl CancellationPolicy {
+    /// 入力検証と並び替え（利用開始までの時間が長い順）
+    pub fn normalize(mut self) -> Result<Self, String> {
+        if self.rules.is_empty() {
+            return Err("キャンセル料の規定を1件以上指定してください".to_string());
+        }
+        if self.rules.len() > MAX_CANCELLATION_RULES {
+            return Err(format!("キャンセル料の規定は{}件までです", MAX_CANCELLATION_RULES));
+        }
+        if let Some(rule) = self
+            .rules
+            .iter()
+            .find(|rule| !(0..=MAX_HOURS_BEFORE).contains(&rule.hours_before))
+        {
+            return Err(format!(
+                "利用開始までの時間は0〜{}時間で指定してください: {}",
+                MAX_HOURS_BEFORE, rule.hours_before
+            ));
+        }
+        if let Some(rule) = self.rules.iter().find(|rule| !(0..=100).contains(&rule.fee_percent)) {
+            return Err(format!("キャンセル料率は0〜100%で指定してください: {}", rule.fee_percent));
+        }
+
+        self.rules.sort_by_key(|rule| std::cmp::Reverse(rule.hours_before));
+        for pair in self.rules.windows(2) {
+            if pair[0].hours_before == pair[1].hours_before {
+                return Err(format!("利用開始{}時間前の規定が重複しています", pair[0].hours_before));
+            }
+            if pair[0].fee_percent > pair[1].fee_percent {
+                return Err("利用開始に近いほどキャンセル料率が高くなるように設定してください".to_string());
+            }
+        }
+        Ok(self)
+    }
+
+    /// キャンセル時点で適用される規定（当てはまらない場合はNone）
+    pub fn rule_at(&self, start: DateTime<Utc>, now: DateTime<Utc>) -> Option<&CancellationRule> {
+        self.rules
+            .iter()
+            .find(|rule| now <= start - Duration::hours(i64::from(rule.hours_before)))
+    }
+
+    /// キャンセル時点のキャンセル料率
+    pub fn fee_percent_at(&self, start: DateTime<Utc>, now: DateTime<Utc>) -> i32 {
+        self.rule_at(start, now).map_or(100, |rule| rule.fee_percent)
+    }
+
+    /// 現在の料率が適用される期限（これを過ぎると料率が変わる）
+    pub fn fee_changes_at(&self, start: DateTime<Utc>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.rule_at(start, now)
+            .map(|rule| start - Duration::hours(i64::from(rule.hours_before)))
+    }
+
+    /// 規定の説明文（例: 利用開始24時間前まで: 無料）
+    pub fn descriptions(&self) -> Vec<String> {
+        let mut descriptions: Vec<String> = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let deadline = if rule.hours_before == 0 {
+                    "利用開始まで".to_string()
+                } else {
+                    format!("利用開始{}時間前まで", rule.hours_before)
+                };
+                format!("{}: {}", deadline, fee_label(rule.fee_percent))
+            })
+            .collect();
+        if self.rules.last().is_none_or(|rule| rule.fee_percent < 100) {
+            descriptions.push(format!("それ以降: {}", fee_label(100)));
+        }
+        descriptions
+    }
+}
+
+fn fee_label(fee_percent: i32) -> String {
+    match fee_percent {
+        0 => "無料".to_string(),
+        100 => "返金なし（100%）".to_string(),
+        percent => format!("{}%", percent),
+    }
+}
+
+/// キャンセル料（円未満切り捨て）
+pub fn cancellation_fee(amount: i64, fee_percent: i32) -> i64 {
+    amount.max(0) * i64::from(fee_percent.clamp(0, 100)) / 100
+}
+
+/// 登録済みのキャンセルポリシー（t_cancellation_policies）
+#[derive(Debug, Clone, FromRow)]
+pub struct CancellationPolicyRow {
+    pub parking_lot_id: String,
+    pub rules: Value,
+    pub updated_datetime: Option<DateTime<Utc>>,
+}
+
+impl CancellationPolicyRow {
+    pub fn policy(&self) -> CancellationPolicy {
+        serde_json::from_value(self.rules.clone())
+            .map(|rules| CancellationPolicy { rules })
+            .unwrap_or_default()
+    }
+}
+
+/// キャンセル対象の予約（予約金額は予約詳細の合計）
+#[derive(Debug, Clone, FromRow)]
+pub struct CancellationReservationRow {
+    pub reservation_id: String,
+    pub parking_lot_id: String,
+    pub status: String,
+    pub start_datetime: DateTime<Utc>,
+    pub amount: i64,
+    pub user_login_id: Option<String>,
+    pub cancel_reason: Option<String>,
+    pub cancellation_fee: Option<i64>,
+    pub cancellation_fee_percent: Option<i32>,
+    pub cancelled_datetime: Option<DateTime<Utc>>,
+}
+
+/// 予約のキャンセルリクエスト
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CancelReservationRequest {
+    pub cancel_reason: Option<String>,
+}
+
+impl CancelReservationRequest {
+    /// 入力検証（空白のみの理由は未指定として扱う）
+    pub fn normalize(self) -> Result<Option<String>, String> {
+        let reason = self
+            .cancel_reason
+            .map(|reason| reason.trim().to_string())
+            .filter(|reason| !reason.is_empty());
+        if reason
+            .as_ref()
+            .is_some_and(|reason| reason.chars().count() > MAX_CANCEL_REASON_LENGTH)
+        {
+            return Err(format!("キャンセル理由は{}文字以内で入力してください", MAX_CANCEL_REASON_LENGTH));
+        }
+        Ok(reason)
+    }
+}
+
+/// キャンセルポリシーレスポンス
+#[derive(Debug, Clone, Serialize)]
+pub struct CancellationPolicyResponse {
+    pub parking_lot_id: String,
+    pub rules: Vec<CancellationRule>,
+    pub descriptions: Vec<String>,
+    /// 未設定（利用開始まで無料）の場合true
+    pub is_default: bool,
+    pub updated_datetime: Option<DateTime<Utc>>,
+}
+
+impl CancellationPolicyResponse {
+    pub fn new(parking_lot_id: &str, row: Option<&CancellationPolicyRow>) -> Self {
+        let policy = row.map(CancellationPolicyRow::policy).unwrap_or_default();
+        Self {
+            parking_lot_id: parking_lot_id.to_string(),
+            descriptions: policy.descriptions(),
+            rules: policy.rules,
+            is_default: row.is_none(),
+            updated_datetime: row.and_then(|row| row.updated_datetime),
+        }
+    }
+}
+
+/// キャンセル料の試算
+#[derive(Debug, Clone, Serialize)]
+pub struct CancellationQuote {
+    pub reservation_id: String,
+    pub parking_lot_id: String,
+    pub start_datetime: DateTime<Utc>,
+    pub quoted_at: DateTime<Utc>,
+    pub reservation_amount: i64,
+    pub fee_percent: i32,
+    pub cancellation_fee: i64,
+    pub refund_amount: i64,
+    /// 現在のキャンセル料率が適用される期限
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_changes_at: Option<DateTime<Utc>>,
+    pub policy: CancellationPolicyResponse,
+}
+
+/// キャンセル結果
+#[derive(Debug, Clone, Serialize)]
+pub struct CancellationResult {
+    pub reservation_id: String,
+    pub cancelled_datetime: Option<DateTime<Utc>>,
+    pub cancel_reason: Option<String>,
+    pub fee_percent: i32,
+    pub cancellation_fee: i64,
+    /// 精算で実際に返金された額（売上確定していない決済・決済がない予約は0）
+    pub refund_amount: i64,
+    /// 決済の精算結果（決済がない予約はNone）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment: Option<PaymentResponse>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(hours_before: i32, fee_percent: i32) -> CancellationRule {
+        CancellationRule { hours_before, fee_percent }
+    }
+
+    #[test]
+    fn test_policy_normalize() {
+        let policy = CancellationPolicy { rules: vec![rule(1, 50), rule(24, 0)] }.normalize().unwrap();
+        assert_eq!(policy.rules, vec![rule(24, 0), rule(1, 50)]);
+        assert_eq!(
+            policy.descriptions(),
+            vec!["利用開始24時間前まで: 無料", "利用開始1時間前まで: 50%", "それ以降: 返金なし（100%）"]
+        );
+
+        assert!(CancellationPolicy { rules: vec![] }.normalize().is_err());
+        assert!(CancellationPolicy { rules: vec![rule(24, 50), rule(1, 0)] }.normalize().is_err());
+        assert!(CancellationPolicy { rules: vec![rule(24, 0), rule(24, 50)] }.normalize().is_err());
+        assert!(CancellationPolicy { rules: vec![rule(-1, 0)] }.normalize().is_err());
+        assert!(CancellationPolicy { rules: vec![rule(1, 120)] }.normalize().is_err());
+    }
+
+    #[test]
+    fn test_policy_fee_at() {
+        let policy = CancellationPolicy { rules: vec![rule(24, 0), rule(1, 50)] };
+        let start = DateTime::parse_from_rfc3339("2026-03-10T10:00:00Z").unwrap().with_timezone(&Utc);
+
+        let two_days_before = start - Duration::hours(48);
+        assert_eq!(policy.fee_percent_at(start, two_days_before), 0);
+        assert_eq!(policy.fee_changes_at(start, two_days_before), Some(start - Duration::hours(24)));
+        assert_eq!(policy.fee_percent_at(start, start - Duration::hours(24)), 0);
+        assert_eq!(policy.fee_percent_at(start, start - Duration::hours(3)), 50);
+        assert_eq!(policy.fee_percent_at(start, start - Duration::minutes(30)), 100);
+        assert_eq!(policy.fee_changes_at(start, start - Duration::minutes(30)), None);
+
+        let free = CancellationPolicy::default();
+        assert_eq!(free.fee_percent_at(start, start - Duration::minutes(1)), 0);
+        assert_eq!(free.fee_percent_at(start, start + Duration::minutes(1)), 100);
+
+        assert_eq!(cancellation_fee(1001, 50), 500);
+        assert_eq!(cancellation_fee(1200, 100), 1200);
+        assert_eq!(cancellation_fee(1200, 0), 0);
+    }
+}
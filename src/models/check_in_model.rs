@@ -0,0 +1,445 @@
+// 論理名: 入出庫（チェックイン・チェックアウト）モデル
+// t_parking_status（入出庫の記録）と入出庫用QRコードに対応
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::t_parking_google_maps_model::TParkingGoogleMapsModel;
+
+/// 入庫できる予約ステータス（1: 予約中, 2: 承認済み）
+pub const ACTIVE_RESERVATION_STATUSES: [&str; 2] = ["1", "2"];
+/// 入庫済み・出庫済みを表す入出庫状態
+pub const PARKING_STATUS_DONE: &str = "1";
+
+/// 入出庫の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckInAction {
+    CheckIn,
+    CheckOut,
+}
+
+impl CheckInAction {
+    /// 入出庫区分（1: 入庫, 2: 出庫）から変換
+    pub fn from_kbn(kbn: &str) -> Option<Self> {
+        match kbn {
+            "1" => Some(Self::CheckIn),
+            "2" => Some(Self::CheckOut),
+            _ => None,
+        }
+    }
+}
+
+/// 入出庫の確認方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckInMethod {
+    /// 駐車場のスキャナーでQRコードを読み取り
+    QrScan,
+    /// 利用者の位置情報が駐車場の範囲内
+    Geofence,
+}
+
+impl CheckInMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::QrScan => "qr_scan",
+            Self::Geofence => "geofence",
+        }
+    }
+}
+
+/// 入出庫を受け付けなかった理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckInRejection {
+    InvalidCode,
+    ExpiredCode,
+    CodeAlreadyUsed,
+    WrongParkingLot,
+    ReservationNotActive,
+    TooEarly,
+    ReservationEnded,
+    AlreadyCheckedIn,
+    NotCheckedIn,
+    AlreadyCheckedOut,
+    LocationRequired,
+    LocationInaccurate,
+    LocationUnavailable,
+    OutsideGeofence,
+}
+
+impl CheckInRejection {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::InvalidCode => "QRコードが無効です",
+            Self::ExpiredCode => "QRコードの有効期限が切れています。アプリで表示し直してください",
+            Self::CodeAlreadyUsed => "このQRコードは入庫に使用済みです。出庫時はアプリで表示し直してください",
+            Self::WrongParkingLot => "別の駐車場の予約です",
+            Self::ReservationNotActive => "入出庫できない予約です（キャンセル済み・利用完了など）",
+            Self::TooEarly => "入庫できる時間になっていません",
+            Self::ReservationEnded => "予約の終了時刻を過ぎているため入庫できません",
+            Self::AlreadyCheckedIn => "入庫済みです",
+            Self::NotCheckedIn => "入庫されていないため出庫できません",
+            Self::AlreadyCheckedOut => "出庫済みです",
+            Self::LocationRequired => "位置情報を送信してください",
+            Self::LocationInaccurate => "位置情報の精度が低いため確認できません",
+            Self::LocationUnavailable => "駐車場の位置情報が登録されていないため位置情報では確認できません",
+            Self::OutsideGeofence => "駐車場の範囲外です",
+        }
+    }
+}
+
+/// 入出庫の受付条件
+#[derive(Debug, Clone)]
+pub struct CheckInSettings {
+    /// QRコードの署名用秘密鍵
+    pub signing_secret: String,
+    /// QRコードの有効期間（秒）
+    pub code_ttl_seconds: i64,
+    /// 予約開始の何分前から入庫できるか
+    pub early_check_in_minutes: i64,
+    /// 位置情報で入出庫を確認する駐車場からの距離（メートル）
+    pub geofence_radius_meters: f64,
+    /// 位置情報の精度の下限（誤差がこれより大きい場合は確認しない、メートル）
+    pub max_location_accuracy_meters: f64,
+}
+
+/// QRコードの内容（予約・駐車場・有効期限を署名したもの）
+///
+/// code_idはQRコードごとに異なり、入庫に使ったコードをそのまま出庫に使えないようにする
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckInCodeClaims {
+    #[serde(rename = "r")]
+    pub reservation_id: String,
+    #[serde(rename = "p")]
+    pub parking_lot_id: String,
+    #[serde(rename = "c")]
+    pub code_id: String,
+    #[serde(rename = "e")]
+    pub expires_at: i64,
+}
+
+impl CheckInCodeClaims {
+    pub fn new(reservation_id: &str, parking_lot_id: &str, expires_at: DateTime<Utc>) -> Self {
+        Self {
+            reservation_id: reservation_id.to_string(),
+            parking_lot_id: parking_lot_id.to_string(),
+            code_id: Uuid::new_v4().simple().to_string(),
+            expires_at: expires_at.timestamp(),
+        }
+    }
+
+    /// 署名付きのQRコード文字列に変換
+    pub fn encode(&self, secret: &str) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default());
+        let signature = URL_SAFE_NO_PAD.encode(sign(secret, payload.as_bytes()).finalize().into_bytes());
+        format!("{}.{}", payload, signature)
+    }
+
+    /// 署名と有効期限を検証して復元
+    pub fn decode(code: &str, secret: &str, now: DateTime<Utc>) -> Result<Self, CheckInRejection> {
+        let (payload, signature) = code.trim().split_once('.').ok_or(CheckInRejection::InvalidCode)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| CheckInRejection::InvalidCode)?;
+        sign(secret, payload.as_bytes())
+            .verify_slice(&signature)
+            .map_err(|_| CheckInRejection::InvalidCode)?;
+
+        let json = URL_SAFE_NO_PAD.decode(payload).map_err(|_| CheckInRejection::InvalidCode)?;
+        let claims: Self = serde_json::from_slice(&json).map_err(|_| CheckInRejection::InvalidCode)?;
+        if claims.expires_at < now.timestamp() {
+            return Err(CheckInRejection::ExpiredCode);
+        }
+        Ok(claims)
+    }
+}
+
+fn sign(secret: &str, message: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMACは任意長の鍵を受け付ける");
+    mac.update(message);
+    mac
+}
+
+/// 入出庫の対象（予約・入出庫状況・駐車場の位置）
+#[derive(Debug, Clone, FromRow)]
+pub struct CheckInTargetRow {
+    pub reservation_id: String,
+    pub parking_lot_id: String,
+    pub status: String,
+    pub start_datetime: DateTime<Utc>,
+    pub end_datetime: DateTime<Utc>,
+    pub user_login_id: Option<String>,
+    pub status_id: Option<String>,
+    pub entry_status: Option<String>,
+    pub exit_status: Option<String>,
+    pub exit_datetime: Option<DateTime<Utc>>,
+    pub entry_code_id: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+impl CheckInTargetRow {
+    pub fn is_checked_in(&self) -> bool {
+        self.entry_status.as_deref() == Some(PARKING_STATUS_DONE)
+    }
+
+    pub fn is_checked_out(&self) -> bool {
+        self.exit_status.as_deref() == Some(PARKING_STATUS_DONE)
+    }
+
+    fn is_active(&self) -> bool {
+        ACTIVE_RESERVATION_STATUSES.contains(&self.status.as_str())
+    }
+
+    /// 受け付ける入出庫の種類（未指定の場合は入庫前なら入庫、入庫後なら出庫）
+    ///
+    /// 入庫は予約開始のearly_check_in_minutes分前から予約終了まで。出庫は入庫後いつでも受け付ける。
+    /// 出庫済みでも予約が完了になっていない場合（売上確定の失敗など）は出庫処理の再試行を受け付ける
+    pub fn evaluate(
+        &self,
+        requested: Option<CheckInAction>,
+        now: DateTime<Utc>,
+        early_check_in_minutes: i64,
+    ) -> Result<CheckInAction, CheckInRejection> {
+        if self.is_checked_out() {
+            return match requested {
+                Some(CheckInAction::CheckIn) => Err(CheckInRejection::AlreadyCheckedIn),
+                _ if self.is_active() => Ok(CheckInAction::CheckOut),
+                _ => Err(CheckInRejection::AlreadyCheckedOut),
+            };
+        }
+        if !self.is_active() {
+            return Err(CheckInRejection::ReservationNotActive);
+        }
+
+        let action = requested.unwrap_or(if self.is_checked_in() {
+            CheckInAction::CheckOut
+        } else {
+            CheckInAction::CheckIn
+        });
+        match action {
+            CheckInAction::CheckIn if self.is_checked_in() => Err(CheckInRejection::AlreadyCheckedIn),
+            CheckInAction::CheckIn if now < self.start_datetime - Duration::minutes(early_check_in_minutes) => {
+                Err(CheckInRejection::TooEarly)
+            }
+            CheckInAction::CheckIn if now > self.end_datetime => Err(CheckInRejection::ReservationEnded),
+            CheckInAction::CheckOut if !self.is_checked_in() => Err(CheckInRejection::NotCheckedIn),
+            _ => Ok(action),
+        }
+    }
+
+    /// 駐車場までの距離（メートル、駐車場の位置が未登録の場合はNone）
+    pub fn distance_meters(&self, latitude: f64, longitude: f64) -> Option<f64> {
+        let (lot_latitude, lot_longitude) = (self.latitude?, self.longitude?);
+        Some(TParkingGoogleMapsModel::haversine_distance(lot_latitude, lot_longitude, latitude, longitude) * 1000.0)
+    }
+}
+
+/// 入出庫の記録結果
+#[derive(Debug, Clone, FromRow)]
+pub struct CheckInRecordRow {
+    pub status_id: String,
+    pub recorded_datetime: Option<DateTime<Utc>>,
+}
+
+/// 駐車場のスキャナーからの読み取り（actionを省略した場合は入庫前なら入庫、入庫後なら出庫）
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanCheckInRequest {
+    pub code: String,
+    pub action: Option<CheckInAction>,
+}
+
+/// 位置情報による入出庫
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LocationCheckInRequest {
+    pub action: Option<CheckInAction>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// 位置情報の誤差（メートル）
+    pub accuracy_meters: Option<f64>,
+}
+
+impl LocationCheckInRequest {
+    /// 入力検証（位置情報が未送信の場合はNone）
+    pub fn location(&self) -> Result<Option<(f64, f64)>, String> {
+        let (Some(latitude), Some(longitude)) = (self.latitude, self.longitude) else {
+            return Ok(None);
+        };
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err("緯度・経度の値が不正です".to_string());
+        }
+        if self.accuracy_meters.is_some_and(|accuracy| !accuracy.is_finite() || accuracy < 0.0) {
+            return Err("位置情報の誤差の値が不正です".to_string());
+        }
+        Ok(Some((latitude, longitude)))
+    }
+}
+
+/// 入出庫用QRコード（アプリで表示し、有効期限が切れる前に取得し直す）
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckInCodeResponse {
+    pub reservation_id: String,
+    pub parking_lot_id: String,
+    pub code: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 入出庫の結果
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckInResponse {
+    pub accepted: bool,
+    pub reservation_id: Option<String>,
+    pub parking_lot_id: Option<String>,
+    pub method: CheckInMethod,
+    pub action: Option<CheckInAction>,
+    pub status_id: Option<String>,
+    pub recorded_datetime: Option<DateTime<Utc>>,
+    pub distance_meters: Option<f64>,
+    pub rejection_reason: Option<CheckInRejection>,
+    pub message: String,
+}
+
+impl CheckInResponse {
+    pub fn accepted(
+        target: &CheckInTargetRow,
+        method: CheckInMethod,
+        action: CheckInAction,
+        record: CheckInRecordRow,
+        distance_meters: Option<f64>,
+    ) -> Self {
+        let message = match action {
+            CheckInAction::CheckIn => "入庫を記録しました",
+            CheckInAction::CheckOut => "出庫を記録しました",
+        };
+        Self {
+            accepted: true,
+            reservation_id: Some(target.reservation_id.clone()),
+            parking_lot_id: Some(target.parking_lot_id.clone()),
+            method,
+            action: Some(action),
+            status_id: Some(record.status_id),
+            recorded_datetime: record.recorded_datetime,
+            distance_meters: distance_meters.map(round_meters),
+            rejection_reason: None,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn rejected(
+        target: Option<&CheckInTargetRow>,
+        method: CheckInMethod,
+        action: Option<CheckInAction>,
+        reason: CheckInRejection,
+        distance_meters: Option<f64>,
+    ) -> Self {
+        Self {
+            accepted: false,
+            reservation_id: target.map(|t| t.reservation_id.clone()),
+            parking_lot_id: target.map(|t| t.parking_lot_id.clone()),
+            method,
+            action,
+            status_id: target.and_then(|t| t.status_id.clone()),
+            recorded_datetime: None,
+            distance_meters: distance_meters.map(round_meters),
+            rejection_reason: Some(reason),
+            message: reason.message().to_string(),
+        }
+    }
+}
+
+fn round_meters(meters: f64) -> f64 {
+    (meters * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(status: &str, entry: &str, exit: &str) -> CheckInTargetRow {
+        let start = DateTime::parse_from_rfc3339("2026-03-02T10:00:00+09:00").unwrap().with_timezone(&Utc);
+        CheckInTargetRow {
+            reservation_id: "R1".to_string(),
+            parking_lot_id: "P1".to_string(),
+            status: status.to_string(),
+            start_datetime: start,
+            end_datetime: start + Duration::hours(2),
+            user_login_id: None,
+            status_id: Some("S1".to_string()),
+            entry_status: Some(entry.to_string()),
+            exit_status: Some(exit.to_string()),
+            exit_datetime: None,
+            entry_code_id: None,
+            latitude: Some(35.681236),
+            longitude: Some(139.767125),
+        }
+    }
+
+    #[test]
+    fn test_code_roundtrip_and_tampering() {
+        let now = Utc::now();
+        let claims = CheckInCodeClaims::new("R1", "P1", now + Duration::minutes(5));
+        let code = claims.encode("secret");
+        assert_eq!(CheckInCodeClaims::decode(&code, "secret", now).unwrap(), claims);
+
+        assert_eq!(CheckInCodeClaims::decode(&code, "other", now), Err(CheckInRejection::InvalidCode));
+        assert_eq!(
+            CheckInCodeClaims::decode(&code, "secret", now + Duration::minutes(6)),
+            Err(CheckInRejection::ExpiredCode)
+        );
+
+        // 別の駐車場に書き換えたコードは署名検証で弾く
+        let forged = CheckInCodeClaims { parking_lot_id: "P2".to_string(), ..claims }.encode("other");
+        let (forged_payload, _) = forged.split_once('.').unwrap();
+        let (_, signature) = code.split_once('.').unwrap();
+        assert_eq!(
+            CheckInCodeClaims::decode(&format!("{}.{}", forged_payload, signature), "secret", now),
+            Err(CheckInRejection::InvalidCode)
+        );
+        assert_eq!(CheckInCodeClaims::decode("invalid", "secret", now), Err(CheckInRejection::InvalidCode));
+    }
+
+    #[test]
+    fn test_evaluate_window_and_state() {
+        let reserved = target("2", "0", "0");
+        let start = reserved.start_datetime;
+
+        assert_eq!(reserved.evaluate(None, start - Duration::minutes(15), 15), Ok(CheckInAction::CheckIn));
+        assert_eq!(reserved.evaluate(None, start - Duration::minutes(16), 15), Err(CheckInRejection::TooEarly));
+        assert_eq!(
+            reserved.evaluate(None, reserved.end_datetime + Duration::minutes(1), 15),
+            Err(CheckInRejection::ReservationEnded)
+        );
+        assert_eq!(
+            reserved.evaluate(Some(CheckInAction::CheckOut), start, 15),
+            Err(CheckInRejection::NotCheckedIn)
+        );
+
+        // 入庫後は終了時刻を過ぎても出庫できる
+        let parked = target("2", "1", "0");
+        assert_eq!(parked.evaluate(None, parked.end_datetime + Duration::hours(1), 15), Ok(CheckInAction::CheckOut));
+        assert_eq!(
+            parked.evaluate(Some(CheckInAction::CheckIn), start, 15),
+            Err(CheckInRejection::AlreadyCheckedIn)
+        );
+
+        assert_eq!(target("3", "0", "0").evaluate(None, start, 15), Err(CheckInRejection::ReservationNotActive));
+        assert_eq!(target("4", "1", "1").evaluate(None, start, 15), Err(CheckInRejection::AlreadyCheckedOut));
+        // 出庫済みで予約が完了になっていない場合は出庫処理を再試行する
+        assert_eq!(target("2", "1", "1").evaluate(None, start, 15), Ok(CheckInAction::CheckOut));
+        assert_eq!(CheckInAction::from_kbn("2"), Some(CheckInAction::CheckOut));
+        assert_eq!(CheckInAction::from_kbn("x"), None);
+    }
+
+    #[test]
+    fn test_distance_meters() {
+        let lot = target("2", "0", "0");
+        assert!(lot.distance_meters(35.681236, 139.767125).unwrap() < 1.0);
+        let distance = lot.distance_meters(35.682236, 139.767125).unwrap();
+        assert!((distance - 111.2).abs() < 1.0);
+        assert_eq!(CheckInTargetRow { latitude: None, ..lot }.distance_meters(35.0, 139.0), None);
+    }
+}
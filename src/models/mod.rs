@@ -7,6 +7,16 @@ pub mod auth_signin_model;
 pub mod m_login_model;
 pub mod m_users_model;
 pub mod m_owners_model;
+pub mod profile_model;
+pub mod account_model;
+pub mod vehicle_model;
+pub mod saved_search_model;
+pub mod notification_model;
+pub mod owner_analytics_model;
+pub mod payment_model;
+pub mod cancellation_policy_model;
+pub mod receipt_model;
+pub mod check_in_model;
 
 // Parking-related models
 pub mod t_parking_lots_model;
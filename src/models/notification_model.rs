@@ -0,0 +1,69 @@
+// 論理名: アプリ内通知モデル
+// t_notifications に対応
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// 通知種別: 保存検索に一致する駐車場
+pub const NOTIFICATION_TYPE_SAVED_SEARCH_MATCH: &str = "saved_search_match";
+
+/// アプリ内通知（t_notifications）
+#[derive(Debug, Clone, FromRow)]
+pub struct NotificationRow {
+    pub notification_id: Uuid,
+    pub user_id: String,
+    pub notification_type: String,
+    pub title: String,
+    pub body: String,
+    pub payload: Option<Value>,
+    pub read_datetime: Option<DateTime<Utc>>,
+    pub created_datetime: Option<DateTime<Utc>>,
+}
+
+/// アプリ内通知レスポンス
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationResponse {
+    pub notification_id: Uuid,
+    pub notification_type: String,
+    pub title: String,
+    pub body: String,
+    pub payload: Option<Value>,
+    pub is_read: bool,
+    pub read_datetime: Option<DateTime<Utc>>,
+    pub created_datetime: Option<DateTime<Utc>>,
+}
+
+impl From<NotificationRow> for NotificationResponse {
+    fn from(row: NotificationRow) -> Self {
+        Self {
+            notification_id: row.notification_id,
+            notification_type: row.notification_type,
+            title: row.title,
+            body: row.body,
+            payload: row.payload,
+            is_read: row.read_datetime.is_some(),
+            read_datetime: row.read_datetime,
+            created_datetime: row.created_datetime,
+        }
+    }
+}
+
+/// 通知一覧のクエリ
+#[derive(Debug, Deserialize)]
+pub struct NotificationListQuery {
+    /// trueの場合は未読のみ
+    pub unread_only: Option<bool>,
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+}
+
+/// 通知一覧レスポンス
+#[derive(Debug, Serialize)]
+pub struct NotificationListResponse {
+    pub notifications: Vec<NotificationResponse>,
+    pub unread_count: i64,
+    pub page: i32,
+    pub page_size: i32,
+}
@@ -0,0 +1,321 @@
+// 論理名: オーナー向け売上・稼働分析モデル
+// t_reservations / t_reservation_details / t_parking_status を駐車場・期間ごとに集計
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 集計期間の最大日数
+pub const MAX_ANALYTICS_RANGE_DAYS: i64 = 366;
+/// 期間未指定時の集計日数（終了日を含む）
+pub const DEFAULT_ANALYTICS_RANGE_DAYS: i64 = 30;
+
+/// 売上・稼働の集計単位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsGranularity {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl AnalyticsGranularity {
+    /// date_truncの単位
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    /// 日付を含む期間の開始日（週は月曜始まり、date_truncと同じ）
+    pub fn truncate(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => date - Duration::days(i64::from(date.weekday().num_days_from_monday())),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// 次の期間の開始日
+    pub fn next_start(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => start + Duration::days(1),
+            Self::Week => start + Duration::days(7),
+            Self::Month => start
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap_or(start + Duration::days(31)),
+        }
+    }
+}
+
+/// 売上・稼働分析のクエリ
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OwnerAnalyticsQuery {
+    /// 集計開始日（日本時間、未指定時は終了日を含む直近30日）
+    pub from: Option<NaiveDate>,
+    /// 集計終了日（日本時間、この日を含む。未指定時は今日）
+    pub to: Option<NaiveDate>,
+    /// day, week, month（既定: day）
+    pub granularity: Option<AnalyticsGranularity>,
+    /// 指定時はその駐車場のみ集計
+    pub parking_lot_id: Option<String>,
+}
+
+impl OwnerAnalyticsQuery {
+    /// 集計期間を検証して(開始日, 終了日)を返す
+    pub fn date_range(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+        let to = self.to.unwrap_or(today);
+        let from = self.from.unwrap_or(to - Duration::days(DEFAULT_ANALYTICS_RANGE_DAYS - 1));
+        if from > to {
+            return Err("fromはto以前の日付で指定してください".to_string());
+        }
+        if (to - from).num_days() >= MAX_ANALYTICS_RANGE_DAYS {
+            return Err(format!("集計期間は{}日以内で指定してください", MAX_ANALYTICS_RANGE_DAYS));
+        }
+        Ok((from, to))
+    }
+
+    pub fn parking_lot_id(&self) -> Option<&str> {
+        self.parking_lot_id.as_deref().map(str::trim).filter(|id| !id.is_empty())
+    }
+}
+
+/// 分析対象の駐車場
+#[derive(Debug, Clone, FromRow)]
+pub struct AnalyticsLotRow {
+    pub parking_lot_id: String,
+    pub parking_lot_name: String,
+    pub capacity: i32,
+}
+
+/// 駐車場・期間ごとの集計行（予約開始日時の期間に計上）
+#[derive(Debug, Clone, Default, FromRow)]
+pub struct AnalyticsBucketRow {
+    pub parking_lot_id: String,
+    pub period_start: NaiveDate,
+    pub revenue: i64,
+    pub reservation_count: i64,
+    pub completed_count: i64,
+    pub cancelled_count: i64,
+    pub no_show_count: i64,
+    /// 無断キャンセル率の分母（利用終了時刻を過ぎたキャンセル以外の予約）
+    pub no_show_eligible_count: i64,
+    /// 利用時間の合計（分）
+    pub occupied_minutes: f64,
+    /// 入出庫の記録がある利用の滞在時間の合計（分）
+    pub stay_minutes: f64,
+    pub stay_count: i64,
+}
+
+/// 曜日・時間帯ごとの入庫件数
+#[derive(Debug, Clone, FromRow)]
+pub struct PeakHourRow {
+    pub parking_lot_id: String,
+    /// 0: 月曜 〜 6: 日曜
+    pub weekday: i32,
+    pub hour: i32,
+    pub entry_count: i64,
+}
+
+/// 集計値
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AnalyticsSummary {
+    /// 売上（利用完了の予約金額の合計、円）
+    pub revenue: i64,
+    pub reservation_count: i64,
+    pub completed_count: i64,
+    pub cancelled_count: i64,
+    pub no_show_count: i64,
+    /// キャンセル率（0〜1）
+    pub cancellation_rate: f64,
+    /// 無断キャンセル率（0〜1、利用終了時刻を過ぎても入庫のない予約の割合）
+    pub no_show_rate: f64,
+    /// 稼働率（0〜1、利用時間の合計 ÷ 収容台数×期間）
+    pub occupancy_rate: f64,
+    /// 平均利用時間（分、入出庫の記録がある利用のみ）
+    pub average_stay_minutes: Option<f64>,
+}
+
+impl AnalyticsSummary {
+    /// 集計行を合算（capacity_minutesは稼働率の分母となる収容台数×期間の分数）
+    pub fn from_rows<'a>(rows: impl IntoIterator<Item = &'a AnalyticsBucketRow>, capacity_minutes: f64) -> Self {
+        let total = rows.into_iter().fold(AnalyticsBucketRow::default(), |mut acc, row| {
+            acc.revenue += row.revenue;
+            acc.reservation_count += row.reservation_count;
+            acc.completed_count += row.completed_count;
+            acc.cancelled_count += row.cancelled_count;
+            acc.no_show_count += row.no_show_count;
+            acc.no_show_eligible_count += row.no_show_eligible_count;
+            acc.occupied_minutes += row.occupied_minutes;
+            acc.stay_minutes += row.stay_minutes;
+            acc.stay_count += row.stay_count;
+            acc
+        });
+        let ratio = |numerator: f64, denominator: f64| {
+            if denominator > 0.0 {
+                ((numerator / denominator).min(1.0) * 10_000.0).round() / 10_000.0
+            } else {
+                0.0
+            }
+        };
+
+        Self {
+            revenue: total.revenue,
+            reservation_count: total.reservation_count,
+            completed_count: total.completed_count,
+            cancelled_count: total.cancelled_count,
+            no_show_count: total.no_show_count,
+            cancellation_rate: ratio(total.cancelled_count as f64, total.reservation_count as f64),
+            no_show_rate: ratio(total.no_show_count as f64, total.no_show_eligible_count as f64),
+            occupancy_rate: ratio(total.occupied_minutes, capacity_minutes),
+            average_stay_minutes: (total.stay_count > 0)
+                .then(|| (total.stay_minutes / total.stay_count as f64 * 10.0).round() / 10.0),
+        }
+    }
+}
+
+/// 期間ごとの集計
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyticsPeriod {
+    /// 期間の開始日・終了日（集計期間で切り詰め）
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    #[serde(flatten)]
+    pub summary: AnalyticsSummary,
+}
+
+/// 駐車場ごとの分析
+#[derive(Debug, Clone, Serialize)]
+pub struct ParkingLotAnalytics {
+    pub parking_lot_id: String,
+    pub parking_lot_name: String,
+    pub capacity: i32,
+    pub summary: AnalyticsSummary,
+    pub periods: Vec<AnalyticsPeriod>,
+    /// 曜日（0: 月曜 〜 6: 日曜）×時間帯（0〜23時）の入庫件数
+    pub peak_hours: Vec<Vec<i64>>,
+}
+
+/// 売上・稼働分析レスポンス
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnerAnalyticsResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub granularity: AnalyticsGranularity,
+    /// 全駐車場の合計
+    pub summary: AnalyticsSummary,
+    pub lots: Vec<ParkingLotAnalytics>,
+}
+
+impl OwnerAnalyticsResponse {
+    /// 駐車場・期間ごとのCSV（Excelで開けるようBOM付きUTF-8）
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("\u{feff}");
+        csv.push_str("駐車場ID,駐車場名,期間開始,期間終了,売上,予約数,利用完了数,キャンセル数,無断キャンセル数,キャンセル率,無断キャンセル率,稼働率,平均利用時間（分）\r\n");
+        for lot in &self.lots {
+            for period in &lot.periods {
+                let s = &period.summary;
+                let fields = [
+                    csv_field(&lot.parking_lot_id),
+                    csv_field(&lot.parking_lot_name),
+                    period.period_start.to_string(),
+                    period.period_end.to_string(),
+                    s.revenue.to_string(),
+                    s.reservation_count.to_string(),
+                    s.completed_count.to_string(),
+                    s.cancelled_count.to_string(),
+                    s.no_show_count.to_string(),
+                    s.cancellation_rate.to_string(),
+                    s.no_show_rate.to_string(),
+                    s.occupancy_rate.to_string(),
+                    s.average_stay_minutes.map(|m| m.to_string()).unwrap_or_default(),
+                ];
+                csv.push_str(&fields.join(","));
+                csv.push_str("\r\n");
+            }
+        }
+        csv
+    }
+}
+
+/// CSVの項目（カンマ・引用符・改行を含む場合は引用符で囲む）
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_date_range_and_granularity() {
+        let today = date(2026, 3, 31);
+        assert_eq!(OwnerAnalyticsQuery::default().date_range(today), Ok((date(2026, 3, 2), today)));
+        let query = OwnerAnalyticsQuery { from: Some(date(2026, 4, 1)), ..Default::default() };
+        assert!(query.date_range(today).is_err());
+        let query = OwnerAnalyticsQuery { from: Some(date(2024, 1, 1)), ..Default::default() };
+        assert!(query.date_range(today).is_err());
+
+        // 2026-03-04は水曜
+        assert_eq!(AnalyticsGranularity::Week.truncate(date(2026, 3, 4)), date(2026, 3, 2));
+        assert_eq!(AnalyticsGranularity::Month.truncate(date(2026, 3, 4)), date(2026, 3, 1));
+        assert_eq!(AnalyticsGranularity::Month.next_start(date(2026, 1, 1)), date(2026, 2, 1));
+    }
+
+    #[test]
+    fn test_summary_and_csv() {
+        let row = AnalyticsBucketRow {
+            parking_lot_id: "P-000001".to_string(),
+            period_start: date(2026, 3, 1),
+            revenue: 1200,
+            reservation_count: 4,
+            completed_count: 2,
+            cancelled_count: 1,
+            no_show_count: 1,
+            no_show_eligible_count: 3,
+            occupied_minutes: 360.0,
+            stay_minutes: 200.0,
+            stay_count: 2,
+            ..Default::default()
+        };
+        let summary = AnalyticsSummary::from_rows([&row, &row], 2.0 * 1440.0);
+        assert_eq!(summary.revenue, 2400);
+        assert_eq!(summary.cancellation_rate, 0.25);
+        assert_eq!(summary.no_show_rate, 0.3333);
+        assert_eq!(summary.occupancy_rate, 0.25);
+        assert_eq!(summary.average_stay_minutes, Some(100.0));
+        assert_eq!(AnalyticsSummary::from_rows([], 0.0).average_stay_minutes, None);
+
+        let response = OwnerAnalyticsResponse {
+            from: date(2026, 3, 1),
+            to: date(2026, 3, 1),
+            granularity: AnalyticsGranularity::Day,
+            summary: summary.clone(),
+            lots: vec![ParkingLotAnalytics {
+                parking_lot_id: row.parking_lot_id.clone(),
+                parking_lot_name: "駅前\"第1\",パーキング".to_string(),
+                capacity: 2,
+                summary: summary.clone(),
+                periods: vec![AnalyticsPeriod { period_start: row.period_start, period_end: row.period_start, summary }],
+                peak_hours: vec![vec![0; 24]; 7],
+            }],
+        };
+        let csv = response.to_csv();
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert!(lines[0].starts_with("\u{feff}駐車場ID,"));
+        assert_eq!(
+            lines[1],
+            "P-000001,\"駅前\"\"第1\"\",パーキング\",2026-03-01,2026-03-01,2400,8,4,2,2,0.25,0.3333,0.25,100"
+        );
+    }
+}
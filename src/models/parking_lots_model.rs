@@ -3,12 +3,18 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::geocoding::PREFECTURES;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParkingLotRequest {
     pub owner_id: String,
     pub parking_lot_name: String,
     pub postal_code: String,
+    /// 省略時は郵便番号から補完
+    #[serde(default)]
     pub prefecture: String,
+    /// 省略時は郵便番号から補完
+    #[serde(default)]
     pub city: String,
     pub address_detail: String,
     pub phone_number: String,
@@ -18,7 +24,14 @@ pub struct ParkingLotRequest {
     pub charge: String,
     pub features_tip: Option<String>,
     pub nearest_station: Option<String>,
+    /// 最寄り駅ID（駅マスタ）。省略時はnearest_stationの駅名から特定
+    #[serde(default)]
+    pub nearest_station_id: Option<String>,
+    /// 省略時は住所から補完
+    #[serde(default)]
     pub latitude: String,
+    /// 省略時は住所から補完
+    #[serde(default)]
     pub longitude: String,
     pub status: String,
     pub start_date: NaiveDate,
@@ -57,6 +70,22 @@ pub struct ParkingVehicleTypeRequest {
 pub struct ParkingImageRequest {
     pub parking_lot_id: String,
     pub image_url: String,
+    /// 中サイズ画像のパス
+    #[serde(default)]
+    pub medium_url: Option<String>,
+    /// サムネイル画像のパス
+    #[serde(default)]
+    pub thumbnail_url: Option<String>,
+    /// 画像のSHA-256ハッシュ
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub width: Option<i32>,
+    #[serde(default)]
+    pub height: Option<i32>,
+    /// ファイルサイズ（バイト）
+    #[serde(default)]
+    pub file_size: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +161,116 @@ pub struct ParkingLotRow {
     pub parking_lot_name: String,
 }
 
+/// 駐車場画像アップロード結果
+#[derive(Debug, Clone, Serialize)]
+pub struct ParkingImageUploadResponse {
+    /// 登録した画像数
+    pub uploaded: usize,
+    /// 登録済みと同一内容のためスキップした画像数
+    pub skipped_duplicates: usize,
+    /// 登録した画像のパス
+    pub images: Vec<String>,
+    /// 登録した画像の詳細（サイズ別パス・ハッシュ等）
+    pub details: Vec<ParkingImageRequest>,
+}
+
+/// 駐車場と所有オーナーのログインID
+#[derive(Debug, Clone, FromRow)]
+pub struct ParkingLotOwnerRow {
+    pub parking_lot_id: String,
+    pub owner_id: String,
+    pub owner_login_id: Option<String>,
+}
+
+/// 登録済み駐車場画像（t_parking_images）
+#[derive(Debug, Clone, FromRow)]
+pub struct ParkingImageRow {
+    pub id: i32,
+    pub parking_lot_id: String,
+    pub image_url: String,
+    pub medium_url: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub sort_order: i32,
+    pub caption: Option<String>,
+    pub is_cover: bool,
+}
+
+/// 駐車場画像レスポンス（URLは署名済み）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParkingImageResponse {
+    pub image_id: i32,
+    pub image_url: String,
+    /// 中サイズ画像のURL（未生成の場合は元画像）
+    pub medium_url: String,
+    /// サムネイル画像のURL（未生成の場合は元画像）
+    pub thumbnail_url: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub sort_order: i32,
+    pub caption: Option<String>,
+    pub is_cover: bool,
+}
+
+impl ParkingImageRow {
+    /// 保存キーを署名済みURLに変換してレスポンスを作成
+    pub fn into_response<E>(self, sign: impl Fn(&str) -> Result<String, E>) -> Result<ParkingImageResponse, E> {
+        let image_url = sign(&self.image_url)?;
+        let medium_url = match &self.medium_url {
+            Some(key) => sign(key)?,
+            None => image_url.clone(),
+        };
+        let thumbnail_url = match &self.thumbnail_url {
+            Some(key) => sign(key)?,
+            None => medium_url.clone(),
+        };
+
+        Ok(ParkingImageResponse {
+            image_id: self.id,
+            image_url,
+            medium_url,
+            thumbnail_url,
+            width: self.width,
+            height: self.height,
+            sort_order: self.sort_order,
+            caption: self.caption,
+            is_cover: self.is_cover,
+        })
+    }
+}
+
+/// 駐車場画像の並び替えリクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParkingImageOrderRequest {
+    /// 新しい表示順の画像IDリスト（登録済みの全画像を指定）
+    pub image_ids: Vec<i32>,
+}
+
+/// 駐車場画像の更新リクエスト（指定した項目のみ更新）
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParkingImageUpdateRequest {
+    /// 説明文（空文字で削除）
+    pub caption: Option<String>,
+    /// trueでカバー画像に設定
+    pub is_cover: Option<bool>,
+}
+
+impl ParkingImageUpdateRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.caption.is_none() && self.is_cover.is_none() {
+            return Err("更新する項目を指定してください".to_string());
+        }
+        if self.caption.as_ref().is_some_and(|caption| caption.chars().count() > 200) {
+            return Err("説明文は200文字以内で入力してください".to_string());
+        }
+        if self.is_cover == Some(false) {
+            return Err("カバー画像は別の画像をカバーに設定することで変更してください".to_string());
+        }
+        Ok(())
+    }
+}
+
 
 impl ParkingLotRequest {
     /// 駐車場登録リクエストの検証処理（バリデーション）
@@ -162,6 +301,9 @@ impl ParkingLotRequest {
         if self.prefecture.len() > 50 {
             return Err("都道府県は50文字以内で入力してください".to_string());
         }
+        if !PREFECTURES.contains(&self.prefecture.as_str()) {
+            return Err("都道府県が正しくありません".to_string());
+        }
 
         // 市区町村 - VARCHAR(100)
         if self.city.trim().is_empty() {
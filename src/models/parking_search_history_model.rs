@@ -1,7 +1,10 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::FromRow;
 
+use crate::models::parking_search_model::{ParkingSearchRequest, VehicleDimensions};
+
 /// t_favoritesテーブルのモデル
 #[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
 pub struct ParkingSearchHistoryResponse {
@@ -17,6 +20,8 @@ pub struct ParkingSearchHistoryResponse {
     pub condition_rental_type_id: String,
     pub rental_type: String,
     pub rental_value: String,
+    /// 同一条件での検索回数
+    pub search_count: i32,
     pub created_datetime: DateTime<Utc>,
     pub updated_datetime: DateTime<Utc>,
 }
@@ -24,4 +29,165 @@ pub struct ParkingSearchHistoryResponse {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ParkingSearchHistoryRequest {
     pub user_id: String,
-}    
+}
+
+/// 検索履歴として保存する正規化済みの検索条件
+///
+/// ページ番号・件数・ユーザーIDは含めない。座標は小数点以下4桁（約10m）に丸め、
+/// 空文字は未指定として扱う
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchHistoryCriteria {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub address: Option<String>,
+    pub station_id: Option<String>,
+    pub station_name: Option<String>,
+    pub radius_km: Option<f64>,
+    pub usage_start_datetime: Option<DateTime<Utc>>,
+    pub usage_end_datetime: Option<DateTime<Utc>>,
+    pub vehicle_type_id: Option<String>,
+    pub vehicle_id: Option<uuid::Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_default_vehicle: Option<bool>,
+    pub vehicle_dimensions: Option<VehicleDimensions>,
+    pub include_incompatible: Option<bool>,
+    pub feature_ids: Option<Vec<String>>,
+    /// 未指定時は出力しない（追加前に保存した履歴のハッシュを変えないため）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rental_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_range: Option<String>,
+    pub min_rating: Option<f64>,
+    pub max_hourly_rate: Option<i32>,
+    pub max_daily_rate: Option<i32>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+}
+
+impl SearchHistoryCriteria {
+    pub fn from_request(req: &ParkingSearchRequest) -> Self {
+        let text = |v: &Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+        let round = |v: Option<f64>| v.map(|v| (v * 10_000.0).round() / 10_000.0);
+
+        let feature_ids = req.feature_ids.as_ref().map(|ids| {
+            let mut ids: Vec<String> = ids.iter().map(|id| id.trim().to_string()).filter(|id| !id.is_empty()).collect();
+            ids.sort();
+            ids.dedup();
+            ids
+        });
+
+        Self {
+            latitude: round(req.latitude),
+            longitude: round(req.longitude),
+            address: text(&req.address),
+            station_id: text(&req.station_id),
+            station_name: text(&req.station_name),
+            radius_km: req.radius_km,
+            usage_start_datetime: req.usage_start_datetime,
+            usage_end_datetime: req.usage_end_datetime,
+            vehicle_type_id: text(&req.vehicle_type_id),
+            vehicle_id: req.vehicle_id,
+            use_default_vehicle: req.use_default_vehicle.filter(|v| *v),
+            vehicle_dimensions: req.vehicle_dimensions.clone().filter(|d| !d.is_empty()),
+            include_incompatible: req.include_incompatible.filter(|v| *v),
+            feature_ids: feature_ids.filter(|ids| !ids.is_empty()),
+            rental_type: text(&req.rental_type),
+            price_range: text(&req.price_range),
+            min_rating: req.min_rating,
+            max_hourly_rate: req.max_hourly_rate,
+            max_daily_rate: req.max_daily_rate,
+            sort_by: text(&req.sort_by),
+            sort_order: text(&req.sort_order),
+        }
+    }
+
+    /// 重複排除用のハッシュ（正規化済み条件のJSONのSHA-256）
+    pub fn condition_hash(&self) -> String {
+        let json = serde_json::to_string(self).unwrap_or_default();
+        Sha256::digest(json.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// 一覧表示用のフリーワード（住所または駅名）
+    pub fn keyword(&self) -> Option<String> {
+        self.address.clone().or_else(|| self.station_name.clone())
+    }
+
+    /// 一覧表示用の利用日時（日本時間）
+    pub fn format_use_date(value: Option<DateTime<Utc>>) -> Option<String> {
+        let jst = FixedOffset::east_opt(9 * 3600)?;
+        value.map(|dt| dt.with_timezone(&jst).format("%Y/%m/%d %H:%M").to_string())
+    }
+
+    /// 再検索用のリクエストに変換
+    pub fn into_request(self, user_id: &str, page: Option<i32>, page_size: Option<i32>) -> ParkingSearchRequest {
+        ParkingSearchRequest {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            address: self.address,
+            station_id: self.station_id,
+            station_name: self.station_name,
+            radius_km: self.radius_km,
+            usage_start_datetime: self.usage_start_datetime,
+            usage_end_datetime: self.usage_end_datetime,
+            vehicle_type_id: self.vehicle_type_id,
+            feature_ids: self.feature_ids,
+            rental_type: self.rental_type,
+            price_range: self.price_range,
+            min_rating: self.min_rating,
+            max_hourly_rate: self.max_hourly_rate,
+            max_daily_rate: self.max_daily_rate,
+            sort_by: self.sort_by,
+            sort_order: self.sort_order,
+            page,
+            page_size,
+            cursor: None,
+            favorites_only: None,
+            user_id: Some(user_id.to_string()),
+            vehicle_id: self.vehicle_id,
+            use_default_vehicle: self.use_default_vehicle,
+            vehicle_dimensions: self.vehicle_dimensions,
+            include_incompatible: self.include_incompatible,
+        }
+    }
+}
+
+/// 検索履歴からの再検索のクエリ
+#[derive(Debug, Deserialize)]
+pub struct SearchHistoryRerunQuery {
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+    /// 次ページ取得用のカーソル（pageの代わりに指定）
+    pub cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condition_hash_ignores_paging_and_noise() {
+        let base = ParkingSearchRequest {
+            latitude: Some(35.658034),
+            longitude: Some(139.701636),
+            feature_ids: Some(vec!["F2".to_string(), "F1".to_string()]),
+            page: Some(1),
+            ..Default::default()
+        };
+        let repeated = ParkingSearchRequest {
+            latitude: Some(35.658012),
+            address: Some("  ".to_string()),
+            feature_ids: Some(vec!["F1".to_string(), " F2 ".to_string(), "F1".to_string()]),
+            page: Some(3),
+            ..base.clone()
+        };
+        let other = ParkingSearchRequest { radius_km: Some(1.0), ..base.clone() };
+
+        let hash = SearchHistoryCriteria::from_request(&base).condition_hash();
+        assert_eq!(hash, SearchHistoryCriteria::from_request(&repeated).condition_hash());
+        assert_ne!(hash, SearchHistoryCriteria::from_request(&other).condition_hash());
+        assert_eq!(hash.len(), 64);
+
+        let start = "2025-05-04T01:00:00Z".parse::<DateTime<Utc>>().ok();
+        assert_eq!(SearchHistoryCriteria::format_use_date(start).as_deref(), Some("2025/05/04 10:00"));
+    }
+}
@@ -9,6 +9,7 @@ use crate::models::{
     t_parking_rental_types_model::RentalTypeResponse,
     m_parking_vehicle_types_model::VehicleTypeResponse,
     m_parking_features_model::ParkingFeatureResponse,
+    parking_lots_model::{ParkingImageResponse, ParkingLimitModel},
 };
 
 /// 駐車場検索リクエストモデル
@@ -20,6 +21,10 @@ pub struct ParkingSearchRequest {
     pub longitude: Option<f64>,
     /// 検索地点の住所
     pub address: Option<String>,
+    /// 検索地点の駅ID（駅マスタ）
+    pub station_id: Option<String>,
+    /// 検索地点の駅名（例: 新宿駅。同名の駅が複数ある場合はstation_idを指定）
+    pub station_name: Option<String>,
     /// 検索半径（km）
     pub radius_km: Option<f64>,
     /// 利用予定日時（開始）
@@ -28,8 +33,12 @@ pub struct ParkingSearchRequest {
     pub usage_end_datetime: Option<chrono::DateTime<chrono::Utc>>,
     /// 車両タイプID
     pub vehicle_type_id: Option<String>,
-    /// 希望する設備・機能IDリスト
+    /// 希望する設備・機能（特徴ID、特徴種別、または「種別:値」。すべてを満たす駐車場に絞り込む）
     pub feature_ids: Option<Vec<String>>,
+    /// 貸出タイプ（時間単位、日間単位）
+    pub rental_type: Option<String>,
+    /// 料金帯（検索フィルター一覧のprice_rangesの値）
+    pub price_range: Option<String>,
     /// 最低評価値
     pub min_rating: Option<f64>,
     /// 最大料金（時間単位）
@@ -44,10 +53,121 @@ pub struct ParkingSearchRequest {
     pub page: Option<i32>,
     /// 1ページあたりの件数
     pub page_size: Option<i32>,
+    /// 次ページ取得用のカーソル（前回レスポンスのpagination.next_cursor、pageの代わりに指定）
+    #[serde(default)]
+    pub cursor: Option<String>,
     /// お気に入りのみ表示するか
     pub favorites_only: Option<bool>,
     /// ユーザーID（お気に入り検索用）
     pub user_id: Option<String>,
+    /// 登録車両ID（指定時はその車両の車種・寸法で検索）
+    pub vehicle_id: Option<uuid::Uuid>,
+    /// 車両の指定がない場合にデフォルト車両の車種・寸法で検索するか（既定: 使用しない）
+    #[serde(default)]
+    pub use_default_vehicle: Option<bool>,
+    /// 車両寸法（駐車場の寸法制限と照合）
+    pub vehicle_dimensions: Option<VehicleDimensions>,
+    /// 寸法制限に合わない駐車場も結果に含めるか（既定: 除外）
+    pub include_incompatible: Option<bool>,
+}
+
+/// 検索用の車両寸法（t_parking_limits と同じ mm / kg 単位）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VehicleDimensions {
+    pub length_mm: Option<i32>,
+    pub width_mm: Option<i32>,
+    pub height_mm: Option<i32>,
+    pub weight_kg: Option<i32>,
+}
+
+impl VehicleDimensions {
+    pub fn validate(&self) -> Result<(), String> {
+        let checks = [
+            (self.length_mm, "車両の長さ", 20000),
+            (self.width_mm, "車両の幅", 5000),
+            (self.height_mm, "車両の高さ", 5000),
+            (self.weight_kg, "車両の重量", 30000),
+        ];
+        for (value, label, max) in checks {
+            if value.is_some_and(|v| !(1..=max).contains(&v)) {
+                return Err(format!("{}は1以上{}以下で指定してください", label, max));
+            }
+        }
+        Ok(())
+    }
+
+    /// 寸法が1つも指定されていないか
+    pub fn is_empty(&self) -> bool {
+        self.length_mm.is_none() && self.width_mm.is_none() && self.height_mm.is_none() && self.weight_kg.is_none()
+    }
+
+    /// 駐車場の制限と照合し、超過している制限と注意事項を返す
+    pub fn check_limits(&self, limits: Option<&ParkingLimitModel>) -> VehicleCompatibility {
+        let Some(limits) = limits else {
+            return VehicleCompatibility { is_compatible: true, reasons: vec![], notes: vec![] };
+        };
+
+        let exceeded = [
+            (self.length_mm, limits.length_limit, "長さ制限", format_meters as fn(i32) -> String),
+            (self.width_mm, limits.width_limit, "幅制限", format_meters),
+            (self.height_mm, limits.height_limit, "高さ制限", format_meters),
+            (self.weight_kg, limits.weight_limit, "重量制限", format_weight),
+        ];
+        let reasons: Vec<String> = exceeded
+            .into_iter()
+            .filter_map(|(value, limit, label, format)| match (value, limit) {
+                (Some(v), Some(l)) if v > l => Some(format!("{} {}", label, format(l))),
+                _ => None,
+            })
+            .collect();
+
+        // 車高・タイヤ幅・車下の制限は自由記述のため注意事項として返す
+        let notes = [
+            ("車高制限", &limits.car_height_limit),
+            ("タイヤ幅制限", &limits.tire_width_limit),
+            ("車下制限", &limits.car_bottom_limit),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(|v| format!("{}: {}", label, v))
+        })
+        .collect();
+
+        VehicleCompatibility { is_compatible: reasons.is_empty(), reasons, notes }
+    }
+}
+
+/// 車両と駐車場の寸法制限の適合結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VehicleCompatibility {
+    /// 駐車可能か
+    pub is_compatible: bool,
+    /// 超過している制限（例: 高さ制限 2.0m）
+    pub reasons: Vec<String>,
+    /// 確認が必要な制限（例: 車下制限: エアロ不可）
+    pub notes: Vec<String>,
+}
+
+/// mm を m 表記に変換（例: 2000 → 2.0m、1550 → 1.55m）
+fn format_meters(mm: i32) -> String {
+    if mm % 100 == 0 {
+        format!("{:.1}m", mm as f64 / 1000.0)
+    } else {
+        format!("{:.2}m", mm as f64 / 1000.0)
+    }
+}
+
+/// kg を表示用に変換（例: 2500 → 2.5t、800 → 800kg）
+fn format_weight(kg: i32) -> String {
+    if kg >= 1000 && kg % 100 == 0 {
+        format!("{:.1}t", kg as f64 / 1000.0)
+    } else {
+        format!("{}kg", kg)
+    }
 }
 
 /// 駐車場検索レスポンスモデル
@@ -76,6 +196,9 @@ pub struct ParkingSearchResult {
     pub vehicle_types: Vec<VehicleTypeResponse>,
     /// 設備・機能リスト
     pub features: Vec<ParkingFeatureResponse>,
+    /// 駐車場画像リスト（表示順、サムネイルURL付き）
+    #[serde(default)]
+    pub images: Vec<ParkingImageResponse>,
     /// 距離情報（検索地点からの距離）
     pub distance_info: Option<DistanceInfo>,
     /// 利用可能性情報
@@ -86,6 +209,12 @@ pub struct ParkingSearchResult {
     pub favorite_info: Option<FavoriteInfo>,
     /// 評価情報
     pub rating_info: Option<RatingInfo>,
+    /// 車両寸法との適合結果（車両・寸法を指定した場合のみ）
+    #[serde(default)]
+    pub vehicle_compatibility: Option<VehicleCompatibility>,
+    /// おすすめ順のスコア内訳（sort_by=recommendedの場合のみ、調整・確認用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommendation_score: Option<RecommendationScore>,
 }
 
 /// ページネーション情報
@@ -103,6 +232,25 @@ pub struct PaginationInfo {
     pub has_previous: bool,
     /// 次のページがあるか
     pub has_next: bool,
+    /// 次ページ取得用のカーソル（次のページがある場合のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// 一覧取得のページ指定（page/page_sizeまたはcursor）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PageQuery {
+    pub page: Option<i32>,
+    pub page_size: Option<i32>,
+    /// 次ページ取得用のカーソル（前回レスポンスのpagination.next_cursor）
+    pub cursor: Option<String>,
+}
+
+/// ページネーション付きの一覧
+#[derive(Debug, Clone, Serialize)]
+pub struct PagedResponse<T> {
+    pub items: Vec<T>,
+    pub pagination: PaginationInfo,
 }
 
 /// 検索条件情報
@@ -166,6 +314,114 @@ pub struct SortInfo {
     pub sort_display_name: String,
 }
 
+/// 検索結果の並べ替え項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchSortBy {
+    /// 検索地点からの距離
+    Distance,
+    /// 利用予定時間の推定料金
+    Price,
+    /// 空き台数（利用できない駐車場は末尾）
+    Availability,
+    /// 登録日時
+    Newest,
+    /// 距離・料金・利用実績を合わせたスコア
+    Recommended,
+}
+
+impl SearchSortBy {
+    pub const ALL: [Self; 5] = [
+        Self::Distance,
+        Self::Price,
+        Self::Availability,
+        Self::Newest,
+        Self::Recommended,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|sort| sort.as_str() == value)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Distance => "distance",
+            Self::Price => "price",
+            Self::Availability => "availability",
+            Self::Newest => "newest",
+            Self::Recommended => "recommended",
+        }
+    }
+
+    /// sort_order未指定時の並び順（距離・料金は昇順、その他は降順）
+    pub fn default_descending(self) -> bool {
+        !matches!(self, Self::Distance | Self::Price)
+    }
+
+    /// 表示名
+    pub fn display_name(self, descending: bool) -> &'static str {
+        match (self, descending) {
+            (Self::Distance, false) => "距離順",
+            (Self::Distance, true) => "距離の遠い順",
+            (Self::Price, false) => "料金の安い順",
+            (Self::Price, true) => "料金の高い順",
+            (Self::Availability, true) => "空き台数の多い順",
+            (Self::Availability, false) => "空き台数の少ない順",
+            (Self::Newest, true) => "新着順",
+            (Self::Newest, false) => "登録の古い順",
+            (Self::Recommended, _) => "おすすめ順",
+        }
+    }
+}
+
+/// おすすめ順の重み（距離・料金・利用実績、合計1.0）
+///
+/// 評価は集計されていないため、集計を始めるまでスコアに含めない
+pub const SCORE_WEIGHT_DISTANCE: f64 = 0.5;
+pub const SCORE_WEIGHT_PRICE: f64 = 0.35;
+pub const SCORE_WEIGHT_USAGE: f64 = 0.15;
+/// 利用実績が満点になる利用回数
+pub const SCORE_FULL_USAGE_COUNT: i64 = 5;
+
+/// おすすめ順のスコア内訳
+///
+/// 各要素を0〜1に正規化して重みを掛け、合計を100点満点にしたもの
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecommendationScore {
+    /// 合計スコア（0〜100）
+    pub total_score: f64,
+    /// 距離（検索半径に対して近いほど高い）
+    pub distance: ScoreComponent,
+    /// 料金（検索結果の中で安いほど高い）
+    pub price: ScoreComponent,
+    /// 利用実績（同じ駐車場を利用した回数、5回で満点）
+    pub usage: ScoreComponent,
+}
+
+/// スコアの要素
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreComponent {
+    /// 元の値（距離km・推定料金・利用回数、不明な場合はなし）
+    pub raw_value: Option<f64>,
+    /// 正規化した値（0〜1、不明な場合は0）
+    pub normalized: f64,
+    /// 重み
+    pub weight: f64,
+    /// 合計スコアへの寄与（normalized × weight × 100）
+    pub points: f64,
+}
+
+impl ScoreComponent {
+    pub fn new(raw_value: Option<f64>, normalized: f64, weight: f64) -> Self {
+        let normalized = normalized.clamp(0.0, 1.0);
+        Self {
+            raw_value,
+            normalized,
+            weight,
+            points: normalized * weight * 100.0,
+        }
+    }
+}
+
 /// 検索統計情報
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchStats {
@@ -192,6 +448,42 @@ pub struct DistanceInfo {
     pub walking_time_minutes: Option<i32>,
     /// 車での時間（分）推定
     pub driving_time_minutes: Option<i32>,
+    /// 検索駅からの徒歩距離・時間（駅指定検索の場合）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub station_walk: Option<StationWalkInfo>,
+}
+
+/// 駅からの徒歩情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StationWalkInfo {
+    pub station_id: String,
+    pub station_name: String,
+    /// 徒歩距離（m、直線距離に道のり係数を掛けた推定値）
+    pub walking_distance_m: i32,
+    /// 徒歩時間（分、80m/分で端数切り上げ）
+    pub walking_time_minutes: i32,
+    /// 表示用（例: 新宿駅 徒歩5分）
+    pub walking_display: String,
+}
+
+impl StationWalkInfo {
+    /// 直線距離に対する道のりの係数
+    const ROUTE_FACTOR: f64 = 1.25;
+    /// 徒歩速度（m/分、不動産の表示に関する公正競争規約に準拠）
+    const WALKING_METERS_PER_MINUTE: f64 = 80.0;
+
+    pub fn new(station_id: &str, station_name: &str, straight_distance_km: f64) -> Self {
+        let walking_distance_m = (straight_distance_km * 1000.0 * Self::ROUTE_FACTOR).round() as i32;
+        let walking_time_minutes =
+            ((walking_distance_m as f64 / Self::WALKING_METERS_PER_MINUTE).ceil() as i32).max(1);
+        Self {
+            station_id: station_id.to_string(),
+            station_name: station_name.to_string(),
+            walking_distance_m,
+            walking_time_minutes,
+            walking_display: format!("{} 徒歩{}分", station_name, walking_time_minutes),
+        }
+    }
 }
 
 /// 利用可能性情報
@@ -246,6 +538,12 @@ pub struct FavoriteInfo {
     pub favorite_added_at: Option<chrono::DateTime<chrono::Utc>>,
     /// お気に入りメモ
     pub favorite_memo: Option<String>,
+    /// ユーザー指定の表示順
+    #[serde(default)]
+    pub favorite_order: Option<i32>,
+    /// お気に入りラベル
+    #[serde(default)]
+    pub favorite_labels: Vec<String>,
 }
 
 /// 評価情報
@@ -298,6 +596,368 @@ pub struct FavoriteOperationRequest {
     pub operation: String,
     /// お気に入りメモ（追加時のみ）
     pub memo: Option<String>,
+    /// お気に入りラベル（追加時のみ）
+    #[serde(default)]
+    pub labels: Option<Vec<String>>,
+}
+
+/// お気に入りのメモ・ラベル更新リクエスト（未指定の項目は変更しない）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoriteUpdateRequest {
+    /// メモ（空文字でクリア）
+    pub memo: Option<String>,
+    /// ラベル（指定した内容で置き換え）
+    pub labels: Option<Vec<String>>,
+}
+
+/// お気に入りの並べ替えリクエスト
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoriteOrderRequest {
+    /// 並べ替え後の駐車場ID（お気に入り全件を表示順に指定）
+    pub parking_lot_ids: Vec<String>,
+}
+
+/// お気に入り一覧の絞り込み・並べ替え条件（SQLで適用）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FavoriteListCriteria {
+    pub label: Option<String>,
+    pub keyword: Option<String>,
+    pub filters: SearchFilterCriteria,
+    /// custom（ユーザー指定順）, added_at（登録日時順）, name（名前順）
+    pub sort_by: String,
+    pub sort_order: String,
+}
+
+/// 駐車場検索の絞り込み・並べ替え条件（距離・推定料金・おすすめ順のスコアを含めリポジトリのSQLで適用）
+#[derive(Debug, Clone)]
+pub struct SearchPageCriteria {
+    pub filters: SearchFilterCriteria,
+    /// 検索地点（緯度, 経度）。指定時は検索半径内の座標のある駐車場のみ
+    pub origin: Option<(f64, f64)>,
+    pub radius_km: f64,
+    /// 車両寸法（寸法制限に合わない駐車場はinclude_incompatible指定時のみ含める）
+    pub dimensions: VehicleDimensions,
+    pub include_incompatible: bool,
+    /// お気に入りのみ検索する場合のユーザーID
+    pub favorites_user_id: Option<String>,
+    /// 利用実績を数えるユーザーID（おすすめ順の場合のみ）
+    pub usage_user_id: Option<String>,
+    /// 推定料金の利用時間（分）
+    pub duration_minutes: Option<i32>,
+    pub sort_by: SearchSortBy,
+    pub descending: bool,
+}
+
+impl SearchPageCriteria {
+    /// 検索リクエストから作成（user_idは認証済みの利用者）
+    pub fn from_request(request: &ParkingSearchRequest, user_id: Option<&str>) -> Self {
+        let (sort_by, descending) = request.sort();
+        let user_id = user_id.map(str::to_string);
+
+        Self {
+            filters: SearchFilterCriteria::from_request(request),
+            origin: request.latitude.zip(request.longitude),
+            radius_km: request.radius_km.unwrap_or(5.0),
+            dimensions: request.vehicle_dimensions.clone().unwrap_or_default(),
+            include_incompatible: request.include_incompatible.unwrap_or(false),
+            favorites_user_id: user_id.clone().filter(|_| request.is_favorites_search()),
+            usage_user_id: user_id.filter(|_| sort_by == SearchSortBy::Recommended),
+            duration_minutes: request.get_duration_minutes(),
+            sort_by,
+            descending,
+        }
+    }
+}
+
+/// 車種・設備・貸出タイプ・料金帯の絞り込み条件（リポジトリのSQLで適用）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchFilterCriteria {
+    pub vehicle_type_id: Option<String>,
+    pub feature_ids: Vec<String>,
+    pub rental_type: Option<String>,
+    /// 料金の下限・上限（料金帯から変換）
+    pub min_charge: Option<i32>,
+    pub max_charge: Option<i32>,
+}
+
+impl SearchFilterCriteria {
+    /// 検索リクエストから絞り込み条件を作成（空文字は未指定として扱う）
+    pub fn from_request(request: &ParkingSearchRequest) -> Self {
+        let text = |v: &Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+        let price_range = request.price_range.as_deref().and_then(PriceRangeBucket::find);
+
+        Self {
+            vehicle_type_id: text(&request.vehicle_type_id),
+            feature_ids: request.feature_ids.iter()
+                .flatten()
+                .map(|id| id.trim())
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect(),
+            rental_type: text(&request.rental_type),
+            min_charge: price_range.and_then(|r| r.min_charge),
+            max_charge: price_range.and_then(|r| r.max_charge),
+        }
+    }
+}
+
+/// 料金帯（駐車場の料金 t_parking_lots.charge で区分）
+#[derive(Debug, Clone, Copy)]
+pub struct PriceRangeBucket {
+    pub key: &'static str,
+    pub display_name: &'static str,
+    pub min_charge: Option<i32>,
+    pub max_charge: Option<i32>,
+}
+
+/// 検索フィルターで選択できる料金帯
+pub const PRICE_RANGE_BUCKETS: [PriceRangeBucket; 5] = [
+    PriceRangeBucket { key: "0-300", display_name: "300円以下", min_charge: None, max_charge: Some(300) },
+    PriceRangeBucket { key: "301-500", display_name: "301〜500円", min_charge: Some(301), max_charge: Some(500) },
+    PriceRangeBucket { key: "501-1000", display_name: "501〜1,000円", min_charge: Some(501), max_charge: Some(1000) },
+    PriceRangeBucket { key: "1001-3000", display_name: "1,001〜3,000円", min_charge: Some(1001), max_charge: Some(3000) },
+    PriceRangeBucket { key: "3001-", display_name: "3,001円以上", min_charge: Some(3001), max_charge: None },
+];
+
+impl PriceRangeBucket {
+    pub fn find(key: &str) -> Option<&'static PriceRangeBucket> {
+        PRICE_RANGE_BUCKETS.iter().find(|r| r.key == key.trim())
+    }
+
+    pub fn contains(&self, charge: i32) -> bool {
+        self.min_charge.is_none_or(|min| charge >= min) && self.max_charge.is_none_or(|max| charge <= max)
+    }
+}
+
+/// 検索フィルター一覧の取得条件（地点指定時はその周辺の駐車場で件数を集計）
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchFilterCatalogueQuery {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub radius_km: Option<f64>,
+}
+
+/// 検索フィルターの選択肢と該当駐車場数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterOption {
+    /// 検索リクエストに指定する値
+    pub value: String,
+    /// 表示名
+    pub display_name: String,
+    /// 該当する駐車場数
+    pub count: i64,
+}
+
+/// 設備・機能フィルター（特徴種別ごと）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFilterGroup {
+    pub feature_type: String,
+    pub options: Vec<FilterOption>,
+}
+
+/// 検索フィルター一覧
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchFilterCatalogue {
+    /// 集計対象の駐車場数
+    pub total_count: i64,
+    /// 車種（vehicle_type_id に指定）
+    pub vehicle_types: Vec<FilterOption>,
+    /// 設備・機能（feature_ids に指定）
+    pub features: Vec<FeatureFilterGroup>,
+    /// 貸出タイプ（rental_type に指定）
+    pub rental_types: Vec<FilterOption>,
+    /// 料金帯（price_range に指定）
+    pub price_ranges: Vec<FilterOption>,
+}
+
+/// 近隣駐車場検索の条件（地図のピン表示用）
+#[derive(Debug, Clone, Deserialize)]
+pub struct NearbyParkingQuery {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// 検索半径（km、既定: 1.0）
+    pub radius_km: Option<f64>,
+    /// 最大件数（既定: 50）
+    pub limit: Option<i64>,
+}
+
+/// 近隣検索の検索半径の上限（km）
+pub const MAX_NEARBY_RADIUS_KM: f64 = 20.0;
+/// 近隣検索の最大件数の上限
+pub const MAX_NEARBY_LIMIT: i64 = 200;
+
+impl NearbyParkingQuery {
+    /// 近隣検索条件のバリデーション
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-90.0..=90.0).contains(&self.latitude) {
+            return Err("緯度は-90.0から90.0の範囲で指定してください".to_string());
+        }
+        if !(-180.0..=180.0).contains(&self.longitude) {
+            return Err("経度は-180.0から180.0の範囲で指定してください".to_string());
+        }
+        if self.radius_km.is_some_and(|r| r <= 0.0 || r > MAX_NEARBY_RADIUS_KM) {
+            return Err(format!("検索半径は{}km以下で指定してください", MAX_NEARBY_RADIUS_KM));
+        }
+        if self.limit.is_some_and(|l| !(1..=MAX_NEARBY_LIMIT).contains(&l)) {
+            return Err(format!("件数は1以上{}以下で指定してください", MAX_NEARBY_LIMIT));
+        }
+        Ok(())
+    }
+}
+
+/// 地図表示用の駐車場ピン
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NearbyParkingPin {
+    pub parking_lot_id: String,
+    pub parking_lot_name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// 検索地点からの直線距離（km）
+    pub distance_km: f64,
+    pub capacity: i32,
+    pub available_capacity: Option<i32>,
+    pub rental_type: Option<String>,
+    pub charge: String,
+}
+
+/// 近隣駐車場検索レスポンス
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NearbyParkingResponse {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_km: f64,
+    /// 距離の近い順
+    pub parking_lots: Vec<NearbyParkingPin>,
+}
+
+/// 地図の表示範囲による検索条件
+#[derive(Debug, Clone, Deserialize)]
+pub struct MapViewportQuery {
+    pub min_lat: f64,
+    pub max_lat: f64,
+    pub min_lng: f64,
+    pub max_lng: f64,
+    /// 地図のズームレベル（0〜22）
+    pub zoom: i32,
+}
+
+/// このズームレベル以下ではクラスタを返す
+pub const MAP_CLUSTER_MAX_ZOOM: i32 = 14;
+/// ピンで返す最大件数（超える場合はクラスタを返す）
+pub const MAP_MAX_PINS: i64 = 1000;
+/// 地図タイル1枚（256px）あたりのクラスタの分割数
+const MAP_CLUSTER_CELLS_PER_TILE: f64 = 4.0;
+
+impl MapViewportQuery {
+    /// 表示範囲のバリデーション
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-90.0..=90.0).contains(&self.min_lat) || !(-90.0..=90.0).contains(&self.max_lat) {
+            return Err("緯度は-90.0から90.0の範囲で指定してください".to_string());
+        }
+        if !(-180.0..=180.0).contains(&self.min_lng) || !(-180.0..=180.0).contains(&self.max_lng) {
+            return Err("経度は-180.0から180.0の範囲で指定してください".to_string());
+        }
+        if self.min_lat >= self.max_lat || self.min_lng >= self.max_lng {
+            return Err("表示範囲の最小値は最大値より小さくしてください".to_string());
+        }
+        if !(0..=22).contains(&self.zoom) {
+            return Err("ズームレベルは0から22の範囲で指定してください".to_string());
+        }
+        Ok(())
+    }
+
+    /// クラスタにまとめる格子の一辺（度）。ズームが1段階上がるごとに半分になる
+    pub fn cluster_cell_degrees(&self) -> f64 {
+        360.0 / 2f64.powi(self.zoom) / MAP_CLUSTER_CELLS_PER_TILE
+    }
+}
+
+/// 地図検索のピン（[駐車場ID, 緯度, 経度, 空き台数, 料金]）
+pub type MapPin = (String, f64, f64, Option<i32>, Option<i32>);
+/// 地図検索のクラスタ（[件数, 重心の緯度, 重心の経度, 最低料金, 駐車場ID（1件の場合のみ）]）
+pub type MapCluster = (i64, f64, f64, Option<i32>, Option<String>);
+
+/// 地図検索レスポンス（転送量を抑えるため、ピン・クラスタは配列で返す）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapSearchResponse {
+    /// pins または clusters
+    pub mode: String,
+    pub zoom: i32,
+    /// 配列の各要素の項目名
+    pub fields: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pins: Vec<MapPin>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clusters: Vec<MapCluster>,
+}
+
+impl MapSearchResponse {
+    pub fn pins(zoom: i32, pins: Vec<MapPin>) -> Self {
+        Self {
+            mode: "pins".to_string(),
+            zoom,
+            fields: ["parking_lot_id", "latitude", "longitude", "available_capacity", "charge"]
+                .map(String::from).to_vec(),
+            pins,
+            clusters: Vec::new(),
+        }
+    }
+
+    pub fn clusters(zoom: i32, clusters: Vec<MapCluster>) -> Self {
+        Self {
+            mode: "clusters".to_string(),
+            zoom,
+            fields: ["count", "latitude", "longitude", "min_charge", "parking_lot_id"]
+                .map(String::from).to_vec(),
+            pins: Vec::new(),
+            clusters,
+        }
+    }
+}
+
+/// エリア別統計の取得条件
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParkingStatsQuery {
+    /// 集計単位（prefecture: 都道府県, city: 市区町村、既定: prefecture）
+    pub group_by: Option<String>,
+    /// 都道府県での絞り込み
+    pub prefecture: Option<String>,
+}
+
+/// エリア別の駐車場統計
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ParkingAreaStats {
+    pub prefecture: String,
+    /// 市区町村（都道府県単位の集計ではNone）
+    pub city: Option<String>,
+    /// 駐車場数
+    pub lot_count: i64,
+    /// 総収容台数
+    pub total_capacity: i64,
+    /// 現在利用中（予約中・承認済みで利用時間内）の台数
+    pub occupied_count: i64,
+    /// 現在の稼働率（%）
+    pub occupancy_rate: f64,
+    /// 平均料金（料金が数値で登録されている駐車場のみ）
+    pub average_charge: Option<f64>,
+    pub min_charge: Option<i32>,
+    pub max_charge: Option<i32>,
+}
+
+/// エリア別統計レスポンス
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParkingStatsResponse {
+    pub group_by: String,
+    /// 集計時点
+    pub aggregated_at: chrono::DateTime<chrono::Utc>,
+    pub areas: Vec<ParkingAreaStats>,
+}
+
+/// お気に入りラベルと件数
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FavoriteLabelCount {
+    pub label: String,
+    pub count: i64,
 }
 
 /// お気に入り操作レスポンス
@@ -316,9 +976,13 @@ pub struct FavoriteOperationResponse {
 impl ParkingSearchRequest {
     /// 検索リクエストのバリデーション
     pub fn validate(&self) -> Result<(), String> {
-        // 位置情報または住所のいずれかは必須
-        if self.latitude.is_none() && self.longitude.is_none() && self.address.is_none() {
-            return Err("検索地点の指定が必要です（緯度経度または住所）".to_string());
+        // 位置情報・住所・駅のいずれかは必須
+        if self.latitude.is_none()
+            && self.longitude.is_none()
+            && self.address.is_none()
+            && !self.is_station_search()
+        {
+            return Err("検索地点の指定が必要です（緯度経度、住所または駅）".to_string());
         }
 
         // 緯度経度が指定されている場合の妥当性チェック
@@ -357,6 +1021,17 @@ impl ParkingSearchRequest {
             }
         }
 
+        // 車両寸法の妥当性チェック
+        if let Some(dimensions) = &self.vehicle_dimensions {
+            dimensions.validate()?;
+        }
+
+        // 料金帯の妥当性チェック
+        let price_range = self.price_range.as_deref().filter(|r| !r.trim().is_empty());
+        if price_range.is_some_and(|r| PriceRangeBucket::find(r).is_none()) {
+            return Err("料金帯は検索フィルター一覧のprice_rangesの値で指定してください".to_string());
+        }
+
         // ページング情報の妥当性チェック
         if let Some(page) = self.page {
             if page < 1 {
@@ -370,6 +1045,23 @@ impl ParkingSearchRequest {
             }
         }
 
+        // 並べ替え条件の妥当性チェック（評価は集計されていないため評価順は指定できない）
+        if self.sort_by.as_deref() == Some("rating") {
+            return Err("評価は集計されていないため、sort_byにratingは指定できません".to_string());
+        }
+        if self.sort_by.as_deref().is_some_and(|v| SearchSortBy::parse(v).is_none()) {
+            return Err(
+                "sort_byはdistance、price、availability、newest、recommendedのいずれかで指定してください".to_string(),
+            );
+        }
+        if self.sort_order.as_deref().is_some_and(|v| !matches!(v, "asc" | "desc")) {
+            return Err("sort_orderはascまたはdescで指定してください".to_string());
+        }
+
+        if self.cursor.as_deref().is_some_and(|c| !c.is_empty()) && self.page.is_some_and(|p| p != 1) {
+            return Err("cursorとpageは同時に指定できません".to_string());
+        }
+
         Ok(())
     }
 
@@ -392,6 +1084,17 @@ impl ParkingSearchRequest {
         }
     }
 
+    /// 並べ替え項目と降順かどうか（未指定時は距離順、おすすめ順は常に降順）
+    pub fn sort(&self) -> (SearchSortBy, bool) {
+        let sort_by = self.sort_by.as_deref().and_then(SearchSortBy::parse).unwrap_or(SearchSortBy::Distance);
+        let descending = match (sort_by, self.sort_order.as_deref()) {
+            (SearchSortBy::Recommended, _) => true,
+            (_, Some(order)) => order == "desc",
+            (_, None) => sort_by.default_descending(),
+        };
+        (sort_by, descending)
+    }
+
     /// 検索期間の長さを分単位で取得
     pub fn get_duration_minutes(&self) -> Option<i32> {
         if let (Some(start), Some(end)) = (self.usage_start_datetime, self.usage_end_datetime) {
@@ -402,19 +1105,71 @@ impl ParkingSearchRequest {
         }
     }
 
+    /// 駅検索かどうか
+    pub fn is_station_search(&self) -> bool {
+        [&self.station_id, &self.station_name]
+            .iter()
+            .any(|v| v.as_deref().is_some_and(|v| !v.trim().is_empty()))
+    }
+
     /// お気に入り検索かどうか
     pub fn is_favorites_search(&self) -> bool {
         self.favorites_only.unwrap_or(false) && self.user_id.is_some()
     }
 
+    /// 1ページ目の検索かどうか（カーソル・2ページ目以降の指定がない）
+    pub fn is_first_page(&self) -> bool {
+        self.cursor.as_deref().is_none_or(str::is_empty) && self.page.unwrap_or(1) <= 1
+    }
+
     /// フィルターが適用されているかチェック
     pub fn has_filters(&self) -> bool {
         self.vehicle_type_id.is_some() ||
+        self.vehicle_dimensions.as_ref().is_some_and(|d| !d.is_empty()) ||
         (self.feature_ids.is_some() && !self.feature_ids.as_ref().unwrap().is_empty()) ||
+        self.rental_type.is_some() ||
+        self.price_range.is_some() ||
         self.min_rating.is_some() ||
         self.max_hourly_rate.is_some() ||
         self.max_daily_rate.is_some()
     }
+
+    /// 検索結果に反映した絞り込み条件の一覧（SearchInfo.applied_filters 用）
+    pub fn applied_filters(&self) -> Vec<AppliedFilter> {
+        let filter = |name: &str, value: String, display_name: String| AppliedFilter {
+            filter_name: name.to_string(),
+            filter_value: value,
+            display_name,
+        };
+        let criteria = SearchFilterCriteria::from_request(self);
+        let mut filters = Vec::new();
+
+        if let Some(vehicle_type) = criteria.vehicle_type_id {
+            filters.push(filter("vehicle_type_id", vehicle_type.clone(), format!("車種: {}", vehicle_type)));
+        }
+        for feature in criteria.feature_ids {
+            filters.push(filter("feature_ids", feature.clone(), format!("設備: {}", feature)));
+        }
+        if let Some(rental_type) = criteria.rental_type {
+            filters.push(filter("rental_type", rental_type.clone(), format!("貸出タイプ: {}", rental_type)));
+        }
+        if let Some(range) = self.price_range.as_deref().and_then(PriceRangeBucket::find) {
+            filters.push(filter("price_range", range.key.to_string(), format!("料金: {}", range.display_name)));
+        }
+        if let Some(dimensions) = self.vehicle_dimensions.as_ref().filter(|d| !d.is_empty()) {
+            let display_name = if self.include_incompatible.unwrap_or(false) {
+                "車両寸法: 制限を超える駐車場も表示"
+            } else {
+                "車両寸法: 制限内の駐車場のみ"
+            };
+            filters.push(filter(
+                "vehicle_dimensions",
+                serde_json::to_string(dimensions).unwrap_or_default(),
+                display_name.to_string(),
+            ));
+        }
+        filters
+    }
 }
 
 impl PaginationInfo {
@@ -431,8 +1186,46 @@ impl PaginationInfo {
             total_pages,
             has_previous,
             has_next,
+            next_cursor: None,
         }
     }
+
+    /// ページ番号またはカーソルで取得した結果のページネーション情報
+    ///
+    /// カーソル指定時はページ番号が定まらないためcurrent_pageは0とする
+    pub fn with_cursor(current_page: i32, page_size: i32, total_count: i64, next_cursor: Option<String>) -> Self {
+        let mut info = Self::new(current_page.max(1), page_size, total_count);
+        if current_page == 0 {
+            info.current_page = 0;
+            info.has_previous = true;
+        }
+        info.has_next = next_cursor.is_some();
+        info.next_cursor = next_cursor;
+        info
+    }
+}
+
+impl PageQuery {
+    /// ページ指定を検証し、(ページ番号, 件数)を返す（カーソル指定時のページ番号は0）
+    pub fn validate(&self) -> Result<(i32, i32), String> {
+        validate_paging(self.page, self.page_size, self.cursor.as_deref())
+    }
+}
+
+/// page/page_size/cursorの検証（カーソル指定時のページ番号は0）
+pub fn validate_paging(page: Option<i32>, page_size: Option<i32>, cursor: Option<&str>) -> Result<(i32, i32), String> {
+    let page_size = page_size.unwrap_or(20);
+    if !(1..=100).contains(&page_size) {
+        return Err("page_sizeは1以上100以下で指定してください".to_string());
+    }
+    match cursor.filter(|c| !c.is_empty()) {
+        Some(_) if page.is_some_and(|p| p != 1) => Err("cursorとpageは同時に指定できません".to_string()),
+        Some(_) => Ok((0, page_size)),
+        None => match page.unwrap_or(1) {
+            page if page >= 1 => Ok((page, page_size)),
+            _ => Err("pageは1以上で指定してください".to_string()),
+        },
+    }
 }
 
 impl DistanceInfo {
@@ -456,6 +1249,7 @@ impl DistanceInfo {
             distance_display,
             walking_time_minutes,
             driving_time_minutes,
+            station_walk: None,
         }
     }
 }
@@ -471,7 +1265,195 @@ impl FavoriteOperationRequest {
         if self.operation != "add" && self.operation != "remove" {
             return Err("操作タイプはaddまたはremoveで指定してください".to_string());
         }
+        validate_favorite_memo(self.memo.as_deref())?;
+
+        Ok(())
+    }
+
+    /// 保存するメモ（空白のみは未設定）
+    pub fn normalized_memo(&self) -> Option<String> {
+        self.memo.as_deref().map(str::trim).filter(|m| !m.is_empty()).map(str::to_string)
+    }
+}
+
+impl FavoriteUpdateRequest {
+    /// 更新リクエストのバリデーション
+    pub fn validate(&self) -> Result<(), String> {
+        if self.memo.is_none() && self.labels.is_none() {
+            return Err("memoまたはlabelsのいずれかを指定してください".to_string());
+        }
+        validate_favorite_memo(self.memo.as_deref())
+    }
+}
 
+impl FavoriteOrderRequest {
+    /// 並べ替えリクエストのバリデーション
+    pub fn validate(&self) -> Result<(), String> {
+        if self.parking_lot_ids.is_empty() {
+            return Err("並べ替える駐車場IDを指定してください".to_string());
+        }
+        let unique: std::collections::HashSet<&String> = self.parking_lot_ids.iter().collect();
+        if unique.len() != self.parking_lot_ids.len() {
+            return Err("駐車場IDが重複しています".to_string());
+        }
         Ok(())
     }
 }
+
+/// お気に入りメモの最大文字数
+pub const MAX_FAVORITE_MEMO_LENGTH: usize = 500;
+/// お気に入り1件あたりのラベル数の上限
+pub const MAX_FAVORITE_LABELS: usize = 10;
+/// ラベルの最大文字数
+pub const MAX_FAVORITE_LABEL_LENGTH: usize = 20;
+
+fn validate_favorite_memo(memo: Option<&str>) -> Result<(), String> {
+    if memo.is_some_and(|m| m.trim().chars().count() > MAX_FAVORITE_MEMO_LENGTH) {
+        return Err(format!("メモは{}文字以内で入力してください", MAX_FAVORITE_MEMO_LENGTH));
+    }
+    Ok(())
+}
+
+/// ラベルの前後空白を除去し、空・重複を取り除く（指定順は維持）
+pub fn normalize_favorite_labels(labels: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for label in labels.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        if label.chars().count() > MAX_FAVORITE_LABEL_LENGTH {
+            return Err(format!("ラベルは{}文字以内で入力してください", MAX_FAVORITE_LABEL_LENGTH));
+        }
+        if !normalized.iter().any(|l| l == label) {
+            normalized.push(label.to_string());
+        }
+    }
+    if normalized.len() > MAX_FAVORITE_LABELS {
+        return Err(format!("ラベルは{}個まで登録できます", MAX_FAVORITE_LABELS));
+    }
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> ParkingLimitModel {
+        ParkingLimitModel {
+            parking_lot_id: "P-000001".to_string(),
+            length_limit: Some(5000),
+            width_limit: Some(1850),
+            height_limit: Some(2000),
+            weight_limit: Some(2500),
+            car_height_limit: Some("ハイルーフ不可".to_string()),
+            tire_width_limit: None,
+            car_bottom_limit: Some(" ".to_string()),
+            created_datetime: None,
+            updated_datetime: None,
+        }
+    }
+
+    #[test]
+    fn test_check_limits() {
+        let van = VehicleDimensions { length_mm: Some(4700), width_mm: Some(1880), height_mm: Some(2100), weight_kg: Some(2600) };
+        let result = van.check_limits(Some(&limits()));
+        assert!(!result.is_compatible);
+        assert_eq!(result.reasons, vec!["幅制限 1.85m", "高さ制限 2.0m", "重量制限 2.5t"]);
+        assert_eq!(result.notes, vec!["車高制限: ハイルーフ不可"]);
+
+        let compact = VehicleDimensions { length_mm: Some(3400), height_mm: Some(1500), ..Default::default() };
+        assert!(compact.check_limits(Some(&limits())).is_compatible);
+        assert!(van.check_limits(None).is_compatible);
+    }
+
+    #[test]
+    fn test_applied_filters() {
+        let request = ParkingSearchRequest {
+            address: Some("渋谷区".to_string()),
+            vehicle_type_id: Some("軽自動車".to_string()),
+            feature_ids: Some(vec!["照明:あり".to_string(), " ".to_string()]),
+            rental_type: Some(" ".to_string()),
+            price_range: Some("301-500".to_string()),
+            ..Default::default()
+        };
+        let filters = request.applied_filters();
+        let names: Vec<&str> = filters.iter().map(|f| f.filter_name.as_str()).collect();
+        assert_eq!(names, vec!["vehicle_type_id", "feature_ids", "price_range"]);
+        assert_eq!(filters[2].display_name, "料金: 301〜500円");
+
+        let criteria = SearchFilterCriteria::from_request(&request);
+        assert_eq!((criteria.min_charge, criteria.max_charge), (Some(301), Some(500)));
+        assert!(ParkingSearchRequest { price_range: Some("1-2".to_string()), ..request }.validate().is_err());
+    }
+
+    #[test]
+    fn test_nearby_query_validate() {
+        let query = NearbyParkingQuery { latitude: 35.69, longitude: 139.70, radius_km: None, limit: None };
+        assert!(query.validate().is_ok());
+        assert!(NearbyParkingQuery { radius_km: Some(MAX_NEARBY_RADIUS_KM + 1.0), ..query.clone() }.validate().is_err());
+        assert!(NearbyParkingQuery { limit: Some(0), ..query.clone() }.validate().is_err());
+        assert!(NearbyParkingQuery { latitude: 91.0, ..query }.validate().is_err());
+    }
+
+    #[test]
+    fn test_search_sort() {
+        let request = ParkingSearchRequest { address: Some("渋谷区".to_string()), ..Default::default() };
+        assert_eq!(request.sort(), (SearchSortBy::Distance, false));
+
+        let sort = |sort_by: &str, sort_order: Option<&str>| ParkingSearchRequest {
+            sort_by: Some(sort_by.to_string()),
+            sort_order: sort_order.map(str::to_string),
+            ..request.clone()
+        };
+        assert_eq!(sort("newest", None).sort(), (SearchSortBy::Newest, true));
+        assert_eq!(sort("price", Some("desc")).sort(), (SearchSortBy::Price, true));
+        assert_eq!(sort("recommended", Some("asc")).sort(), (SearchSortBy::Recommended, true));
+        assert_eq!(SearchSortBy::Price.display_name(false), "料金の安い順");
+
+        assert!(sort("newest", Some("desc")).validate().is_ok());
+        assert!(sort("popular", None).validate().is_err());
+        assert!(sort("rating", None).validate().is_err());
+        assert!(sort("price", Some("up")).validate().is_err());
+
+        let component = ScoreComponent::new(Some(0.5), 1.2, 0.4);
+        assert_eq!((component.normalized, component.points), (1.0, 40.0));
+    }
+
+    #[test]
+    fn test_map_viewport() {
+        let query = MapViewportQuery { min_lat: 35.6, max_lat: 35.7, min_lng: 139.6, max_lng: 139.8, zoom: 10 };
+        assert!(query.validate().is_ok());
+        assert!((query.cluster_cell_degrees() - 360.0 / 1024.0 / 4.0).abs() < 1e-12);
+        let zoomed = MapViewportQuery { zoom: 11, ..query.clone() };
+        assert!((zoomed.cluster_cell_degrees() * 2.0 - query.cluster_cell_degrees()).abs() < 1e-12);
+        assert!(MapViewportQuery { min_lat: 35.8, ..query }.validate().is_err());
+
+        let json = serde_json::to_value(MapSearchResponse::pins(15, vec![("P-000001".to_string(), 35.68, 139.76, Some(3), Some(300))])).unwrap();
+        assert_eq!(json["pins"][0], serde_json::json!(["P-000001", 35.68, 139.76, 3, 300]));
+        assert!(json.get("clusters").is_none());
+    }
+
+    #[test]
+    fn test_price_range_buckets() {
+        for charge in [0, 300, 301, 1000, 3000, 3001, 50000] {
+            let matched = PRICE_RANGE_BUCKETS.iter().filter(|r| r.contains(charge)).count();
+            assert_eq!(matched, 1, "charge {}", charge);
+        }
+    }
+
+    #[test]
+    fn test_normalize_favorite_labels() {
+        let labels = vec![" 自宅 ".to_string(), "職場".to_string(), "".to_string(), "自宅".to_string()];
+        assert_eq!(normalize_favorite_labels(&labels).unwrap(), vec!["自宅", "職場"]);
+
+        let too_many: Vec<String> = (0..=MAX_FAVORITE_LABELS).map(|i| format!("label{}", i)).collect();
+        assert!(normalize_favorite_labels(&too_many).is_err());
+        assert!(normalize_favorite_labels(&["あ".repeat(MAX_FAVORITE_LABEL_LENGTH + 1)]).is_err());
+    }
+
+    #[test]
+    fn test_station_walk_info() {
+        let walk = StationWalkInfo::new("ST-0001", "新宿駅", 0.4);
+        assert_eq!(walk.walking_distance_m, 500);
+        assert_eq!(walk.walking_time_minutes, 7);
+        assert_eq!(walk.walking_display, "新宿駅 徒歩7分");
+        assert_eq!(StationWalkInfo::new("ST-0001", "新宿駅", 0.0).walking_time_minutes, 1);
+    }
+}
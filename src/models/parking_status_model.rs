@@ -26,10 +26,15 @@ pub struct ParkingStatusRequest {
     pub status: String,
 }    
 
-/// 入出庫状況更新のリクエスト
+/// 入出庫状況更新のリクエスト（入出庫区分 1: 入庫, 2: 出庫）
+///
+/// 入出庫は利用者の位置情報が駐車場の範囲内の場合だけ記録する
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateParkingStatusRequest {
     pub status_id: String,
     pub reservation_id: String,
     pub check_inout_kbn: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub accuracy_meters: Option<f64>,
 }  
\ No newline at end of file
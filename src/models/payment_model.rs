@@ -0,0 +1,282 @@
+// 論理名: 決済モデル
+// t_payments / t_payment_events に対応
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// 決済ステータス
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentStatus {
+    /// 与信待ち
+    Pending,
+    /// 与信済み
+    Authorized,
+    /// 売上確定（一部返金済みを含む）
+    Captured,
+    /// 全額返金
+    Refunded,
+    /// 与信取消
+    Voided,
+    /// 与信・売上確定の失敗
+    Failed,
+}
+
+impl PaymentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentStatus::Pending => "pending",
+            PaymentStatus::Authorized => "authorized",
+            PaymentStatus::Captured => "captured",
+            PaymentStatus::Refunded => "refunded",
+            PaymentStatus::Voided => "voided",
+            PaymentStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(PaymentStatus::Pending),
+            "authorized" => Some(PaymentStatus::Authorized),
+            "captured" => Some(PaymentStatus::Captured),
+            "refunded" => Some(PaymentStatus::Refunded),
+            "voided" => Some(PaymentStatus::Voided),
+            "failed" => Some(PaymentStatus::Failed),
+            _ => None,
+        }
+    }
+
+    /// 遷移可能なステータスか（失敗した決済は与信待ちに戻して再試行できる）
+    pub fn can_transition_to(&self, next: PaymentStatus) -> bool {
+        use PaymentStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Authorized | Failed | Voided)
+                | (Authorized, Captured | Voided | Failed)
+                | (Captured, Refunded)
+                | (Failed, Pending | Voided)
+        )
+    }
+}
+
+/// 決済（t_payments）
+#[derive(Debug, Clone, FromRow)]
+pub struct PaymentRow {
+    pub payment_id: Uuid,
+    pub reservation_id: String,
+    pub user_id: String,
+    pub provider: String,
+    pub provider_payment_id: Option<String>,
+    pub status: String,
+    pub amount: i64,
+    pub captured_amount: i64,
+    pub refunded_amount: i64,
+    pub currency: String,
+    pub attempt_count: i32,
+    pub failure_reason: Option<String>,
+    pub authorized_datetime: Option<DateTime<Utc>>,
+    pub captured_datetime: Option<DateTime<Utc>>,
+    pub refunded_datetime: Option<DateTime<Utc>>,
+    pub voided_datetime: Option<DateTime<Utc>>,
+    pub created_datetime: Option<DateTime<Utc>>,
+    pub updated_datetime: Option<DateTime<Utc>>,
+}
+
+impl PaymentRow {
+    pub fn status(&self) -> PaymentStatus {
+        PaymentStatus::parse(&self.status).unwrap_or(PaymentStatus::Failed)
+    }
+
+    /// 返金可能な金額
+    pub fn refundable_amount(&self) -> i64 {
+        (self.captured_amount - self.refunded_amount).max(0)
+    }
+}
+
+/// ステータス遷移時に更新する項目（Noneの項目は変更しない、failure_reasonは常に上書き）
+#[derive(Debug, Clone, Default)]
+pub struct PaymentChanges {
+    pub provider_payment_id: Option<String>,
+    pub captured_amount: Option<i64>,
+    pub refunded_amount: Option<i64>,
+    pub failure_reason: Option<String>,
+    /// 返金の二重計上を防ぐため、返金済み金額がこの値の場合のみ更新する
+    pub expected_refunded_amount: Option<i64>,
+}
+
+/// 決済対象の予約（予約金額は予約詳細の合計、利用者・オーナーのログインIDを付与）
+#[derive(Debug, Clone, FromRow)]
+pub struct PaymentReservationRow {
+    pub reservation_id: String,
+    pub user_id: String,
+    pub parking_lot_id: String,
+    pub status: String,
+    pub amount: i64,
+    pub user_login_id: Option<String>,
+    pub owner_login_id: Option<String>,
+}
+
+/// 与信リクエスト
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuthorizePaymentRequest {
+    /// カードトークン等（未指定の場合は登録済みの支払い方法）
+    pub payment_method: Option<String>,
+}
+
+/// 返金リクエスト
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RefundPaymentRequest {
+    /// 返金額（円、未指定の場合は返金可能な全額）
+    pub amount: Option<i64>,
+    pub reason: Option<String>,
+}
+
+/// Webhookイベント種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaymentEventType {
+    #[serde(rename = "payment.authorized")]
+    Authorized,
+    #[serde(rename = "payment.captured")]
+    Captured,
+    #[serde(rename = "payment.refunded")]
+    Refunded,
+    #[serde(rename = "payment.voided")]
+    Voided,
+    #[serde(rename = "payment.failed")]
+    Failed,
+}
+
+impl PaymentEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentEventType::Authorized => "payment.authorized",
+            PaymentEventType::Captured => "payment.captured",
+            PaymentEventType::Refunded => "payment.refunded",
+            PaymentEventType::Voided => "payment.voided",
+            PaymentEventType::Failed => "payment.failed",
+        }
+    }
+
+    /// イベントが示す遷移先（一部返金の場合はcapturedのまま）
+    pub fn target_status(&self) -> PaymentStatus {
+        match self {
+            PaymentEventType::Authorized => PaymentStatus::Authorized,
+            PaymentEventType::Captured => PaymentStatus::Captured,
+            PaymentEventType::Refunded => PaymentStatus::Refunded,
+            PaymentEventType::Voided => PaymentStatus::Voided,
+            PaymentEventType::Failed => PaymentStatus::Failed,
+        }
+    }
+}
+
+/// 決済プロバイダーからのWebhookイベント
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentWebhookEvent {
+    pub event_id: String,
+    pub event_type: PaymentEventType,
+    pub provider_payment_id: String,
+    /// イベント時点の金額（売上確定は確定金額、返金は累計返金額）
+    pub amount: Option<i64>,
+    pub failure_reason: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Webhookの模擬送信リクエスト（テスト用プロバイダーのみ）
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulatePaymentWebhookRequest {
+    pub reservation_id: String,
+    pub event_type: PaymentEventType,
+    pub amount: Option<i64>,
+    pub failure_reason: Option<String>,
+}
+
+/// Webhookの処理結果
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentWebhookResult {
+    pub event_id: String,
+    /// 処理済みのイベント（再送）の場合true
+    pub duplicate: bool,
+    /// 決済状態に反映した場合true
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_status: Option<PaymentStatus>,
+}
+
+/// 決済情報レスポンス
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentResponse {
+    pub payment_id: Uuid,
+    pub reservation_id: String,
+    pub provider: String,
+    pub status: PaymentStatus,
+    pub amount: i64,
+    pub captured_amount: i64,
+    pub refunded_amount: i64,
+    pub currency: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+    pub authorized_datetime: Option<DateTime<Utc>>,
+    pub captured_datetime: Option<DateTime<Utc>>,
+    pub refunded_datetime: Option<DateTime<Utc>>,
+    pub voided_datetime: Option<DateTime<Utc>>,
+    pub updated_datetime: Option<DateTime<Utc>>,
+}
+
+impl From<PaymentRow> for PaymentResponse {
+    fn from(row: PaymentRow) -> Self {
+        Self {
+            status: row.status(),
+            payment_id: row.payment_id,
+            reservation_id: row.reservation_id,
+            provider: row.provider,
+            amount: row.amount,
+            captured_amount: row.captured_amount,
+            refunded_amount: row.refunded_amount,
+            currency: row.currency,
+            failure_reason: row.failure_reason,
+            authorized_datetime: row.authorized_datetime,
+            captured_datetime: row.captured_datetime,
+            refunded_datetime: row.refunded_datetime,
+            voided_datetime: row.voided_datetime,
+            updated_datetime: row.updated_datetime,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payment_status_transitions() {
+        use PaymentStatus::*;
+        assert!(Pending.can_transition_to(Authorized));
+        assert!(Authorized.can_transition_to(Captured));
+        assert!(Authorized.can_transition_to(Voided));
+        assert!(Captured.can_transition_to(Refunded));
+        assert!(Failed.can_transition_to(Pending));
+
+        assert!(!Captured.can_transition_to(Voided));
+        assert!(!Captured.can_transition_to(Authorized));
+        assert!(!Refunded.can_transition_to(Captured));
+        assert!(!Voided.can_transition_to(Authorized));
+        assert!(!Authorized.can_transition_to(Authorized));
+
+        for status in [Pending, Authorized, Captured, Refunded, Voided, Failed] {
+            assert_eq!(PaymentStatus::parse(status.as_str()), Some(status));
+        }
+    }
+
+    #[test]
+    fn test_webhook_event_format() {
+        let event: PaymentWebhookEvent = serde_json::from_str(
+            r#"{"event_id":"evt_1","event_type":"payment.refunded","provider_payment_id":"fake_pay_00000001","amount":500,"failure_reason":null,"occurred_at":"2026-03-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(event.event_type, PaymentEventType::Refunded);
+        assert_eq!(event.event_type.target_status(), PaymentStatus::Refunded);
+        assert_eq!(event.amount, Some(500));
+        assert!(serde_json::from_str::<PaymentEventType>(r#""payment.unknown""#).is_err());
+    }
+}
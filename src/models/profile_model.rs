@@ -0,0 +1,203 @@
+// 論理名: プロフィール管理モデル
+// m_users / m_owners / m_profiles / t_contact_change_requests に対応
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::models::receipt_model::normalize_invoice_registration_number;
+
+/// 連絡先変更種別: メールアドレス
+pub const CONTACT_CHANGE_EMAIL: &str = "email";
+/// 連絡先変更種別: 電話番号
+pub const CONTACT_CHANGE_PHONE: &str = "phone";
+
+/// プロフィール取得結果（m_login + m_users/m_owners + m_profiles）
+#[derive(Debug, Clone, FromRow)]
+pub struct ProfileRow {
+    pub login_id: String,
+    pub email: String,
+    pub phone_number: String,
+    pub is_owner: bool,
+    /// user_id または owner_id（未登録の場合はNone）
+    pub account_id: Option<String>,
+    pub full_name: Option<String>,
+    pub full_name_kana: Option<String>,
+    pub birthday: Option<NaiveDate>,
+    pub gender: Option<String>,
+    pub postal_code: Option<String>,
+    pub address: Option<String>,
+    pub registrant_type: Option<String>,
+    pub invoice_registration_number: Option<String>,
+    pub photo_path: Option<String>,
+    pub photo_description: Option<String>,
+}
+
+/// プロフィールレスポンス
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileResponse {
+    pub login_id: String,
+    /// user または owner
+    pub user_type: String,
+    /// user_id または owner_id
+    pub account_id: String,
+    pub email: String,
+    pub phone_number: String,
+    pub full_name: String,
+    pub full_name_kana: Option<String>,
+    pub birthday: Option<NaiveDate>,
+    pub gender: Option<String>,
+    pub postal_code: Option<String>,
+    pub address: String,
+    /// 登録者種別（オーナーのみ）
+    pub registrant_type: Option<String>,
+    /// 適格請求書発行事業者登録番号（オーナーのみ）
+    pub invoice_registration_number: Option<String>,
+    /// プロフィール写真の署名済みURL
+    pub photo_url: Option<String>,
+    pub photo_description: Option<String>,
+}
+
+/// プロフィール更新リクエスト（指定した項目のみ更新、任意項目は空文字で削除）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProfileUpdateRequest {
+    pub full_name: Option<String>,
+    pub full_name_kana: Option<String>,
+    /// YYYY-MM-DD形式
+    pub birthday: Option<String>,
+    pub gender: Option<String>,
+    pub postal_code: Option<String>,
+    pub address: Option<String>,
+    /// 適格請求書発行事業者登録番号（オーナーのみ、空文字で削除）
+    pub invoice_registration_number: Option<String>,
+}
+
+impl ProfileUpdateRequest {
+    /// 入力検証（サインアップ時と同じ制約）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.full_name.is_none()
+            && self.full_name_kana.is_none()
+            && self.birthday.is_none()
+            && self.gender.is_none()
+            && self.postal_code.is_none()
+            && self.address.is_none()
+            && self.invoice_registration_number.is_none()
+        {
+            return Err("更新する項目を指定してください".to_string());
+        }
+
+        if let Some(full_name) = &self.full_name {
+            if full_name.trim().is_empty() {
+                return Err("氏名は必須です".to_string());
+            }
+            if full_name.chars().count() > 100 {
+                return Err("氏名は100文字以内で入力してください".to_string());
+            }
+        }
+
+        if let Some(kana) = self.full_name_kana.as_deref().filter(|k| !k.is_empty()) {
+            if kana.chars().count() > 100 {
+                return Err("氏名（カナ）は100文字以内で入力してください".to_string());
+            }
+            if !Regex::new(r"^[ァ-ヶー・\s　]+$").unwrap().is_match(kana) {
+                return Err("氏名（カナ）は全角カタカナで入力してください".to_string());
+            }
+        }
+
+        if let Some(birthday) = self.birthday.as_deref().filter(|b| !b.is_empty()) {
+            match NaiveDate::parse_from_str(birthday, "%Y-%m-%d") {
+                Ok(date) if date > Utc::now().date_naive() => {
+                    return Err("生年月日に未来の日付は指定できません".to_string());
+                }
+                Ok(_) => {}
+                Err(_) => return Err("生年月日は YYYY-MM-DD 形式で入力してください".to_string()),
+            }
+        }
+
+        let invalid_gender = self
+            .gender
+            .as_deref()
+            .is_some_and(|g| !g.is_empty() && !["male", "female", "other"].contains(&g));
+        if invalid_gender {
+            return Err("性別は 'male', 'female', 'other' のいずれかを指定してください".to_string());
+        }
+
+        let invalid_postal_code = self
+            .postal_code
+            .as_deref()
+            .is_some_and(|p| !p.is_empty() && !Regex::new(r"^\d{3}-\d{4}$").unwrap().is_match(p));
+        if invalid_postal_code {
+            return Err("有効な郵便番号形式を入力してください（例：123-4567）".to_string());
+        }
+
+        if let Some(address) = &self.address {
+            if address.trim().is_empty() {
+                return Err("住所は必須です".to_string());
+            }
+            if address.chars().count() > 1000 {
+                return Err("住所は1000文字以内で入力してください".to_string());
+            }
+        }
+
+        if let Some(number) = self.invoice_registration_number.as_deref().filter(|n| !n.is_empty()) {
+            normalize_invoice_registration_number(number)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 連絡先変更リクエスト（新しい連絡先に認証コードを送信）
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContactChangeRequest {
+    /// email または phone
+    pub change_type: String,
+    pub new_value: String,
+}
+
+impl ContactChangeRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.change_type.as_str() {
+            CONTACT_CHANGE_EMAIL => {
+                let email_regex = Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
+                if self.new_value.len() > 255 || !email_regex.is_match(&self.new_value) {
+                    return Err("有効なメールアドレス形式を入力してください".to_string());
+                }
+            }
+            CONTACT_CHANGE_PHONE => {
+                crate::utils::validation::validate_phone_number(&self.new_value)
+                    .map_err(|e| e.to_string())?;
+            }
+            _ => {
+                return Err("change_type は 'email' または 'phone' を指定してください".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 連絡先変更の確認リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContactChangeVerifyRequest {
+    pub change_type: String,
+    pub verification_code: String,
+}
+
+/// 連絡先変更リクエストのレスポンス
+#[derive(Debug, Clone, Serialize)]
+pub struct ContactChangeResponse {
+    pub change_type: String,
+    /// 送信先（一部マスク）
+    pub destination: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 未使用の連絡先変更リクエスト
+#[derive(Debug, Clone, FromRow)]
+pub struct ContactChangeRow {
+    pub request_id: uuid::Uuid,
+    pub new_value: String,
+    pub code_hash: String,
+    pub attempt_count: i32,
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,318 @@
+// 論理名: 領収書モデル
+// t_receipts / t_receipt_sequences に対応（適格簡易請求書）
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::utils::pdf::{PdfDocument, A4_HEIGHT, A4_WIDTH};
+
+/// 駐車場利用料の消費税率（標準税率）
+pub const STANDARD_TAX_RATE: i32 = 10;
+/// 宛名の最大文字数
+pub const MAX_RECIPIENT_NAME_LENGTH: usize = 100;
+/// 領収書の但し書き
+pub const RECEIPT_DESCRIPTION: &str = "駐車場利用料";
+
+/// 適格請求書発行事業者登録番号の正規化と検証（T+13桁、先頭の1桁は検査用数字）
+///
+/// ハイフン・空白を除き、小文字のtは大文字にする
+pub fn normalize_invoice_registration_number(value: &str) -> Result<String, String> {
+    let normalized: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_uppercase();
+    let invalid = || "登録番号は「T」と13桁の数字で入力してください（例：T1234567890123）".to_string();
+
+    let digits = normalized.strip_prefix('T').ok_or_else(invalid)?;
+    if digits.len() != 13 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    // 検査用数字 = 9 - (下位の桁から奇数桁×1・偶数桁×2 の合計 mod 9)
+    let values: Vec<u32> = digits.bytes().map(|b| u32::from(b - b'0')).collect();
+    let sum: u32 = values[1..]
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| digit * if index % 2 == 0 { 1 } else { 2 })
+        .sum();
+    if values[0] != 9 - sum % 9 {
+        return Err("登録番号が正しくありません。番号をご確認ください".to_string());
+    }
+    Ok(normalized)
+}
+
+/// 税込金額に含まれる消費税額（1円未満切り捨て）
+pub fn included_tax(total_amount: i64, tax_rate: i32) -> i64 {
+    let rate = i64::from(tax_rate.max(0));
+    total_amount.max(0) * rate / (100 + rate)
+}
+
+/// 領収書番号（例: R2026-000001）
+pub fn format_receipt_number(issue_year: i32, number: i64) -> String {
+    format!("R{}-{:06}", issue_year, number)
+}
+
+/// 領収書の発行元データ（予約・駐車場・オーナー・利用実績）
+///
+/// 金額は売上確定済みの決済があれば確定額から返金額を差し引いた額、なければ予約詳細の合計
+#[derive(Debug, Clone, FromRow)]
+pub struct ReceiptSourceRow {
+    pub reservation_id: String,
+    pub status: String,
+    pub user_id: String,
+    pub user_login_id: Option<String>,
+    pub user_full_name: Option<String>,
+    pub user_email: Option<String>,
+    pub owner_id: String,
+    pub owner_login_id: Option<String>,
+    pub owner_name: Option<String>,
+    pub invoice_registration_number: Option<String>,
+    pub parking_lot_name: String,
+    pub start_datetime: DateTime<Utc>,
+    pub end_datetime: DateTime<Utc>,
+    pub entry_datetime: Option<DateTime<Utc>>,
+    pub exit_datetime: Option<DateTime<Utc>>,
+    pub amount: i64,
+}
+
+/// 登録する領収書
+#[derive(Debug, Clone)]
+pub struct NewReceipt {
+    pub reservation_id: String,
+    pub user_id: String,
+    pub owner_id: String,
+    pub issuer_name: String,
+    pub invoice_registration_number: Option<String>,
+    pub recipient_name: Option<String>,
+    pub parking_lot_name: String,
+    pub usage_start_datetime: DateTime<Utc>,
+    pub usage_end_datetime: DateTime<Utc>,
+    pub total_amount: i64,
+    pub tax_rate: i32,
+    pub tax_amount: i64,
+}
+
+/// 発行済みの領収書（t_receipts）
+#[derive(Debug, Clone, FromRow)]
+pub struct ReceiptRow {
+    pub receipt_id: uuid::Uuid,
+    pub receipt_number: String,
+    pub reservation_id: String,
+    pub user_id: String,
+    pub owner_id: String,
+    pub issuer_name: String,
+    pub invoice_registration_number: Option<String>,
+    pub recipient_name: Option<String>,
+    pub parking_lot_name: String,
+    pub usage_start_datetime: DateTime<Utc>,
+    pub usage_end_datetime: DateTime<Utc>,
+    pub total_amount: i64,
+    pub tax_rate: i32,
+    pub tax_amount: i64,
+    pub issued_datetime: DateTime<Utc>,
+}
+
+/// 領収書の発行リクエスト
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IssueReceiptRequest {
+    /// 宛名（未指定の場合は利用者の氏名）
+    pub recipient_name: Option<String>,
+}
+
+impl IssueReceiptRequest {
+    /// 入力検証（空白のみの宛名は未指定として扱う）
+    pub fn normalize(self) -> Result<Option<String>, String> {
+        let name = self
+            .recipient_name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+        if name
+            .as_ref()
+            .is_some_and(|name| name.chars().count() > MAX_RECIPIENT_NAME_LENGTH)
+        {
+            return Err(format!("宛名は{}文字以内で入力してください", MAX_RECIPIENT_NAME_LENGTH));
+        }
+        Ok(name)
+    }
+}
+
+/// 領収書の発行者
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiptIssuer {
+    pub name: String,
+    /// 適格請求書発行事業者登録番号（未登録の場合はNone）
+    pub invoice_registration_number: Option<String>,
+}
+
+/// 税率ごとの内訳
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiptTaxBreakdown {
+    pub tax_rate: i32,
+    /// 税率の対象となる金額（税込）
+    pub amount: i64,
+    pub tax_amount: i64,
+}
+
+/// 領収書レスポンス
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiptResponse {
+    pub receipt_number: String,
+    pub reservation_id: String,
+    pub issued_datetime: DateTime<Utc>,
+    /// 発行者が登録番号を持ち、適格簡易請求書の要件を満たす場合true
+    pub is_qualified_invoice: bool,
+    pub issuer: ReceiptIssuer,
+    pub recipient_name: Option<String>,
+    pub description: String,
+    pub parking_lot_name: String,
+    pub usage_start_datetime: DateTime<Utc>,
+    pub usage_end_datetime: DateTime<Utc>,
+    /// 合計金額（税込）
+    pub total_amount: i64,
+    pub tax_breakdown: Vec<ReceiptTaxBreakdown>,
+}
+
+impl From<ReceiptRow> for ReceiptResponse {
+    fn from(row: ReceiptRow) -> Self {
+        Self {
+            receipt_number: row.receipt_number,
+            reservation_id: row.reservation_id,
+            issued_datetime: row.issued_datetime,
+            is_qualified_invoice: row.invoice_registration_number.is_some(),
+            issuer: ReceiptIssuer {
+                name: row.issuer_name,
+                invoice_registration_number: row.invoice_registration_number,
+            },
+            recipient_name: row.recipient_name,
+            description: RECEIPT_DESCRIPTION.to_string(),
+            parking_lot_name: row.parking_lot_name,
+            usage_start_datetime: row.usage_start_datetime,
+            usage_end_datetime: row.usage_end_datetime,
+            total_amount: row.total_amount,
+            tax_breakdown: vec![ReceiptTaxBreakdown {
+                tax_rate: row.tax_rate,
+                amount: row.total_amount,
+                tax_amount: row.tax_amount,
+            }],
+        }
+    }
+}
+
+impl ReceiptResponse {
+    /// ダウンロード用のファイル名
+    pub fn pdf_filename(&self) -> String {
+        format!("receipt_{}.pdf", self.receipt_number)
+    }
+
+    /// 領収書のPDF（A4縦1枚、日時は日本時間）
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let jst = FixedOffset::east_opt(9 * 3600).expect("日本時間のオフセット");
+        let date = |dt: &DateTime<Utc>| dt.with_timezone(&jst).format("%Y年%m月%d日").to_string();
+        let datetime = |dt: &DateTime<Utc>| dt.with_timezone(&jst).format("%Y年%m月%d日 %H:%M").to_string();
+
+        let left = 60.0;
+        let right = A4_WIDTH - 60.0;
+        let mut doc = PdfDocument::new();
+
+        doc.text_center(A4_WIDTH / 2.0, A4_HEIGHT - 90.0, 26.0, "領収書");
+        doc.text_right(right, A4_HEIGHT - 130.0, 10.0, &format!("No. {}", self.receipt_number));
+        doc.text_right(right, A4_HEIGHT - 146.0, 10.0, &format!("発行日 {}", date(&self.issued_datetime)));
+
+        let recipient = self.recipient_name.as_deref().unwrap_or("");
+        doc.text(left, A4_HEIGHT - 190.0, 16.0, &format!("{} 様", recipient));
+        doc.line(left, A4_HEIGHT - 196.0, left + 300.0, A4_HEIGHT - 196.0, 0.8);
+
+        doc.rect(left, A4_HEIGHT - 280.0, right - left, 56.0, 1.2);
+        doc.text_center(A4_WIDTH / 2.0, A4_HEIGHT - 260.0, 24.0, &format!("{}-（税込）", yen(self.total_amount)));
+        doc.text(left, A4_HEIGHT - 310.0, 11.0, &format!("但し {}として", self.description));
+        doc.text(left, A4_HEIGHT - 328.0, 11.0, "上記正に領収いたしました");
+
+        let mut y = A4_HEIGHT - 380.0;
+        doc.text(left, y, 12.0, "内訳");
+        doc.line(left, y - 6.0, right, y - 6.0, 0.5);
+        let rows = [
+            ("駐車場", self.parking_lot_name.clone()),
+            (
+                "利用日時",
+                format!("{} 〜 {}", datetime(&self.usage_start_datetime), datetime(&self.usage_end_datetime)),
+            ),
+            ("予約番号", self.reservation_id.clone()),
+        ];
+        for (label, value) in rows {
+            y -= 24.0;
+            doc.text(left, y, 10.0, label);
+            doc.text(left + 80.0, y, 10.0, &value);
+        }
+        for tax in &self.tax_breakdown {
+            y -= 24.0;
+            doc.text(left, y, 10.0, &format!("{}%対象", tax.tax_rate));
+            doc.text_right(right, y, 10.0, &format!("{}（うち消費税 {}）", yen(tax.amount), yen(tax.tax_amount)));
+        }
+        doc.line(left, y - 10.0, right, y - 10.0, 0.5);
+
+        let mut y = A4_HEIGHT - 620.0;
+        doc.text(A4_WIDTH / 2.0 + 20.0, y, 12.0, &self.issuer.name);
+        if let Some(number) = &self.issuer.invoice_registration_number {
+            y -= 18.0;
+            doc.text(A4_WIDTH / 2.0 + 20.0, y, 10.0, &format!("登録番号 {}", number));
+        }
+        if !self.is_qualified_invoice {
+            doc.text(
+                left,
+                90.0,
+                8.0,
+                "※発行者が適格請求書発行事業者の登録番号を登録していないため、適格簡易請求書には該当しません",
+            );
+        }
+        doc.finish()
+    }
+}
+
+/// 金額表示（例: ￥1,200）
+fn yen(amount: i64) -> String {
+    let digits = amount.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (index, c) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{}￥{}", if amount < 0 { "-" } else { "" }, grouped)
+}
+
+/// 領収書のメール送信結果
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiptEmailResponse {
+    pub receipt_number: String,
+    /// 送信先（アカウントのメールアドレス）
+    pub sent_to: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invoice_registration_number() {
+        // 国税庁の法人番号（7000012050002）
+        assert_eq!(normalize_invoice_registration_number("T7000012050002").unwrap(), "T7000012050002");
+        assert_eq!(normalize_invoice_registration_number(" t7-0000-1205-0002 ").unwrap(), "T7000012050002");
+        assert!(normalize_invoice_registration_number("T8000012050002").is_err());
+        assert!(normalize_invoice_registration_number("7000012050002").is_err());
+        assert!(normalize_invoice_registration_number("T700001205000").is_err());
+        assert!(normalize_invoice_registration_number("T70000120500O2").is_err());
+    }
+
+    #[test]
+    fn test_included_tax_and_format() {
+        assert_eq!(included_tax(1100, 10), 100);
+        assert_eq!(included_tax(1200, 10), 109);
+        assert_eq!(included_tax(0, 10), 0);
+        assert_eq!(format_receipt_number(2026, 12), "R2026-000012");
+        assert_eq!(yen(1234567), "￥1,234,567");
+        assert_eq!(yen(500), "￥500");
+    }
+}
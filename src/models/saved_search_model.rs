@@ -0,0 +1,192 @@
+// 論理名: 保存検索モデル
+// t_saved_searches に対応
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::parking_search_history_model::SearchHistoryCriteria;
+use crate::models::parking_search_model::ParkingSearchRequest;
+
+/// 通知休止期間の上限（分、7日）
+pub const MAX_QUIET_PERIOD_MINUTES: i32 = 7 * 24 * 60;
+
+/// 保存検索（t_saved_searches）
+#[derive(Debug, Clone, FromRow)]
+pub struct SavedSearchRow {
+    pub saved_search_id: Uuid,
+    pub user_id: String,
+    pub search_name: String,
+    pub condition_json: Value,
+    pub notify_email: bool,
+    pub notify_in_app: bool,
+    pub quiet_period_minutes: i32,
+    pub is_active: bool,
+    pub last_notified_datetime: Option<DateTime<Utc>>,
+    pub created_datetime: Option<DateTime<Utc>>,
+    pub updated_datetime: Option<DateTime<Utc>>,
+}
+
+impl SavedSearchRow {
+    /// 保存された検索条件を復元
+    pub fn criteria(&self) -> Result<SearchHistoryCriteria, serde_json::Error> {
+        serde_json::from_value(self.condition_json.clone())
+    }
+
+    /// 通知休止期間の終了日時（未通知の場合はNone）
+    pub fn quiet_until(&self) -> Option<DateTime<Utc>> {
+        self.last_notified_datetime
+            .map(|notified| notified + Duration::minutes(self.quiet_period_minutes.into()))
+    }
+
+    /// 指定日時が通知休止期間中か
+    pub fn is_quiet_at(&self, now: DateTime<Utc>) -> bool {
+        self.quiet_until().is_some_and(|until| now < until)
+    }
+}
+
+/// 保存検索の登録・更新リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct SavedSearchRequest {
+    pub search_name: String,
+    /// 検索条件（駐車場検索と同じ形式。ページ・並び順は保存しない）
+    pub condition: ParkingSearchRequest,
+    pub notify_email: Option<bool>,
+    pub notify_in_app: Option<bool>,
+    /// 未指定の場合は既定値
+    pub quiet_period_minutes: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+impl SavedSearchRequest {
+    /// 入力検証と名称の正規化
+    pub fn normalize(mut self) -> Result<Self, String> {
+        self.search_name = self.search_name.trim().to_string();
+        if self.search_name.is_empty() {
+            return Err("保存検索の名称は必須です".to_string());
+        }
+        if self.search_name.chars().count() > 100 {
+            return Err("保存検索の名称は100文字以内で入力してください".to_string());
+        }
+
+        if self.condition.favorites_only.unwrap_or(false) {
+            return Err("お気に入り検索は保存できません".to_string());
+        }
+        self.condition.validate()?;
+
+        if self
+            .quiet_period_minutes
+            .is_some_and(|m| !(0..=MAX_QUIET_PERIOD_MINUTES).contains(&m))
+        {
+            return Err(format!(
+                "通知休止期間は0分以上{}分以下で指定してください",
+                MAX_QUIET_PERIOD_MINUTES
+            ));
+        }
+        if self.is_active.unwrap_or(true) && !self.notify_email.unwrap_or(true) && !self.notify_in_app.unwrap_or(true) {
+            return Err("通知方法を1つ以上選択してください".to_string());
+        }
+        Ok(self)
+    }
+
+    /// 保存する検索条件
+    pub fn criteria(&self) -> SearchHistoryCriteria {
+        SearchHistoryCriteria::from_request(&self.condition)
+    }
+}
+
+/// 保存検索レスポンス
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSearchResponse {
+    pub saved_search_id: Uuid,
+    pub search_name: String,
+    pub condition: SearchHistoryCriteria,
+    pub notify_email: bool,
+    pub notify_in_app: bool,
+    pub quiet_period_minutes: i32,
+    pub is_active: bool,
+    pub last_notified_datetime: Option<DateTime<Utc>>,
+    /// 通知休止期間の終了日時（休止中でない場合はNone）
+    pub quiet_until: Option<DateTime<Utc>>,
+    pub created_datetime: Option<DateTime<Utc>>,
+    pub updated_datetime: Option<DateTime<Utc>>,
+}
+
+impl From<SavedSearchRow> for SavedSearchResponse {
+    fn from(row: SavedSearchRow) -> Self {
+        let quiet_until = row.quiet_until().filter(|until| *until > Utc::now());
+        Self {
+            condition: row.criteria().unwrap_or_default(),
+            saved_search_id: row.saved_search_id,
+            search_name: row.search_name,
+            notify_email: row.notify_email,
+            notify_in_app: row.notify_in_app,
+            quiet_period_minutes: row.quiet_period_minutes,
+            is_active: row.is_active,
+            last_notified_datetime: row.last_notified_datetime,
+            quiet_until,
+            created_datetime: row.created_datetime,
+            updated_datetime: row.updated_datetime,
+        }
+    }
+}
+
+/// 保存検索の再評価イベント（t_saved_search_events）
+#[derive(Debug, Clone, FromRow)]
+pub struct SavedSearchEventRow {
+    pub event_id: i64,
+    /// lot_created / reservation_cancelled
+    pub event_type: String,
+    pub parking_lot_id: String,
+}
+
+/// 保存検索に一致した駐車場（通知本文用）
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSearchMatch {
+    pub parking_lot_id: String,
+    pub parking_lot_name: String,
+    pub address: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(last_notified: Option<DateTime<Utc>>, quiet_minutes: i32) -> SavedSearchRow {
+        SavedSearchRow {
+            saved_search_id: Uuid::new_v4(),
+            user_id: "user_000001".to_string(),
+            search_name: "通勤".to_string(),
+            condition_json: Value::Null,
+            notify_email: true,
+            notify_in_app: true,
+            quiet_period_minutes: quiet_minutes,
+            is_active: true,
+            last_notified_datetime: last_notified,
+            created_datetime: None,
+            updated_datetime: None,
+        }
+    }
+
+    #[test]
+    fn test_quiet_period() {
+        let now = Utc::now();
+        assert!(!row(None, 60).is_quiet_at(now));
+        assert!(row(Some(now - Duration::minutes(30)), 60).is_quiet_at(now));
+        assert!(!row(Some(now - Duration::minutes(60)), 60).is_quiet_at(now));
+        assert!(!row(Some(now), 0).is_quiet_at(now));
+
+        let request = SavedSearchRequest {
+            search_name: "  通勤  ".to_string(),
+            condition: ParkingSearchRequest { address: Some("渋谷区".to_string()), ..Default::default() },
+            notify_email: Some(false),
+            notify_in_app: Some(false),
+            quiet_period_minutes: None,
+            is_active: None,
+        };
+        assert!(request.clone().normalize().is_err());
+        let request = SavedSearchRequest { notify_in_app: Some(true), ..request }.normalize().unwrap();
+        assert_eq!(request.search_name, "通勤");
+    }
+}
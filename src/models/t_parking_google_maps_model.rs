@@ -149,7 +149,7 @@ impl TParkingGoogleMapsModel {
     }
     
     /// Haversine公式による2点間距離計算（km単位）
-    fn haversine_distance(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    pub fn haversine_distance(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
         const EARTH_RADIUS_KM: f64 = 6371.0;
         
         let lat1_rad = lat1.to_radians();
@@ -0,0 +1,215 @@
+// 論理名: 登録車両モデル
+// t_user_vehicles に対応
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+lazy_static! {
+    /// ナンバープレート形式（地名・分類番号・ひらがな・一連指定番号）
+    ///
+    /// ひらがなは事業用・自家用で使用される文字のみ（お・し・へ・ん は使用されない）
+    static ref LICENSE_PLATE_REGEX: Regex = Regex::new(
+        r"^([\p{Han}\p{Hiragana}ー]{1,4}) ?([0-9][0-9ACFHKLMPRXY]{0,2}) ?([あいうえかきくけこさすせそたちつてとなにぬねのはひふほまみむめもやゆよらりるれろわを]) ?(\d{2}-\d{2}|・\d-\d{2}|・・\d{2}|・・・\d|\d{1,4})$"
+    ).unwrap();
+}
+
+/// 登録車両（t_user_vehicles + m_parking_vehicle_types）
+#[derive(Debug, Clone, FromRow)]
+pub struct UserVehicleRow {
+    pub vehicle_id: Uuid,
+    pub user_id: String,
+    pub vehicle_name: String,
+    pub vehicle_type_id: Option<String>,
+    pub vehicle_type_name: Option<String>,
+    pub license_plate: String,
+    pub length_cm: Option<i32>,
+    pub width_cm: Option<i32>,
+    pub height_cm: Option<i32>,
+    pub weight_kg: Option<i32>,
+    pub is_default: bool,
+    pub created_datetime: Option<DateTime<Utc>>,
+    pub updated_datetime: Option<DateTime<Utc>>,
+}
+
+/// 車両登録・更新リクエスト
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserVehicleRequest {
+    pub vehicle_name: String,
+    /// m_parking_vehicle_types.vehicle_type_id
+    pub vehicle_type_id: Option<String>,
+    /// 例: 品川 300 あ 12-34（全角・空白の有無は正規化する）
+    pub license_plate: String,
+    pub length_cm: Option<i32>,
+    pub width_cm: Option<i32>,
+    pub height_cm: Option<i32>,
+    pub weight_kg: Option<i32>,
+    /// trueの場合はデフォルト車両に設定
+    pub is_default: Option<bool>,
+}
+
+impl UserVehicleRequest {
+    /// 入力検証とナンバーの正規化
+    pub fn normalize(mut self) -> Result<Self, String> {
+        self.vehicle_name = self.vehicle_name.trim().to_string();
+        if self.vehicle_name.is_empty() {
+            return Err("車両名は必須です".to_string());
+        }
+        if self.vehicle_name.chars().count() > 100 {
+            return Err("車両名は100文字以内で入力してください".to_string());
+        }
+
+        self.vehicle_type_id = self
+            .vehicle_type_id
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty());
+        self.license_plate = normalize_license_plate(&self.license_plate)?;
+
+        check_dimension(self.length_cm, "全長", 2000, "cm")?;
+        check_dimension(self.width_cm, "全幅", 500, "cm")?;
+        check_dimension(self.height_cm, "全高", 500, "cm")?;
+        check_dimension(self.weight_kg, "車両重量", 30000, "kg")?;
+
+        Ok(self)
+    }
+}
+
+/// 登録車両レスポンス
+#[derive(Debug, Clone, Serialize)]
+pub struct UserVehicleResponse {
+    pub vehicle_id: Uuid,
+    pub vehicle_name: String,
+    pub vehicle_type_id: Option<String>,
+    pub vehicle_type_name: Option<String>,
+    pub license_plate: String,
+    pub length_cm: Option<i32>,
+    pub width_cm: Option<i32>,
+    pub height_cm: Option<i32>,
+    pub weight_kg: Option<i32>,
+    pub is_default: bool,
+    pub created_datetime: Option<DateTime<Utc>>,
+    pub updated_datetime: Option<DateTime<Utc>>,
+}
+
+impl From<UserVehicleRow> for UserVehicleResponse {
+    fn from(row: UserVehicleRow) -> Self {
+        Self {
+            vehicle_id: row.vehicle_id,
+            vehicle_name: row.vehicle_name,
+            vehicle_type_id: row.vehicle_type_id,
+            vehicle_type_name: row.vehicle_type_name,
+            license_plate: row.license_plate,
+            length_cm: row.length_cm,
+            width_cm: row.width_cm,
+            height_cm: row.height_cm,
+            weight_kg: row.weight_kg,
+            is_default: row.is_default,
+            created_datetime: row.created_datetime,
+            updated_datetime: row.updated_datetime,
+        }
+    }
+}
+
+/// 予約時の利用情報（t_usage_info）の初期値
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageInfoPrefill {
+    pub vehicle_name: String,
+    pub vehicle_type_name: Option<String>,
+    pub license_plate: String,
+}
+
+/// 駐車場検索条件の初期値
+#[derive(Debug, Clone, Serialize)]
+pub struct VehicleSearchPrefill {
+    pub vehicle_type_id: Option<String>,
+}
+
+/// デフォルト車両と予約・検索の初期値
+#[derive(Debug, Clone, Serialize)]
+pub struct DefaultVehicleResponse {
+    pub vehicle: UserVehicleResponse,
+    pub usage_info: UsageInfoPrefill,
+    pub search_filters: VehicleSearchPrefill,
+}
+
+impl From<UserVehicleRow> for DefaultVehicleResponse {
+    fn from(row: UserVehicleRow) -> Self {
+        Self {
+            usage_info: UsageInfoPrefill {
+                vehicle_name: row.vehicle_name.clone(),
+                vehicle_type_name: row.vehicle_type_name.clone(),
+                license_plate: row.license_plate.clone(),
+            },
+            search_filters: VehicleSearchPrefill {
+                vehicle_type_id: row.vehicle_type_id.clone(),
+            },
+            vehicle: row.into(),
+        }
+    }
+}
+
+/// ナンバープレートを検証し「品川 300 あ 12-34」形式に正規化
+///
+/// 全角英数字・空白・ハイフン・中点は半角（中点は「・」）に揃える。
+/// 一連指定番号は「1234」のように数字のみでも入力できる
+pub fn normalize_license_plate(value: &str) -> Result<String, String> {
+    let converted: String = value
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '０'..='９' | 'Ａ'..='Ｚ' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            'ａ'..='ｚ' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c).to_ascii_uppercase(),
+            'a'..='z' => c.to_ascii_uppercase(),
+            '－' | '‐' | '−' | '–' | '―' => '-',
+            '･' | '·' | '.' | '．' => '・',
+            '　' => ' ',
+            _ => c,
+        })
+        .collect();
+    let compact = converted.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let invalid = || "ナンバーの形式が正しくありません（例：品川 300 あ 12-34）".to_string();
+    let caps = LICENSE_PLATE_REGEX.captures(&compact).ok_or_else(invalid)?;
+
+    let digits: String = caps[4].chars().filter(char::is_ascii_digit).collect();
+    if digits.starts_with('0') {
+        return Err(invalid());
+    }
+    let serial = match digits.len() {
+        4 => format!("{}-{}", &digits[..2], &digits[2..]),
+        3 => format!("・{}-{}", &digits[..1], &digits[1..]),
+        2 => format!("・・{}", digits),
+        _ => format!("・・・{}", digits),
+    };
+
+    Ok(format!("{} {} {} {}", &caps[1], &caps[2], &caps[3], serial))
+}
+
+fn check_dimension(value: Option<i32>, label: &str, max: i32, unit: &str) -> Result<(), String> {
+    match value {
+        Some(v) if !(1..=max).contains(&v) => Err(format!("{}は1{}以上{}{}以下で入力してください", label, unit, max, unit)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_license_plate() {
+        assert_eq!(normalize_license_plate("品川300あ1234").unwrap(), "品川 300 あ 12-34");
+        assert_eq!(normalize_license_plate("品川 300 あ 12-34").unwrap(), "品川 300 あ 12-34");
+        assert_eq!(normalize_license_plate("尾張小牧　５３０　さ　・１－２３").unwrap(), "尾張小牧 530 さ ・1-23");
+        assert_eq!(normalize_license_plate("いわき 33a ね 7").unwrap(), "いわき 33A ね ・・・7");
+        assert_eq!(normalize_license_plate("横浜 500 わ ・・12").unwrap(), "横浜 500 わ ・・12");
+
+        assert!(normalize_license_plate("品川 300 お 12-34").is_err());
+        assert!(normalize_license_plate("品川 300 あ 0123").is_err());
+        assert!(normalize_license_plate("品川 300 あ 12345").is_err());
+        assert!(normalize_license_plate("ABC-1234").is_err());
+        assert!(normalize_license_plate("").is_err());
+    }
+}
@@ -0,0 +1,317 @@
+// src/payments/fake_payment_provider.rs
+//! テスト用の決済プロバイダー
+//!
+//! プロセス内のメモリ上で与信・売上確定・返金・取消を再現する（実際の請求は行わない）。
+//! 再起動でメモリ上の決済が失われても、このプロバイダーが採番した決済IDへの操作は受け付ける
+//! （操作の可否と金額の上限はt_payments側で検証済みのため）。
+//! 支払い方法に`tok_declined`を指定すると与信が拒否され、`tok_insufficient_funds`は残高不足として拒否される。
+//! Webhookの署名は`PAYMENT_WEBHOOK_SECRET`によるHMAC-SHA256（16進数）。
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use tracing::debug;
+use uuid::Uuid;
+
+use super::{AuthorizeParams, PaymentError, PaymentProvider, ProviderAuthorization, ProviderCapture, ProviderRefund};
+use crate::models::payment_model::PaymentWebhookEvent;
+use crate::storage::{hmac_sha256, to_hex};
+
+/// 与信を拒否する支払い方法
+const DECLINED_PAYMENT_METHODS: [(&str, &str); 2] = [
+    ("tok_declined", "カードが拒否されました"),
+    ("tok_insufficient_funds", "残高が不足しています"),
+];
+
+/// 決済IDの接頭辞
+const CHARGE_ID_PREFIX: &str = "fake_pay";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FakeChargeStatus {
+    Authorized,
+    Captured,
+    Voided,
+}
+
+#[derive(Debug, Clone)]
+struct FakeCharge {
+    status: FakeChargeStatus,
+    amount: i64,
+    captured_amount: i64,
+    refunded_amount: i64,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    charges: HashMap<String, FakeCharge>,
+    // 冪等キーごとの処理結果
+    authorizations: HashMap<String, ProviderAuthorization>,
+    captures: HashMap<String, ProviderCapture>,
+    refunds: HashMap<String, ProviderRefund>,
+    voids: HashSet<String>,
+}
+
+impl FakeState {
+    /// 再起動後もt_paymentsに保存済みのIDと重複しないようにランダムなIDを採番
+    fn next_id(&self, prefix: &str) -> String {
+        format!("{}_{}", prefix, Uuid::new_v4().simple())
+    }
+
+    /// 決済を取得（再起動などでメモリ上にない場合、採番済みの形式のIDなら指定の状態で復元する）
+    fn charge_mut(&mut self, provider_payment_id: &str, restored: FakeCharge) -> Result<&mut FakeCharge, PaymentError> {
+        let issued = provider_payment_id
+            .strip_prefix(CHARGE_ID_PREFIX)
+            .is_some_and(|rest| rest.starts_with('_'));
+        match self.charges.entry(provider_payment_id.to_string()) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) if issued => {
+                debug!("メモリ上にない決済を復元します: {}", provider_payment_id);
+                Ok(entry.insert(restored))
+            }
+            Entry::Vacant(_) => Err(PaymentError::NotFound(provider_payment_id.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FakePaymentProvider {
+    webhook_secret: String,
+    state: Mutex<FakeState>,
+}
+
+impl FakePaymentProvider {
+    pub fn new(webhook_secret: &str) -> Self {
+        Self {
+            webhook_secret: webhook_secret.to_string(),
+            state: Mutex::new(FakeState::default()),
+        }
+    }
+
+    fn sign(&self, body: &[u8]) -> String {
+        to_hex(&hmac_sha256(self.webhook_secret.as_bytes(), body))
+    }
+}
+
+#[async_trait]
+impl PaymentProvider for FakePaymentProvider {
+    fn provider_name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn authorize(&self, params: &AuthorizeParams) -> Result<ProviderAuthorization, PaymentError> {
+        if params.amount <= 0 {
+            return Err(PaymentError::InvalidRequest("与信金額は1円以上を指定してください".to_string()));
+        }
+        let mut state = self.state.lock().unwrap();
+        if let Some(authorization) = state.authorizations.get(&params.idempotency_key) {
+            debug!("冪等キーが一致するため前回の与信結果を返します: {}", params.idempotency_key);
+            return Ok(authorization.clone());
+        }
+        if let Some((_, reason)) = DECLINED_PAYMENT_METHODS
+            .iter()
+            .find(|(method, _)| params.payment_method.as_deref() == Some(*method))
+        {
+            return Err(PaymentError::Declined(reason.to_string()));
+        }
+
+        let authorization = ProviderAuthorization {
+            provider_payment_id: state.next_id(CHARGE_ID_PREFIX),
+            amount: params.amount,
+        };
+        state.charges.insert(
+            authorization.provider_payment_id.clone(),
+            FakeCharge {
+                status: FakeChargeStatus::Authorized,
+                amount: params.amount,
+                captured_amount: 0,
+                refunded_amount: 0,
+            },
+        );
+        state.authorizations.insert(params.idempotency_key.clone(), authorization.clone());
+        Ok(authorization)
+    }
+
+    async fn capture(
+        &self,
+        provider_payment_id: &str,
+        amount: i64,
+        idempotency_key: &str,
+    ) -> Result<ProviderCapture, PaymentError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(capture) = state.captures.get(idempotency_key) {
+            return Ok(capture.clone());
+        }
+        let restored = FakeCharge {
+            status: FakeChargeStatus::Authorized,
+            amount,
+            captured_amount: 0,
+            refunded_amount: 0,
+        };
+        let charge = state.charge_mut(provider_payment_id, restored)?;
+        if charge.status != FakeChargeStatus::Authorized {
+            return Err(PaymentError::InvalidRequest("与信済みの決済ではありません".to_string()));
+        }
+        if amount <= 0 || amount > charge.amount {
+            return Err(PaymentError::InvalidRequest("売上確定金額が与信金額を超えています".to_string()));
+        }
+        charge.status = FakeChargeStatus::Captured;
+        charge.captured_amount = amount;
+
+        let capture = ProviderCapture { amount };
+        state.captures.insert(idempotency_key.to_string(), capture.clone());
+        Ok(capture)
+    }
+
+    async fn refund(
+        &self,
+        provider_payment_id: &str,
+        amount: i64,
+        idempotency_key: &str,
+    ) -> Result<ProviderRefund, PaymentError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(refund) = state.refunds.get(idempotency_key) {
+            return Ok(refund.clone());
+        }
+        // 再起動前の売上確定金額・返金済み金額は分からないため、返金額の上限はt_payments側の検証に任せる
+        let restored = FakeCharge {
+            status: FakeChargeStatus::Captured,
+            amount: i64::MAX,
+            captured_amount: i64::MAX,
+            refunded_amount: 0,
+        };
+        let charge = state.charge_mut(provider_payment_id, restored)?;
+        if charge.status != FakeChargeStatus::Captured {
+            return Err(PaymentError::InvalidRequest("売上確定済みの決済ではありません".to_string()));
+        }
+        if amount <= 0 || charge.refunded_amount + amount > charge.captured_amount {
+            return Err(PaymentError::InvalidRequest("返金額が売上確定金額を超えています".to_string()));
+        }
+        charge.refunded_amount += amount;
+
+        let refund = ProviderRefund { refund_id: state.next_id("fake_re"), amount };
+        state.refunds.insert(idempotency_key.to_string(), refund.clone());
+        Ok(refund)
+    }
+
+    async fn void(&self, provider_payment_id: &str, idempotency_key: &str) -> Result<(), PaymentError> {
+        let mut state = self.state.lock().unwrap();
+        if state.voids.contains(idempotency_key) {
+            return Ok(());
+        }
+        let restored = FakeCharge {
+            status: FakeChargeStatus::Authorized,
+            amount: 0,
+            captured_amount: 0,
+            refunded_amount: 0,
+        };
+        let charge = state.charge_mut(provider_payment_id, restored)?;
+        if charge.status != FakeChargeStatus::Authorized {
+            return Err(PaymentError::InvalidRequest("与信済みの決済ではありません".to_string()));
+        }
+        charge.status = FakeChargeStatus::Voided;
+        state.voids.insert(idempotency_key.to_string());
+        Ok(())
+    }
+
+    fn parse_webhook(&self, body: &[u8], signature: &str) -> Result<PaymentWebhookEvent, PaymentError> {
+        if !constant_time_eq(self.sign(body).as_bytes(), signature.trim().as_bytes()) {
+            return Err(PaymentError::InvalidSignature);
+        }
+        serde_json::from_slice(body)
+            .map_err(|e| PaymentError::InvalidRequest(format!("Webhookの本文が正しくありません: {}", e)))
+    }
+
+    fn simulate_webhook(&self, event: &PaymentWebhookEvent) -> Result<(Vec<u8>, String), PaymentError> {
+        let body = serde_json::to_vec(event).map_err(|e| PaymentError::Provider(e.to_string()))?;
+        let signature = self.sign(&body);
+        Ok((body, signature))
+    }
+}
+
+/// タイミング攻撃対策の比較
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::payment_model::PaymentEventType;
+
+    fn params(key: &str, payment_method: Option<&str>) -> AuthorizeParams {
+        AuthorizeParams {
+            reference: "R1".to_string(),
+            amount: 1000,
+            currency: "JPY".to_string(),
+            payment_method: payment_method.map(str::to_string),
+            idempotency_key: key.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fake_provider_lifecycle_and_idempotency() {
+        let provider = FakePaymentProvider::new("secret");
+
+        let first = provider.authorize(&params("k1", None)).await.unwrap();
+        let retried = provider.authorize(&params("k1", None)).await.unwrap();
+        assert_eq!(first.provider_payment_id, retried.provider_payment_id);
+        assert!(matches!(
+            provider.authorize(&params("k2", Some("tok_declined"))).await,
+            Err(PaymentError::Declined(_))
+        ));
+
+        let id = first.provider_payment_id;
+        assert!(provider.capture(&id, 2000, "c0").await.is_err());
+        assert_eq!(provider.capture(&id, 800, "c1").await.unwrap().amount, 800);
+        assert_eq!(provider.capture(&id, 800, "c1").await.unwrap().amount, 800);
+        assert!(provider.void(&id, "v1").await.is_err());
+
+        let refund = provider.refund(&id, 500, "r1").await.unwrap();
+        assert_eq!(provider.refund(&id, 500, "r1").await.unwrap().refund_id, refund.refund_id);
+        assert!(provider.refund(&id, 500, "r2").await.is_err());
+        assert!(provider.refund(&id, 300, "r3").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fake_provider_restores_charges_after_restart() {
+        let before_restart = FakePaymentProvider::new("secret");
+        let captured = before_restart.authorize(&params("k1", None)).await.unwrap().provider_payment_id;
+        let voided = before_restart.authorize(&params("k2", None)).await.unwrap().provider_payment_id;
+
+        let provider = FakePaymentProvider::new("secret");
+        assert_eq!(provider.capture(&captured, 800, "c1").await.unwrap().amount, 800);
+        assert!(provider.refund(&captured, 800, "r1").await.is_ok());
+        assert!(provider.void(&voided, "v1").await.is_ok());
+        assert!(provider.refund(&voided, 100, "r2").await.is_err());
+
+        let restarted = FakePaymentProvider::new("secret");
+        assert!(restarted.refund(&captured, 300, "r3").await.is_ok());
+        assert!(matches!(
+            restarted.capture("unknown_payment", 800, "c2").await,
+            Err(PaymentError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_fake_provider_webhook_signature() {
+        let provider = FakePaymentProvider::new("secret");
+        let event = PaymentWebhookEvent {
+            event_id: "evt_1".to_string(),
+            event_type: PaymentEventType::Captured,
+            provider_payment_id: "fake_pay_00000001".to_string(),
+            amount: Some(800),
+            failure_reason: None,
+            occurred_at: chrono::Utc::now(),
+        };
+
+        let (body, signature) = provider.simulate_webhook(&event).unwrap();
+        let parsed = provider.parse_webhook(&body, &signature).unwrap();
+        assert_eq!(parsed.event_id, "evt_1");
+        assert_eq!(parsed.event_type, PaymentEventType::Captured);
+
+        let other = FakePaymentProvider::new("other-secret");
+        assert!(matches!(other.parse_webhook(&body, &signature), Err(PaymentError::InvalidSignature)));
+    }
+}
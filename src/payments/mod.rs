@@ -0,0 +1,180 @@
+// src/payments/mod.rs
+//! 決済処理（与信・売上確定・返金・取消）
+//!
+//! `PaymentProvider`トレイトで決済代行会社を抽象化する。開発・テスト用に
+//! プロセス内で完結する`FakePaymentProvider`を提供する。
+//! 各操作には冪等キーを渡し、同じキーでの再送は同じ結果を返すことをプロバイダーに求める。
+
+use std::env;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use thiserror::Error;
+use tracing::warn;
+
+use crate::controllers::ApiError;
+use crate::models::payment_model::PaymentWebhookEvent;
+use crate::utils::env::{is_development, require_signing_secret};
+
+pub mod fake_payment_provider;
+
+pub use fake_payment_provider::FakePaymentProvider;
+
+/// 決済の通貨（円）
+pub const DEFAULT_CURRENCY: &str = "JPY";
+
+/// Webhook署名のヘッダー名
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Payment-Signature";
+
+#[derive(Error, Debug)]
+pub enum PaymentError {
+    #[error("決済が承認されませんでした: {0}")]
+    Declined(String),
+
+    #[error("無効な決済リクエストです: {0}")]
+    InvalidRequest(String),
+
+    #[error("決済が見つかりません: {0}")]
+    NotFound(String),
+
+    #[error("Webhookの署名が正しくありません")]
+    InvalidSignature,
+
+    #[error("決済プロバイダーエラー: {0}")]
+    Provider(String),
+
+    #[error("決済設定エラー: {0}")]
+    Config(String),
+}
+
+impl From<PaymentError> for ApiError {
+    fn from(err: PaymentError) -> Self {
+        match err {
+            PaymentError::Declined(msg) => ApiError::BadRequestError(format!("決済が承認されませんでした: {}", msg)),
+            PaymentError::InvalidRequest(msg) => ApiError::ValidationError(msg),
+            PaymentError::NotFound(msg) => ApiError::NotFoundError(msg),
+            PaymentError::InvalidSignature => {
+                ApiError::AuthenticationError("Webhookの署名が正しくありません".to_string())
+            }
+            PaymentError::Provider(msg) => {
+                tracing::error!("決済プロバイダーエラー: {}", msg);
+                ApiError::ServiceUnavailableError("決済サービスに接続できません".to_string())
+            }
+            PaymentError::Config(msg) => ApiError::ServiceUnavailableError(msg),
+        }
+    }
+}
+
+/// 与信リクエスト
+#[derive(Debug, Clone)]
+pub struct AuthorizeParams {
+    /// 加盟店側の参照番号（予約ID）
+    pub reference: String,
+    /// 与信金額（円）
+    pub amount: i64,
+    pub currency: String,
+    /// カードトークン等の支払い方法（未指定の場合は登録済みの支払い方法）
+    pub payment_method: Option<String>,
+    pub idempotency_key: String,
+}
+
+/// 与信結果
+#[derive(Debug, Clone)]
+pub struct ProviderAuthorization {
+    pub provider_payment_id: String,
+    pub amount: i64,
+}
+
+/// 売上確定結果
+#[derive(Debug, Clone)]
+pub struct ProviderCapture {
+    pub amount: i64,
+}
+
+/// 返金結果
+#[derive(Debug, Clone)]
+pub struct ProviderRefund {
+    pub refund_id: String,
+    pub amount: i64,
+}
+
+/// 決済プロバイダー
+#[async_trait]
+pub trait PaymentProvider: Send + Sync {
+    /// プロバイダー名（t_payments.providerに保存）
+    fn provider_name(&self) -> &'static str;
+
+    /// 与信（オーソリ）
+    async fn authorize(&self, params: &AuthorizeParams) -> Result<ProviderAuthorization, PaymentError>;
+
+    /// 与信済みの決済の売上確定（与信金額以下）
+    async fn capture(
+        &self,
+        provider_payment_id: &str,
+        amount: i64,
+        idempotency_key: &str,
+    ) -> Result<ProviderCapture, PaymentError>;
+
+    /// 売上確定済みの決済の返金（一部返金可）
+    async fn refund(
+        &self,
+        provider_payment_id: &str,
+        amount: i64,
+        idempotency_key: &str,
+    ) -> Result<ProviderRefund, PaymentError>;
+
+    /// 与信の取消
+    async fn void(&self, provider_payment_id: &str, idempotency_key: &str) -> Result<(), PaymentError>;
+
+    /// Webhookの署名を検証してイベントを取得
+    fn parse_webhook(&self, body: &[u8], signature: &str) -> Result<PaymentWebhookEvent, PaymentError>;
+
+    /// Webhookの本文と署名を生成（ローカルでの動作確認用、テスト用プロバイダーのみ実装）
+    fn simulate_webhook(&self, _event: &PaymentWebhookEvent) -> Result<(Vec<u8>, String), PaymentError> {
+        Err(PaymentError::InvalidRequest(
+            "この決済プロバイダーはWebhookの模擬送信に対応していません".to_string(),
+        ))
+    }
+}
+
+/// 決済設定
+#[derive(Debug, Clone)]
+pub struct PaymentConfig {
+    /// プロバイダー種別 (fake)
+    pub provider: String,
+    /// Webhook署名用の秘密鍵
+    pub webhook_secret: String,
+}
+
+impl PaymentConfig {
+    /// 環境変数から決済設定を読み込む
+    pub fn from_env() -> Self {
+        Self {
+            provider: provider_from_env(),
+            webhook_secret: require_signing_secret("PAYMENT_WEBHOOK_SECRET"),
+        }
+    }
+}
+
+/// 環境変数PAYMENT_PROVIDERからプロバイダー種別を読み込む（未設定の場合はfake）
+fn provider_from_env() -> String {
+    env::var("PAYMENT_PROVIDER")
+        .unwrap_or_else(|_| "fake".to_string())
+        .to_lowercase()
+}
+
+/// Webhookの模擬送信を許可するか（開発環境かつテスト用プロバイダーの場合のみ）
+pub fn simulated_webhooks_enabled() -> bool {
+    is_development() && provider_from_env() == "fake"
+}
+
+/// 設定に応じた決済プロバイダーを生成
+pub fn create_payment_provider(config: &PaymentConfig) -> Result<Arc<dyn PaymentProvider>, PaymentError> {
+    match config.provider.as_str() {
+        "fake" => {
+            warn!("決済プロバイダー: テスト用（実際の請求は行われません）");
+            Ok(Arc::new(FakePaymentProvider::new(&config.webhook_secret)))
+        }
+        other => Err(PaymentError::Config(format!("不明な決済プロバイダーです: {}", other))),
+    }
+}
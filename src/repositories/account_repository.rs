@@ -0,0 +1,556 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{Postgres, Transaction};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::account_model::{
+    AccountDeletionRow, AccountExport, AccountRow, DELETED_ACCOUNT_ID, DELETION_STATUS_CANCELLED,
+    DELETION_STATUS_COMPLETED, DELETION_STATUS_PENDING,
+};
+
+/// 個人データのエクスポート・アカウント削除のリポジトリ
+#[derive(Debug, Clone)]
+pub struct AccountRepository {
+    db: PostgresDatabase,
+}
+
+impl AccountRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    /// ログインIDからアカウントを取得
+    pub async fn find_account(&self, login_id: Uuid) -> Result<Option<AccountRow>, DatabaseError> {
+        let sql = r#"
+            SELECT
+                l.login_id::text AS login_id,
+                l.email,
+                l.pass_word,
+                (l.is_user_owner = '1') AS is_owner,
+                COALESCE(o.owner_id, u.user_id) AS account_id
+            FROM m_login l
+            LEFT JOIN m_users u ON u.login_id = l.login_id AND l.is_user_owner = '0'
+            LEFT JOIN m_owners o ON o.login_id = l.login_id AND l.is_user_owner = '1'
+            WHERE l.login_id = $1
+        "#;
+
+        let params = vec![SqlParam::String(login_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, AccountRow>(sql)
+            .bind(login_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("アカウント取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("アカウント取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 個人データを収集（パスワード・トークンは含めない）
+    pub async fn export_account(&self, account: &AccountRow) -> Result<AccountExport, DatabaseError> {
+        let account_id = account.account_id.clone().unwrap_or_default();
+
+        let login = self
+            .fetch_json(
+                r#"
+                SELECT json_build_object(
+                    'login_id', login_id,
+                    'email', email,
+                    'phone_number', phone_number,
+                    'login_datetime', login_datetime,
+                    'password_changed_datetime', password_changed_datetime,
+                    'created_datetime', created_datetime,
+                    'updated_datetime', updated_datetime
+                )
+                FROM m_login
+                WHERE login_id = $1::uuid
+                "#,
+                &account.login_id,
+            )
+            .await?;
+
+        let profile_sql = if account.is_owner {
+            "SELECT row_to_json(o) FROM m_owners o WHERE o.owner_id = $1"
+        } else {
+            "SELECT row_to_json(u) FROM m_users u WHERE u.user_id = $1"
+        };
+        let profile = self.fetch_json(profile_sql, &account_id).await?;
+
+        let photos_sql = if account.is_owner {
+            "SELECT COALESCE(json_agg(p ORDER BY p.created_datetime), '[]'::json) FROM m_profiles p WHERE p.owner_id = $1"
+        } else {
+            "SELECT COALESCE(json_agg(p ORDER BY p.created_datetime), '[]'::json) FROM m_profiles p WHERE p.user_id = $1"
+        };
+        let profile_photos = self.fetch_json(photos_sql, &account_id).await?;
+
+        let vehicles = self
+            .fetch_json(
+                r#"
+                SELECT COALESCE(json_agg(
+                    to_jsonb(v) || jsonb_build_object('vehicle_type_name', vt.vehicle_type)
+                    ORDER BY v.created_datetime
+                ), '[]'::json)
+                FROM t_user_vehicles v
+                LEFT JOIN m_parking_vehicle_types vt ON vt.vehicle_type_id = v.vehicle_type_id
+                WHERE v.user_id = $1
+                "#,
+                &account_id,
+            )
+            .await?;
+
+        let reservations = self
+            .fetch_json(
+                r#"
+                SELECT COALESCE(json_agg(
+                    to_jsonb(r) || jsonb_build_object(
+                        'details', (
+                            SELECT COALESCE(json_agg(d), '[]'::json)
+                            FROM t_reservation_details d
+                            WHERE d.reservation_id = r.reservation_id
+                        ),
+                        'usage_info', (
+                            SELECT row_to_json(ui) FROM t_usage_info ui WHERE ui.reservation_id = r.reservation_id
+                        ),
+                        'parking_status', (
+                            SELECT row_to_json(ps) FROM t_parking_status ps WHERE ps.reservation_id = r.reservation_id
+                        )
+                    )
+                    ORDER BY r.start_datetime DESC
+                ), '[]'::json)
+                FROM t_reservations r
+                WHERE r.user_id = $1
+                "#,
+                &account_id,
+            )
+            .await?;
+
+        let favorites = self
+            .fetch_json(
+                "SELECT COALESCE(json_agg(f ORDER BY f.created_datetime), '[]'::json) FROM t_favorites f WHERE f.user_id = $1",
+                &account_id,
+            )
+            .await?;
+
+        let search_history = self
+            .fetch_json(
+                "SELECT COALESCE(json_agg(h ORDER BY h.created_datetime DESC), '[]'::json) FROM t_parking_search_history h WHERE h.user_id = $1",
+                &account_id,
+            )
+            .await?;
+
+        let saved_searches = self
+            .fetch_json(
+                r#"
+                SELECT COALESCE(json_agg(
+                    to_jsonb(s) || jsonb_build_object(
+                        'notified_parking_lots', (
+                            SELECT COALESCE(json_agg(m ORDER BY m.notified_datetime), '[]'::json)
+                            FROM t_saved_search_matches m
+                            WHERE m.saved_search_id = s.saved_search_id
+                        )
+                    )
+                    ORDER BY s.created_datetime
+                ), '[]'::json)
+                FROM t_saved_searches s
+                WHERE s.user_id = $1
+                "#,
+                &account_id,
+            )
+            .await?;
+
+        let notifications = self
+            .fetch_json(
+                "SELECT COALESCE(json_agg(n ORDER BY n.created_datetime DESC), '[]'::json) FROM t_notifications n WHERE n.user_id = $1",
+                &account_id,
+            )
+            .await?;
+
+        let parking_lots = self
+            .fetch_json(
+                "SELECT COALESCE(json_agg(pl ORDER BY pl.created_datetime), '[]'::json) FROM t_parking_lots pl WHERE pl.owner_id = $1",
+                &account_id,
+            )
+            .await?;
+
+        Ok(AccountExport {
+            exported_at: Utc::now(),
+            user_type: if account.is_owner { "owner" } else { "user" }.to_string(),
+            login,
+            profile,
+            profile_photos,
+            vehicles,
+            reservations,
+            favorites,
+            search_history,
+            saved_searches,
+            notifications,
+            parking_lots,
+        })
+    }
+
+    /// 利用中・利用予定の予約があるか確認（オーナーは所有駐車場の予約）
+    pub async fn has_active_reservations(&self, account: &AccountRow) -> Result<bool, DatabaseError> {
+        let sql = if account.is_owner {
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM t_reservations r
+                INNER JOIN t_parking_lots pl ON pl.parking_lot_id = r.parking_lot_id
+                WHERE pl.owner_id = $1 AND r.status IN ('1', '2') AND r.end_datetime > CURRENT_TIMESTAMP
+            )
+            "#
+        } else {
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM t_reservations r
+                WHERE r.user_id = $1 AND r.status IN ('1', '2') AND r.end_datetime > CURRENT_TIMESTAMP
+            )
+            "#
+        };
+        let account_id = account.account_id.clone().unwrap_or_default();
+
+        let params = vec![SqlParam::String(account_id.clone())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, bool>(sql)
+            .bind(&account_id)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(exists) => Ok(exists),
+            Err(e) => {
+                error!("予約の確認に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("予約の確認に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 猶予期間中の削除リクエストを取得
+    pub async fn find_pending_deletion(&self, login_id: Uuid) -> Result<Option<AccountDeletionRow>, DatabaseError> {
+        let sql = r#"
+            SELECT request_id, login_id, status, scheduled_datetime, created_datetime
+            FROM t_account_deletion_requests
+            WHERE login_id = $1 AND status = $2
+        "#;
+
+        let params = vec![
+            SqlParam::String(login_id.to_string()),
+            SqlParam::String(DELETION_STATUS_PENDING.to_string()),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, AccountDeletionRow>(sql)
+            .bind(login_id)
+            .bind(DELETION_STATUS_PENDING)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("削除リクエストの取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("削除リクエストの取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 削除リクエストを登録
+    pub async fn create_deletion_request(
+        &self,
+        login_id: Uuid,
+        scheduled_at: DateTime<Utc>,
+    ) -> Result<AccountDeletionRow, DatabaseError> {
+        let sql = r#"
+            INSERT INTO t_account_deletion_requests (login_id, status, scheduled_datetime)
+            VALUES ($1, $2, $3)
+            RETURNING request_id, login_id, status, scheduled_datetime, created_datetime
+        "#;
+
+        let params = vec![
+            SqlParam::String(login_id.to_string()),
+            SqlParam::String(DELETION_STATUS_PENDING.to_string()),
+            SqlParam::DateTime(scheduled_at),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, AccountDeletionRow>(sql)
+            .bind(login_id)
+            .bind(DELETION_STATUS_PENDING)
+            .bind(scheduled_at)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(row) => {
+                info!("削除リクエスト登録: login_id={}, scheduled={}", login_id, scheduled_at);
+                Ok(row)
+            }
+            Err(e) => {
+                error!("削除リクエストの登録に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("削除リクエストの登録に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 猶予期間中の削除リクエストを取り消し
+    pub async fn cancel_deletion_request(&self, login_id: Uuid) -> Result<bool, DatabaseError> {
+        let sql = r#"
+            UPDATE t_account_deletion_requests
+            SET status = $2, cancelled_datetime = CURRENT_TIMESTAMP, updated_datetime = CURRENT_TIMESTAMP
+            WHERE login_id = $1 AND status = $3
+        "#;
+
+        let params = vec![
+            SqlParam::String(login_id.to_string()),
+            SqlParam::String(DELETION_STATUS_CANCELLED.to_string()),
+            SqlParam::String(DELETION_STATUS_PENDING.to_string()),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql)
+            .bind(login_id)
+            .bind(DELETION_STATUS_CANCELLED)
+            .bind(DELETION_STATUS_PENDING)
+            .execute(self.db.pool())
+            .await
+        {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(e) => {
+                error!("削除リクエストの取り消しに失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("削除リクエストの取り消しに失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 猶予期間を過ぎた削除リクエストを取得
+    pub async fn find_due_deletions(&self, limit: i64) -> Result<Vec<AccountDeletionRow>, DatabaseError> {
+        let sql = r#"
+            SELECT request_id, login_id, status, scheduled_datetime, created_datetime
+            FROM t_account_deletion_requests
+            WHERE status = $1 AND scheduled_datetime <= CURRENT_TIMESTAMP
+            ORDER BY scheduled_datetime
+            LIMIT $2
+        "#;
+
+        let params = vec![
+            SqlParam::String(DELETION_STATUS_PENDING.to_string()),
+            SqlParam::Integer(limit),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, AccountDeletionRow>(sql)
+            .bind(DELETION_STATUS_PENDING)
+            .bind(limit)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("削除対象リクエストの取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("削除対象リクエストの取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// アカウントを削除し、削除したプロフィール写真のパスを返す
+    ///
+    /// 予約・決済・駐車場は会計記録として残し、利用者・オーナーIDを匿名化する。
+    /// リクエストが既に処理・取り消し済みの場合はNoneを返す
+    pub async fn delete_account(
+        &self,
+        request_id: Uuid,
+        account: &AccountRow,
+    ) -> Result<Option<Vec<String>>, DatabaseError> {
+        let mut tx = self.db.pool().begin().await.map_err(|e| {
+            error!("トランザクション開始に失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクション開始に失敗: {}", e))
+        })?;
+
+        // 他のワーカーとの二重実行・取り消しとの競合をここで排除する
+        let claimed = Self::execute(
+            &mut tx,
+            r#"
+            UPDATE t_account_deletion_requests
+            SET status = $2, completed_datetime = CURRENT_TIMESTAMP, updated_datetime = CURRENT_TIMESTAMP
+            WHERE request_id = $1::uuid AND status = $3
+            "#,
+            &[&request_id.to_string(), DELETION_STATUS_COMPLETED, DELETION_STATUS_PENDING],
+        )
+        .await?;
+        if claimed == 0 {
+            return Ok(None);
+        }
+
+        let account_id = account.account_id.clone().unwrap_or_default();
+        let photo_sql = if account.is_owner {
+            "SELECT photo_path FROM m_profiles WHERE owner_id = $1"
+        } else {
+            "SELECT photo_path FROM m_profiles WHERE user_id = $1"
+        };
+        let photo_params = vec![SqlParam::String(account_id.clone())];
+        log_sql_query(photo_sql, &photo_params, None);
+        let photo_paths = sqlx::query_scalar::<_, String>(photo_sql)
+            .bind(&account_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("プロフィール写真の取得に失敗: {}", e);
+                log_sql_error(photo_sql, &photo_params, &e.to_string());
+                DatabaseError::QueryError(format!("プロフィール写真の取得に失敗: {}", e))
+            })?;
+
+        let id = account_id.as_str();
+        let account_statements: Vec<(&str, Vec<&str>)> = if account.is_owner {
+            vec![
+                ("DELETE FROM m_profiles WHERE owner_id = $1", vec![id]),
+                (
+                    r#"
+                    UPDATE t_parking_lots
+                    SET owner_id = $2, status = '停止中', end_date = COALESCE(end_date, CURRENT_DATE),
+                        end_reason = 'アカウント削除', updated_datetime = CURRENT_TIMESTAMP
+                    WHERE owner_id = $1
+                    "#,
+                    vec![id, DELETED_ACCOUNT_ID],
+                ),
+                ("DELETE FROM m_owners WHERE owner_id = $1", vec![id]),
+            ]
+        } else {
+            vec![
+                ("DELETE FROM m_profiles WHERE user_id = $1", vec![id]),
+                ("DELETE FROM t_favorites WHERE user_id = $1", vec![id]),
+                ("DELETE FROM t_parking_search_history WHERE user_id = $1", vec![id]),
+                // 通知済みの駐車場は保存検索と一緒に削除される
+                ("DELETE FROM t_saved_searches WHERE user_id = $1", vec![id]),
+                ("DELETE FROM t_notifications WHERE user_id = $1", vec![id]),
+                ("DELETE FROM t_user_vehicles WHERE user_id = $1", vec![id]),
+                (
+                    r#"
+                    UPDATE t_usage_info
+                    SET vehicle_name = NULL, license_plate = NULL, updated_datetime = CURRENT_TIMESTAMP
+                    WHERE reservation_id IN (SELECT reservation_id FROM t_reservations WHERE user_id = $1)
+                    "#,
+                    vec![id],
+                ),
+                (
+                    "UPDATE t_reservations SET user_id = $2, updated_datetime = CURRENT_TIMESTAMP WHERE user_id = $1",
+                    vec![id, DELETED_ACCOUNT_ID],
+                ),
+                // 決済は返金・売上確定の照合に使うため削除しない
+                (
+                    "UPDATE t_payments SET user_id = $2, updated_datetime = CURRENT_TIMESTAMP WHERE user_id = $1",
+                    vec![id, DELETED_ACCOUNT_ID],
+                ),
+                // 領収書は発行者側の保存義務があるため削除しない
+                (
+                    "UPDATE t_receipts SET user_id = $2, updated_datetime = CURRENT_TIMESTAMP WHERE user_id = $1",
+                    vec![id, DELETED_ACCOUNT_ID],
+                ),
+                ("DELETE FROM m_users WHERE user_id = $1", vec![id]),
+            ]
+        };
+        for (sql, args) in account_statements {
+            Self::execute(&mut tx, sql, &args).await?;
+        }
+
+        let login_statements = [
+            ("DELETE FROM t_sessions WHERE user_id = $1", account.login_id.as_str()),
+            ("DELETE FROM t_contact_change_requests WHERE login_id = $1::uuid", account.login_id.as_str()),
+            ("DELETE FROM t_password_history WHERE login_id = $1::uuid", account.login_id.as_str()),
+            ("DELETE FROM password_reset_codes WHERE email = $1", account.email.as_str()),
+            ("DELETE FROM m_login WHERE login_id = $1::uuid", account.login_id.as_str()),
+        ];
+        for (sql, value) in login_statements {
+            Self::execute(&mut tx, sql, &[value]).await?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            error!("トランザクションコミットに失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクションコミットに失敗: {}", e))
+        })?;
+
+        info!("アカウント削除完了: login_id={}", account.login_id);
+        Ok(Some(photo_paths))
+    }
+
+    async fn execute(
+        tx: &mut Transaction<'_, Postgres>,
+        sql: &str,
+        args: &[&str],
+    ) -> Result<u64, DatabaseError> {
+        let params: Vec<SqlParam> = args.iter().map(|a| SqlParam::String(a.to_string())).collect();
+        log_sql_query(sql, &params, None);
+
+        let mut query = sqlx::query(sql);
+        for arg in args {
+            query = query.bind(*arg);
+        }
+        match query.execute(&mut **tx).await {
+            Ok(result) => Ok(result.rows_affected()),
+            Err(e) => {
+                error!("アカウント削除処理に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("アカウント削除処理に失敗: {}", e)))
+            }
+        }
+    }
+
+    async fn fetch_json(&self, sql: &str, value: &str) -> Result<Value, DatabaseError> {
+        let params = vec![SqlParam::String(value.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, Option<Value>>(sql)
+            .bind(value)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(json) => Ok(json.flatten().unwrap_or(Value::Null)),
+            Err(e) => {
+                error!("個人データの取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("個人データの取得に失敗: {}", e)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::postgresql_database::DatabaseConfig;
+
+    /// テスト用データベース（DB_*環境変数）に接続する
+    async fn test_repository() -> AccountRepository {
+        let config = DatabaseConfig::from_env().expect("テスト用データベースの接続設定（DB_*環境変数）が必要です");
+        let db = PostgresDatabase::connect(&config).await.expect("テスト用データベースに接続できません");
+        AccountRepository::new(db)
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DB_* test database"]
+    async fn test_export_account_shape() {
+        let repository = test_repository().await;
+        let login_id: Uuid = sqlx::query_scalar("SELECT login_id FROM m_login ORDER BY created_datetime LIMIT 1")
+            .fetch_one(repository.db.pool())
+            .await
+            .expect("ログイン情報が登録されたテストデータが必要です");
+        let account = repository.find_account(login_id).await.unwrap().unwrap();
+
+        let export = repository.export_account(&account).await.unwrap();
+        assert_eq!(export.login["email"], account.email.as_str());
+        assert!(export.login.get("pass_word").is_none());
+        let sections = [&export.vehicles, &export.reservations, &export.favorites, &export.saved_searches, &export.notifications];
+        for section in sections {
+            assert!(section.is_array());
+        }
+    }
+}
@@ -701,6 +701,171 @@ impl AuthSigninRepository {
         }
     }
 
+    /// 获取最近使用过的密码哈希（当前密码 + 历史记录）
+    /// 
+    /// # 参数
+    /// * `login_id` - 登录ID (String format)
+    /// * `limit` - 历史记录条数
+    #[instrument(skip(self))]
+    pub async fn get_recent_password_hashes(&self, login_id: &str, limit: i64) -> Result<Vec<String>, ApiError> {
+        // 解析UUID
+        let login_uuid = Uuid::parse_str(login_id)
+            .map_err(|_| ApiError::ValidationError("无效的登录ID格式".to_string()))?;
+        
+        let query = r#"
+            SELECT pass_word FROM (
+                SELECT pass_word, CURRENT_TIMESTAMP AS created_datetime FROM m_login WHERE login_id = $1
+                UNION ALL
+                (
+                    SELECT pass_word, created_datetime
+                    FROM t_password_history
+                    WHERE login_id = $1
+                    ORDER BY created_datetime DESC
+                    LIMIT $2
+                )
+            ) recent
+            ORDER BY created_datetime DESC
+        "#;
+        
+        self.log_sql_query(query, Some(&format!("login_id: {}, limit: {}", login_id, limit)));
+        
+        match sqlx::query_scalar::<_, String>(query)
+            .bind(login_uuid)
+            .bind(limit)
+            .fetch_all(self.get_pool())
+            .await
+        {
+            Ok(hashes) => Ok(hashes),
+            Err(e) => {
+                error!("获取密码历史错误: {}", e);
+                Err(ApiError::InternalServerError)
+            }
+        }
+    }
+
+    /// 修改密码（记录历史、更新修改时间）
+    /// 
+    /// 旧密码写入t_password_history，超出保留条数的历史记录被删除。
+    /// password_changed_datetime之前签发的刷新令牌将失效。
+    /// 
+    /// # 参数
+    /// * `login_id` - 登录ID (String format)
+    /// * `hashed_password` - 哈希后的新密码
+    /// * `history_count` - 历史记录保留条数
+    #[instrument(skip(self, hashed_password))]
+    pub async fn change_password(
+        &self,
+        login_id: &str,
+        hashed_password: &str,
+        history_count: i64,
+    ) -> Result<(), ApiError> {
+        info!("修改用户密码: {}", login_id);
+        
+        // 解析UUID
+        let login_uuid = Uuid::parse_str(login_id)
+            .map_err(|_| ApiError::ValidationError("无效的登录ID格式".to_string()))?;
+        
+        let mut tx = self.get_pool().begin().await.map_err(|e| {
+            error!("开始事务错误: {}", e);
+            ApiError::InternalServerError
+        })?;
+        
+        let history_query = r#"
+            INSERT INTO t_password_history (login_id, pass_word)
+            SELECT login_id, pass_word FROM m_login WHERE login_id = $1
+        "#;
+        
+        if let Err(e) = sqlx::query(history_query)
+            .bind(login_uuid)
+            .execute(&mut *tx)
+            .await
+        {
+            error!("记录密码历史错误: {}", e);
+            return Err(ApiError::InternalServerError);
+        }
+        
+        let update_query = r#"
+            UPDATE m_login 
+            SET 
+                pass_word = $2,
+                password_changed_datetime = CURRENT_TIMESTAMP,
+                updated_datetime = CURRENT_TIMESTAMP
+            WHERE login_id = $1
+        "#;
+        
+        match sqlx::query(update_query)
+            .bind(login_uuid)
+            .bind(hashed_password)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(result) if result.rows_affected() == 0 => {
+                error!("用户密码修改失败: 未找到用户 {}", login_id);
+                return Err(ApiError::NotFoundError("用户不存在".to_string()));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                error!("修改用户密码错误: {}", e);
+                return Err(ApiError::InternalServerError);
+            }
+        }
+        
+        // 删除超出保留条数的历史记录
+        let prune_query = r#"
+            DELETE FROM t_password_history
+            WHERE login_id = $1
+              AND history_id NOT IN (
+                  SELECT history_id FROM t_password_history
+                  WHERE login_id = $1
+                  ORDER BY created_datetime DESC
+                  LIMIT $2
+              )
+        "#;
+        
+        if let Err(e) = sqlx::query(prune_query)
+            .bind(login_uuid)
+            .bind(history_count)
+            .execute(&mut *tx)
+            .await
+        {
+            error!("删除旧密码历史错误: {}", e);
+            return Err(ApiError::InternalServerError);
+        }
+        
+        tx.commit().await.map_err(|e| {
+            error!("提交事务错误: {}", e);
+            ApiError::InternalServerError
+        })?;
+        
+        info!("用户密码修改成功: {}", login_id);
+        Ok(())
+    }
+
+    /// 获取密码修改时间
+    /// 
+    /// # 参数
+    /// * `login_id` - 登录ID (String format)
+    #[instrument(skip(self))]
+    pub async fn get_password_changed_datetime(&self, login_id: &str) -> Result<Option<DateTime<Utc>>, ApiError> {
+        // 解析UUID
+        let login_uuid = Uuid::parse_str(login_id)
+            .map_err(|_| ApiError::ValidationError("无效的登录ID格式".to_string()))?;
+        
+        let query = "SELECT password_changed_datetime FROM m_login WHERE login_id = $1";
+        
+        match sqlx::query_scalar::<_, Option<DateTime<Utc>>>(query)
+            .bind(login_uuid)
+            .fetch_optional(self.get_pool())
+            .await
+        {
+            Ok(changed) => Ok(changed.flatten()),
+            Err(e) => {
+                error!("获取密码修改时间错误: {}", e);
+                Err(ApiError::InternalServerError)
+            }
+        }
+    }
+
     /// 删除已使用的密码重置验证码
     /// 
     /// # 参数
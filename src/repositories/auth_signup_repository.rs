@@ -249,8 +249,8 @@ impl AuthSignupRepository {
     ) -> Result<String, DatabaseError> {
         let user_sql = r#"
             INSERT INTO m_users 
-            (login_id, full_name, birthday, gender, phone_number, address, promotional_email_opt, service_email_opt, created_datetime, updated_datetime) 
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) 
+            (login_id, full_name, full_name_kana, birthday, gender, phone_number, address, promotional_email_opt, service_email_opt, created_datetime, updated_datetime) 
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) 
             RETURNING user_id
         "#;
 
@@ -260,6 +260,7 @@ impl AuthSignupRepository {
         let user_params = vec![
             SqlParam::String(login_id.to_string()),
             SqlParam::String(req.full_name.clone()),
+            SqlParam::OptionString(req.full_name_kana.clone()),
             if let Some(bd) = birthday_date {
                 SqlParam::String(bd.to_string())
             } else {
@@ -283,6 +284,7 @@ impl AuthSignupRepository {
         match sqlx::query_as::<_, UserIdRow>(user_sql)
             .bind(login_id)
             .bind(&req.full_name)
+            .bind(req.full_name_kana.as_deref())
             .bind(birthday_date)
             .bind(req.gender.as_deref())
             .bind(&req.phone_number)
@@ -318,10 +320,11 @@ impl AuthSignupRepository {
         birthday_date: Option<chrono::NaiveDate>,
         now: chrono::DateTime<Utc>,
     ) -> Result<String, DatabaseError> {
+        let invoice_registration_number = req.normalized_invoice_registration_number();
         let owner_sql = r#"
             INSERT INTO m_owners 
-            (login_id, registrant_type, full_name, full_name_kana, birthday, gender, postal_code, address, phone_number, remarks, created_datetime, updated_datetime) 
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) 
+            (login_id, registrant_type, full_name, full_name_kana, birthday, gender, postal_code, address, phone_number, remarks, created_datetime, updated_datetime, invoice_registration_number) 
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) 
             RETURNING owner_id
         "#;
 
@@ -354,6 +357,7 @@ impl AuthSignupRepository {
             },
             SqlParam::String(now.to_rfc3339()),
             SqlParam::String(now.to_rfc3339()),
+            SqlParam::OptionString(invoice_registration_number.clone()),
         ];
 
         log_sql_query(owner_sql, &owner_params, None);
@@ -371,6 +375,7 @@ impl AuthSignupRepository {
             .bind(req.remarks.as_deref())
             .bind(now)
             .bind(now)
+            .bind(invoice_registration_number.as_deref())
             .fetch_one(&mut **tx)
             .await
         {
@@ -0,0 +1,227 @@
+use serde_json::Value;
+use tracing::error;
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::cancellation_policy_model::{CancellationPolicyRow, CancellationReservationRow};
+
+/// キャンセル対象の予約の取得列（予約金額は予約詳細の合計を円単位に丸めた値）
+const CANCELLATION_RESERVATION_SELECT: &str = r#"
+    SELECT
+        r.reservation_id,
+        r.parking_lot_id,
+        r.status,
+        r.start_datetime,
+        COALESCE((
+            SELECT ROUND(SUM(d.amount))
+            FROM t_reservation_details d
+            WHERE d.reservation_id = r.reservation_id
+        ), 0)::BIGINT AS amount,
+        u.login_id::TEXT AS user_login_id,
+        r.cancel_reason,
+        r.cancellation_fee,
+        r.cancellation_fee_percent,
+        r.cancelled_datetime
+    FROM t_reservations r
+    LEFT JOIN m_users u ON u.user_id = r.user_id
+"#;
+
+/// キャンセルポリシーのリポジトリ
+#[derive(Debug, Clone)]
+pub struct CancellationPolicyRepository {
+    db: PostgresDatabase,
+}
+
+impl CancellationPolicyRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    /// 駐車場のキャンセルポリシー（未設定の場合はNone）
+    pub async fn find_policy(&self, parking_lot_id: &str) -> Result<Option<CancellationPolicyRow>, DatabaseError> {
+        let sql = r#"
+            SELECT parking_lot_id, rules, updated_datetime
+            FROM t_cancellation_policies
+            WHERE parking_lot_id = $1
+        "#;
+
+        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, CancellationPolicyRow>(sql)
+            .bind(parking_lot_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("キャンセルポリシーの取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("キャンセルポリシーの取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 駐車場が存在するか
+    pub async fn parking_lot_exists(&self, parking_lot_id: &str) -> Result<bool, DatabaseError> {
+        let sql = "SELECT EXISTS (SELECT 1 FROM t_parking_lots WHERE parking_lot_id = $1)";
+
+        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, bool>(sql)
+            .bind(parking_lot_id)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(exists) => Ok(exists),
+            Err(e) => {
+                error!("駐車場の存在確認に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("駐車場の存在確認に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// キャンセルポリシーを登録（既存の場合は置き換え）
+    pub async fn upsert_policy(&self, parking_lot_id: &str, rules: &Value) -> Result<CancellationPolicyRow, DatabaseError> {
+        let sql = r#"
+            INSERT INTO t_cancellation_policies (parking_lot_id, rules)
+            VALUES ($1, $2)
+            ON CONFLICT (parking_lot_id) DO UPDATE
+            SET rules = EXCLUDED.rules, updated_datetime = NOW()
+            RETURNING parking_lot_id, rules, updated_datetime
+        "#;
+
+        let params = vec![
+            SqlParam::String(parking_lot_id.to_string()),
+            SqlParam::String(rules.to_string()),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, CancellationPolicyRow>(sql)
+            .bind(parking_lot_id)
+            .bind(rules)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("キャンセルポリシーの登録に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("キャンセルポリシーの登録に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// キャンセルポリシーを削除（利用開始まで無料に戻す）
+    pub async fn delete_policy(&self, parking_lot_id: &str) -> Result<bool, DatabaseError> {
+        let sql = "DELETE FROM t_cancellation_policies WHERE parking_lot_id = $1";
+
+        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(parking_lot_id).execute(self.db.pool()).await {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(e) => {
+                error!("キャンセルポリシーの削除に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("キャンセルポリシーの削除に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// キャンセル対象の予約
+    pub async fn find_reservation(
+        &self,
+        reservation_id: &str,
+    ) -> Result<Option<CancellationReservationRow>, DatabaseError> {
+        let sql = format!("{} WHERE r.reservation_id = $1", CANCELLATION_RESERVATION_SELECT);
+
+        let params = vec![SqlParam::String(reservation_id.to_string())];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, CancellationReservationRow>(&sql)
+            .bind(reservation_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("キャンセル対象の予約取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("キャンセル対象の予約取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 予約をキャンセルし、キャンセル料・適用したポリシーを記録（返金額は精算後に記録する）
+    ///
+    /// 予約中・承認済みの予約のみ更新し、先にキャンセル済みの場合はfalseを返す
+    pub async fn cancel_reservation(
+        &self,
+        reservation_id: &str,
+        cancel_reason: Option<&str>,
+        fee_percent: i32,
+        cancellation_fee: i64,
+        policy: &Value,
+    ) -> Result<bool, DatabaseError> {
+        let sql = r#"
+            UPDATE t_reservations
+            SET status = '3',
+                cancel_reason = $2,
+                cancellation_fee_percent = $3,
+                cancellation_fee = $4,
+                cancellation_policy = $5,
+                cancelled_datetime = NOW(),
+                updated_datetime = NOW()
+            WHERE reservation_id = $1 AND status IN ('1', '2')
+        "#;
+
+        let params = vec![
+            SqlParam::String(reservation_id.to_string()),
+            SqlParam::OptionString(cancel_reason.map(str::to_string)),
+            SqlParam::I32(fee_percent),
+            SqlParam::Integer(cancellation_fee),
+            SqlParam::String(policy.to_string()),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql)
+            .bind(reservation_id)
+            .bind(cancel_reason)
+            .bind(fee_percent)
+            .bind(cancellation_fee)
+            .bind(policy)
+            .execute(self.db.pool())
+            .await
+        {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(e) => {
+                error!("予約のキャンセルに失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("予約のキャンセルに失敗: {}", e)))
+            }
+        }
+    }
+    /// キャンセルした予約に精算で返金した額を記録
+    pub async fn record_refund_amount(&self, reservation_id: &str, refund_amount: i64) -> Result<(), DatabaseError> {
+        let sql = r#"
+            UPDATE t_reservations
+            SET refund_amount = $2, updated_datetime = NOW()
+            WHERE reservation_id = $1 AND status = '3'
+        "#;
+
+        let params = vec![SqlParam::String(reservation_id.to_string()), SqlParam::Integer(refund_amount)];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(reservation_id).bind(refund_amount).execute(self.db.pool()).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("返金額の記録に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("返金額の記録に失敗: {}", e)))
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use tracing::error;
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::check_in_model::{CheckInMethod, CheckInRecordRow, CheckInTargetRow};
+
+/// 入出庫のリポジトリ
+#[derive(Debug, Clone)]
+pub struct CheckInRepository {
+    db: PostgresDatabase,
+}
+
+impl CheckInRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    /// 入出庫の対象（予約・入出庫状況・駐車場の位置）
+    pub async fn find_target(&self, reservation_id: &str) -> Result<Option<CheckInTargetRow>, DatabaseError> {
+        let sql = r#"
+            SELECT
+                r.reservation_id,
+                r.parking_lot_id,
+                r.status,
+                r.start_datetime,
+                r.end_datetime,
+                u.login_id::TEXT AS user_login_id,
+                s.status_id,
+                s.entry_status,
+                s.exit_status,
+                s.exit_datetime,
+                s.entry_code_id,
+                gm.latitude::FLOAT8 AS latitude,
+                gm.longitude::FLOAT8 AS longitude
+            FROM t_reservations r
+            JOIN t_parking_lots pl ON pl.parking_lot_id = r.parking_lot_id
+            LEFT JOIN m_users u ON u.user_id = r.user_id
+            LEFT JOIN t_parking_status s ON s.reservation_id = r.reservation_id
+            LEFT JOIN LATERAL (
+                SELECT g.latitude, g.longitude
+                FROM t_parking_google_maps g
+                WHERE g.parking_lot_id = r.parking_lot_id
+                LIMIT 1
+            ) gm ON TRUE
+            WHERE r.reservation_id = $1
+        "#;
+
+        let params = vec![SqlParam::String(reservation_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, CheckInTargetRow>(sql)
+            .bind(reservation_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("入出庫の対象の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("入出庫の対象の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 入庫を記録（入庫済みの場合はNone）
+    pub async fn record_check_in(
+        &self,
+        target: &CheckInTargetRow,
+        method: CheckInMethod,
+        code_id: Option<&str>,
+    ) -> Result<Option<CheckInRecordRow>, DatabaseError> {
+        let sql = r#"
+            INSERT INTO t_parking_status (
+                reservation_id, parking_lot_id, entry_status, exit_status, entry_datetime, entry_method, entry_code_id
+            )
+            VALUES ($1, $2, '1', '0', NOW(), $3, $4)
+            ON CONFLICT (reservation_id) DO UPDATE
+            SET
+                entry_status = '1',
+                entry_datetime = NOW(),
+                entry_method = EXCLUDED.entry_method,
+                entry_code_id = EXCLUDED.entry_code_id,
+                updated_datetime = NOW()
+            WHERE t_parking_status.entry_status <> '1'
+            RETURNING status_id, entry_datetime AS recorded_datetime
+        "#;
+
+        let params = vec![
+            SqlParam::String(target.reservation_id.clone()),
+            SqlParam::String(target.parking_lot_id.clone()),
+            SqlParam::String(method.as_str().to_string()),
+            SqlParam::OptionString(code_id.map(str::to_string)),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, CheckInRecordRow>(sql)
+            .bind(&target.reservation_id)
+            .bind(&target.parking_lot_id)
+            .bind(method.as_str())
+            .bind(code_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("入庫の記録に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("入庫の記録に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 出庫を記録（未入庫・出庫済みの場合はNone）
+    pub async fn record_check_out(
+        &self,
+        status_id: &str,
+        method: CheckInMethod,
+        code_id: Option<&str>,
+    ) -> Result<Option<CheckInRecordRow>, DatabaseError> {
+        let sql = r#"
+            UPDATE t_parking_status
+            SET
+                exit_status = '1',
+                exit_datetime = NOW(),
+                exit_method = $2,
+                exit_code_id = $3,
+                updated_datetime = NOW()
+            WHERE status_id = $1 AND entry_status = '1' AND exit_status <> '1'
+            RETURNING status_id, exit_datetime AS recorded_datetime
+        "#;
+
+        let params = vec![
+            SqlParam::String(status_id.to_string()),
+            SqlParam::String(method.as_str().to_string()),
+            SqlParam::OptionString(code_id.map(str::to_string)),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, CheckInRecordRow>(sql)
+            .bind(status_id)
+            .bind(method.as_str())
+            .bind(code_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("出庫の記録に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("出庫の記録に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 予約を利用完了にする
+    pub async fn complete_reservation(&self, reservation_id: &str) -> Result<(), DatabaseError> {
+        let sql = r#"
+            UPDATE t_reservations
+            SET status = '4', updated_datetime = NOW()
+            WHERE reservation_id = $1 AND status IN ('1', '2')
+        "#;
+
+        let params = vec![SqlParam::String(reservation_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(reservation_id).execute(self.db.pool()).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("予約の利用完了への更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("予約の利用完了への更新に失敗: {}", e)))
+            }
+        }
+    }
+}
@@ -34,6 +34,38 @@ pub use user_home_repository::UserHomeRepository;
 pub mod use_history_repository;
 pub use use_history_repository::UseHistoryRepository;
 
+pub mod profile_repository;
+pub use profile_repository::ProfileRepository;
+
+pub mod account_repository;
+pub use account_repository::AccountRepository;
+
+pub mod vehicle_repository;
+pub use vehicle_repository::VehicleRepository;
+
+pub mod search_history_repository;
+pub use search_history_repository::SearchHistoryRepository;
+
+pub mod saved_search_repository;
+pub use saved_search_repository::SavedSearchRepository;
+
+pub mod notification_repository;
+pub use notification_repository::NotificationRepository;
+
+pub mod owner_analytics_repository;
+pub use owner_analytics_repository::OwnerAnalyticsRepository;
+
+pub mod payment_repository;
+pub use payment_repository::PaymentRepository;
+
+pub mod cancellation_policy_repository;
+pub use cancellation_policy_repository::CancellationPolicyRepository;
+
+pub mod receipt_repository;
+pub use receipt_repository::ReceiptRepository;
+pub mod check_in_repository;
+pub use check_in_repository::CheckInRepository;
+
 // Common repository utilities and interfaces
 mod repository_utils {
     use sqlx::PgPool;
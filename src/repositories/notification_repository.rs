@@ -0,0 +1,165 @@
+use serde_json::Value;
+use sqlx::{Postgres, Transaction};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::notification_model::NotificationRow;
+
+/// アプリ内通知のリポジトリ
+#[derive(Debug, Clone)]
+pub struct NotificationRepository {
+    db: PostgresDatabase,
+}
+
+impl NotificationRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    /// 通知を作成（呼び出し元のトランザクション内で実行）
+    pub async fn insert_notification(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        notification_type: &str,
+        title: &str,
+        body: &str,
+        payload: &Value,
+    ) -> Result<Uuid, DatabaseError> {
+        let sql = r#"
+            INSERT INTO t_notifications (notification_id, user_id, notification_type, title, body, payload, created_datetime)
+            VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP)
+        "#;
+
+        let notification_id = Uuid::new_v4();
+        let params = vec![
+            SqlParam::String(notification_id.to_string()),
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(notification_type.to_string()),
+            SqlParam::String(title.to_string()),
+            SqlParam::String(body.to_string()),
+            SqlParam::String(payload.to_string()),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql)
+            .bind(notification_id)
+            .bind(user_id)
+            .bind(notification_type)
+            .bind(title)
+            .bind(body)
+            .bind(payload)
+            .execute(&mut **tx)
+            .await
+        {
+            Ok(_) => Ok(notification_id),
+            Err(e) => {
+                error!("通知の作成に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("通知の作成に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 通知一覧（新しい順）
+    pub async fn list_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<NotificationRow>, DatabaseError> {
+        let sql = r#"
+            SELECT notification_id, user_id, notification_type, title, body, payload, read_datetime, created_datetime
+            FROM t_notifications
+            WHERE user_id = $1 AND ($2 = FALSE OR read_datetime IS NULL)
+            ORDER BY created_datetime DESC
+            LIMIT $3 OFFSET $4
+        "#;
+
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::Boolean(unread_only),
+            SqlParam::Integer(limit),
+            SqlParam::Integer(offset),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, NotificationRow>(sql)
+            .bind(user_id)
+            .bind(unread_only)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("通知一覧の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("通知一覧の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 未読件数
+    pub async fn count_unread(&self, user_id: &str) -> Result<i64, DatabaseError> {
+        let sql = "SELECT COUNT(*) FROM t_notifications WHERE user_id = $1 AND read_datetime IS NULL";
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, i64>(sql).bind(user_id).fetch_one(self.db.pool()).await {
+            Ok(count) => Ok(count),
+            Err(e) => {
+                error!("未読通知件数の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("未読通知件数の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 通知を既読にする（既読済みの場合も該当すればtrue）
+    pub async fn mark_read(&self, user_id: &str, notification_id: Uuid) -> Result<bool, DatabaseError> {
+        let sql = r#"
+            UPDATE t_notifications
+            SET read_datetime = COALESCE(read_datetime, CURRENT_TIMESTAMP)
+            WHERE user_id = $1 AND notification_id = $2
+        "#;
+
+        let params = vec![SqlParam::String(user_id.to_string()), SqlParam::String(notification_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(user_id).bind(notification_id).execute(self.db.pool()).await {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(e) => {
+                error!("通知の既読更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("通知の既読更新に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 未読の通知をすべて既読にする（更新件数を返す）
+    pub async fn mark_all_read(&self, user_id: &str) -> Result<u64, DatabaseError> {
+        let sql = r#"
+            UPDATE t_notifications
+            SET read_datetime = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND read_datetime IS NULL
+        "#;
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(user_id).execute(self.db.pool()).await {
+            Ok(result) => Ok(result.rows_affected()),
+            Err(e) => {
+                error!("通知の一括既読更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("通知の一括既読更新に失敗: {}", e)))
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use chrono::{DateTime, Utc};
+use tracing::error;
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::owner_analytics_model::{
+    AnalyticsBucketRow, AnalyticsGranularity, AnalyticsLotRow, PeakHourRow,
+};
+
+/// 分析対象の駐車場（$1: オーナーのログインID、NULLの場合は全オーナー、$2: 駐車場ID、NULLの場合は全駐車場）
+const OWNER_LOTS_SQL: &str = r#"
+    SELECT pl.parking_lot_id, pl.parking_lot_name, pl.capacity
+    FROM t_parking_lots pl
+    LEFT JOIN m_owners o ON o.owner_id = pl.owner_id
+    WHERE ($1::TEXT IS NULL OR o.login_id::TEXT = $1)
+      AND ($2::TEXT IS NULL OR pl.parking_lot_id = $2)
+"#;
+
+/// 集計期間内の予約（$3以上$4未満に開始した予約、入出庫状況と予約金額の合計を付与）
+const ANALYTICS_RESERVATIONS_SQL: &str = r#"
+    SELECT
+        r.parking_lot_id,
+        r.status,
+        r.start_datetime,
+        r.end_datetime,
+        ps.entry_datetime,
+        ps.exit_datetime,
+        COALESCE(d.amount, 0) AS amount
+    FROM t_reservations r
+    INNER JOIN lots ON lots.parking_lot_id = r.parking_lot_id
+    LEFT JOIN t_parking_status ps ON ps.reservation_id = r.reservation_id
+    LEFT JOIN (
+        SELECT reservation_id, SUM(amount) AS amount
+        FROM t_reservation_details
+        GROUP BY reservation_id
+    ) d ON d.reservation_id = r.reservation_id
+    WHERE r.status IN ('1', '2', '3', '4')
+      AND r.start_datetime >= $3
+      AND r.start_datetime < $4
+"#;
+
+/// 売上・稼働分析のリポジトリ
+#[derive(Debug, Clone)]
+pub struct OwnerAnalyticsRepository {
+    db: PostgresDatabase,
+}
+
+impl OwnerAnalyticsRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    /// 分析対象の駐車場一覧（owner_login_idがNoneの場合は全オーナー）
+    pub async fn list_lots(
+        &self,
+        owner_login_id: Option<&str>,
+        parking_lot_id: Option<&str>,
+    ) -> Result<Vec<AnalyticsLotRow>, DatabaseError> {
+        let sql = format!("{} ORDER BY pl.parking_lot_id", OWNER_LOTS_SQL);
+        let params = vec![
+            SqlParam::OptionString(owner_login_id.map(str::to_string)),
+            SqlParam::OptionString(parking_lot_id.map(str::to_string)),
+        ];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, AnalyticsLotRow>(&sql)
+            .bind(owner_login_id)
+            .bind(parking_lot_id)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("分析対象駐車場取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("分析対象駐車場取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 駐車場・期間ごとの売上・予約件数・利用時間
+    ///
+    /// 予約は開始日時（日本時間）の期間に計上する。利用時間は入庫から出庫（未出庫の場合は利用終了時刻と現在の早い方）まで、
+    /// 入出庫の記録がない利用完了の予約は予約時間で計上する
+    pub async fn aggregate_buckets(
+        &self,
+        owner_login_id: Option<&str>,
+        parking_lot_id: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        granularity: AnalyticsGranularity,
+    ) -> Result<Vec<AnalyticsBucketRow>, DatabaseError> {
+        let sql = format!(
+            r#"
+            WITH lots AS ({}),
+            reservations AS ({})
+            SELECT
+                parking_lot_id,
+                date_trunc($5, start_datetime AT TIME ZONE 'Asia/Tokyo')::DATE AS period_start,
+                COALESCE(SUM(amount) FILTER (WHERE status = '4'), 0)::BIGINT AS revenue,
+                COUNT(*) AS reservation_count,
+                COUNT(*) FILTER (WHERE status = '4') AS completed_count,
+                COUNT(*) FILTER (WHERE status = '3') AS cancelled_count,
+                COUNT(*) FILTER (
+                    WHERE status IN ('1', '2') AND end_datetime < NOW() AND entry_datetime IS NULL
+                ) AS no_show_count,
+                COUNT(*) FILTER (WHERE status IN ('1', '2', '4') AND end_datetime < NOW()) AS no_show_eligible_count,
+                COALESCE(SUM(EXTRACT(EPOCH FROM GREATEST(CASE
+                    WHEN entry_datetime IS NOT NULL
+                        THEN COALESCE(exit_datetime, LEAST(end_datetime, NOW())) - entry_datetime
+                    WHEN status = '4' THEN end_datetime - start_datetime
+                END, INTERVAL '0')) / 60) FILTER (WHERE status <> '3'), 0)::FLOAT8 AS occupied_minutes,
+                COALESCE(SUM(EXTRACT(EPOCH FROM exit_datetime - entry_datetime) / 60), 0)::FLOAT8 AS stay_minutes,
+                COUNT(exit_datetime - entry_datetime) AS stay_count
+            FROM reservations
+            GROUP BY parking_lot_id, period_start
+            ORDER BY parking_lot_id, period_start
+            "#,
+            OWNER_LOTS_SQL, ANALYTICS_RESERVATIONS_SQL
+        );
+        let params = analytics_params(owner_login_id, parking_lot_id, from, to, Some(granularity));
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, AnalyticsBucketRow>(&sql)
+            .bind(owner_login_id)
+            .bind(parking_lot_id)
+            .bind(from)
+            .bind(to)
+            .bind(granularity.as_sql())
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("売上・稼働集計に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("売上・稼働集計に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 駐車場ごとの曜日・時間帯別の入庫件数（入庫の記録がない予約は開始日時で計上）
+    pub async fn aggregate_peak_hours(
+        &self,
+        owner_login_id: Option<&str>,
+        parking_lot_id: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<PeakHourRow>, DatabaseError> {
+        let sql = format!(
+            r#"
+            WITH lots AS ({}),
+            reservations AS ({}),
+            entries AS (
+                SELECT parking_lot_id, COALESCE(entry_datetime, start_datetime) AT TIME ZONE 'Asia/Tokyo' AS entered_at
+                FROM reservations
+                WHERE status <> '3'
+            )
+            SELECT
+                parking_lot_id,
+                (EXTRACT(ISODOW FROM entered_at) - 1)::INTEGER AS weekday,
+                EXTRACT(HOUR FROM entered_at)::INTEGER AS hour,
+                COUNT(*) AS entry_count
+            FROM entries
+            GROUP BY parking_lot_id, weekday, hour
+            "#,
+            OWNER_LOTS_SQL, ANALYTICS_RESERVATIONS_SQL
+        );
+        let params = analytics_params(owner_login_id, parking_lot_id, from, to, None);
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, PeakHourRow>(&sql)
+            .bind(owner_login_id)
+            .bind(parking_lot_id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("時間帯別入庫件数の集計に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("時間帯別入庫件数の集計に失敗: {}", e)))
+            }
+        }
+    }
+}
+
+/// ログ出力用のパラメータ
+fn analytics_params(
+    owner_login_id: Option<&str>,
+    parking_lot_id: Option<&str>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    granularity: Option<AnalyticsGranularity>,
+) -> Vec<SqlParam> {
+    let mut params = vec![
+        SqlParam::OptionString(owner_login_id.map(str::to_string)),
+        SqlParam::OptionString(parking_lot_id.map(str::to_string)),
+        SqlParam::DateTime(from),
+        SqlParam::DateTime(to),
+    ];
+    if let Some(granularity) = granularity {
+        params.push(SqlParam::String(granularity.as_sql().to_string()));
+    }
+    params
+}
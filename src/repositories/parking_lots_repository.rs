@@ -1,7 +1,8 @@
 use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
 use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
 use crate::models::parking_lots_model::{
-    ParkingImageRequest, ParkingLimitRequest, ParkingLotRequest, ParkingLotRow, ParkingVehicleTypeRequest
+    ParkingImageRequest, ParkingImageRow, ParkingLimitRequest, ParkingLotOwnerRow, ParkingLotRequest,
+    ParkingLotRow, ParkingVehicleTypeRequest
 };
 use chrono::{DateTime, Utc};
 use sqlx::{Postgres, Transaction};
@@ -48,6 +49,7 @@ impl ParkingLotsRepository {
                 charge,
                 features_tip,
                 nearest_station,
+                nearest_station_id,
                 latitude,
                 longitude,
                 status,
@@ -61,7 +63,7 @@ impl ParkingLotsRepository {
                 created_datetime,
                 updated_datetime
             ) 
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26) 
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27) 
             RETURNING parking_lot_id,parking_lot_name;
         "#;
 
@@ -82,6 +84,7 @@ impl ParkingLotsRepository {
             SqlParam::String(req.charge.clone()),
             SqlParam::OptionString(req.features_tip.clone()),
             SqlParam::OptionString(req.nearest_station.clone()),
+            SqlParam::OptionString(req.nearest_station_id.clone()),
             SqlParam::String(req.latitude.clone()),
             SqlParam::String(req.longitude.clone()),
             SqlParam::String(req.status.clone()),
@@ -113,6 +116,7 @@ impl ParkingLotsRepository {
             .bind(&req.charge)
             .bind(req.features_tip.as_deref())
             .bind(&req.nearest_station)
+            .bind(&req.nearest_station_id)
             .bind(&req.latitude)
             .bind(&req.longitude)
             .bind(&req.status)
@@ -257,14 +261,26 @@ impl ParkingLotsRepository {
         for image in images {
             let sql = r#"
                 INSERT INTO t_parking_images (
-                    parking_lot_id, image_url, created_datetime, updated_datetime
+                    parking_lot_id, image_url, medium_url, thumbnail_url, content_hash,
+                    width, height, file_size, sort_order, is_cover, created_datetime, updated_datetime
+                )
+                VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, $8,
+                    COALESCE((SELECT MAX(sort_order) + 1 FROM t_parking_images WHERE parking_lot_id = $1), 0),
+                    NOT EXISTS (SELECT 1 FROM t_parking_images WHERE parking_lot_id = $1 AND is_cover),
+                    $9, $10
                 )
-                VALUES ($1, $2, $3, $4)
             "#;
 
             let params = vec![
                 SqlParam::String(image.parking_lot_id.clone()),
                 SqlParam::String(image.image_url.clone()),
+                SqlParam::OptionString(image.medium_url.clone()),
+                SqlParam::OptionString(image.thumbnail_url.clone()),
+                SqlParam::OptionString(image.content_hash.clone()),
+                SqlParam::OptionI32(image.width),
+                SqlParam::OptionI32(image.height),
+                SqlParam::OptionI32(image.file_size),
                 SqlParam::OptionDateTime(Some(now)),
                 SqlParam::OptionDateTime(Some(now)),
             ];
@@ -274,6 +290,12 @@ impl ParkingLotsRepository {
             if let Err(e) = sqlx::query(sql)
                 .bind(&image.parking_lot_id)
                 .bind(&image.image_url)
+                .bind(&image.medium_url)
+                .bind(&image.thumbnail_url)
+                .bind(&image.content_hash)
+                .bind(image.width)
+                .bind(image.height)
+                .bind(image.file_size)
                 .bind(now)
                 .bind(now)
                 .execute(&mut **tx)
@@ -289,4 +311,377 @@ impl ParkingLotsRepository {
         Ok(())
     }
 
+    /// 駐車場と所有オーナーのログインIDを取得（駐車場が存在しない場合はNone）
+    pub async fn find_parking_lot_owner(
+        &self,
+        parking_lot_id: &str,
+    ) -> Result<Option<ParkingLotOwnerRow>, DatabaseError> {
+        let sql = r#"
+            SELECT
+                p.parking_lot_id,
+                p.owner_id,
+                o.login_id::text AS owner_login_id
+            FROM t_parking_lots p
+            LEFT JOIN m_owners o ON o.owner_id = p.owner_id
+            WHERE p.parking_lot_id = $1
+        "#;
+
+        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, ParkingLotOwnerRow>(sql)
+            .bind(parking_lot_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("駐車場オーナー取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("駐車場オーナー取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 駐車場に登録済みの画像ハッシュ一覧を取得
+    pub async fn find_parking_image_hashes(
+        &self,
+        parking_lot_id: &str,
+    ) -> Result<Vec<Option<String>>, DatabaseError> {
+        let sql = r#"
+            SELECT content_hash
+            FROM t_parking_images
+            WHERE parking_lot_id = $1
+        "#;
+
+        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, Option<String>>(sql)
+            .bind(parking_lot_id)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(hashes) => Ok(hashes),
+            Err(e) => {
+                error!("駐車場画像ハッシュ取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("駐車場画像ハッシュ取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 駐車場行をロックし、トランザクション内で登録済みの画像ハッシュ一覧を取得
+    ///
+    /// 同一駐車場への画像登録を直列化し、枚数上限・重複判定を挿入と同じトランザクションで行うために使用する
+    pub async fn lock_parking_image_hashes(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        parking_lot_id: &str,
+    ) -> Result<Option<Vec<Option<String>>>, DatabaseError> {
+        let lock_sql = r#"
+            SELECT parking_lot_id
+            FROM t_parking_lots
+            WHERE parking_lot_id = $1
+            FOR UPDATE
+        "#;
+
+        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        log_sql_query(lock_sql, &params, None);
+
+        let locked = match sqlx::query_scalar::<_, String>(lock_sql)
+            .bind(parking_lot_id)
+            .fetch_optional(&mut **tx)
+            .await
+        {
+            Ok(row) => row,
+            Err(e) => {
+                error!("駐車場行のロックに失敗: {}", e);
+                log_sql_error(lock_sql, &params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("駐車場行のロックに失敗: {}", e)));
+            }
+        };
+        if locked.is_none() {
+            return Ok(None);
+        }
+
+        let sql = r#"
+            SELECT content_hash
+            FROM t_parking_images
+            WHERE parking_lot_id = $1
+        "#;
+
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, Option<String>>(sql)
+            .bind(parking_lot_id)
+            .fetch_all(&mut **tx)
+            .await
+        {
+            Ok(hashes) => Ok(Some(hashes)),
+            Err(e) => {
+                error!("駐車場画像ハッシュ取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("駐車場画像ハッシュ取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 指定キーのうち、t_parking_imagesのいずれかの行から参照されているものを取得
+    pub async fn find_referenced_image_keys(&self, keys: &[String]) -> Result<Vec<String>, DatabaseError> {
+        let sql = r#"
+            SELECT k.key
+            FROM UNNEST($1::text[]) AS k(key)
+            WHERE EXISTS (
+                SELECT 1
+                FROM t_parking_images i
+                WHERE i.image_url = k.key
+                   OR i.medium_url = k.key
+                   OR i.thumbnail_url = k.key
+            )
+        "#;
+
+        let params = vec![SqlParam::String(keys.join(","))];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, String>(sql)
+            .bind(keys)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(referenced) => Ok(referenced),
+            Err(e) => {
+                error!("画像ファイル参照状況の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("画像ファイル参照状況の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 駐車場の画像一覧を表示順で取得
+    pub async fn find_parking_images(&self, parking_lot_id: &str) -> Result<Vec<ParkingImageRow>, DatabaseError> {
+        let sql = r#"
+            SELECT
+                id, parking_lot_id, image_url, medium_url, thumbnail_url,
+                width, height, sort_order, caption, is_cover
+            FROM t_parking_images
+            WHERE parking_lot_id = $1
+            ORDER BY sort_order, id
+        "#;
+
+        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, ParkingImageRow>(sql)
+            .bind(parking_lot_id)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("駐車場画像一覧取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("駐車場画像一覧取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 駐車場画像を削除し、削除した行を返す（存在しない場合はNone）
+    pub async fn delete_parking_image(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        parking_lot_id: &str,
+        image_id: i32,
+    ) -> Result<Option<ParkingImageRow>, DatabaseError> {
+        let sql = r#"
+            DELETE FROM t_parking_images
+            WHERE parking_lot_id = $1 AND id = $2
+            RETURNING
+                id, parking_lot_id, image_url, medium_url, thumbnail_url,
+                width, height, sort_order, caption, is_cover
+        "#;
+
+        let params = vec![
+            SqlParam::String(parking_lot_id.to_string()),
+            SqlParam::I32(image_id),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, ParkingImageRow>(sql)
+            .bind(parking_lot_id)
+            .bind(image_id)
+            .fetch_optional(&mut **tx)
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("t_parking_images レコード削除失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("t_parking_images レコード削除失敗: {}", e)))
+            }
+        }
+    }
+
+    /// カバー画像が無い場合、表示順の先頭画像をカバーに設定
+    pub async fn ensure_parking_image_cover(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        parking_lot_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        let sql = r#"
+            UPDATE t_parking_images
+            SET is_cover = TRUE, updated_datetime = $2
+            WHERE id = (
+                SELECT id FROM t_parking_images
+                WHERE parking_lot_id = $1
+                ORDER BY sort_order, id
+                LIMIT 1
+            )
+            AND NOT EXISTS (SELECT 1 FROM t_parking_images WHERE parking_lot_id = $1 AND is_cover)
+        "#;
+
+        let params = vec![
+            SqlParam::String(parking_lot_id.to_string()),
+            SqlParam::DateTime(now),
+        ];
+        log_sql_query(sql, &params, None);
+
+        if let Err(e) = sqlx::query(sql)
+            .bind(parking_lot_id)
+            .bind(now)
+            .execute(&mut **tx)
+            .await
+        {
+            error!("カバー画像の設定に失敗: {}", e);
+            log_sql_error(sql, &params, &e.to_string());
+            return Err(DatabaseError::QueryError(format!("カバー画像の設定に失敗: {}", e)));
+        }
+        Ok(())
+    }
+
+    /// 画像IDリストの順に表示順を振り直す
+    pub async fn update_parking_image_sort_orders(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        parking_lot_id: &str,
+        image_ids: &[i32],
+        now: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        let sql = r#"
+            UPDATE t_parking_images
+            SET sort_order = $1, updated_datetime = $2
+            WHERE parking_lot_id = $3 AND id = $4
+        "#;
+
+        for (sort_order, image_id) in image_ids.iter().enumerate() {
+            let sort_order = sort_order as i32;
+            let params = vec![
+                SqlParam::I32(sort_order),
+                SqlParam::DateTime(now),
+                SqlParam::String(parking_lot_id.to_string()),
+                SqlParam::I32(*image_id),
+            ];
+            log_sql_query(sql, &params, None);
+
+            if let Err(e) = sqlx::query(sql)
+                .bind(sort_order)
+                .bind(now)
+                .bind(parking_lot_id)
+                .bind(image_id)
+                .execute(&mut **tx)
+                .await
+            {
+                error!("画像の表示順更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("画像の表示順更新に失敗: {}", e)));
+            }
+        }
+
+        info!("t_parking_images 表示順更新成功: {}件", image_ids.len());
+        Ok(())
+    }
+
+    /// 画像の説明文を更新（Noneで削除）
+    pub async fn update_parking_image_caption(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        parking_lot_id: &str,
+        image_id: i32,
+        caption: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        let sql = r#"
+            UPDATE t_parking_images
+            SET caption = $1, updated_datetime = $2
+            WHERE parking_lot_id = $3 AND id = $4
+        "#;
+
+        let params = vec![
+            SqlParam::OptionString(caption.map(str::to_string)),
+            SqlParam::DateTime(now),
+            SqlParam::String(parking_lot_id.to_string()),
+            SqlParam::I32(image_id),
+        ];
+        log_sql_query(sql, &params, None);
+
+        if let Err(e) = sqlx::query(sql)
+            .bind(caption)
+            .bind(now)
+            .bind(parking_lot_id)
+            .bind(image_id)
+            .execute(&mut **tx)
+            .await
+        {
+            error!("画像の説明文更新に失敗: {}", e);
+            log_sql_error(sql, &params, &e.to_string());
+            return Err(DatabaseError::QueryError(format!("画像の説明文更新に失敗: {}", e)));
+        }
+        Ok(())
+    }
+
+    /// 指定画像をカバーに設定（既存のカバーは解除）
+    pub async fn set_parking_image_cover(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        parking_lot_id: &str,
+        image_id: i32,
+        now: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        // 駐車場ごとにカバーは1枚（部分ユニークインデックス）のため、先に解除してから設定する
+        let statements = [
+            r#"
+            UPDATE t_parking_images
+            SET is_cover = FALSE, updated_datetime = $1
+            WHERE parking_lot_id = $2 AND is_cover AND id <> $3
+            "#,
+            r#"
+            UPDATE t_parking_images
+            SET is_cover = TRUE, updated_datetime = $1
+            WHERE parking_lot_id = $2 AND id = $3
+            "#,
+        ];
+
+        for sql in statements {
+            let params = vec![
+                SqlParam::DateTime(now),
+                SqlParam::String(parking_lot_id.to_string()),
+                SqlParam::I32(image_id),
+            ];
+            log_sql_query(sql, &params, None);
+
+            if let Err(e) = sqlx::query(sql)
+                .bind(now)
+                .bind(parking_lot_id)
+                .bind(image_id)
+                .execute(&mut **tx)
+                .await
+            {
+                error!("カバー画像の設定に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("カバー画像の設定に失敗: {}", e)));
+            }
+        }
+        Ok(())
+    }
 }
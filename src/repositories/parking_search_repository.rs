@@ -1,17 +1,25 @@
 use chrono::Utc;
+use std::collections::HashMap;
+use sqlx::{postgres::PgArguments, query::QueryAs, Postgres};
 use tracing::{error, info, instrument, warn};
 use uuid::Uuid;
 
 use crate::{
     config::logging::{log_sql_error, log_sql_query, SqlParam},
     config::postgresql_database::{DatabaseError, PostgresDatabase},
+    utils::cursor::{bind_cursor, keyset_sql, CursorKey, PageCursor},
     models::{
-        parking_search_model::ParkingSearchRequest,
+        parking_search_model::{
+            FavoriteInfo, FavoriteLabelCount, FavoriteListCriteria, MapCluster, MapPin, MapViewportQuery,
+            NearbyParkingPin, ParkingAreaStats, SearchFilterCriteria, SearchPageCriteria, SearchSortBy,
+            SCORE_FULL_USAGE_COUNT, SCORE_WEIGHT_DISTANCE, SCORE_WEIGHT_PRICE, SCORE_WEIGHT_USAGE,
+        },
         t_parking_lots_model::TParkingLotsModel,
         t_parking_google_maps_model::TParkingGoogleMapsModel,
         t_parking_rental_types_model::TParkingRentalTypesModel,
         m_parking_vehicle_types_model::MParkingVehicleTypesModel,
         m_parking_features_model::MParkingFeaturesModel,
+        parking_lots_model::{ParkingImageRow, ParkingLimitModel},
     },
 };
 
@@ -53,6 +61,31 @@ pub struct GoogleMapsRow {
     pub description: Option<String>,
 }
 
+/// 駐車場の寸法制限行（t_parking_limits）
+#[derive(Debug, sqlx::FromRow)]
+pub struct ParkingLimitRow {
+    pub parking_lot_id: String,
+    pub length_limit: Option<i32>,
+    pub width_limit: Option<i32>,
+    pub height_limit: Option<i32>,
+    pub weight_limit: Option<i32>,
+    pub car_height_limit: Option<String>,
+    pub tire_width_limit: Option<String>,
+    pub car_bottom_limit: Option<String>,
+    pub created_datetime: Option<chrono::DateTime<Utc>>,
+    pub updated_datetime: Option<chrono::DateTime<Utc>>,
+}
+
+/// 検索条件に使用する登録車両（寸法はcm単位）
+#[derive(Debug, sqlx::FromRow)]
+pub struct SearchVehicleRow {
+    pub vehicle_type_id: Option<String>,
+    pub length_cm: Option<i32>,
+    pub width_cm: Option<i32>,
+    pub height_cm: Option<i32>,
+    pub weight_kg: Option<i32>,
+}
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct ParkingFeatureRow {
     pub feature_id: String,
@@ -84,6 +117,97 @@ pub struct FavoriteRow {
     pub created_datetime: chrono::DateTime<Utc>,
 }
 
+/// お気に入りのメモ・表示順・ラベル
+#[derive(Debug, sqlx::FromRow)]
+pub struct FavoriteDetailRow {
+    pub favorite_added_at: Option<chrono::DateTime<Utc>>,
+    pub favorite_memo: Option<String>,
+    pub favorite_order: Option<i32>,
+    pub favorite_labels: Vec<String>,
+}
+
+impl From<FavoriteDetailRow> for FavoriteInfo {
+    fn from(row: FavoriteDetailRow) -> Self {
+        Self {
+            is_favorite: true,
+            favorite_added_at: row.favorite_added_at,
+            favorite_memo: row.favorite_memo,
+            favorite_order: row.favorite_order,
+            favorite_labels: row.favorite_labels,
+        }
+    }
+}
+
+/// お気に入り一覧の結果行
+#[derive(Debug, sqlx::FromRow)]
+pub struct FavoriteParkingLotRow {
+    #[sqlx(flatten)]
+    pub parking_lot: ParkingLotRow,
+    #[sqlx(flatten)]
+    pub favorite: FavoriteDetailRow,
+}
+
+/// 駐車場IDつきのお気に入り詳細行
+#[derive(Debug, sqlx::FromRow)]
+pub struct FavoriteLotDetailRow {
+    pub parking_lot_id: String,
+    #[sqlx(flatten)]
+    pub favorite: FavoriteDetailRow,
+}
+
+/// 駐車場と関連データ（貸出タイプ・対応車種・特徴・Google Maps情報）
+pub type ParkingLotWithRelations = (
+    TParkingLotsModel,
+    Vec<TParkingRentalTypesModel>,
+    Vec<MParkingVehicleTypesModel>,
+    Vec<MParkingFeaturesModel>,
+    Option<TParkingGoogleMapsModel>,
+);
+
+/// お気に入り駐車場（駐車場と関連データ・お気に入り情報）
+pub type FavoriteLotRow = (
+    TParkingLotsModel,
+    Vec<TParkingRentalTypesModel>,
+    Vec<MParkingVehicleTypesModel>,
+    Vec<MParkingFeaturesModel>,
+    Option<TParkingGoogleMapsModel>,
+    FavoriteInfo,
+);
+
+/// 地図検索のピン行
+#[derive(Debug, sqlx::FromRow)]
+pub struct MapPinRow {
+    pub parking_lot_id: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub available_capacity: Option<i32>,
+    pub charge_amount: Option<i32>,
+}
+
+/// 地図検索のクラスタ行
+#[derive(Debug, sqlx::FromRow)]
+pub struct MapClusterRow {
+    pub lot_count: i64,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub min_charge: Option<i32>,
+    pub parking_lot_id: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ParkingLotIdRow {
+    pub parking_lot_id: String,
+}
+
+/// 検索フィルター一覧の集計行（filter_kind: total, vehicle_type, feature, rental_type, charge）
+#[derive(Debug, sqlx::FromRow)]
+pub struct FilterCountRow {
+    pub filter_kind: String,
+    pub filter_group: Option<String>,
+    pub filter_value: Option<String>,
+    pub count: i64,
+}
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct FavoriteCountRow {
     pub count: i64,
@@ -94,6 +218,33 @@ pub struct SearchCountRow {
     pub total_count: i64,
 }
 
+/// 駐車場検索の並べ替え・スコア計算に使う値（SQLで計算）
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SearchLotMetrics {
+    pub distance_km: Option<f64>,
+    pub estimated_cost: Option<i32>,
+    pub usage_count: i64,
+    /// 検索結果全体の推定料金の最安・最高（おすすめ順の料金の正規化に使用）
+    pub min_cost: Option<i32>,
+    pub max_cost: Option<i32>,
+    pub is_compatible: bool,
+    pub sort_key: Option<f64>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct SearchLotRow {
+    #[sqlx(flatten)]
+    pub lot: ParkingLotRow,
+    #[sqlx(flatten)]
+    pub metrics: SearchLotMetrics,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct SearchTotalsRow {
+    pub lots_in_radius: i64,
+    pub total_count: i64,
+}
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct ExistsRow {
     pub exists: bool,
@@ -145,80 +296,473 @@ impl ParkingSearchRepository {
     // 駐車場検索メソッド
     // =============================================================================
 
-    /// 位置情報と条件による駐車場検索
+    /// 位置情報と条件による駐車場検索（1ページ分）
+    ///
+    /// 検索半径・車両寸法の判定、並べ替え、カーソル以降への絞り込み、件数制限をすべてSQLで行う
     #[instrument(skip(self))]
     pub async fn search_parking_lots_by_location(
         &self,
-        criteria: &ParkingSearchRequest,
-    ) -> Result<Vec<(TParkingLotsModel, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>, DatabaseError> {
-        info!("位置情報による駐車場検索を開始 - ユーザーID: {:?}", criteria.user_id);
+        criteria: &SearchPageCriteria,
+        after: Option<&PageCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<(ParkingLotWithRelations, SearchLotMetrics)>, DatabaseError> {
+        info!("位置情報による駐車場検索を開始 - 件数: {}, オフセット: {}, カーソル: {}", limit, offset, after.is_some());
+
+        let sort_columns = search_sort_columns(criteria.descending);
+        let query = format!(
+            r#"
+            {},
+            ranked AS (
+                SELECT
+                    m.*,
+                    {} AS sort_key
+                FROM (
+                    SELECT
+                        c.*,
+                        MIN(c.estimated_cost) OVER () AS min_cost,
+                        MAX(c.estimated_cost) OVER () AS max_cost
+                    FROM candidates c
+                    WHERE c.in_radius AND ($16 OR c.is_compatible)
+                ) m
+            )
+            SELECT * FROM ranked r
+            WHERE TRUE {}
+            ORDER BY {}
+            LIMIT $22 OFFSET $23
+        "#,
+            search_candidates_sql(),
+            search_sort_key_sql(criteria.sort_by),
+            after.map_or(String::new(), |_| format!("AND {}", keyset_sql(&sort_columns, 24))),
+            sort_columns
+                .iter()
+                .map(|(column, desc)| format!("{} {}", column, if *desc { "DESC" } else { "ASC" }))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let mut params = search_page_params(criteria);
+        params.push(SqlParam::Integer(limit));
+        params.push(SqlParam::Integer(offset));
+        params.extend(after.map(PageCursor::sql_params).unwrap_or_default());
+
+        log_sql_query(&query, &params, None);
+
+        let db_query = bind_search_page(sqlx::query_as::<_, SearchLotRow>(&query), criteria)
+            .bind(limit)
+            .bind(offset);
+        let db_query = match after {
+            Some(cursor) => bind_cursor(db_query, cursor),
+            None => db_query,
+        };
+
+        match db_query.fetch_all(self.db.pool()).await {
+            Ok(rows) => {
+                info!("駐車場検索完了 - 結果数: {}", rows.len());
+
+                let (parking_lots, metrics): (Vec<_>, Vec<_>) = rows
+                    .into_iter()
+                    .map(|row| Ok((self.row_to_parking_lot(row.lot)?, row.metrics)))
+                    .collect::<Result<Vec<_>, DatabaseError>>()?
+                    .into_iter()
+                    .unzip();
+
+                // 関連データを一括取得
+                let results = self.attach_related_data(parking_lots).await?;
+                Ok(results.into_iter().zip(metrics).collect())
+            }
+            Err(e) => {
+                error!("駐車場検索に失敗: {}", e);
+                log_sql_error(&query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "駐車場検索に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// 駐車場検索の件数（検索半径内の件数と、車両寸法の判定後の件数）
+    #[instrument(skip(self))]
+    pub async fn count_search_parking_lots(&self, criteria: &SearchPageCriteria) -> Result<SearchTotalsRow, DatabaseError> {
+        let query = format!(
+            r#"
+            {}
+            SELECT
+                COUNT(*) AS lots_in_radius,
+                COUNT(*) FILTER (WHERE $16 OR c.is_compatible) AS total_count
+            FROM candidates c
+            WHERE c.in_radius
+        "#,
+            search_candidates_sql()
+        );
+        let params = search_page_params(criteria);
+
+        log_sql_query(&query, &params, None);
+
+        match bind_search_page(sqlx::query_as::<_, SearchTotalsRow>(&query), criteria)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("駐車場検索の件数取得に失敗: {}", e);
+                log_sql_error(&query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "駐車場検索の件数取得に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// 指定の駐車場のうち、絞り込み条件に一致するもののIDを取得
+    #[instrument(skip(self))]
+    pub async fn filter_parking_lot_ids(
+        &self,
+        parking_lot_ids: &[String],
+        filters: &SearchFilterCriteria,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let query = format!(
+            "SELECT pl.parking_lot_id FROM t_parking_lots pl WHERE pl.parking_lot_id = ANY($1) {}",
+            search_filter_sql(2)
+        );
+        let mut params = vec![SqlParam::String(parking_lot_ids.join(","))];
+        params.extend(search_filter_params(filters));
+
+        log_sql_query(&query, &params, None);
+
+        let db_query = sqlx::query_as::<_, ParkingLotIdRow>(&query).bind(parking_lot_ids);
+        match bind_search_filters(db_query, filters)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows.into_iter().map(|row| row.parking_lot_id).collect()),
+            Err(e) => {
+                error!("駐車場の絞り込みに失敗: {}", e);
+                log_sql_error(&query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "駐車場の絞り込みに失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// 検索フィルターの選択肢ごとの駐車場数を集計（範囲指定時はその範囲内の駐車場のみ）
+    ///
+    /// 料金は金額ごとの件数を返し、料金帯への振り分けはサービス層で行う
+    #[instrument(skip(self))]
+    pub async fn get_filter_counts(
+        &self,
+        bounds: Option<(f64, f64, f64, f64)>,
+    ) -> Result<Vec<FilterCountRow>, DatabaseError> {
+        let query = format!(
+            r#"
+            WITH lots AS (
+                SELECT pl.parking_lot_id, {} AS charge_amount
+                FROM t_parking_lots pl
+                WHERE pl.status = $1
+                  AND ($2::float8 IS NULL OR EXISTS (
+                      SELECT 1 FROM t_parking_google_maps gm
+                      WHERE gm.parking_lot_id = pl.parking_lot_id
+                        AND gm.latitude::float8 BETWEEN $2 AND $3
+                        AND gm.longitude::float8 BETWEEN $4 AND $5
+                  ))
+            )
+            SELECT 'total' AS filter_kind, NULL::TEXT AS filter_group, NULL::TEXT AS filter_value, COUNT(*) AS count
+            FROM lots
+            UNION ALL
+            SELECT 'vehicle_type', NULL, vt.vehicle_type, COUNT(DISTINCT vt.parking_lot_id)
+            FROM m_parking_vehicle_types vt JOIN lots ON lots.parking_lot_id = vt.parking_lot_id
+            GROUP BY vt.vehicle_type
+            UNION ALL
+            SELECT 'feature', f.feature_type, f.feature_value, COUNT(DISTINCT f.parking_lot_id)
+            FROM m_parking_features f JOIN lots ON lots.parking_lot_id = f.parking_lot_id
+            GROUP BY f.feature_type, f.feature_value
+            UNION ALL
+            SELECT 'rental_type', NULL, rt.rental_type, COUNT(DISTINCT rt.parking_lot_id)
+            FROM t_parking_rental_types rt JOIN lots ON lots.parking_lot_id = rt.parking_lot_id
+            GROUP BY rt.rental_type
+            UNION ALL
+            SELECT 'charge', NULL, lots.charge_amount::TEXT, COUNT(*)
+            FROM lots WHERE lots.charge_amount IS NOT NULL
+            GROUP BY lots.charge_amount
+        "#,
+            CHARGE_AMOUNT_SQL
+        );
+        let bound_param = |v: Option<f64>| v.map(SqlParam::Float).unwrap_or(SqlParam::Null);
+        let params = vec![
+            SqlParam::String("アクティブ".to_string()),
+            bound_param(bounds.map(|b| b.0)),
+            bound_param(bounds.map(|b| b.1)),
+            bound_param(bounds.map(|b| b.2)),
+            bound_param(bounds.map(|b| b.3)),
+        ];
+
+        log_sql_query(&query, &params, None);
+
+        match sqlx::query_as::<_, FilterCountRow>(&query)
+            .bind("アクティブ")
+            .bind(bounds.map(|b| b.0))
+            .bind(bounds.map(|b| b.1))
+            .bind(bounds.map(|b| b.2))
+            .bind(bounds.map(|b| b.3))
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => {
+                info!("検索フィルター集計完了 - 行数: {}", rows.len());
+                Ok(rows)
+            }
+            Err(e) => {
+                error!("検索フィルター集計に失敗: {}", e);
+                log_sql_error(&query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "検索フィルター集計に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
 
+    /// 近隣駐車場のピン情報を距離の近い順に取得（距離はSQLで計算）
+    #[instrument(skip(self))]
+    pub async fn get_nearby_parking_lots(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        radius_km: f64,
+        limit: i64,
+    ) -> Result<Vec<NearbyParkingPin>, DatabaseError> {
         let query = r#"
-            SELECT 
-                pl.parking_lot_id,
-                pl.owner_id,
-                pl.parking_lot_name,
-                pl.postal_code,
-                pl.prefecture,
-                pl.city,
-                pl.address_detail,
-                pl.phone_number,
-                pl.capacity,
-                pl.available_capacity,
-                pl.rental_type,
-                pl.charge,
-                pl.features_tip,
-                pl.nearest_station,
-                pl.status,
-                pl.start_date,
-                pl.end_date,
-                pl.created_datetime,
-                pl.updated_datetime
-            FROM t_parking_lots pl
-            WHERE pl.status = $1
-            ORDER BY pl.created_datetime DESC
-            LIMIT $2
+            SELECT *
+            FROM (
+                SELECT
+                    pl.parking_lot_id,
+                    pl.parking_lot_name,
+                    gm.latitude::float8 AS latitude,
+                    gm.longitude::float8 AS longitude,
+                    6371.0 * 2 * ASIN(SQRT(
+                        POWER(SIN(RADIANS(gm.latitude::float8 - $2) / 2), 2)
+                        + COS(RADIANS($2)) * COS(RADIANS(gm.latitude::float8))
+                          * POWER(SIN(RADIANS(gm.longitude::float8 - $3) / 2), 2)
+                    )) AS distance_km,
+                    pl.capacity,
+                    pl.available_capacity,
+                    pl.rental_type,
+                    pl.charge
+                FROM t_parking_lots pl
+                INNER JOIN t_parking_google_maps gm ON gm.parking_lot_id = pl.parking_lot_id
+                WHERE pl.status = $1
+                  AND gm.latitude::float8 BETWEEN $4 AND $5
+                  AND gm.longitude::float8 BETWEEN $6 AND $7
+            ) nearby
+            WHERE nearby.distance_km <= $8
+            ORDER BY nearby.distance_km, nearby.parking_lot_id
+            LIMIT $9
         "#;
-
+        let bounds = search_bounds(latitude, longitude, radius_km);
         let params = vec![
             SqlParam::String("アクティブ".to_string()),
-            SqlParam::Integer(100),
+            SqlParam::Float(latitude),
+            SqlParam::Float(longitude),
+            SqlParam::Float(bounds.0),
+            SqlParam::Float(bounds.1),
+            SqlParam::Float(bounds.2),
+            SqlParam::Float(bounds.3),
+            SqlParam::Float(radius_km),
+            SqlParam::Integer(limit),
         ];
-        
+
         log_sql_query(query, &params, None);
 
-        match sqlx::query_as::<_, ParkingLotRow>(query)
+        match sqlx::query_as::<_, NearbyParkingPin>(query)
             .bind("アクティブ")
-            .bind(100_i64)
+            .bind(latitude)
+            .bind(longitude)
+            .bind(bounds.0)
+            .bind(bounds.1)
+            .bind(bounds.2)
+            .bind(bounds.3)
+            .bind(radius_km)
+            .bind(limit)
             .fetch_all(self.db.pool())
             .await
         {
             Ok(rows) => {
-                info!("駐車場検索完了 - 結果数: {}", rows.len());
-                
-                let mut results = Vec::new();
-                for row in rows {
-                    let parking_lot = self.row_to_parking_lot(row)?;
-                    
-                    // 関連データを並行して取得
-                    let (rental_types, vehicle_types, features, google_maps) = tokio::try_join!(
-                        self.get_rental_types_by_parking_lot_id(&parking_lot.parking_lot_id),
-                        self.get_vehicle_types_by_parking_lot_id(&parking_lot.parking_lot_id),
-                        self.get_features_by_parking_lot_id(&parking_lot.parking_lot_id),
-                        self.get_google_maps_by_parking_lot_id(&parking_lot.parking_lot_id)
-                    )?;
-                    
-                    results.push((parking_lot, rental_types, vehicle_types, features, google_maps));
-                }
-
-                info!("駐車場検索処理完了 - 最終結果数: {}", results.len());
-                Ok(results)
+                info!("近隣駐車場検索完了 - 結果数: {}", rows.len());
+                Ok(rows)
             }
             Err(e) => {
-                error!("駐車場検索に失敗: {}", e);
+                error!("近隣駐車場検索に失敗: {}", e);
                 log_sql_error(query, &params, &e.to_string());
                 Err(DatabaseError::QueryError(format!(
-                    "駐車場検索に失敗: {}",
+                    "近隣駐車場検索に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// 表示範囲内の駐車場のピンを取得（limit件まで）
+    ///
+    /// 緯度経度の範囲はNUMERICのまま比較し、(latitude, longitude)のインデックスを使用する
+    #[instrument(skip(self))]
+    pub async fn get_map_pins(&self, viewport: &MapViewportQuery, limit: i64) -> Result<Vec<MapPin>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT
+                pl.parking_lot_id,
+                gm.latitude::float8 AS latitude,
+                gm.longitude::float8 AS longitude,
+                pl.available_capacity,
+                {} AS charge_amount
+            {}
+            ORDER BY pl.parking_lot_id
+            LIMIT $6
+        "#,
+            CHARGE_AMOUNT_SQL, MAP_VIEWPORT_FROM_SQL
+        );
+        let mut params = map_viewport_params(viewport);
+        params.push(SqlParam::Integer(limit));
+
+        log_sql_query(&query, &params, None);
+
+        match bind_map_viewport(sqlx::query_as::<_, MapPinRow>(&query), viewport)
+            .bind(limit)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows
+                .into_iter()
+                .map(|row| (row.parking_lot_id, row.latitude, row.longitude, row.available_capacity, row.charge_amount))
+                .collect()),
+            Err(e) => {
+                error!("地図検索（ピン）に失敗: {}", e);
+                log_sql_error(&query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "地図検索（ピン）に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// 表示範囲内の駐車場を格子単位でクラスタにまとめて取得（件数・重心・最低料金）
+    #[instrument(skip(self))]
+    pub async fn get_map_clusters(&self, viewport: &MapViewportQuery) -> Result<Vec<MapCluster>, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT
+                COUNT(*) AS lot_count,
+                ROUND(AVG(gm.latitude), 6)::float8 AS latitude,
+                ROUND(AVG(gm.longitude), 6)::float8 AS longitude,
+                MIN({}) AS min_charge,
+                CASE WHEN COUNT(*) = 1 THEN MIN(pl.parking_lot_id) END AS parking_lot_id
+            {}
+            GROUP BY FLOOR(gm.latitude / $6::NUMERIC), FLOOR(gm.longitude / $6::NUMERIC)
+        "#,
+            CHARGE_AMOUNT_SQL, MAP_VIEWPORT_FROM_SQL
+        );
+        let cell_degrees = viewport.cluster_cell_degrees();
+        let mut params = map_viewport_params(viewport);
+        params.push(SqlParam::Float(cell_degrees));
+
+        log_sql_query(&query, &params, None);
+
+        match bind_map_viewport(sqlx::query_as::<_, MapClusterRow>(&query), viewport)
+            .bind(cell_degrees)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => {
+                info!("地図検索（クラスタ）完了 - クラスタ数: {}", rows.len());
+                Ok(rows
+                    .into_iter()
+                    .map(|row| (row.lot_count, row.latitude, row.longitude, row.min_charge, row.parking_lot_id))
+                    .collect())
+            }
+            Err(e) => {
+                error!("地図検索（クラスタ）に失敗: {}", e);
+                log_sql_error(&query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "地図検索（クラスタ）に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// 都道府県・市区町村別の駐車場統計（駐車場数・平均料金・現在の稼働率）
+    #[instrument(skip(self))]
+    pub async fn get_area_stats(
+        &self,
+        by_city: bool,
+        prefecture: Option<&str>,
+        now: chrono::DateTime<Utc>,
+    ) -> Result<Vec<ParkingAreaStats>, DatabaseError> {
+        let query = format!(
+            r#"
+            WITH lots AS (
+                SELECT
+                    pl.prefecture,
+                    CASE WHEN $3 THEN pl.city END AS city,
+                    pl.capacity,
+                    LEAST(pl.capacity, COALESCE(active.reservation_count, 0)) AS occupied,
+                    {} AS charge_amount
+                FROM t_parking_lots pl
+                LEFT JOIN (
+                    SELECT parking_lot_id, COUNT(*) AS reservation_count
+                    FROM t_reservations
+                    WHERE status IN ('1', '2') AND start_datetime <= $4 AND end_datetime > $4
+                    GROUP BY parking_lot_id
+                ) active ON active.parking_lot_id = pl.parking_lot_id
+                WHERE pl.status = $1
+                  AND ($2::TEXT IS NULL OR pl.prefecture = $2)
+            )
+            SELECT
+                prefecture,
+                city,
+                COUNT(*) AS lot_count,
+                COALESCE(SUM(capacity), 0)::BIGINT AS total_capacity,
+                COALESCE(SUM(occupied), 0)::BIGINT AS occupied_count,
+                COALESCE(ROUND(100.0 * SUM(occupied) / NULLIF(SUM(capacity), 0), 1), 0)::float8 AS occupancy_rate,
+                ROUND(AVG(charge_amount), 0)::float8 AS average_charge,
+                MIN(charge_amount) AS min_charge,
+                MAX(charge_amount) AS max_charge
+            FROM lots
+            GROUP BY prefecture, city
+            ORDER BY lot_count DESC, prefecture, city
+        "#,
+            CHARGE_AMOUNT_SQL
+        );
+        let params = vec![
+            SqlParam::String("アクティブ".to_string()),
+            SqlParam::OptionString(prefecture.map(str::to_string)),
+            SqlParam::Boolean(by_city),
+            SqlParam::DateTime(now),
+        ];
+
+        log_sql_query(&query, &params, None);
+
+        match sqlx::query_as::<_, ParkingAreaStats>(&query)
+            .bind("アクティブ")
+            .bind(prefecture)
+            .bind(by_city)
+            .bind(now)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => {
+                info!("駐車場エリア統計の集計完了 - エリア数: {}", rows.len());
+                Ok(rows)
+            }
+            Err(e) => {
+                error!("駐車場エリア統計の集計に失敗: {}", e);
+                log_sql_error(&query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "駐車場エリア統計の集計に失敗: {}",
                     e
                 )))
             }
@@ -230,7 +774,7 @@ impl ParkingSearchRepository {
     pub async fn get_parking_lot_detail(
         &self,
         parking_lot_id: &str,
-    ) -> Result<Option<(TParkingLotsModel, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>, DatabaseError> {
+    ) -> Result<Option<ParkingLotWithRelations>, DatabaseError> {
         info!("駐車場詳細取得を開始 - ID: {}", parking_lot_id);
 
         // 入力検証
@@ -281,16 +825,9 @@ impl ParkingSearchRepository {
                 info!("駐車場詳細取得成功 - 名前: {}", row.parking_lot_name);
                 
                 let parking_lot = self.detail_row_to_parking_lot(row)?;
-                
-                // 関連データを並行して取得
-                let (rental_types, vehicle_types, features, google_maps) = tokio::try_join!(
-                    self.get_rental_types_by_parking_lot_id(&parking_lot.parking_lot_id),
-                    self.get_vehicle_types_by_parking_lot_id(&parking_lot.parking_lot_id),
-                    self.get_features_by_parking_lot_id(&parking_lot.parking_lot_id),
-                    self.get_google_maps_by_parking_lot_id(&parking_lot.parking_lot_id)
-                )?;
-                
-                Ok(Some((parking_lot, rental_types, vehicle_types, features, google_maps)))
+
+                // 関連データを取得
+                Ok(self.attach_related_data(vec![parking_lot]).await?.pop())
             }
             Ok(None) => {
                 warn!("駐車場が見つかりません - ID: {}", parking_lot_id);
@@ -307,6 +844,73 @@ impl ParkingSearchRepository {
         }
     }
 
+    /// 複数の駐車場詳細を一括取得（公開中の駐車場のみ、駐車場ID順）
+    #[instrument(skip(self))]
+    pub async fn get_parking_lot_details(
+        &self,
+        parking_lot_ids: &[String],
+    ) -> Result<Vec<ParkingLotWithRelations>, DatabaseError> {
+        if parking_lot_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = r#"
+            SELECT
+                parking_lot_id,
+                owner_id,
+                parking_lot_name,
+                postal_code,
+                prefecture,
+                city,
+                address_detail,
+                phone_number,
+                capacity,
+                available_capacity,
+                rental_type,
+                charge,
+                features_tip,
+                nearest_station,
+                status,
+                start_date,
+                end_date,
+                created_datetime,
+                updated_datetime
+            FROM t_parking_lots
+            WHERE parking_lot_id = ANY($1) AND status = $2
+            ORDER BY parking_lot_id
+        "#;
+
+        let params = vec![
+            SqlParam::String(parking_lot_ids.join(",")),
+            SqlParam::String("アクティブ".to_string()),
+        ];
+
+        log_sql_query(query, &params, None);
+
+        match sqlx::query_as::<_, ParkingLotDetailRow>(query)
+            .bind(parking_lot_ids)
+            .bind("アクティブ")
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => {
+                let parking_lots = rows
+                    .into_iter()
+                    .map(|row| self.detail_row_to_parking_lot(row))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.attach_related_data(parking_lots).await
+            }
+            Err(e) => {
+                error!("駐車場詳細の一括取得に失敗: {}", e);
+                log_sql_error(query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "駐車場詳細の一括取得に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
     // =============================================================================
     // お気に入り操作メソッド（トランザクションベース）
     // =============================================================================
@@ -317,6 +921,8 @@ impl ParkingSearchRepository {
         &self,
         user_id: &str,
         parking_lot_id: &str,
+        memo: Option<&str>,
+        labels: &[String],
     ) -> Result<bool, DatabaseError> {
         info!("お気に入り追加を開始 - ユーザーID: {}, 駐車場ID: {}", user_id, parking_lot_id);
 
@@ -338,14 +944,16 @@ impl ParkingSearchRepository {
 
         let query = r#"
             INSERT INTO t_favorites 
-            (favorite_id, user_id, parking_lot_id, created_datetime, updated_datetime)
-            VALUES ($1, $2, $3, $4, $5)
+            (favorite_id, user_id, parking_lot_id, memo, labels, created_datetime, updated_datetime)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
         "#;
 
         let params = vec![
             SqlParam::String(favorite_id.clone()),
             SqlParam::String(user_id.to_string()),
             SqlParam::String(parking_lot_id.to_string()),
+            SqlParam::OptionString(memo.map(str::to_string)),
+            SqlParam::String(labels.join(",")),
             SqlParam::String(now.to_rfc3339()),
             SqlParam::String(now.to_rfc3339()),
         ];
@@ -356,6 +964,8 @@ impl ParkingSearchRepository {
             .bind(&favorite_id)
             .bind(user_id)
             .bind(parking_lot_id)
+            .bind(memo)
+            .bind(labels)
             .bind(now)
             .bind(now)
             .execute(&mut *tx)
@@ -500,7 +1110,7 @@ impl ParkingSearchRepository {
         }
 
         let query = r#"
-            SELECT parking_lot_id 
+            SELECT favorite_id, user_id, parking_lot_id, created_datetime
             FROM t_favorites 
             WHERE user_id = $1 
             ORDER BY created_datetime DESC
@@ -570,15 +1180,17 @@ impl ParkingSearchRepository {
         }
     }
 
-    /// お気に入り駐車場取得（ページネーション対応）
+    /// お気に入り駐車場取得（絞り込み・並べ替え・ページネーションはSQLで適用、limit未指定で全件）
     #[instrument(skip(self))]
     pub async fn get_favorite_parking_lots(
         &self,
         user_id: &str,
-        page: i32,
-        page_size: i32,
-    ) -> Result<Vec<(TParkingLotsModel, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>, DatabaseError> {
-        info!("お気に入り駐車場取得を開始 - ユーザーID: {}, ページ: {}, サイズ: {}", user_id, page, page_size);
+        criteria: &FavoriteListCriteria,
+        after: Option<&PageCursor>,
+        limit: Option<i64>,
+        offset: i64,
+    ) -> Result<Vec<FavoriteLotRow>, DatabaseError> {
+        info!("お気に入り駐車場取得を開始 - ユーザーID: {}, 件数: {:?}, オフセット: {}, カーソル: {}", user_id, limit, offset, after.is_some());
 
         // 入力検証
         if user_id.trim().is_empty() {
@@ -586,19 +1198,8 @@ impl ParkingSearchRepository {
             return Err(DatabaseError::QueryError("ユーザーIDが空です".to_string()));
         }
 
-        if page < 1 {
-            warn!("無効なページ番号: {}", page);
-            return Err(DatabaseError::QueryError("ページ番号は1以上である必要があります".to_string()));
-        }
-
-        if page_size < 1 || page_size > 100 {
-            warn!("無効なページサイズ: {}", page_size);
-            return Err(DatabaseError::QueryError("ページサイズは1-100の範囲で指定してください".to_string()));
-        }
-
-        let offset = (page - 1) * page_size;
-        
-        let query = r#"
+        let query = format!(
+            r#"
             SELECT 
                 pl.parking_lot_id,
                 pl.owner_id,
@@ -618,57 +1219,350 @@ impl ParkingSearchRepository {
                 pl.start_date,
                 pl.end_date,
                 pl.created_datetime,
-                pl.updated_datetime
-            FROM t_parking_lots pl
-            INNER JOIN t_favorites fav ON pl.parking_lot_id = fav.parking_lot_id
-            WHERE fav.user_id = $1 AND pl.status = $2
-            ORDER BY fav.created_datetime DESC
-            LIMIT $3 OFFSET $4
-        "#;
+                pl.updated_datetime,
+                fav.created_datetime AS favorite_added_at,
+                fav.memo AS favorite_memo,
+                fav.sort_order AS favorite_order,
+                fav.labels AS favorite_labels
+            {}
+              {}
+            ORDER BY {}
+            LIMIT $10 OFFSET $11
+        "#,
+            favorite_list_from_sql(),
+            after.map_or(String::new(), |_| format!("AND {}", keyset_sql(&favorite_sort_columns(criteria), 12))),
+            favorite_order_clause(criteria)
+        );
+
+        let keyword = criteria.keyword.as_deref().map(like_pattern);
+        let mut params = favorite_list_params(user_id, criteria, keyword.as_deref());
+        params.push(limit.map_or(SqlParam::Null, SqlParam::Integer));
+        params.push(SqlParam::Integer(offset));
+        params.extend(after.map(PageCursor::sql_params).unwrap_or_default());
+
+        log_sql_query(&query, &params, None);
+
+        let db_query = sqlx::query_as::<_, FavoriteParkingLotRow>(&query)
+            .bind(user_id)
+            .bind("アクティブ")
+            .bind(criteria.label.as_deref())
+            .bind(keyword.as_deref());
+
+        let db_query = bind_search_filters(db_query, &criteria.filters)
+            .bind(limit)
+            .bind(offset);
+        let db_query = match after {
+            Some(cursor) => bind_cursor(db_query, cursor),
+            None => db_query,
+        };
+
+        match db_query.fetch_all(self.db.pool()).await {
+            Ok(rows) => {
+                info!("お気に入り駐車場取得完了 - 結果数: {}", rows.len());
+
+                let mut parking_lots = Vec::with_capacity(rows.len());
+                let mut favorites = Vec::with_capacity(rows.len());
+                for row in rows {
+                    parking_lots.push(self.row_to_parking_lot(row.parking_lot)?);
+                    favorites.push(FavoriteInfo::from(row.favorite));
+                }
+
+                // 関連データを一括取得
+                let results: Vec<_> = self
+                    .attach_related_data(parking_lots)
+                    .await?
+                    .into_iter()
+                    .zip(favorites)
+                    .map(|((lot, rental_types, vehicle_types, features, google_maps), favorite)| {
+                        (lot, rental_types, vehicle_types, features, google_maps, favorite)
+                    })
+                    .collect();
+
+                info!("お気に入り駐車場処理完了 - 最終結果数: {}", results.len());
+                Ok(results)
+            }
+            Err(e) => {
+                error!("お気に入り駐車場取得に失敗: {}", e);
+                log_sql_error(&query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "お気に入り駐車場取得に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// 絞り込み条件に一致するお気に入り駐車場の件数
+    #[instrument(skip(self))]
+    pub async fn count_favorite_parking_lots(
+        &self,
+        user_id: &str,
+        criteria: &FavoriteListCriteria,
+    ) -> Result<i64, DatabaseError> {
+        let query = format!("SELECT COUNT(*) AS total_count {}", favorite_list_from_sql());
+        let keyword = criteria.keyword.as_deref().map(like_pattern);
+        let params = favorite_list_params(user_id, criteria, keyword.as_deref());
+
+        log_sql_query(&query, &params, None);
+
+        let db_query = sqlx::query_as::<_, SearchCountRow>(&query)
+            .bind(user_id)
+            .bind("アクティブ")
+            .bind(criteria.label.as_deref())
+            .bind(keyword.as_deref());
+
+        match bind_search_filters(db_query, &criteria.filters)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row.total_count),
+            Err(e) => {
+                error!("お気に入り駐車場件数取得に失敗: {}", e);
+                log_sql_error(&query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "お気に入り駐車場件数取得に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// お気に入りのメモ・表示順・ラベルを取得
+    #[instrument(skip(self))]
+    pub async fn get_favorite_detail(
+        &self,
+        user_id: &str,
+        parking_lot_id: &str,
+    ) -> Result<Option<FavoriteInfo>, DatabaseError> {
+        let query = r#"
+            SELECT
+                created_datetime AS favorite_added_at,
+                memo AS favorite_memo,
+                sort_order AS favorite_order,
+                labels AS favorite_labels
+            FROM t_favorites
+            WHERE user_id = $1 AND parking_lot_id = $2
+        "#;
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(parking_lot_id.to_string()),
+        ];
+
+        log_sql_query(query, &params, None);
 
+        match sqlx::query_as::<_, FavoriteDetailRow>(query)
+            .bind(user_id)
+            .bind(parking_lot_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row.map(FavoriteInfo::from)),
+            Err(e) => {
+                error!("お気に入り詳細取得に失敗: {}", e);
+                log_sql_error(query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "お気に入り詳細取得に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// 複数駐車場のお気に入り詳細を一括取得（お気に入り登録済みの駐車場のみ）
+    #[instrument(skip(self))]
+    pub async fn get_favorite_details(
+        &self,
+        user_id: &str,
+        parking_lot_ids: &[String],
+    ) -> Result<HashMap<String, FavoriteInfo>, DatabaseError> {
+        if parking_lot_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let query = r#"
+            SELECT
+                parking_lot_id,
+                created_datetime AS favorite_added_at,
+                memo AS favorite_memo,
+                sort_order AS favorite_order,
+                labels AS favorite_labels
+            FROM t_favorites
+            WHERE user_id = $1 AND parking_lot_id = ANY($2)
+        "#;
         let params = vec![
             SqlParam::String(user_id.to_string()),
-            SqlParam::String("アクティブ".to_string()),
-            SqlParam::Integer(page_size as i64),
-            SqlParam::Integer(offset as i64),
+            SqlParam::String(parking_lot_ids.join(",")),
         ];
 
         log_sql_query(query, &params, None);
 
-        match sqlx::query_as::<_, ParkingLotRow>(query)
+        match sqlx::query_as::<_, FavoriteLotDetailRow>(query)
             .bind(user_id)
-            .bind("アクティブ")
-            .bind(page_size as i64)
-            .bind(offset as i64)
+            .bind(parking_lot_ids)
             .fetch_all(self.db.pool())
             .await
         {
-            Ok(rows) => {
-                info!("お気に入り駐車場取得完了 - 結果数: {}", rows.len());
-                
-                let mut results = Vec::new();
-                for row in rows {
-                    let parking_lot = self.row_to_parking_lot(row)?;
-                    
-                    // 関連データを並行して取得
-                    let (rental_types, vehicle_types, features, google_maps) = tokio::try_join!(
-                        self.get_rental_types_by_parking_lot_id(&parking_lot.parking_lot_id),
-                        self.get_vehicle_types_by_parking_lot_id(&parking_lot.parking_lot_id),
-                        self.get_features_by_parking_lot_id(&parking_lot.parking_lot_id),
-                        self.get_google_maps_by_parking_lot_id(&parking_lot.parking_lot_id)
-                    )?;
-                    
-                    results.push((parking_lot, rental_types, vehicle_types, features, google_maps));
-                }
+            Ok(rows) => Ok(rows
+                .into_iter()
+                .map(|row| (row.parking_lot_id, FavoriteInfo::from(row.favorite)))
+                .collect()),
+            Err(e) => {
+                error!("お気に入り詳細一括取得に失敗: {}", e);
+                log_sql_error(query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "お気に入り詳細一括取得に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
 
-                info!("お気に入り駐車場処理完了 - 最終結果数: {}", results.len());
-                Ok(results)
+    /// お気に入りのメモ・ラベルを更新（Noneの項目は変更しない）
+    #[instrument(skip(self))]
+    pub async fn update_favorite(
+        &self,
+        user_id: &str,
+        parking_lot_id: &str,
+        memo: Option<Option<&str>>,
+        labels: Option<&[String]>,
+    ) -> Result<Option<FavoriteInfo>, DatabaseError> {
+        info!("お気に入り更新を開始 - ユーザーID: {}, 駐車場ID: {}", user_id, parking_lot_id);
+
+        self.validate_favorite_inputs(user_id, parking_lot_id).await?;
+
+        let query = r#"
+            UPDATE t_favorites
+            SET memo = CASE WHEN $3 THEN $4 ELSE memo END,
+                labels = COALESCE($5, labels),
+                updated_datetime = $6
+            WHERE user_id = $1 AND parking_lot_id = $2
+            RETURNING
+                created_datetime AS favorite_added_at,
+                memo AS favorite_memo,
+                sort_order AS favorite_order,
+                labels AS favorite_labels
+        "#;
+        let now = Utc::now();
+        let new_memo = memo.flatten();
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(parking_lot_id.to_string()),
+            SqlParam::Boolean(memo.is_some()),
+            SqlParam::OptionString(new_memo.map(str::to_string)),
+            SqlParam::OptionString(labels.map(|l| l.join(","))),
+            SqlParam::DateTime(now),
+        ];
+
+        log_sql_query(query, &params, None);
+
+        match sqlx::query_as::<_, FavoriteDetailRow>(query)
+            .bind(user_id)
+            .bind(parking_lot_id)
+            .bind(memo.is_some())
+            .bind(new_memo)
+            .bind(labels)
+            .bind(now)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => {
+                info!("お気に入り更新完了 - 更新: {}", row.is_some());
+                Ok(row.map(FavoriteInfo::from))
             }
             Err(e) => {
-                error!("お気に入り駐車場取得に失敗: {}", e);
+                error!("お気に入り更新に失敗: {}", e);
                 log_sql_error(query, &params, &e.to_string());
                 Err(DatabaseError::QueryError(format!(
-                    "お気に入り駐車場取得に失敗: {}",
+                    "お気に入り更新に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// お気に入りの表示順を指定した駐車場IDの順に更新
+    #[instrument(skip(self))]
+    pub async fn reorder_favorites(
+        &self,
+        user_id: &str,
+        parking_lot_ids: &[String],
+    ) -> Result<(), DatabaseError> {
+        info!("お気に入り並べ替えを開始 - ユーザーID: {}, 件数: {}", user_id, parking_lot_ids.len());
+
+        let mut tx = self.begin_transaction().await?;
+
+        let query = r#"
+            UPDATE t_favorites AS fav
+            SET sort_order = o.ord::INTEGER,
+                updated_datetime = $3
+            FROM UNNEST($2::TEXT[]) WITH ORDINALITY AS o(parking_lot_id, ord)
+            WHERE fav.user_id = $1 AND fav.parking_lot_id = o.parking_lot_id
+        "#;
+        let now = Utc::now();
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(parking_lot_ids.join(",")),
+            SqlParam::DateTime(now),
+        ];
+
+        log_sql_query(query, &params, None);
+
+        match sqlx::query(query)
+            .bind(user_id)
+            .bind(parking_lot_ids)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(result) if result.rows_affected() == parking_lot_ids.len() as u64 => {
+                self.commit_transaction(tx).await?;
+                info!("お気に入り並べ替え完了 - 件数: {}", result.rows_affected());
+                Ok(())
+            }
+            Ok(result) => {
+                let _ = tx.rollback().await;
+                warn!("お気に入り並べ替え失敗 - 更新件数が一致しません: {}", result.rows_affected());
+                Err(DatabaseError::QueryError(
+                    "お気に入りの並べ替えに失敗しました".to_string()
+                ))
+            }
+            Err(e) => {
+                let _ = tx.rollback().await;
+                error!("お気に入り並べ替えに失敗: {}", e);
+                log_sql_error(query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "お気に入り並べ替えに失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// ユーザーが使用しているお気に入りラベルと件数
+    #[instrument(skip(self))]
+    pub async fn get_favorite_labels(&self, user_id: &str) -> Result<Vec<FavoriteLabelCount>, DatabaseError> {
+        let query = r#"
+            SELECT label, COUNT(*) AS count
+            FROM t_favorites, UNNEST(labels) AS label
+            WHERE user_id = $1
+            GROUP BY label
+            ORDER BY label
+        "#;
+        let params = vec![SqlParam::String(user_id.to_string())];
+
+        log_sql_query(query, &params, None);
+
+        match sqlx::query_as::<_, FavoriteLabelCount>(query)
+            .bind(user_id)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("お気に入りラベル取得に失敗: {}", e);
+                log_sql_error(query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "お気に入りラベル取得に失敗: {}",
                     e
                 )))
             }
@@ -679,24 +1573,60 @@ impl ParkingSearchRepository {
     // 関連データ取得メソッド
     // =============================================================================
 
-    /// 駐車場IDによる貸出タイプ取得
+    /// 駐車場一覧に関連データ（貸出タイプ・対応車種・特徴・Google Maps情報）を付与
+    ///
+    /// 駐車場の件数によらず、関連テーブルごとに1クエリで一括取得する
+    async fn attach_related_data(
+        &self,
+        parking_lots: Vec<TParkingLotsModel>,
+    ) -> Result<Vec<ParkingLotWithRelations>, DatabaseError> {
+        let parking_lot_ids: Vec<String> = parking_lots.iter().map(|lot| lot.parking_lot_id.clone()).collect();
+
+        let (mut rental_types, mut vehicle_types, mut features, mut google_maps) = tokio::try_join!(
+            self.get_rental_types_by_parking_lot_ids(&parking_lot_ids),
+            self.get_vehicle_types_by_parking_lot_ids(&parking_lot_ids),
+            self.get_features_by_parking_lot_ids(&parking_lot_ids),
+            self.get_google_maps_by_parking_lot_ids(&parking_lot_ids)
+        )?;
+
+        Ok(parking_lots
+            .into_iter()
+            .map(|lot| {
+                let id = &lot.parking_lot_id;
+                let lot_rental_types = rental_types.remove(id).unwrap_or_default();
+                let lot_vehicle_types = vehicle_types.remove(id).unwrap_or_default();
+                let lot_features = features.remove(id).unwrap_or_default();
+                let lot_google_maps = google_maps.remove(id);
+                (lot, lot_rental_types, lot_vehicle_types, lot_features, lot_google_maps)
+            })
+            .collect())
+    }
+
+    /// 複数駐車場の貸出タイプを一括取得
     #[instrument(skip(self))]
-    pub async fn get_rental_types_by_parking_lot_id(
+    pub async fn get_rental_types_by_parking_lot_ids(
         &self,
-        parking_lot_id: &str,
-    ) -> Result<Vec<TParkingRentalTypesModel>, DatabaseError> {
-        let query = "SELECT * FROM t_parking_rental_types WHERE parking_lot_id = $1";
-        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        parking_lot_ids: &[String],
+    ) -> Result<HashMap<String, Vec<TParkingRentalTypesModel>>, DatabaseError> {
+        if parking_lot_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let query = "SELECT * FROM t_parking_rental_types WHERE parking_lot_id = ANY($1)";
+        let params = vec![SqlParam::String(parking_lot_ids.join(","))];
 
         log_sql_query(query, &params, None);
 
         match sqlx::query_as::<_, RentalTypeRow>(query)
-            .bind(parking_lot_id)
+            .bind(parking_lot_ids)
             .fetch_all(self.db.pool())
             .await
         {
             Ok(rows) => {
-                let rental_types = rows.into_iter().map(|row| self.row_to_rental_type(row)).collect();
+                let mut rental_types: HashMap<String, Vec<TParkingRentalTypesModel>> = HashMap::new();
+                for row in rows {
+                    rental_types.entry(row.parking_lot_id.clone()).or_default().push(self.row_to_rental_type(row));
+                }
                 Ok(rental_types)
             }
             Err(e) => {
@@ -710,24 +1640,31 @@ impl ParkingSearchRepository {
         }
     }
 
-    /// 駐車場IDによる対応車種取得
+    /// 複数駐車場の対応車種を一括取得
     #[instrument(skip(self))]
-    pub async fn get_vehicle_types_by_parking_lot_id(
+    pub async fn get_vehicle_types_by_parking_lot_ids(
         &self,
-        parking_lot_id: &str,
-    ) -> Result<Vec<MParkingVehicleTypesModel>, DatabaseError> {
-        let query = "SELECT * FROM m_parking_vehicle_types WHERE parking_lot_id = $1";
-        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        parking_lot_ids: &[String],
+    ) -> Result<HashMap<String, Vec<MParkingVehicleTypesModel>>, DatabaseError> {
+        if parking_lot_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let query = "SELECT * FROM m_parking_vehicle_types WHERE parking_lot_id = ANY($1)";
+        let params = vec![SqlParam::String(parking_lot_ids.join(","))];
 
         log_sql_query(query, &params, None);
 
         match sqlx::query_as::<_, VehicleTypeRow>(query)
-            .bind(parking_lot_id)
+            .bind(parking_lot_ids)
             .fetch_all(self.db.pool())
             .await
         {
             Ok(rows) => {
-                let vehicle_types = rows.into_iter().map(|row| self.row_to_vehicle_type(row)).collect();
+                let mut vehicle_types: HashMap<String, Vec<MParkingVehicleTypesModel>> = HashMap::new();
+                for row in rows {
+                    vehicle_types.entry(row.parking_lot_id.clone()).or_default().push(self.row_to_vehicle_type(row));
+                }
                 Ok(vehicle_types)
             }
             Err(e) => {
@@ -741,24 +1678,31 @@ impl ParkingSearchRepository {
         }
     }
 
-    /// 駐車場IDによる特徴取得
+    /// 複数駐車場の特徴を一括取得
     #[instrument(skip(self))]
-    pub async fn get_features_by_parking_lot_id(
+    pub async fn get_features_by_parking_lot_ids(
         &self,
-        parking_lot_id: &str,
-    ) -> Result<Vec<MParkingFeaturesModel>, DatabaseError> {
-        let query = "SELECT * FROM m_parking_features WHERE parking_lot_id = $1";
-        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        parking_lot_ids: &[String],
+    ) -> Result<HashMap<String, Vec<MParkingFeaturesModel>>, DatabaseError> {
+        if parking_lot_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let query = "SELECT * FROM m_parking_features WHERE parking_lot_id = ANY($1)";
+        let params = vec![SqlParam::String(parking_lot_ids.join(","))];
 
         log_sql_query(query, &params, None);
 
         match sqlx::query_as::<_, ParkingFeatureRow>(query)
-            .bind(parking_lot_id)
+            .bind(parking_lot_ids)
             .fetch_all(self.db.pool())
             .await
         {
             Ok(rows) => {
-                let features = rows.into_iter().map(|row| self.row_to_feature(row)).collect();
+                let mut features: HashMap<String, Vec<MParkingFeaturesModel>> = HashMap::new();
+                for row in rows {
+                    features.entry(row.parking_lot_id.clone()).or_default().push(self.row_to_feature(row));
+                }
                 Ok(features)
             }
             Err(e) => {
@@ -772,24 +1716,35 @@ impl ParkingSearchRepository {
         }
     }
 
-    /// 駐車場IDによるGoogle Maps情報取得
+    /// 複数駐車場のGoogle Maps情報を一括取得（駐車場ごとに1件）
     #[instrument(skip(self))]
-    pub async fn get_google_maps_by_parking_lot_id(
+    pub async fn get_google_maps_by_parking_lot_ids(
         &self,
-        parking_lot_id: &str,
-    ) -> Result<Option<TParkingGoogleMapsModel>, DatabaseError> {
-        let query = "SELECT * FROM t_parking_google_maps WHERE parking_lot_id = $1";
-        let params = vec![SqlParam::String(parking_lot_id.to_string())];
+        parking_lot_ids: &[String],
+    ) -> Result<HashMap<String, TParkingGoogleMapsModel>, DatabaseError> {
+        if parking_lot_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let query = r#"
+            SELECT DISTINCT ON (parking_lot_id) *
+            FROM t_parking_google_maps
+            WHERE parking_lot_id = ANY($1)
+            ORDER BY parking_lot_id
+        "#;
+        let params = vec![SqlParam::String(parking_lot_ids.join(","))];
 
         log_sql_query(query, &params, None);
 
         match sqlx::query_as::<_, GoogleMapsRow>(query)
-            .bind(parking_lot_id)
-            .fetch_optional(self.db.pool())
+            .bind(parking_lot_ids)
+            .fetch_all(self.db.pool())
             .await
         {
-            Ok(Some(row)) => Ok(Some(self.row_to_google_maps(row))),
-            Ok(None) => Ok(None),
+            Ok(rows) => Ok(rows
+                .into_iter()
+                .map(|row| (row.parking_lot_id.clone(), self.row_to_google_maps(row)))
+                .collect()),
             Err(e) => {
                 error!("Google Maps情報取得に失敗: {}", e);
                 log_sql_error(query, &params, &e.to_string());
@@ -801,6 +1756,45 @@ impl ParkingSearchRepository {
         }
     }
 
+    /// 複数駐車場の画像を表示順で一括取得
+    #[instrument(skip(self))]
+    pub async fn get_images_by_parking_lot_ids(
+        &self,
+        parking_lot_ids: &[String],
+    ) -> Result<Vec<ParkingImageRow>, DatabaseError> {
+        if parking_lot_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let query = r#"
+            SELECT
+                id, parking_lot_id, image_url, medium_url, thumbnail_url,
+                width, height, sort_order, caption, is_cover
+            FROM t_parking_images
+            WHERE parking_lot_id = ANY($1)
+            ORDER BY parking_lot_id, sort_order, id
+        "#;
+        let params = vec![SqlParam::String(parking_lot_ids.join(","))];
+
+        log_sql_query(query, &params, None);
+
+        match sqlx::query_as::<_, ParkingImageRow>(query)
+            .bind(parking_lot_ids)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("駐車場画像取得に失敗: {}", e);
+                log_sql_error(query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "駐車場画像取得に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
     // =============================================================================
     // 駐車場存在確認メソッド
     // =============================================================================
@@ -838,6 +1832,117 @@ impl ParkingSearchRepository {
         }
     }
 
+    /// ログインIDから利用者のユーザーIDを取得（オーナーの場合はNone）
+    #[instrument(skip(self))]
+    pub async fn find_user_id(&self, login_id: Uuid) -> Result<Option<String>, DatabaseError> {
+        let query = r#"
+            SELECT u.user_id
+            FROM m_users u
+            INNER JOIN m_login l ON l.login_id = u.login_id
+            WHERE u.login_id = $1 AND l.is_user_owner = '0'
+        "#;
+        let params = vec![SqlParam::String(login_id.to_string())];
+
+        log_sql_query(query, &params, None);
+
+        match sqlx::query_scalar::<_, String>(query)
+            .bind(login_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(user_id) => Ok(user_id),
+            Err(e) => {
+                error!("ユーザーID取得に失敗: {}", e);
+                log_sql_error(query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "ユーザーID取得に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    // =============================================================================
+    // 登録車両
+    // =============================================================================
+
+    /// 検索条件に使用する登録車両を取得（車両ID未指定の場合はデフォルト車両）
+    #[instrument(skip(self))]
+    pub async fn get_search_vehicle(
+        &self,
+        user_id: &str,
+        vehicle_id: Option<Uuid>,
+    ) -> Result<Option<SearchVehicleRow>, DatabaseError> {
+        let query = r#"
+            SELECT vehicle_type_id, length_cm, width_cm, height_cm, weight_kg
+            FROM t_user_vehicles
+            WHERE user_id = $1
+              AND (($2::uuid IS NULL AND is_default) OR vehicle_id = $2)
+        "#;
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::OptionString(vehicle_id.map(|id| id.to_string())),
+        ];
+
+        log_sql_query(query, &params, None);
+
+        match sqlx::query_as::<_, SearchVehicleRow>(query)
+            .bind(user_id)
+            .bind(vehicle_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("登録車両の取得に失敗: {}", e);
+                log_sql_error(query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "登録車両の取得に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    /// 複数駐車場の寸法制限を一括取得
+    #[instrument(skip(self))]
+    pub async fn get_limits_by_parking_lot_ids(
+        &self,
+        parking_lot_ids: &[String],
+    ) -> Result<Vec<ParkingLimitModel>, DatabaseError> {
+        if parking_lot_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let query = r#"
+            SELECT
+                parking_lot_id, length_limit, width_limit, height_limit, weight_limit,
+                car_height_limit, tire_width_limit, car_bottom_limit,
+                created_datetime, updated_datetime
+            FROM t_parking_limits
+            WHERE parking_lot_id = ANY($1)
+        "#;
+        let params = vec![SqlParam::String(parking_lot_ids.join(","))];
+
+        log_sql_query(query, &params, None);
+
+        match sqlx::query_as::<_, ParkingLimitRow>(query)
+            .bind(parking_lot_ids)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows.into_iter().map(|row| self.row_to_limit(row)).collect()),
+            Err(e) => {
+                error!("駐車場の寸法制限取得に失敗: {}", e);
+                log_sql_error(query, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!(
+                    "駐車場の寸法制限取得に失敗: {}",
+                    e
+                )))
+            }
+        }
+    }
+
     // =============================================================================
     // プライベートヘルパーメソッド
     // =============================================================================
@@ -990,6 +2095,22 @@ impl ParkingSearchRepository {
         }
     }
 
+    /// ParkingLimitRow から ParkingLimitModel への変換
+    fn row_to_limit(&self, row: ParkingLimitRow) -> ParkingLimitModel {
+        ParkingLimitModel {
+            parking_lot_id: row.parking_lot_id,
+            length_limit: row.length_limit,
+            width_limit: row.width_limit,
+            height_limit: row.height_limit,
+            weight_limit: row.weight_limit,
+            car_height_limit: row.car_height_limit,
+            tire_width_limit: row.tire_width_limit,
+            car_bottom_limit: row.car_bottom_limit,
+            created_datetime: row.created_datetime,
+            updated_datetime: row.updated_datetime,
+        }
+    }
+
     /// ParkingFeatureRow から MParkingFeaturesModel への変換
     fn row_to_feature(&self, row: ParkingFeatureRow) -> MParkingFeaturesModel {
         MParkingFeaturesModel {
@@ -1025,3 +2146,499 @@ impl ParkingSearchRepository {
         }
     }
 }
+
+/// お気に入り一覧の抽出条件（$1: ユーザーID, $2: ステータス, $3: ラベル, $4: キーワード, $5〜$9: 検索フィルター）
+fn favorite_list_from_sql() -> String {
+    format!(
+        r#"
+            FROM t_parking_lots pl
+            INNER JOIN t_favorites fav ON pl.parking_lot_id = fav.parking_lot_id
+            WHERE fav.user_id = $1 AND pl.status = $2
+              AND ($3::TEXT IS NULL OR $3::TEXT = ANY(fav.labels))
+              AND ($4::TEXT IS NULL
+                   OR pl.parking_lot_name ILIKE $4
+                   OR fav.memo ILIKE $4
+                   OR (pl.prefecture || pl.city || pl.address_detail) ILIKE $4
+                   OR pl.nearest_station ILIKE $4)
+              {}
+"#,
+        search_filter_sql(5)
+    )
+}
+
+/// 地図の表示範囲内の駐車場（$1: ステータス, $2〜$5: 最小緯度・最大緯度・最小経度・最大経度）
+const MAP_VIEWPORT_FROM_SQL: &str = r#"
+            FROM t_parking_google_maps gm
+            INNER JOIN t_parking_lots pl ON pl.parking_lot_id = gm.parking_lot_id
+            WHERE pl.status = $1
+              AND gm.latitude BETWEEN $2::NUMERIC AND $3::NUMERIC
+              AND gm.longitude BETWEEN $4::NUMERIC AND $5::NUMERIC
+"#;
+
+fn map_viewport_params(viewport: &MapViewportQuery) -> Vec<SqlParam> {
+    vec![
+        SqlParam::String("アクティブ".to_string()),
+        SqlParam::Float(viewport.min_lat),
+        SqlParam::Float(viewport.max_lat),
+        SqlParam::Float(viewport.min_lng),
+        SqlParam::Float(viewport.max_lng),
+    ]
+}
+
+fn bind_map_viewport<'q, O>(
+    query: QueryAs<'q, Postgres, O, PgArguments>,
+    viewport: &MapViewportQuery,
+) -> QueryAs<'q, Postgres, O, PgArguments> {
+    query
+        .bind("アクティブ")
+        .bind(viewport.min_lat)
+        .bind(viewport.max_lat)
+        .bind(viewport.min_lng)
+        .bind(viewport.max_lng)
+}
+
+/// 料金（数値のみ登録されている場合）
+const CHARGE_AMOUNT_SQL: &str = "CASE WHEN pl.charge ~ '^[0-9]{1,9}$' THEN pl.charge::INTEGER END";
+
+/// 車種・設備・貸出タイプ・料金帯の絞り込み条件（$first から5個のパラメータを使用）
+///
+/// 車種は車種IDまたは同名の車種を扱う駐車場、設備は指定したすべて（特徴ID・種別・「種別:値」）を持つ駐車場に絞り込む
+fn search_filter_sql(first: usize) -> String {
+    let (vehicle, features, rental, min_charge, max_charge) = (first, first + 1, first + 2, first + 3, first + 4);
+    let charge = CHARGE_AMOUNT_SQL;
+    format!(
+        r#"
+              AND (${vehicle}::TEXT IS NULL OR EXISTS (
+                   SELECT 1 FROM m_parking_vehicle_types vt
+                   WHERE vt.parking_lot_id = pl.parking_lot_id
+                     AND (vt.vehicle_type_id = ${vehicle} OR vt.vehicle_type = ${vehicle} OR vt.vehicle_type = (
+                          SELECT mvt.vehicle_type FROM m_parking_vehicle_types mvt
+                          WHERE mvt.vehicle_type_id = ${vehicle} LIMIT 1))))
+              AND NOT EXISTS (
+                   SELECT 1 FROM UNNEST(${features}::TEXT[]) AS req(feature)
+                   WHERE NOT EXISTS (
+                       SELECT 1 FROM m_parking_features f
+                       WHERE f.parking_lot_id = pl.parking_lot_id
+                         AND (f.feature_id = req.feature
+                              OR f.feature_type = req.feature
+                              OR f.feature_type || ':' || f.feature_value = req.feature)))
+              AND (${rental}::TEXT IS NULL OR EXISTS (
+                   SELECT 1 FROM t_parking_rental_types rt
+                   WHERE rt.parking_lot_id = pl.parking_lot_id AND rt.rental_type = ${rental}))
+              AND (${min_charge}::INTEGER IS NULL OR {charge} >= ${min_charge})
+              AND (${max_charge}::INTEGER IS NULL OR {charge} <= ${max_charge})
+"#
+    )
+}
+
+fn search_filter_params(filters: &SearchFilterCriteria) -> Vec<SqlParam> {
+    vec![
+        SqlParam::OptionString(filters.vehicle_type_id.clone()),
+        SqlParam::String(filters.feature_ids.join(",")),
+        SqlParam::OptionString(filters.rental_type.clone()),
+        SqlParam::OptionI32(filters.min_charge),
+        SqlParam::OptionI32(filters.max_charge),
+    ]
+}
+
+fn bind_search_filters<'q, O>(
+    query: QueryAs<'q, Postgres, O, PgArguments>,
+    filters: &'q SearchFilterCriteria,
+) -> QueryAs<'q, Postgres, O, PgArguments> {
+    query
+        .bind(filters.vehicle_type_id.as_deref())
+        .bind(&filters.feature_ids)
+        .bind(filters.rental_type.as_deref())
+        .bind(filters.min_charge)
+        .bind(filters.max_charge)
+}
+
+/// 検索半径を囲む緯度経度の範囲（最小緯度, 最大緯度, 最小経度, 最大経度）
+pub fn search_bounds(lat: f64, lng: f64, radius_km: f64) -> (f64, f64, f64, f64) {
+    let lat_delta = radius_km / 111.0;
+    let lng_delta = radius_km / (111.0 * lat.to_radians().cos().max(0.01));
+    (lat - lat_delta, lat + lat_delta, lng - lng_delta, lng + lng_delta)
+}
+
+/// 駐車場検索の候補（$1: ステータス, $2: お気に入りのユーザーID, $3〜$5: 検索地点の緯度・経度・半径,
+/// $6〜$9: 検索半径を囲む範囲, $10: 利用時間（分）, $11: 利用実績のユーザーID, $12〜$15: 車両寸法,
+/// $17〜$21: 検索フィルター）
+///
+/// 距離・推定料金・評価・利用実績・寸法制限の適合を駐車場ごとに計算する。
+/// 推定料金は料金を貸出単位（時間単位は最短の貸出値、日間単位は最短の日数）あたりとみなし、利用時間を貸出単位に切り上げて掛ける
+fn search_candidates_sql() -> String {
+    let charge = CHARGE_AMOUNT_SQL;
+    format!(
+        r#"
+            WITH candidates AS (
+                SELECT
+                    pl.parking_lot_id,
+                    pl.owner_id,
+                    pl.parking_lot_name,
+                    pl.postal_code,
+                    pl.prefecture,
+                    pl.city,
+                    pl.address_detail,
+                    pl.phone_number,
+                    pl.capacity,
+                    pl.available_capacity,
+                    pl.rental_type,
+                    pl.charge,
+                    pl.features_tip,
+                    pl.nearest_station,
+                    pl.status,
+                    pl.start_date,
+                    pl.end_date,
+                    pl.created_datetime,
+                    pl.updated_datetime,
+                    dist.distance_km,
+                    COALESCE($3::FLOAT8 IS NULL OR dist.distance_km <= $5::FLOAT8, FALSE) AS in_radius,
+                    CASE WHEN {charge} IS NOT NULL
+                         THEN LEAST(({charge})::BIGINT * unit.units, 2147483647)::INTEGER
+                    END AS estimated_cost,
+                    CASE WHEN $11::TEXT IS NULL THEN 0 ELSE (
+                        SELECT COUNT(*) FROM t_reservations r
+                        WHERE r.user_id = $11 AND r.parking_lot_id = pl.parking_lot_id AND r.status = '4'
+                    ) END AS usage_count,
+                    NOT EXISTS (
+                        SELECT 1 FROM t_parking_limits lim
+                        WHERE lim.parking_lot_id = pl.parking_lot_id
+                          AND (lim.length_limit < $12::INTEGER OR lim.width_limit < $13::INTEGER
+                               OR lim.height_limit < $14::INTEGER OR lim.weight_limit < $15::INTEGER)
+                    ) AS is_compatible
+                FROM t_parking_lots pl
+                LEFT JOIN LATERAL (
+                    SELECT g.latitude::FLOAT8 AS latitude, g.longitude::FLOAT8 AS longitude
+                    FROM t_parking_google_maps g
+                    WHERE g.parking_lot_id = pl.parking_lot_id AND g.latitude IS NOT NULL AND g.longitude IS NOT NULL
+                    LIMIT 1
+                ) gm ON TRUE
+                CROSS JOIN LATERAL (
+                    SELECT POWER(SIN(RADIANS($3::FLOAT8 - gm.latitude) / 2), 2)
+                         + COS(RADIANS(gm.latitude)) * COS(RADIANS($3::FLOAT8))
+                           * POWER(SIN(RADIANS($4::FLOAT8 - gm.longitude) / 2), 2) AS a
+                ) hav
+                CROSS JOIN LATERAL (
+                    SELECT 6371.0 * 2 * ATAN2(SQRT(hav.a), SQRT(GREATEST(1 - hav.a, 0))) AS distance_km
+                ) dist
+                CROSS JOIN LATERAL (
+                    SELECT
+                        COUNT(*) AS type_count,
+                        BOOL_AND(rt.rental_type = '日間単位') AS all_daily,
+                        MIN(CASE rt.rental_value WHEN '15分' THEN 15 WHEN '30分' THEN 30 WHEN '1時間' THEN 60 END)
+                            FILTER (WHERE rt.rental_type = '時間単位') AS min_minutes,
+                        MIN(CASE rt.rental_value WHEN '1日' THEN 1 WHEN '2日' THEN 2 WHEN '1週間' THEN 7 WHEN '1ヶ月' THEN 30 END)
+                            FILTER (WHERE rt.rental_type = '日間単位') AS min_days
+                    FROM t_parking_rental_types rt
+                    WHERE rt.parking_lot_id = pl.parking_lot_id
+                ) rental
+                CROSS JOIN LATERAL (
+                    SELECT CASE
+                        WHEN COALESCE(pl.rental_type = '日間単位', rental.type_count > 0 AND rental.all_daily)
+                        THEN COALESCE(rental.min_days, 1) * 1440
+                        ELSE COALESCE(rental.min_minutes, 60)
+                    END AS unit_minutes
+                ) rental_unit
+                CROSS JOIN LATERAL (
+                    SELECT CASE WHEN $10::INTEGER IS NULL THEN 1
+                        ELSE GREATEST(($10::INTEGER + rental_unit.unit_minutes - 1) / rental_unit.unit_minutes, 1)
+                    END AS units
+                ) unit
+                WHERE pl.status = $1
+                  AND ($2::TEXT IS NULL OR EXISTS (
+                       SELECT 1 FROM t_favorites fav
+                       WHERE fav.user_id = $2 AND fav.parking_lot_id = pl.parking_lot_id))
+                  AND ($3::FLOAT8 IS NULL OR (gm.latitude BETWEEN $6::FLOAT8 AND $7::FLOAT8
+                                              AND gm.longitude BETWEEN $8::FLOAT8 AND $9::FLOAT8))
+                  {filters}
+            )"#,
+        filters = search_filter_sql(17)
+    )
+}
+
+/// 検索結果の並べ替え項目の値（SQL式。値のない駐車場は並び順によらず末尾）
+///
+/// おすすめ順は距離（検索半径）・料金（検索結果内の最安・最高）・利用実績を正規化して重み付けした合計
+fn search_sort_key_sql(sort_by: SearchSortBy) -> String {
+    match sort_by {
+        SearchSortBy::Distance => "m.distance_km".to_string(),
+        SearchSortBy::Price => "m.estimated_cost::FLOAT8".to_string(),
+        SearchSortBy::Availability => "COALESCE(m.available_capacity, 0)::FLOAT8".to_string(),
+        SearchSortBy::Newest => "EXTRACT(EPOCH FROM m.created_datetime)::FLOAT8".to_string(),
+        SearchSortBy::Recommended => format!(
+            r#"100 * (
+                    GREATEST(LEAST(COALESCE(1 - m.distance_km / $5::FLOAT8, 0), 1), 0) * {}
+                    + GREATEST(LEAST(CASE
+                          WHEN m.estimated_cost IS NULL THEN 0
+                          WHEN m.max_cost > m.min_cost THEN (m.max_cost - m.estimated_cost)::FLOAT8 / (m.max_cost - m.min_cost)
+                          ELSE 1 END, 1), 0) * {}
+                    + LEAST(m.usage_count, {})::FLOAT8 / {} * {})"#,
+            SCORE_WEIGHT_DISTANCE,
+            SCORE_WEIGHT_PRICE,
+            SCORE_FULL_USAGE_COUNT,
+            SCORE_FULL_USAGE_COUNT,
+            SCORE_WEIGHT_USAGE
+        ),
+    }
+}
+
+/// 検索結果の並べ替えキー（SQL式, 降順か）
+///
+/// 値の有無、並べ替え項目、距離（近い順）、駐車場IDの順で固定し、ページ間の重複・欠落を防ぐ
+fn search_sort_columns(descending: bool) -> Vec<(&'static str, bool)> {
+    vec![
+        ("CASE WHEN r.sort_key IS NULL THEN 1 ELSE 0 END", false),
+        ("COALESCE(r.sort_key, 0)", descending),
+        ("COALESCE(r.distance_km, 0)", false),
+        ("r.parking_lot_id", false),
+    ]
+}
+
+/// 検索結果の行のカーソルキー（search_sort_columnsと同じ順・同じ値）
+pub fn search_cursor_keys(parking_lot_id: &str, metrics: &SearchLotMetrics) -> Vec<CursorKey> {
+    vec![
+        CursorKey::Int(i64::from(metrics.sort_key.is_none())),
+        CursorKey::Float(metrics.sort_key.unwrap_or(0.0)),
+        CursorKey::Float(metrics.distance_km.unwrap_or(0.0)),
+        CursorKey::Text(parking_lot_id.to_string()),
+    ]
+}
+
+fn search_page_params(criteria: &SearchPageCriteria) -> Vec<SqlParam> {
+    let bounds = criteria.origin.map(|(lat, lng)| search_bounds(lat, lng, criteria.radius_km));
+    let option_float = |value: Option<f64>| value.map_or(SqlParam::Null, SqlParam::Float);
+    let dimensions = &criteria.dimensions;
+    let mut params = vec![
+        SqlParam::String("アクティブ".to_string()),
+        SqlParam::OptionString(criteria.favorites_user_id.clone()),
+        option_float(criteria.origin.map(|(lat, _)| lat)),
+        option_float(criteria.origin.map(|(_, lng)| lng)),
+        SqlParam::Float(criteria.radius_km),
+        option_float(bounds.map(|b| b.0)),
+        option_float(bounds.map(|b| b.1)),
+        option_float(bounds.map(|b| b.2)),
+        option_float(bounds.map(|b| b.3)),
+        SqlParam::OptionI32(criteria.duration_minutes),
+        SqlParam::OptionString(criteria.usage_user_id.clone()),
+        SqlParam::OptionI32(dimensions.length_mm),
+        SqlParam::OptionI32(dimensions.width_mm),
+        SqlParam::OptionI32(dimensions.height_mm),
+        SqlParam::OptionI32(dimensions.weight_kg),
+        SqlParam::Boolean(criteria.include_incompatible),
+    ];
+    params.extend(search_filter_params(&criteria.filters));
+    params
+}
+
+fn bind_search_page<'q, O>(
+    query: QueryAs<'q, Postgres, O, PgArguments>,
+    criteria: &'q SearchPageCriteria,
+) -> QueryAs<'q, Postgres, O, PgArguments> {
+    let bounds = criteria.origin.map(|(lat, lng)| search_bounds(lat, lng, criteria.radius_km));
+    let dimensions = &criteria.dimensions;
+    let query = query
+        .bind("アクティブ")
+        .bind(criteria.favorites_user_id.as_deref())
+        .bind(criteria.origin.map(|(lat, _)| lat))
+        .bind(criteria.origin.map(|(_, lng)| lng))
+        .bind(criteria.radius_km)
+        .bind(bounds.map(|b| b.0))
+        .bind(bounds.map(|b| b.1))
+        .bind(bounds.map(|b| b.2))
+        .bind(bounds.map(|b| b.3))
+        .bind(criteria.duration_minutes)
+        .bind(criteria.usage_user_id.as_deref())
+        .bind(dimensions.length_mm)
+        .bind(dimensions.width_mm)
+        .bind(dimensions.height_mm)
+        .bind(dimensions.weight_kg)
+        .bind(criteria.include_incompatible);
+    bind_search_filters(query, &criteria.filters)
+}
+
+/// お気に入りの登録日時（未設定はUNIXエポック扱い）
+const FAVORITE_ADDED_AT_SQL: &str = "COALESCE(fav.created_datetime, 'epoch'::TIMESTAMPTZ)";
+
+/// お気に入り一覧の並べ替えキー（SQL式, 降順か）
+///
+/// 同順位は駐車場IDで固定し、ページ間の重複・欠落を防ぐ。
+/// 表示順の未設定は昇順・降順とも末尾になるよう番兵値に置き換える
+fn favorite_sort_columns(criteria: &FavoriteListCriteria) -> Vec<(&'static str, bool)> {
+    let desc = criteria.sort_order == "desc";
+    match criteria.sort_by.as_str() {
+        "added_at" => vec![(FAVORITE_ADDED_AT_SQL, desc), ("pl.parking_lot_id", false)],
+        "name" => vec![("pl.parking_lot_name", desc), ("pl.parking_lot_id", false)],
+        _ => {
+            let sort_order = if desc {
+                "COALESCE(fav.sort_order, -2147483648)"
+            } else {
+                "COALESCE(fav.sort_order, 2147483647)"
+            };
+            vec![(sort_order, desc), (FAVORITE_ADDED_AT_SQL, true), ("pl.parking_lot_id", false)]
+        }
+    }
+}
+
+/// お気に入り一覧の並び順
+fn favorite_order_clause(criteria: &FavoriteListCriteria) -> String {
+    favorite_sort_columns(criteria)
+        .iter()
+        .map(|(column, desc)| format!("{} {}", column, if *desc { "DESC" } else { "ASC" }))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// お気に入り一覧の行のカーソルキー（favorite_sort_columnsと同じ順・同じ値）
+pub fn favorite_cursor_keys(criteria: &FavoriteListCriteria, lot: &TParkingLotsModel, favorite: &FavoriteInfo) -> Vec<CursorKey> {
+    let added_at = CursorKey::Time(favorite.favorite_added_at.unwrap_or(chrono::DateTime::UNIX_EPOCH));
+    let lot_id = CursorKey::Text(lot.parking_lot_id.clone());
+    match criteria.sort_by.as_str() {
+        "added_at" => vec![added_at, lot_id],
+        "name" => vec![CursorKey::Text(lot.parking_lot_name.clone()), lot_id],
+        _ => {
+            let missing = if criteria.sort_order == "desc" { i32::MIN } else { i32::MAX };
+            vec![CursorKey::Int(favorite.favorite_order.unwrap_or(missing).into()), added_at, lot_id]
+        }
+    }
+}
+
+fn favorite_list_params(user_id: &str, criteria: &FavoriteListCriteria, keyword: Option<&str>) -> Vec<SqlParam> {
+    let mut params = vec![
+        SqlParam::String(user_id.to_string()),
+        SqlParam::String("アクティブ".to_string()),
+        SqlParam::OptionString(criteria.label.clone()),
+        SqlParam::OptionString(keyword.map(str::to_string)),
+    ];
+    params.extend(search_filter_params(&criteria.filters));
+    params
+}
+
+/// 部分一致検索用のILIKEパターン（ワイルドカード文字はエスケープ）
+fn like_pattern(keyword: &str) -> String {
+    let escaped = keyword
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{logging::count_sql_queries, postgresql_database::DatabaseConfig};
+    use crate::models::parking_search_model::ParkingSearchRequest;
+
+    /// テスト用データベース（DB_*環境変数）に接続する
+    ///
+    /// データベースが必要なテストは`cargo test -- --ignored`で実行する
+    async fn test_repository() -> ParkingSearchRepository {
+        let config = DatabaseConfig::from_env().expect("テスト用データベースの接続設定（DB_*環境変数）が必要です");
+        let db = PostgresDatabase::connect(&config).await.expect("テスト用データベースに接続できません");
+        ParkingSearchRepository::new(db)
+    }
+
+    /// お気に入りの最も多いユーザー
+    async fn favorite_user_id(repository: &ParkingSearchRepository) -> String {
+        sqlx::query_scalar::<_, String>(
+            "SELECT user_id FROM t_favorites GROUP BY user_id ORDER BY COUNT(*) DESC LIMIT 1",
+        )
+        .fetch_optional(repository.db.pool())
+        .await
+        .unwrap()
+        .expect("お気に入りが登録されたテストデータが必要です")
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DB_* test database"]
+    async fn test_search_query_count_is_constant() {
+        let repository = test_repository().await;
+
+        let criteria = SearchPageCriteria::from_request(&ParkingSearchRequest::default(), None);
+        let (results, query_count) =
+            count_sql_queries(repository.search_parking_lots_by_location(&criteria, None, 21, 0)).await;
+        let results = results.unwrap();
+
+        // 検索1回 + 関連テーブル4回（結果件数によらない）
+        assert_eq!(query_count, if results.is_empty() { 1 } else { 5 });
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DB_* test database"]
+    async fn test_favorite_query_count_is_constant() {
+        let repository = test_repository().await;
+        let user_id = favorite_user_id(&repository).await;
+
+        let criteria = FavoriteListCriteria::default();
+        let (results, query_count) =
+            count_sql_queries(repository.get_favorite_parking_lots(&user_id, &criteria, None, Some(20), 0)).await;
+        let results = results.unwrap();
+        assert_eq!(query_count, if results.is_empty() { 1 } else { 5 });
+
+        let lot_ids: Vec<String> = results.iter().map(|(lot, ..)| lot.parking_lot_id.clone()).collect();
+        let (favorites, query_count) = count_sql_queries(repository.get_favorite_details(&user_id, &lot_ids)).await;
+        assert_eq!(favorites.unwrap().len(), lot_ids.len());
+        assert_eq!(query_count, usize::from(!lot_ids.is_empty()));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DB_* test database"]
+    async fn test_favorite_cursor_matches_offset() {
+        let repository = test_repository().await;
+        let user_id = favorite_user_id(&repository).await;
+
+        for sort_by in ["custom", "added_at", "name"] {
+            let criteria = FavoriteListCriteria {
+                sort_by: sort_by.to_string(),
+                sort_order: "asc".to_string(),
+                ..Default::default()
+            };
+            let all: Vec<String> = repository.get_favorite_parking_lots(&user_id, &criteria, None, None, 0).await.unwrap()
+                .into_iter()
+                .map(|(lot, ..)| lot.parking_lot_id)
+                .collect();
+
+            // 3件ずつカーソルでたどった結果がOFFSETでの一括取得と一致する
+            let mut paged = Vec::new();
+            let mut cursor: Option<PageCursor> = None;
+            loop {
+                let rows = repository.get_favorite_parking_lots(&user_id, &criteria, cursor.as_ref(), Some(3), 0).await.unwrap();
+                let Some((lot, .., favorite)) = rows.last() else { break };
+                cursor = Some(PageCursor::new("test", "", favorite_cursor_keys(&criteria, lot, favorite)));
+                paged.extend(rows.into_iter().map(|(lot, ..)| lot.parking_lot_id));
+            }
+            assert_eq!(paged, all, "sort_by: {}", sort_by);
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DB_* test database"]
+    async fn test_search_cursor_matches_offset() {
+        let repository = test_repository().await;
+
+        for sort_by in ["distance", "price", "availability", "newest", "recommended"] {
+            let request = ParkingSearchRequest {
+                latitude: Some(35.681236),
+                longitude: Some(139.767125),
+                radius_km: Some(50.0),
+                sort_by: Some(sort_by.to_string()),
+                ..Default::default()
+            };
+            let criteria = SearchPageCriteria::from_request(&request, None);
+            let all: Vec<String> = repository.search_parking_lots_by_location(&criteria, None, 1000, 0).await.unwrap()
+                .into_iter()
+                .map(|((lot, ..), _)| lot.parking_lot_id)
+                .collect();
+
+            // 3件ずつカーソルでたどった結果がOFFSETでの一括取得と一致する
+            let mut paged = Vec::new();
+            let mut cursor: Option<PageCursor> = None;
+            loop {
+                let rows = repository.search_parking_lots_by_location(&criteria, cursor.as_ref(), 3, 0).await.unwrap();
+                let Some(((lot, ..), metrics)) = rows.last() else { break };
+                cursor = Some(PageCursor::new("test", "", search_cursor_keys(&lot.parking_lot_id, metrics)));
+                paged.extend(rows.into_iter().map(|((lot, ..), _)| lot.parking_lot_id));
+            }
+            assert_eq!(paged, all, "sort_by: {}", sort_by);
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use serde_json::Value;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::payment_model::{
+    PaymentChanges, PaymentReservationRow, PaymentRow, PaymentStatus, PaymentWebhookEvent,
+};
+
+/// 決済の取得列
+const PAYMENT_COLUMNS: &str = r#"
+    payment_id, reservation_id, user_id, provider, provider_payment_id, status,
+    amount, captured_amount, refunded_amount, currency, attempt_count, failure_reason,
+    authorized_datetime, captured_datetime, refunded_datetime, voided_datetime,
+    created_datetime, updated_datetime
+"#;
+
+/// 決済のリポジトリ
+#[derive(Debug, Clone)]
+pub struct PaymentRepository {
+    db: PostgresDatabase,
+}
+
+impl PaymentRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    /// 決済対象の予約（予約金額は予約詳細の合計を円単位に丸めた値）
+    pub async fn find_reservation(&self, reservation_id: &str) -> Result<Option<PaymentReservationRow>, DatabaseError> {
+        let sql = r#"
+            SELECT
+                r.reservation_id,
+                r.user_id,
+                r.parking_lot_id,
+                r.status,
+                COALESCE((
+                    SELECT ROUND(SUM(d.amount))
+                    FROM t_reservation_details d
+                    WHERE d.reservation_id = r.reservation_id
+                ), 0)::BIGINT AS amount,
+                u.login_id::TEXT AS user_login_id,
+                o.login_id::TEXT AS owner_login_id
+            FROM t_reservations r
+            LEFT JOIN m_users u ON u.user_id = r.user_id
+            LEFT JOIN t_parking_lots pl ON pl.parking_lot_id = r.parking_lot_id
+            LEFT JOIN m_owners o ON o.owner_id = pl.owner_id
+            WHERE r.reservation_id = $1
+        "#;
+
+        let params = vec![SqlParam::String(reservation_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, PaymentReservationRow>(sql)
+            .bind(reservation_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("決済対象の予約取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("決済対象の予約取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 予約の決済を取得
+    pub async fn find_by_reservation(&self, reservation_id: &str) -> Result<Option<PaymentRow>, DatabaseError> {
+        let sql = format!("SELECT {} FROM t_payments WHERE reservation_id = $1", PAYMENT_COLUMNS);
+
+        let params = vec![SqlParam::String(reservation_id.to_string())];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, PaymentRow>(&sql)
+            .bind(reservation_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("決済情報の取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("決済情報の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// プロバイダー決済IDから決済を取得
+    pub async fn find_by_provider_payment_id(
+        &self,
+        provider: &str,
+        provider_payment_id: &str,
+    ) -> Result<Option<PaymentRow>, DatabaseError> {
+        let sql = format!(
+            "SELECT {} FROM t_payments WHERE provider = $1 AND provider_payment_id = $2",
+            PAYMENT_COLUMNS
+        );
+
+        let params = vec![
+            SqlParam::String(provider.to_string()),
+            SqlParam::String(provider_payment_id.to_string()),
+        ];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, PaymentRow>(&sql)
+            .bind(provider)
+            .bind(provider_payment_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("決済情報の取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("決済情報の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 予約の決済を作成（既に存在する場合は既存の決済を返す）
+    pub async fn ensure_payment(
+        &self,
+        reservation: &PaymentReservationRow,
+        provider: &str,
+        currency: &str,
+    ) -> Result<PaymentRow, DatabaseError> {
+        let sql = format!(
+            r#"
+            WITH inserted AS (
+                INSERT INTO t_payments (reservation_id, user_id, provider, amount, currency)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (reservation_id) DO NOTHING
+                RETURNING {columns}
+            )
+            SELECT {columns} FROM inserted
+            UNION ALL
+            SELECT {columns} FROM t_payments WHERE reservation_id = $1
+            LIMIT 1
+            "#,
+            columns = PAYMENT_COLUMNS
+        );
+
+        let params = vec![
+            SqlParam::String(reservation.reservation_id.clone()),
+            SqlParam::String(reservation.user_id.clone()),
+            SqlParam::String(provider.to_string()),
+            SqlParam::Integer(reservation.amount),
+            SqlParam::String(currency.to_string()),
+        ];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, PaymentRow>(&sql)
+            .bind(&reservation.reservation_id)
+            .bind(&reservation.user_id)
+            .bind(provider)
+            .bind(reservation.amount)
+            .bind(currency)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("決済情報の作成に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("決済情報の作成に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 失敗した決済を与信待ちに戻す（試行回数を加算し、与信金額を最新の予約金額にする）
+    pub async fn reset_failed_payment(&self, payment_id: Uuid, amount: i64) -> Result<Option<PaymentRow>, DatabaseError> {
+        let sql = format!(
+            r#"
+            UPDATE t_payments
+            SET status = 'pending',
+                amount = $2,
+                attempt_count = attempt_count + 1,
+                failure_reason = NULL,
+                updated_datetime = NOW()
+            WHERE payment_id = $1 AND status = 'failed'
+            RETURNING {}
+            "#,
+            PAYMENT_COLUMNS
+        );
+
+        let params = vec![SqlParam::String(payment_id.to_string()), SqlParam::Integer(amount)];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, PaymentRow>(&sql)
+            .bind(payment_id)
+            .bind(amount)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("決済の再試行準備に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("決済の再試行準備に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// ステータスを遷移（現在のステータスがfromのいずれかの場合のみ）
+    ///
+    /// 他のリクエストやWebhookで先に遷移済みの場合はNoneを返す
+    pub async fn transition(
+        &self,
+        payment_id: Uuid,
+        from: &[PaymentStatus],
+        to: PaymentStatus,
+        changes: &PaymentChanges,
+    ) -> Result<Option<PaymentRow>, DatabaseError> {
+        let sql = format!(
+            r#"
+            UPDATE t_payments
+            SET status = $2,
+                provider_payment_id = COALESCE($3, provider_payment_id),
+                captured_amount = COALESCE($4, captured_amount),
+                refunded_amount = COALESCE($5, refunded_amount),
+                failure_reason = $6,
+                authorized_datetime = CASE WHEN $2 = 'authorized' THEN NOW() ELSE authorized_datetime END,
+                captured_datetime = CASE WHEN $4::BIGINT IS NOT NULL THEN NOW() ELSE captured_datetime END,
+                refunded_datetime = CASE WHEN $5::BIGINT IS NOT NULL THEN NOW() ELSE refunded_datetime END,
+                voided_datetime = CASE WHEN $2 = 'voided' THEN NOW() ELSE voided_datetime END,
+                updated_datetime = NOW()
+            WHERE payment_id = $1
+              AND status = ANY($7)
+              AND ($8::BIGINT IS NULL OR refunded_amount = $8)
+            RETURNING {}
+            "#,
+            PAYMENT_COLUMNS
+        );
+        let from: Vec<String> = from.iter().map(|status| status.as_str().to_string()).collect();
+
+        let params = vec![
+            SqlParam::String(payment_id.to_string()),
+            SqlParam::String(to.as_str().to_string()),
+            SqlParam::OptionString(changes.provider_payment_id.clone()),
+            changes.captured_amount.map_or(SqlParam::Null, SqlParam::Integer),
+            changes.refunded_amount.map_or(SqlParam::Null, SqlParam::Integer),
+            SqlParam::OptionString(changes.failure_reason.clone()),
+            SqlParam::String(from.join(",")),
+            changes.expected_refunded_amount.map_or(SqlParam::Null, SqlParam::Integer),
+        ];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, PaymentRow>(&sql)
+            .bind(payment_id)
+            .bind(to.as_str())
+            .bind(&changes.provider_payment_id)
+            .bind(changes.captured_amount)
+            .bind(changes.refunded_amount)
+            .bind(&changes.failure_reason)
+            .bind(&from)
+            .bind(changes.expected_refunded_amount)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("決済ステータスの更新に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("決済ステータスの更新に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// Webhookイベントを記録（処理済みのイベントの場合はfalse）
+    ///
+    /// 受信済みでも決済状態への反映前に失敗したイベントは、再送時に再処理できるようtrueを返す
+    pub async fn insert_event(
+        &self,
+        provider: &str,
+        event: &PaymentWebhookEvent,
+        payload: &Value,
+    ) -> Result<bool, DatabaseError> {
+        let sql = r#"
+            INSERT INTO t_payment_events (provider, event_id, event_type, provider_payment_id, payload)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (provider, event_id) DO UPDATE
+                SET received_datetime = CURRENT_TIMESTAMP
+                WHERE t_payment_events.processed_datetime IS NULL
+        "#;
+
+        let params = vec![
+            SqlParam::String(provider.to_string()),
+            SqlParam::String(event.event_id.clone()),
+            SqlParam::String(event.event_type.as_str().to_string()),
+            SqlParam::String(event.provider_payment_id.clone()),
+            SqlParam::String(payload.to_string()),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql)
+            .bind(provider)
+            .bind(&event.event_id)
+            .bind(event.event_type.as_str())
+            .bind(&event.provider_payment_id)
+            .bind(payload)
+            .execute(self.db.pool())
+            .await
+        {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(e) => {
+                error!("Webhookイベントの記録に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("Webhookイベントの記録に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// Webhookイベントを処理済みにする
+    pub async fn mark_event_processed(&self, provider: &str, event_id: &str) -> Result<(), DatabaseError> {
+        let sql = r#"
+            UPDATE t_payment_events
+            SET processed_datetime = NOW()
+            WHERE provider = $1 AND event_id = $2
+        "#;
+
+        let params = vec![SqlParam::String(provider.to_string()), SqlParam::String(event_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql)
+            .bind(provider)
+            .bind(event_id)
+            .execute(self.db.pool())
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("Webhookイベントの処理済み更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("Webhookイベントの処理済み更新に失敗: {}", e)))
+            }
+        }
+    }
+}
@@ -0,0 +1,445 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, Transaction};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::profile_model::{ContactChangeRow, ProfileRow, ProfileUpdateRequest, CONTACT_CHANGE_EMAIL};
+
+/// プロフィール・連絡先変更のリポジトリ
+#[derive(Debug, Clone)]
+pub struct ProfileRepository {
+    db: PostgresDatabase,
+}
+
+impl ProfileRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    pub async fn begin_transaction(&self) -> Result<Transaction<'static, Postgres>, DatabaseError> {
+        self.db.pool().begin().await.map_err(|e| {
+            error!("トランザクション開始に失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクション開始に失敗: {}", e))
+        })
+    }
+
+    /// ログインIDからプロフィールを取得（ユーザー・オーナー共通）
+    pub async fn find_profile(&self, login_id: Uuid) -> Result<Option<ProfileRow>, DatabaseError> {
+        let sql = r#"
+            SELECT
+                l.login_id::text AS login_id,
+                l.email,
+                l.phone_number,
+                (l.is_user_owner = '1') AS is_owner,
+                COALESCE(o.owner_id, u.user_id) AS account_id,
+                COALESCE(o.full_name, u.full_name) AS full_name,
+                COALESCE(o.full_name_kana, u.full_name_kana) AS full_name_kana,
+                COALESCE(o.birthday, u.birthday) AS birthday,
+                COALESCE(o.gender, u.gender) AS gender,
+                COALESCE(o.postal_code, u.postal_code) AS postal_code,
+                COALESCE(o.address, u.address) AS address,
+                o.registrant_type,
+                o.invoice_registration_number,
+                p.photo_path,
+                p.photo_description
+            FROM m_login l
+            LEFT JOIN m_users u ON u.login_id = l.login_id AND l.is_user_owner = '0'
+            LEFT JOIN m_owners o ON o.login_id = l.login_id AND l.is_user_owner = '1'
+            LEFT JOIN m_profiles p ON p.user_id = u.user_id OR p.owner_id = o.owner_id
+            WHERE l.login_id = $1
+            ORDER BY p.updated_datetime DESC NULLS LAST
+            LIMIT 1
+        "#;
+
+        let params = vec![SqlParam::String(login_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, ProfileRow>(sql)
+            .bind(login_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("プロフィール取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("プロフィール取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// プロフィール項目を更新（NULLの項目は変更しない、空文字の任意項目はNULLにする）
+    pub async fn update_profile(
+        &self,
+        login_id: Uuid,
+        is_owner: bool,
+        req: &ProfileUpdateRequest,
+        now: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        let sql = if is_owner {
+            // m_owners.postal_code はNOT NULLのため削除不可
+            r#"
+            UPDATE m_owners SET
+                full_name = COALESCE($2, full_name),
+                full_name_kana = CASE WHEN $3::text IS NULL THEN full_name_kana ELSE NULLIF($3, '') END,
+                birthday = CASE WHEN $4::text IS NULL THEN birthday ELSE NULLIF($4, '')::date END,
+                gender = CASE WHEN $5::text IS NULL THEN gender ELSE NULLIF($5, '') END,
+                postal_code = COALESCE(NULLIF($6, ''), postal_code),
+                address = COALESCE($7, address),
+                updated_datetime = $8,
+                invoice_registration_number = CASE WHEN $9::text IS NULL THEN invoice_registration_number ELSE NULLIF($9, '') END
+            WHERE login_id = $1
+            "#
+        } else {
+            r#"
+            UPDATE m_users SET
+                full_name = COALESCE($2, full_name),
+                full_name_kana = CASE WHEN $3::text IS NULL THEN full_name_kana ELSE NULLIF($3, '') END,
+                birthday = CASE WHEN $4::text IS NULL THEN birthday ELSE NULLIF($4, '')::date END,
+                gender = CASE WHEN $5::text IS NULL THEN gender ELSE NULLIF($5, '') END,
+                postal_code = CASE WHEN $6::text IS NULL THEN postal_code ELSE NULLIF($6, '') END,
+                address = COALESCE($7, address),
+                updated_datetime = $8
+            WHERE login_id = $1
+            "#
+        };
+
+        let mut params = vec![
+            SqlParam::String(login_id.to_string()),
+            SqlParam::OptionString(req.full_name.clone()),
+            SqlParam::OptionString(req.full_name_kana.clone()),
+            SqlParam::OptionString(req.birthday.clone()),
+            SqlParam::OptionString(req.gender.clone()),
+            SqlParam::OptionString(req.postal_code.clone()),
+            SqlParam::OptionString(req.address.clone()),
+            SqlParam::DateTime(now),
+        ];
+        if is_owner {
+            params.push(SqlParam::OptionString(req.invoice_registration_number.clone()));
+        }
+        log_sql_query(sql, &params, None);
+
+        let query = sqlx::query(sql)
+            .bind(login_id)
+            .bind(&req.full_name)
+            .bind(&req.full_name_kana)
+            .bind(&req.birthday)
+            .bind(&req.gender)
+            .bind(&req.postal_code)
+            .bind(&req.address)
+            .bind(now);
+        // 登録番号はオーナーのみ
+        let query = if is_owner { query.bind(&req.invoice_registration_number) } else { query };
+
+        match query.execute(self.db.pool()).await {
+            Ok(result) if result.rows_affected() > 0 => {
+                info!("プロフィール更新成功: login_id={}", login_id);
+                Ok(())
+            }
+            Ok(_) => Err(DatabaseError::RowNotFound),
+            Err(e) => {
+                error!("プロフィール更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("プロフィール更新に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// プロフィール写真を登録・差し替えし、差し替え前の写真パスを返す
+    pub async fn upsert_profile_photo(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        is_owner: bool,
+        account_id: &str,
+        photo_path: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<String>, DatabaseError> {
+        let (select_sql, update_sql, insert_sql) = if is_owner {
+            (
+                "SELECT photo_path FROM m_profiles WHERE owner_id = $1 ORDER BY updated_datetime DESC LIMIT 1 FOR UPDATE",
+                "UPDATE m_profiles SET photo_path = $2, updated_datetime = $3 WHERE owner_id = $1",
+                "INSERT INTO m_profiles (owner_id, photo_path, created_datetime, updated_datetime) VALUES ($1, $2, $3, $3)",
+            )
+        } else {
+            (
+                "SELECT photo_path FROM m_profiles WHERE user_id = $1 ORDER BY updated_datetime DESC LIMIT 1 FOR UPDATE",
+                "UPDATE m_profiles SET photo_path = $2, updated_datetime = $3 WHERE user_id = $1",
+                "INSERT INTO m_profiles (user_id, photo_path, created_datetime, updated_datetime) VALUES ($1, $2, $3, $3)",
+            )
+        };
+
+        let select_params = vec![SqlParam::String(account_id.to_string())];
+        log_sql_query(select_sql, &select_params, None);
+
+        let previous = sqlx::query_scalar::<_, String>(select_sql)
+            .bind(account_id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| {
+                error!("プロフィール写真取得に失敗: {}", e);
+                log_sql_error(select_sql, &select_params, &e.to_string());
+                DatabaseError::QueryError(format!("プロフィール写真取得に失敗: {}", e))
+            })?;
+
+        let sql = if previous.is_some() { update_sql } else { insert_sql };
+        let params = vec![
+            SqlParam::String(account_id.to_string()),
+            SqlParam::String(photo_path.to_string()),
+            SqlParam::DateTime(now),
+        ];
+        log_sql_query(sql, &params, None);
+
+        if let Err(e) = sqlx::query(sql)
+            .bind(account_id)
+            .bind(photo_path)
+            .bind(now)
+            .execute(&mut **tx)
+            .await
+        {
+            error!("m_profiles レコード保存失敗: {}", e);
+            log_sql_error(sql, &params, &e.to_string());
+            return Err(DatabaseError::QueryError(format!("m_profiles レコード保存失敗: {}", e)));
+        }
+
+        info!("m_profiles 写真保存成功: account_id={}", account_id);
+        Ok(previous)
+    }
+
+    /// メールアドレス・電話番号が他のアカウントで使用されているか確認
+    pub async fn contact_in_use(&self, change_type: &str, value: &str) -> Result<bool, DatabaseError> {
+        let sql = if change_type == CONTACT_CHANGE_EMAIL {
+            "SELECT EXISTS(SELECT 1 FROM m_login WHERE LOWER(email) = LOWER($1))"
+        } else {
+            r#"
+            SELECT EXISTS(SELECT 1 FROM m_login WHERE phone_number = $1)
+                OR EXISTS(SELECT 1 FROM m_users WHERE phone_number = $1)
+                OR EXISTS(SELECT 1 FROM m_owners WHERE phone_number = $1)
+            "#
+        };
+
+        let params = vec![SqlParam::String(value.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, bool>(sql)
+            .bind(value)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(exists) => Ok(exists),
+            Err(e) => {
+                error!("連絡先の重複確認に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("連絡先の重複確認に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 連絡先変更リクエストを登録（同一種別の未使用リクエストは破棄）
+    pub async fn replace_contact_change(
+        &self,
+        login_id: Uuid,
+        change_type: &str,
+        new_value: &str,
+        code_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.begin_transaction().await?;
+
+        let delete_sql = r#"
+            DELETE FROM t_contact_change_requests
+            WHERE login_id = $1 AND change_type = $2 AND is_used = '0'
+        "#;
+        let delete_params = vec![
+            SqlParam::String(login_id.to_string()),
+            SqlParam::String(change_type.to_string()),
+        ];
+        log_sql_query(delete_sql, &delete_params, None);
+
+        if let Err(e) = sqlx::query(delete_sql)
+            .bind(login_id)
+            .bind(change_type)
+            .execute(&mut *tx)
+            .await
+        {
+            error!("旧連絡先変更リクエストの削除に失敗: {}", e);
+            log_sql_error(delete_sql, &delete_params, &e.to_string());
+            return Err(DatabaseError::QueryError(format!("旧連絡先変更リクエストの削除に失敗: {}", e)));
+        }
+
+        let insert_sql = r#"
+            INSERT INTO t_contact_change_requests (login_id, change_type, new_value, code_hash, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+        "#;
+        let insert_params = vec![
+            SqlParam::String(login_id.to_string()),
+            SqlParam::String(change_type.to_string()),
+            SqlParam::String(new_value.to_string()),
+            SqlParam::String("********".to_string()),
+            SqlParam::DateTime(expires_at),
+        ];
+        log_sql_query(insert_sql, &insert_params, None);
+
+        if let Err(e) = sqlx::query(insert_sql)
+            .bind(login_id)
+            .bind(change_type)
+            .bind(new_value)
+            .bind(code_hash)
+            .bind(expires_at)
+            .execute(&mut *tx)
+            .await
+        {
+            error!("連絡先変更リクエストの登録に失敗: {}", e);
+            log_sql_error(insert_sql, &insert_params, &e.to_string());
+            return Err(DatabaseError::QueryError(format!("連絡先変更リクエストの登録に失敗: {}", e)));
+        }
+
+        tx.commit().await.map_err(|e| {
+            error!("トランザクションコミットに失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクションコミットに失敗: {}", e))
+        })
+    }
+
+    /// 未使用の連絡先変更リクエストを取得
+    pub async fn find_pending_contact_change(
+        &self,
+        login_id: Uuid,
+        change_type: &str,
+    ) -> Result<Option<ContactChangeRow>, DatabaseError> {
+        let sql = r#"
+            SELECT request_id, new_value, code_hash, attempt_count, expires_at
+            FROM t_contact_change_requests
+            WHERE login_id = $1 AND change_type = $2 AND is_used = '0'
+            ORDER BY created_datetime DESC
+            LIMIT 1
+        "#;
+
+        let params = vec![
+            SqlParam::String(login_id.to_string()),
+            SqlParam::String(change_type.to_string()),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, ContactChangeRow>(sql)
+            .bind(login_id)
+            .bind(change_type)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("連絡先変更リクエストの取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("連絡先変更リクエストの取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 認証コードの試行回数を加算（上限に達している場合はfalse）
+    ///
+    /// 同時に照合された場合も上限を超えないよう、上限の確認と加算を1つのUPDATEで行う
+    pub async fn consume_contact_change_attempt(&self, request_id: Uuid, max_attempts: i32) -> Result<bool, DatabaseError> {
+        let sql = r#"
+            UPDATE t_contact_change_requests
+            SET attempt_count = attempt_count + 1, updated_datetime = CURRENT_TIMESTAMP
+            WHERE request_id = $1 AND attempt_count < $2 AND is_used = '0'
+            RETURNING attempt_count
+        "#;
+
+        let params = vec![SqlParam::String(request_id.to_string()), SqlParam::I32(max_attempts)];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, i32>(sql)
+            .bind(request_id)
+            .bind(max_attempts)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(attempt_count) => Ok(attempt_count.is_some()),
+            Err(e) => {
+                error!("認証試行回数の更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("認証試行回数の更新に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 連絡先を変更し、リクエストを使用済みにする
+    ///
+    /// 電話番号はm_loginとm_users/m_ownersの両方を更新する
+    pub async fn apply_contact_change(
+        &self,
+        login_id: Uuid,
+        is_owner: bool,
+        change_type: &str,
+        new_value: &str,
+        request_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        let mut statements: Vec<&str> = Vec::new();
+        if change_type == CONTACT_CHANGE_EMAIL {
+            statements.push("UPDATE m_login SET email = $2, updated_datetime = $3 WHERE login_id = $1");
+        } else {
+            statements.push("UPDATE m_login SET phone_number = $2, updated_datetime = $3 WHERE login_id = $1");
+            statements.push(if is_owner {
+                "UPDATE m_owners SET phone_number = $2, updated_datetime = $3 WHERE login_id = $1"
+            } else {
+                "UPDATE m_users SET phone_number = $2, updated_datetime = $3 WHERE login_id = $1"
+            });
+        }
+
+        let mut tx = self.begin_transaction().await?;
+
+        for sql in statements {
+            let params = vec![
+                SqlParam::String(login_id.to_string()),
+                SqlParam::String(new_value.to_string()),
+                SqlParam::DateTime(now),
+            ];
+            log_sql_query(sql, &params, None);
+
+            if let Err(e) = sqlx::query(sql)
+                .bind(login_id)
+                .bind(new_value)
+                .bind(now)
+                .execute(&mut *tx)
+                .await
+            {
+                error!("連絡先の変更に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("連絡先の変更に失敗: {}", e)));
+            }
+        }
+
+        let used_sql = r#"
+            UPDATE t_contact_change_requests
+            SET is_used = '1', updated_datetime = $2
+            WHERE request_id = $1
+        "#;
+        let used_params = vec![
+            SqlParam::String(request_id.to_string()),
+            SqlParam::DateTime(now),
+        ];
+        log_sql_query(used_sql, &used_params, None);
+
+        if let Err(e) = sqlx::query(used_sql)
+            .bind(request_id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+        {
+            error!("連絡先変更リクエストの更新に失敗: {}", e);
+            log_sql_error(used_sql, &used_params, &e.to_string());
+            return Err(DatabaseError::QueryError(format!("連絡先変更リクエストの更新に失敗: {}", e)));
+        }
+
+        tx.commit().await.map_err(|e| {
+            error!("トランザクションコミットに失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクションコミットに失敗: {}", e))
+        })?;
+
+        info!("連絡先を変更しました: login_id={}, change_type={}", login_id, change_type);
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use tracing::{error, info};
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::receipt_model::{format_receipt_number, NewReceipt, ReceiptRow, ReceiptSourceRow};
+
+/// t_receiptsの取得列
+const RECEIPT_COLUMNS: &str = r#"
+    receipt_id, receipt_number, reservation_id, user_id, owner_id, issuer_name,
+    invoice_registration_number, recipient_name, parking_lot_name,
+    usage_start_datetime, usage_end_datetime, total_amount, tax_rate, tax_amount, issued_datetime
+"#;
+
+/// 領収書のリポジトリ
+#[derive(Debug, Clone)]
+pub struct ReceiptRepository {
+    db: PostgresDatabase,
+}
+
+impl ReceiptRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    /// 領収書の発行元データ（予約・駐車場・オーナー・利用実績）
+    pub async fn find_source(&self, reservation_id: &str) -> Result<Option<ReceiptSourceRow>, DatabaseError> {
+        let sql = r#"
+            SELECT
+                r.reservation_id,
+                r.status,
+                r.user_id,
+                u.login_id::TEXT AS user_login_id,
+                u.full_name AS user_full_name,
+                l.email AS user_email,
+                pl.owner_id,
+                o.login_id::TEXT AS owner_login_id,
+                o.full_name AS owner_name,
+                o.invoice_registration_number,
+                pl.parking_lot_name,
+                r.start_datetime,
+                r.end_datetime,
+                ps.entry_datetime,
+                ps.exit_datetime,
+                COALESCE(
+                    (
+                        SELECT p.captured_amount - p.refunded_amount
+                        FROM t_payments p
+                        WHERE p.reservation_id = r.reservation_id AND p.status IN ('captured', 'refunded')
+                    ),
+                    (
+                        SELECT ROUND(SUM(d.amount))
+                        FROM t_reservation_details d
+                        WHERE d.reservation_id = r.reservation_id
+                    ),
+                    0
+                )::BIGINT AS amount
+            FROM t_reservations r
+            JOIN t_parking_lots pl ON pl.parking_lot_id = r.parking_lot_id
+            LEFT JOIN m_users u ON u.user_id = r.user_id
+            LEFT JOIN m_login l ON l.login_id = u.login_id
+            LEFT JOIN m_owners o ON o.owner_id = pl.owner_id
+            LEFT JOIN LATERAL (
+                SELECT s.entry_datetime, s.exit_datetime
+                FROM t_parking_status s
+                WHERE s.reservation_id = r.reservation_id
+                ORDER BY s.updated_datetime DESC
+                LIMIT 1
+            ) ps ON TRUE
+            WHERE r.reservation_id = $1
+        "#;
+
+        let params = vec![SqlParam::String(reservation_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, ReceiptSourceRow>(sql)
+            .bind(reservation_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("領収書の発行元データの取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("領収書の発行元データの取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 予約の領収書（未発行の場合はNone）
+    pub async fn find_by_reservation(&self, reservation_id: &str) -> Result<Option<ReceiptRow>, DatabaseError> {
+        let sql = format!("SELECT {} FROM t_receipts WHERE reservation_id = $1", RECEIPT_COLUMNS);
+
+        let params = vec![SqlParam::String(reservation_id.to_string())];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, ReceiptRow>(&sql)
+            .bind(reservation_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("領収書の取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("領収書の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 領収書番号を採番して登録
+    ///
+    /// 採番と登録を同じトランザクションで行い、同じ予約の領収書が先に登録された場合は
+    /// ロールバックして番号を消費せずNoneを返す
+    pub async fn insert_receipt(&self, receipt: &NewReceipt, issue_year: i32) -> Result<Option<ReceiptRow>, DatabaseError> {
+        let mut tx = self.db.pool().begin().await.map_err(|e| {
+            error!("トランザクション開始に失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクション開始に失敗: {}", e))
+        })?;
+
+        let sequence_sql = r#"
+            INSERT INTO t_receipt_sequences (issue_year, last_number)
+            VALUES ($1, 1)
+            ON CONFLICT (issue_year) DO UPDATE
+            SET last_number = t_receipt_sequences.last_number + 1, updated_datetime = NOW()
+            RETURNING last_number
+        "#;
+        let sequence_params = vec![SqlParam::I32(issue_year)];
+        log_sql_query(sequence_sql, &sequence_params, None);
+
+        let number = sqlx::query_scalar::<_, i64>(sequence_sql)
+            .bind(issue_year)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("領収書番号の採番に失敗: {}", e);
+                log_sql_error(sequence_sql, &sequence_params, &e.to_string());
+                DatabaseError::QueryError(format!("領収書番号の採番に失敗: {}", e))
+            })?;
+        let receipt_number = format_receipt_number(issue_year, number);
+
+        let sql = format!(
+            r#"
+            INSERT INTO t_receipts (
+                receipt_number, reservation_id, user_id, owner_id, issuer_name,
+                invoice_registration_number, recipient_name, parking_lot_name,
+                usage_start_datetime, usage_end_datetime, total_amount, tax_rate, tax_amount
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (reservation_id) DO NOTHING
+            RETURNING {}
+            "#,
+            RECEIPT_COLUMNS
+        );
+        let params = vec![
+            SqlParam::String(receipt_number.clone()),
+            SqlParam::String(receipt.reservation_id.clone()),
+            SqlParam::String(receipt.user_id.clone()),
+            SqlParam::String(receipt.owner_id.clone()),
+            SqlParam::String(receipt.issuer_name.clone()),
+            SqlParam::OptionString(receipt.invoice_registration_number.clone()),
+            SqlParam::OptionString(receipt.recipient_name.clone()),
+            SqlParam::String(receipt.parking_lot_name.clone()),
+            SqlParam::DateTime(receipt.usage_start_datetime),
+            SqlParam::DateTime(receipt.usage_end_datetime),
+            SqlParam::Integer(receipt.total_amount),
+            SqlParam::I32(receipt.tax_rate),
+            SqlParam::Integer(receipt.tax_amount),
+        ];
+        log_sql_query(&sql, &params, None);
+
+        let row = sqlx::query_as::<_, ReceiptRow>(&sql)
+            .bind(&receipt_number)
+            .bind(&receipt.reservation_id)
+            .bind(&receipt.user_id)
+            .bind(&receipt.owner_id)
+            .bind(&receipt.issuer_name)
+            .bind(receipt.invoice_registration_number.as_deref())
+            .bind(receipt.recipient_name.as_deref())
+            .bind(&receipt.parking_lot_name)
+            .bind(receipt.usage_start_datetime)
+            .bind(receipt.usage_end_datetime)
+            .bind(receipt.total_amount)
+            .bind(receipt.tax_rate)
+            .bind(receipt.tax_amount)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("領収書の登録に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                DatabaseError::QueryError(format!("領収書の登録に失敗: {}", e))
+            })?;
+
+        let Some(row) = row else {
+            tx.rollback().await.map_err(|e| {
+                error!("トランザクションロールバックに失敗: {}", e);
+                DatabaseError::TransactionError(format!("トランザクションロールバックに失敗: {}", e))
+            })?;
+            info!("領収書は発行済みのため採番を取り消しました: reservation_id={}", receipt.reservation_id);
+            return Ok(None);
+        };
+
+        tx.commit().await.map_err(|e| {
+            error!("トランザクションコミットに失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクションコミットに失敗: {}", e))
+        })?;
+        Ok(Some(row))
+    }
+}
@@ -0,0 +1,672 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{Postgres, Transaction};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::saved_search_model::{SavedSearchEventRow, SavedSearchMatch, SavedSearchRow};
+
+/// 保存検索の取得列
+const SAVED_SEARCH_SELECT: &str = r#"
+    SELECT
+        saved_search_id,
+        user_id,
+        search_name,
+        condition_json,
+        notify_email,
+        notify_in_app,
+        quiet_period_minutes,
+        is_active,
+        last_notified_datetime,
+        created_datetime,
+        updated_datetime
+    FROM t_saved_searches
+"#;
+
+/// 保存検索の登録・更新内容
+#[derive(Debug, Clone)]
+pub struct SavedSearchValues {
+    pub search_name: String,
+    pub condition_json: Value,
+    pub notify_email: bool,
+    pub notify_in_app: bool,
+    pub quiet_period_minutes: i32,
+    pub is_active: bool,
+}
+
+/// 保存検索のリポジトリ
+#[derive(Debug, Clone)]
+pub struct SavedSearchRepository {
+    db: PostgresDatabase,
+}
+
+impl SavedSearchRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    pub async fn begin_transaction(&self) -> Result<Transaction<'static, Postgres>, DatabaseError> {
+        self.db.pool().begin().await.map_err(|e| {
+            error!("トランザクション開始に失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクション開始に失敗: {}", e))
+        })
+    }
+
+    /// ログインIDから利用者のユーザーIDを取得（オーナーの場合はNone）
+    pub async fn find_user_id(&self, login_id: Uuid) -> Result<Option<String>, DatabaseError> {
+        let sql = r#"
+            SELECT u.user_id
+            FROM m_users u
+            INNER JOIN m_login l ON l.login_id = u.login_id
+            WHERE u.login_id = $1 AND l.is_user_owner = '0'
+        "#;
+
+        let params = vec![SqlParam::String(login_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, String>(sql)
+            .bind(login_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(user_id) => Ok(user_id),
+            Err(e) => {
+                error!("ユーザーID取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("ユーザーID取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 保存検索一覧（登録順）
+    pub async fn list_saved_searches(&self, user_id: &str) -> Result<Vec<SavedSearchRow>, DatabaseError> {
+        let sql = format!("{} WHERE user_id = $1 ORDER BY created_datetime", SAVED_SEARCH_SELECT);
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, SavedSearchRow>(&sql)
+            .bind(user_id)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("保存検索一覧の取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保存検索一覧の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 保存検索を1件取得
+    pub async fn find_saved_search(
+        &self,
+        user_id: &str,
+        saved_search_id: Uuid,
+    ) -> Result<Option<SavedSearchRow>, DatabaseError> {
+        let sql = format!("{} WHERE user_id = $1 AND saved_search_id = $2", SAVED_SEARCH_SELECT);
+
+        let params = vec![SqlParam::String(user_id.to_string()), SqlParam::String(saved_search_id.to_string())];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, SavedSearchRow>(&sql)
+            .bind(user_id)
+            .bind(saved_search_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("保存検索の取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保存検索の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 有効な保存検索をすべて取得（バックグラウンド評価用）
+    pub async fn list_active_saved_searches(&self) -> Result<Vec<SavedSearchRow>, DatabaseError> {
+        let sql = format!("{} WHERE is_active = TRUE ORDER BY saved_search_id", SAVED_SEARCH_SELECT);
+
+        let params: Vec<SqlParam> = vec![];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, SavedSearchRow>(&sql).fetch_all(self.db.pool()).await {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("有効な保存検索の取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("有効な保存検索の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 保存検索の件数
+    pub async fn count_saved_searches(&self, user_id: &str) -> Result<i64, DatabaseError> {
+        let sql = "SELECT COUNT(*) FROM t_saved_searches WHERE user_id = $1";
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, i64>(sql).bind(user_id).fetch_one(self.db.pool()).await {
+            Ok(count) => Ok(count),
+            Err(e) => {
+                error!("保存検索件数の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保存検索件数の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 同じ名称の保存検索があるか（更新時は対象自身を除く）
+    pub async fn name_exists(
+        &self,
+        user_id: &str,
+        search_name: &str,
+        exclude_id: Option<Uuid>,
+    ) -> Result<bool, DatabaseError> {
+        let sql = r#"
+            SELECT EXISTS(
+                SELECT 1 FROM t_saved_searches
+                WHERE user_id = $1 AND search_name = $2
+                  AND ($3::uuid IS NULL OR saved_search_id <> $3)
+            )
+        "#;
+
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(search_name.to_string()),
+            SqlParam::OptionString(exclude_id.map(|id| id.to_string())),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, bool>(sql)
+            .bind(user_id)
+            .bind(search_name)
+            .bind(exclude_id)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(exists) => Ok(exists),
+            Err(e) => {
+                error!("保存検索名の重複確認に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保存検索名の重複確認に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 保存検索を登録
+    pub async fn insert_saved_search(&self, user_id: &str, values: &SavedSearchValues) -> Result<Uuid, DatabaseError> {
+        let sql = r#"
+            INSERT INTO t_saved_searches (
+                saved_search_id, user_id, search_name, condition_json,
+                notify_email, notify_in_app, quiet_period_minutes, is_active,
+                created_datetime, updated_datetime
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+        "#;
+
+        let saved_search_id = Uuid::new_v4();
+        let params = vec![
+            SqlParam::String(saved_search_id.to_string()),
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(values.search_name.clone()),
+            SqlParam::String(values.condition_json.to_string()),
+            SqlParam::Boolean(values.notify_email),
+            SqlParam::Boolean(values.notify_in_app),
+            SqlParam::I32(values.quiet_period_minutes),
+            SqlParam::Boolean(values.is_active),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql)
+            .bind(saved_search_id)
+            .bind(user_id)
+            .bind(&values.search_name)
+            .bind(&values.condition_json)
+            .bind(values.notify_email)
+            .bind(values.notify_in_app)
+            .bind(values.quiet_period_minutes)
+            .bind(values.is_active)
+            .execute(self.db.pool())
+            .await
+        {
+            Ok(_) => {
+                info!("保存検索を登録しました: user_id={}, saved_search_id={}", user_id, saved_search_id);
+                Ok(saved_search_id)
+            }
+            Err(e) => {
+                error!("保存検索の登録に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保存検索の登録に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 保存検索を更新
+    ///
+    /// reset_matches が true の場合は通知済みの駐車場をリセットする（検索条件の変更時）
+    pub async fn update_saved_search(
+        &self,
+        user_id: &str,
+        saved_search_id: Uuid,
+        values: &SavedSearchValues,
+        reset_matches: bool,
+    ) -> Result<bool, DatabaseError> {
+        let mut tx = self.begin_transaction().await?;
+
+        let sql = r#"
+            UPDATE t_saved_searches
+            SET search_name = $3,
+                condition_json = $4,
+                notify_email = $5,
+                notify_in_app = $6,
+                quiet_period_minutes = $7,
+                is_active = $8,
+                updated_datetime = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND saved_search_id = $2
+        "#;
+
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(saved_search_id.to_string()),
+            SqlParam::String(values.search_name.clone()),
+            SqlParam::String(values.condition_json.to_string()),
+            SqlParam::Boolean(values.notify_email),
+            SqlParam::Boolean(values.notify_in_app),
+            SqlParam::I32(values.quiet_period_minutes),
+            SqlParam::Boolean(values.is_active),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql)
+            .bind(user_id)
+            .bind(saved_search_id)
+            .bind(&values.search_name)
+            .bind(&values.condition_json)
+            .bind(values.notify_email)
+            .bind(values.notify_in_app)
+            .bind(values.quiet_period_minutes)
+            .bind(values.is_active)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(result) if result.rows_affected() == 0 => return Ok(false),
+            Ok(_) => {}
+            Err(e) => {
+                error!("保存検索の更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("保存検索の更新に失敗: {}", e)));
+            }
+        }
+
+        if reset_matches {
+            let reset_sql = "DELETE FROM t_saved_search_matches WHERE saved_search_id = $1";
+            let reset_params = vec![SqlParam::String(saved_search_id.to_string())];
+            log_sql_query(reset_sql, &reset_params, None);
+
+            if let Err(e) = sqlx::query(reset_sql).bind(saved_search_id).execute(&mut *tx).await {
+                error!("通知済み駐車場のリセットに失敗: {}", e);
+                log_sql_error(reset_sql, &reset_params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("通知済み駐車場のリセットに失敗: {}", e)));
+            }
+        }
+
+        tx.commit().await.map_err(|e| {
+            error!("トランザクションコミットに失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクションコミットに失敗: {}", e))
+        })?;
+        Ok(true)
+    }
+
+    /// 保存検索を削除（通知済み駐車場はカスケード削除）
+    pub async fn delete_saved_search(&self, user_id: &str, saved_search_id: Uuid) -> Result<bool, DatabaseError> {
+        let sql = "DELETE FROM t_saved_searches WHERE user_id = $1 AND saved_search_id = $2";
+
+        let params = vec![SqlParam::String(user_id.to_string()), SqlParam::String(saved_search_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(user_id).bind(saved_search_id).execute(self.db.pool()).await {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(e) => {
+                error!("保存検索の削除に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保存検索の削除に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 未処理の再評価イベントを古い順に取得し、処理中にする
+    ///
+    /// 複数のインスタンスで同じイベントを処理しないよう、他で処理中のイベントは取得しない。
+    /// 処理中のまま`claim_timeout_secs`秒を過ぎたイベント（処理中に停止した場合）は再取得する
+    pub async fn claim_pending_events(
+        &self,
+        limit: i64,
+        claim_timeout_secs: i64,
+    ) -> Result<Vec<SavedSearchEventRow>, DatabaseError> {
+        let sql = r#"
+            UPDATE t_saved_search_events e
+            SET claimed_datetime = CURRENT_TIMESTAMP
+            WHERE e.event_id IN (
+                SELECT event_id
+                FROM t_saved_search_events
+                WHERE processed_datetime IS NULL
+                  AND (claimed_datetime IS NULL
+                       OR claimed_datetime < CURRENT_TIMESTAMP - make_interval(secs => $2::FLOAT8))
+                ORDER BY event_id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING e.event_id, e.event_type, e.parking_lot_id
+        "#;
+
+        let params = vec![SqlParam::Integer(limit), SqlParam::Integer(claim_timeout_secs)];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, SavedSearchEventRow>(sql)
+            .bind(limit)
+            .bind(claim_timeout_secs as f64)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(mut rows) => {
+                rows.sort_by_key(|row| row.event_id);
+                Ok(rows)
+            }
+            Err(e) => {
+                error!("保存検索イベントの取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保存検索イベントの取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 再評価イベントを処理済みにする
+    pub async fn mark_events_processed(&self, event_ids: &[i64]) -> Result<(), DatabaseError> {
+        if event_ids.is_empty() {
+            return Ok(());
+        }
+
+        let sql = "UPDATE t_saved_search_events SET processed_datetime = CURRENT_TIMESTAMP WHERE event_id = ANY($1)";
+
+        let params = vec![SqlParam::String(
+            event_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(","),
+        )];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(event_ids).execute(self.db.pool()).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("保存検索イベントの更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保存検索イベントの更新に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 通知休止期間中の保存検索について、休止明けに評価する駐車場を保留する
+    pub async fn hold_pending_lots(
+        &self,
+        saved_search_ids: &[Uuid],
+        parking_lot_ids: &[String],
+    ) -> Result<(), DatabaseError> {
+        if saved_search_ids.is_empty() || parking_lot_ids.is_empty() {
+            return Ok(());
+        }
+
+        let sql = r#"
+            INSERT INTO t_saved_search_pending_lots (saved_search_id, parking_lot_id)
+            SELECT s.saved_search_id, l.parking_lot_id
+            FROM UNNEST($1::uuid[]) AS s(saved_search_id)
+            CROSS JOIN UNNEST($2::varchar[]) AS l(parking_lot_id)
+            ON CONFLICT (saved_search_id, parking_lot_id) DO NOTHING
+        "#;
+
+        let params = vec![
+            SqlParam::String(saved_search_ids.iter().map(Uuid::to_string).collect::<Vec<_>>().join(",")),
+            SqlParam::String(parking_lot_ids.join(",")),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql)
+            .bind(saved_search_ids)
+            .bind(parking_lot_ids)
+            .execute(self.db.pool())
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("保留する駐車場の記録に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保留する駐車場の記録に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 保留中の駐車場があるか
+    pub async fn has_pending_lots(&self) -> Result<bool, DatabaseError> {
+        let sql = "SELECT EXISTS (SELECT 1 FROM t_saved_search_pending_lots)";
+
+        let params: Vec<SqlParam> = vec![];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, bool>(sql).fetch_one(self.db.pool()).await {
+            Ok(exists) => Ok(exists),
+            Err(e) => {
+                error!("保留中の駐車場の確認に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保留中の駐車場の確認に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 保存検索の保留中の駐車場を取り出す（取り出した駐車場は保留から削除する）
+    pub async fn take_pending_lots(&self, saved_search_ids: &[Uuid]) -> Result<Vec<(Uuid, String)>, DatabaseError> {
+        if saved_search_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = r#"
+            DELETE FROM t_saved_search_pending_lots
+            WHERE saved_search_id = ANY($1)
+            RETURNING saved_search_id, parking_lot_id
+        "#;
+
+        let params = vec![SqlParam::String(
+            saved_search_ids.iter().map(Uuid::to_string).collect::<Vec<_>>().join(","),
+        )];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, (Uuid, String)>(sql)
+            .bind(saved_search_ids)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("保留中の駐車場の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("保留中の駐車場の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 指定の駐車場のうち、保存検索で通知済みのもの
+    pub async fn find_notified_lot_ids(
+        &self,
+        saved_search_id: Uuid,
+        parking_lot_ids: &[String],
+    ) -> Result<Vec<String>, DatabaseError> {
+        let sql = r#"
+            SELECT parking_lot_id FROM t_saved_search_matches
+            WHERE saved_search_id = $1 AND parking_lot_id = ANY($2)
+        "#;
+
+        let params = vec![
+            SqlParam::String(saved_search_id.to_string()),
+            SqlParam::String(parking_lot_ids.join(",")),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, String>(sql)
+            .bind(saved_search_id)
+            .bind(parking_lot_ids)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(ids) => Ok(ids),
+            Err(e) => {
+                error!("通知済み駐車場の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("通知済み駐車場の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 指定の駐車場のうち、利用期間に重なる有効な予約（予約中・予約済み）があるもの
+    pub async fn find_reserved_lot_ids(
+        &self,
+        parking_lot_ids: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let sql = r#"
+            SELECT DISTINCT parking_lot_id FROM t_reservations
+            WHERE parking_lot_id = ANY($1)
+              AND status IN ('1', '2')
+              AND start_datetime < $3
+              AND end_datetime > $2
+        "#;
+
+        let params = vec![
+            SqlParam::String(parking_lot_ids.join(",")),
+            SqlParam::DateTime(start),
+            SqlParam::DateTime(end),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, String>(sql)
+            .bind(parking_lot_ids)
+            .bind(start)
+            .bind(end)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(ids) => Ok(ids),
+            Err(e) => {
+                error!("予約済み駐車場の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("予約済み駐車場の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 通知本文用の駐車場名・住所
+    pub async fn find_match_summaries(&self, parking_lot_ids: &[String]) -> Result<Vec<SavedSearchMatch>, DatabaseError> {
+        let sql = r#"
+            SELECT
+                parking_lot_id,
+                parking_lot_name,
+                CONCAT(prefecture, city, address_detail) AS address
+            FROM t_parking_lots
+            WHERE parking_lot_id = ANY($1)
+            ORDER BY created_datetime DESC
+        "#;
+
+        let params = vec![SqlParam::String(parking_lot_ids.join(","))];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_as::<_, (String, String, String)>(sql)
+            .bind(parking_lot_ids)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows
+                .into_iter()
+                .map(|(parking_lot_id, parking_lot_name, address)| SavedSearchMatch {
+                    parking_lot_id,
+                    parking_lot_name,
+                    address,
+                })
+                .collect()),
+            Err(e) => {
+                error!("駐車場情報の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("駐車場情報の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// サービスメールの受信を許可している利用者のメールアドレス
+    pub async fn find_notification_email(&self, user_id: &str) -> Result<Option<String>, DatabaseError> {
+        let sql = r#"
+            SELECT l.email
+            FROM m_users u
+            INNER JOIN m_login l ON l.login_id = u.login_id
+            WHERE u.user_id = $1 AND u.service_email_opt = '1' AND l.email IS NOT NULL AND l.email <> ''
+        "#;
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, String>(sql)
+            .bind(user_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(email) => Ok(email),
+            Err(e) => {
+                error!("通知先メールアドレスの取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("通知先メールアドレスの取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 通知済みの駐車場と最終通知日時を記録（呼び出し元のトランザクション内で実行）
+    pub async fn record_matches(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        saved_search_id: Uuid,
+        parking_lot_ids: &[String],
+    ) -> Result<(), DatabaseError> {
+        let insert_sql = r#"
+            INSERT INTO t_saved_search_matches (saved_search_id, parking_lot_id, notified_datetime)
+            SELECT $1, UNNEST($2::varchar[]), CURRENT_TIMESTAMP
+            ON CONFLICT (saved_search_id, parking_lot_id) DO NOTHING
+        "#;
+
+        let insert_params = vec![
+            SqlParam::String(saved_search_id.to_string()),
+            SqlParam::String(parking_lot_ids.join(",")),
+        ];
+        log_sql_query(insert_sql, &insert_params, None);
+
+        if let Err(e) = sqlx::query(insert_sql)
+            .bind(saved_search_id)
+            .bind(parking_lot_ids)
+            .execute(&mut **tx)
+            .await
+        {
+            error!("通知済み駐車場の記録に失敗: {}", e);
+            log_sql_error(insert_sql, &insert_params, &e.to_string());
+            return Err(DatabaseError::QueryError(format!("通知済み駐車場の記録に失敗: {}", e)));
+        }
+
+        let update_sql = "UPDATE t_saved_searches SET last_notified_datetime = CURRENT_TIMESTAMP WHERE saved_search_id = $1";
+        let update_params = vec![SqlParam::String(saved_search_id.to_string())];
+        log_sql_query(update_sql, &update_params, None);
+
+        if let Err(e) = sqlx::query(update_sql).bind(saved_search_id).execute(&mut **tx).await {
+            error!("最終通知日時の更新に失敗: {}", e);
+            log_sql_error(update_sql, &update_params, &e.to_string());
+            return Err(DatabaseError::QueryError(format!("最終通知日時の更新に失敗: {}", e)));
+        }
+        Ok(())
+    }
+}
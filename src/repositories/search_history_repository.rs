@@ -0,0 +1,309 @@
+use serde_json::Value;
+use sqlx::{Postgres, Transaction};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::parking_search_history_model::{ParkingSearchHistoryResponse, SearchHistoryCriteria};
+use crate::utils::cursor::{bind_cursor, keyset_sql, CursorKey, PageCursor};
+
+/// 検索履歴一覧の並べ替えキー（更新日時の降順、同順位は検索IDの昇順）
+const SEARCH_HISTORY_SORT_COLUMNS: [(&str, bool); 2] = [
+    ("COALESCE(h.updated_datetime, 'epoch'::TIMESTAMPTZ)", true),
+    ("h.search_id", false),
+];
+
+/// 検索履歴一覧の行のカーソルキー
+pub fn search_history_cursor_keys(row: &ParkingSearchHistoryResponse) -> Vec<CursorKey> {
+    vec![CursorKey::Time(row.updated_datetime), CursorKey::Text(row.search_id.clone())]
+}
+
+/// 駐車場検索履歴のリポジトリ
+#[derive(Debug, Clone)]
+pub struct SearchHistoryRepository {
+    db: PostgresDatabase,
+}
+
+impl SearchHistoryRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    async fn begin_transaction(&self) -> Result<Transaction<'static, Postgres>, DatabaseError> {
+        self.db.pool().begin().await.map_err(|e| {
+            error!("トランザクション開始に失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクション開始に失敗: {}", e))
+        })
+    }
+
+    /// 検索条件を保存（同一条件が既にある場合は検索回数と日時を更新）
+    ///
+    /// 保存後、上限件数を超えた古い履歴を削除する
+    pub async fn upsert_search_history(
+        &self,
+        user_id: &str,
+        criteria: &SearchHistoryCriteria,
+        max_entries: i64,
+    ) -> Result<String, DatabaseError> {
+        let mut tx = self.begin_transaction().await?;
+
+        let upsert_sql = r#"
+            INSERT INTO t_parking_search_history (
+                search_id,
+                user_id,
+                condition_keyword_free,
+                condition_use_date_start,
+                condition_use_date_end,
+                condition_vehicle_type_id,
+                condition_json,
+                condition_hash,
+                search_count,
+                created_datetime,
+                updated_datetime
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 1, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+            ON CONFLICT (user_id, condition_hash) DO UPDATE
+            SET search_count = t_parking_search_history.search_count + 1,
+                updated_datetime = CURRENT_TIMESTAMP
+            RETURNING search_id
+        "#;
+
+        let condition_json = serde_json::to_value(criteria)
+            .map_err(|e| DatabaseError::QueryError(format!("検索条件の変換に失敗: {}", e)))?;
+        let condition_hash = criteria.condition_hash();
+        let keyword = criteria.keyword();
+        let use_date_start = SearchHistoryCriteria::format_use_date(criteria.usage_start_datetime);
+        let use_date_end = SearchHistoryCriteria::format_use_date(criteria.usage_end_datetime);
+        let search_id = Uuid::new_v4().to_string();
+
+        let upsert_params = vec![
+            SqlParam::String(search_id.clone()),
+            SqlParam::String(user_id.to_string()),
+            SqlParam::OptionString(keyword.clone()),
+            SqlParam::OptionString(use_date_start.clone()),
+            SqlParam::OptionString(use_date_end.clone()),
+            SqlParam::OptionString(criteria.vehicle_type_id.clone()),
+            SqlParam::String(condition_json.to_string()),
+            SqlParam::String(condition_hash.clone()),
+        ];
+        log_sql_query(upsert_sql, &upsert_params, None);
+
+        let search_id = match sqlx::query_scalar::<_, String>(upsert_sql)
+            .bind(&search_id)
+            .bind(user_id)
+            .bind(&keyword)
+            .bind(&use_date_start)
+            .bind(&use_date_end)
+            .bind(&criteria.vehicle_type_id)
+            .bind(&condition_json)
+            .bind(&condition_hash)
+            .fetch_one(&mut *tx)
+            .await
+        {
+            Ok(search_id) => search_id,
+            Err(e) => {
+                error!("検索履歴の保存に失敗: {}", e);
+                log_sql_error(upsert_sql, &upsert_params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("検索履歴の保存に失敗: {}", e)));
+            }
+        };
+
+        let trim_sql = r#"
+            DELETE FROM t_parking_search_history
+            WHERE user_id = $1
+              AND search_id IN (
+                  SELECT search_id FROM t_parking_search_history
+                  WHERE user_id = $1
+                  ORDER BY updated_datetime DESC
+                  OFFSET $2
+              )
+        "#;
+
+        let trim_params = vec![SqlParam::String(user_id.to_string()), SqlParam::Integer(max_entries)];
+        log_sql_query(trim_sql, &trim_params, None);
+
+        if let Err(e) = sqlx::query(trim_sql).bind(user_id).bind(max_entries).execute(&mut *tx).await {
+            error!("古い検索履歴の削除に失敗: {}", e);
+            log_sql_error(trim_sql, &trim_params, &e.to_string());
+            return Err(DatabaseError::QueryError(format!("古い検索履歴の削除に失敗: {}", e)));
+        }
+
+        tx.commit().await.map_err(|e| {
+            error!("トランザクションコミットに失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクションコミットに失敗: {}", e))
+        })?;
+        Ok(search_id)
+    }
+
+    /// 検索履歴の検索条件を取得（条件が保存されていない旧形式の履歴はNone）
+    pub async fn find_search_criteria(&self, user_id: &str, search_id: &str) -> Result<Option<Option<Value>>, DatabaseError> {
+        let sql = r#"
+            SELECT condition_json
+            FROM t_parking_search_history
+            WHERE user_id = $1 AND search_id = $2
+        "#;
+
+        let params = vec![SqlParam::String(user_id.to_string()), SqlParam::String(search_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, Option<Value>>(sql)
+            .bind(user_id)
+            .bind(search_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("検索履歴の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("検索履歴の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 検索履歴一覧（新しい順。afterを指定した場合はそのカーソルより後ろから取得）
+    pub async fn list_search_history(
+        &self,
+        user_id: &str,
+        after: Option<&PageCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ParkingSearchHistoryResponse>, DatabaseError> {
+        let sql = format!(
+            r#"
+            SELECT
+                h.search_id,
+                h.user_id,
+                COALESCE(h.parking_lot_id, '') AS parking_lot_id,
+                COALESCE(pl.parking_lot_name, '') AS parking_lot_name,
+                COALESCE(h.condition_keyword_free, '-') AS condition_keyword_free,
+                COALESCE(h.condition_use_date_start, '-') AS condition_use_date_start,
+                COALESCE(h.condition_use_date_end, '-') AS condition_use_date_end,
+                COALESCE(h.condition_vehicle_type_id, '-') AS condition_vehicle_type_id,
+                COALESCE(vt.vehicle_type, '-') AS vehicle_type,
+                COALESCE(h.condition_rental_type_id, '') AS condition_rental_type_id,
+                COALESCE(rt.rental_type, '-') AS rental_type,
+                COALESCE(rt.rental_value, '-') AS rental_value,
+                COALESCE(h.search_count, 1) AS search_count,
+                COALESCE(h.created_datetime, 'epoch'::TIMESTAMPTZ) AS created_datetime,
+                COALESCE(h.updated_datetime, 'epoch'::TIMESTAMPTZ) AS updated_datetime
+            FROM t_parking_search_history h
+            LEFT JOIN t_parking_lots pl ON pl.parking_lot_id = h.parking_lot_id
+            LEFT JOIN m_parking_vehicle_types vt ON vt.vehicle_type_id = h.condition_vehicle_type_id
+            LEFT JOIN t_parking_rental_types rt ON rt.rental_type_id = h.condition_rental_type_id
+            WHERE h.user_id = $1
+              {}
+            ORDER BY {}
+            LIMIT $2 OFFSET $3
+        "#,
+            after.map_or(String::new(), |_| format!("AND {}", keyset_sql(&SEARCH_HISTORY_SORT_COLUMNS, 4))),
+            SEARCH_HISTORY_SORT_COLUMNS
+                .iter()
+                .map(|(column, desc)| format!("{} {}", column, if *desc { "DESC" } else { "ASC" }))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let mut params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::Integer(limit),
+            SqlParam::Integer(offset),
+        ];
+        params.extend(after.map(PageCursor::sql_params).unwrap_or_default());
+        log_sql_query(&sql, &params, None);
+
+        let query = sqlx::query_as::<_, ParkingSearchHistoryResponse>(&sql)
+            .bind(user_id)
+            .bind(limit)
+            .bind(offset);
+        let query = match after {
+            Some(cursor) => bind_cursor(query, cursor),
+            None => query,
+        };
+
+        match query.fetch_all(self.db.pool()).await {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("検索履歴一覧の取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("検索履歴一覧の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 検索履歴の件数
+    pub async fn count_search_history(&self, user_id: &str) -> Result<i64, DatabaseError> {
+        let sql = "SELECT COUNT(*) FROM t_parking_search_history WHERE user_id = $1";
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, i64>(sql).bind(user_id).fetch_one(self.db.pool()).await {
+            Ok(count) => Ok(count),
+            Err(e) => {
+                error!("検索履歴の件数取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("検索履歴の件数取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 検索履歴の検索日時のみ更新（履歴からの再検索用。検索回数は変えない）
+    pub async fn touch_search_history(&self, user_id: &str, search_id: &str) -> Result<bool, DatabaseError> {
+        let sql = r#"
+            UPDATE t_parking_search_history
+            SET updated_datetime = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND search_id = $2
+        "#;
+
+        let params = vec![SqlParam::String(user_id.to_string()), SqlParam::String(search_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(user_id).bind(search_id).execute(self.db.pool()).await {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(e) => {
+                error!("検索履歴の検索日時の更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("検索履歴の検索日時の更新に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 検索履歴を1件削除
+    pub async fn delete_search_history(&self, user_id: &str, search_id: &str) -> Result<bool, DatabaseError> {
+        let sql = "DELETE FROM t_parking_search_history WHERE user_id = $1 AND search_id = $2";
+
+        let params = vec![SqlParam::String(user_id.to_string()), SqlParam::String(search_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(user_id).bind(search_id).execute(self.db.pool()).await {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(e) => {
+                error!("検索履歴の削除に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("検索履歴の削除に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 検索履歴を全件削除
+    pub async fn clear_search_history(&self, user_id: &str) -> Result<u64, DatabaseError> {
+        let sql = "DELETE FROM t_parking_search_history WHERE user_id = $1";
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(user_id).execute(self.db.pool()).await {
+            Ok(result) => {
+                info!("検索履歴を削除しました: user_id={}, 件数={}", user_id, result.rows_affected());
+                Ok(result.rows_affected())
+            }
+            Err(e) => {
+                error!("検索履歴の全件削除に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("検索履歴の全件削除に失敗: {}", e)))
+            }
+        }
+    }
+}
@@ -3,8 +3,65 @@ use crate::models::{
     parking_use_history_model::{ParkingUseHistoryRequest, ParkingUseHistoryDetailRequest, ParkingUseHistoryResponse, ParkingUseHistoryDetailResponse},
     parking_feature_model::{ParkingFeatureRequest, ParkingFeatureResponse},
 };
+use crate::utils::cursor::{bind_cursor, keyset_sql, CursorKey, PageCursor};
 use sqlx::{postgres::PgPool};
 use tracing::{debug, error};
+use uuid::Uuid;
+
+/// 利用履歴一覧（$1: ユーザーID、利用完了の予約のみ）
+const USE_HISTORY_SQL: &str = "
+            select
+                t_parking_status.reservation_id
+                , t_parking_lots.parking_lot_name
+                , t_reservation_details.area
+                , COALESCE( 
+                    to_char( 
+                        t_parking_status.entry_datetime AT TIME ZONE 'Asia/Tokyo'
+                        , 'yyyy/MM/dd HH24:MI'
+                    ) 
+                    , '-'
+                ) AS entry_datetime
+                , COALESCE( 
+                    to_char( 
+                        t_parking_status.exit_datetime AT TIME ZONE 'Asia/Tokyo'
+                        , 'yyyy/MM/dd HH24:MI'
+                    ) 
+                    , '-'
+                ) AS exit_datetime
+                , to_char( 
+                    t_reservations.start_datetime AT TIME ZONE 'Asia/Tokyo'
+                    , 'yyyy/MM/dd HH24:MI'
+                ) as start_datetime
+                , to_char( 
+                    t_reservations.end_datetime AT TIME ZONE 'Asia/Tokyo'
+                    , 'yyyy/MM/dd HH24:MI'
+                ) as end_datetime
+                , to_char(t_reservation_details.amount,  'FM99,999,999') as amount
+                , t_reservations.created_datetime 
+                , t_reservations.updated_datetime 
+            from
+                t_reservations 
+                inner join t_parking_lots 
+                    on t_parking_lots.parking_lot_id = t_reservations.parking_lot_id 
+                inner join t_parking_status 
+                    on t_parking_status.reservation_id = t_reservations.reservation_id 
+                inner join t_reservation_details
+                on t_reservation_details.reservation_id = t_reservations.reservation_id
+            where
+                t_reservations.user_id = $1 
+                and t_reservations.status = '4'
+";
+
+/// 利用履歴一覧の並べ替えキー（更新日時の降順、同順位は予約IDの昇順）
+const USE_HISTORY_SORT_COLUMNS: [(&str, bool); 2] = [
+    ("t_reservations.updated_datetime", true),
+    ("t_reservations.reservation_id", false),
+];
+
+/// 利用履歴一覧の行のカーソルキー
+pub fn use_history_cursor_keys(row: &ParkingUseHistoryResponse) -> Vec<CursorKey> {
+    vec![CursorKey::Time(row.updated_datetime), CursorKey::Text(row.reservation_id.clone())]
+}
 
 pub struct UseHistoryRepository {
     pool: PgPool,
@@ -84,52 +141,12 @@ impl UseHistoryRepository {
     pub async fn get_parking_use_history(&self, req: &ParkingUseHistoryRequest) -> Result<Vec<ParkingUseHistoryResponse>, ApiError> {
         debug!("Fetching t_reservations by user_id: {} ", req.user_id);
 
-        let sql = "
-            select
-                t_parking_status.reservation_id
-                , t_parking_lots.parking_lot_name
-                , t_reservation_details.area
-                , COALESCE( 
-                    to_char( 
-                        t_parking_status.entry_datetime AT TIME ZONE 'Asia/Tokyo'
-                        , 'yyyy/MM/dd HH24:MI'
-                    ) 
-                    , '-'
-                ) AS entry_datetime
-                , COALESCE( 
-                    to_char( 
-                        t_parking_status.exit_datetime AT TIME ZONE 'Asia/Tokyo'
-                        , 'yyyy/MM/dd HH24:MI'
-                    ) 
-                    , '-'
-                ) AS exit_datetime
-                , to_char( 
-                    t_reservations.start_datetime AT TIME ZONE 'Asia/Tokyo'
-                    , 'yyyy/MM/dd HH24:MI'
-                ) as start_datetime
-                , to_char( 
-                    t_reservations.end_datetime AT TIME ZONE 'Asia/Tokyo'
-                    , 'yyyy/MM/dd HH24:MI'
-                ) as end_datetime
-                , to_char(t_reservation_details.amount,  'FM99,999,999') as amount
-                , t_reservations.created_datetime 
-                , t_reservations.updated_datetime 
-            from
-                t_reservations 
-                inner join t_parking_lots 
-                    on t_parking_lots.parking_lot_id = t_reservations.parking_lot_id 
-                inner join t_parking_status 
-                    on t_parking_status.reservation_id = t_reservations.reservation_id 
-                inner join t_reservation_details
-                on t_reservation_details.reservation_id = t_reservations.reservation_id
-            where
-                t_reservations.user_id = $1 
-                and t_reservations.status = '4' 
+        let sql = format!("{}
             order by
                 t_reservations.updated_datetime desc
-        ";
+        ", USE_HISTORY_SQL);
 
-        let res: Vec<ParkingUseHistoryResponse> = sqlx::query_as::<_, ParkingUseHistoryResponse>(sql)
+        let res: Vec<ParkingUseHistoryResponse> = sqlx::query_as::<_, ParkingUseHistoryResponse>(&sql)
             .bind(&req.user_id)
             .fetch_all(&self.pool)
             .await
@@ -146,6 +163,79 @@ impl UseHistoryRepository {
         Ok(res)
     }
 
+    /// 駐車場利用履歴の取得（新しい順。afterを指定した場合はそのカーソルより後ろから取得）
+    pub async fn list_parking_use_history(
+        &self,
+        user_id: &str,
+        after: Option<&PageCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ParkingUseHistoryResponse>, ApiError> {
+        debug!("Fetching t_reservations page by user_id: {} limit: {} offset: {}", user_id, limit, offset);
+
+        let sql = format!("{}
+                {}
+            order by
+                {}
+            limit $2 offset $3
+        ",
+            USE_HISTORY_SQL,
+            after.map_or(String::new(), |_| format!("and {}", keyset_sql(&USE_HISTORY_SORT_COLUMNS, 4))),
+            USE_HISTORY_SORT_COLUMNS
+                .iter()
+                .map(|(column, desc)| format!("{} {}", column, if *desc { "desc" } else { "asc" }))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let query = sqlx::query_as::<_, ParkingUseHistoryResponse>(&sql)
+            .bind(user_id)
+            .bind(limit)
+            .bind(offset);
+        let query = match after {
+            Some(cursor) => bind_cursor(query, cursor),
+            None => query,
+        };
+
+        query.fetch_all(&self.pool).await.map_err(|e| {
+            error!("Database error: {:?}", e);
+            ApiError::DatabaseError(format!("駐車場利用履歴の取得に失敗しました: {}", e))
+        })
+    }
+
+    /// ログインIDから利用者のユーザーIDを取得（オーナーの場合はNone）
+    pub async fn find_user_id(&self, login_id: Uuid) -> Result<Option<String>, ApiError> {
+        let sql = "
+            select u.user_id
+            from m_users u
+            inner join m_login l on l.login_id = u.login_id
+            where u.login_id = $1 and l.is_user_owner = '0'
+        ";
+
+        sqlx::query_scalar::<_, String>(sql)
+            .bind(login_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("Database error: {:?}", e);
+                ApiError::DatabaseError(format!("ユーザーIDの取得に失敗しました: {}", e))
+            })
+    }
+
+    /// 駐車場利用履歴の件数
+    pub async fn count_parking_use_history(&self, user_id: &str) -> Result<i64, ApiError> {
+        let sql = format!("select count(*) from ({}) use_history", USE_HISTORY_SQL);
+
+        sqlx::query_scalar::<_, i64>(&sql)
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("Database error: {:?}", e);
+                ApiError::DatabaseError(format!("駐車場利用履歴の件数取得に失敗しました: {}", e))
+            })
+    }
+
     /// 駐車場特徴の取得
     pub async fn get_parking_features(&self, req: &ParkingFeatureRequest) -> Result<Vec<ParkingFeatureResponse>, ApiError> {
         debug!("Fetching m_parking_features by parking_lot_id: {} ", req.parking_lot_id);
@@ -123,6 +123,7 @@ impl UserHomeRepository {
                     t_parking_rental_types.rental_value
                     , '-'
                 ) as rental_value 
+                , COALESCE(t_parking_search_history.search_count, 1) as search_count
                 , t_parking_search_history.created_datetime
                 , t_parking_search_history.updated_datetime
             from
@@ -136,7 +137,7 @@ impl UserHomeRepository {
             where
                 t_parking_search_history.user_id = $1 
             order by
-                t_parking_search_history.created_datetime desc 
+                t_parking_search_history.updated_datetime desc 
             limit
                 5
         ";
@@ -200,93 +201,4 @@ impl UserHomeRepository {
 
         Ok(res)
     }
-
-    /// 入庫の更新
-    pub async fn update_parking_status_checkin(
-        &self,
-        status_id: &String,
-    ) -> Result<(), ApiError> {
-        debug!("update_parking_status_checkin status_id: {}", status_id);
-
-        let sql = "
-            UPDATE t_parking_status 
-            SET
-                entry_datetime = now()
-                , entry_status = '1'
-                , updated_datetime = now() 
-            WHERE
-                status_id = $1
-        ";
-
-        sqlx::query(sql)
-            .bind(status_id)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| {
-                error!("Database error: {:?}", e);
-                ApiError::DatabaseError(format!("入庫の更新に失敗しました: {}", e))
-            })?;
-
-        Ok(())
-    }
-
-     /// 出庫の更新
-    pub async fn update_parking_status_checkout(
-        &self,
-        status_id: &String,
-    ) -> Result<(), ApiError> {
-        debug!("update_parking_status_checkout status_id: {}", status_id);
-
-        let sql = "
-            UPDATE t_parking_status 
-            SET
-                exit_datetime = now()
-                , exit_status = '1'
-                , updated_datetime = now() 
-            WHERE
-                status_id = $1
-        ";
-
-        sqlx::query(sql)
-            .bind(status_id)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| {
-                error!("Database error: {:?}", e);
-                ApiError::DatabaseError(format!("出庫の更新に失敗しました: {}", e))
-            })?;
-
-        Ok(())
-    }
-
-     /// 予約ステータスの更新
-    pub async fn update_reservation_status(
-        &self,
-        reservation_id: &String,
-    ) -> Result<(), ApiError> {
-        debug!("update_parking_status_checkout status_id: {}", reservation_id);
-
-        let sql = "
-            UPDATE t_reservations 
-            SET
-                status = '4'
-                , updated_datetime = now() 
-            WHERE
-                reservation_id = $1
-        ";
-
-        sqlx::query(sql)
-            .bind(reservation_id)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| {
-                error!("Database error: {:?}", e);
-                ApiError::DatabaseError(format!("予約ステータスの更新に失敗しました: {}", e))
-            })?;
-
-        Ok(())
-    }
-
-
-    
 }
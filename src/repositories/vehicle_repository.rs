@@ -0,0 +1,472 @@
+use sqlx::{Postgres, Transaction};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::config::logging::{log_sql_error, log_sql_query, SqlParam};
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::models::vehicle_model::{UserVehicleRequest, UserVehicleRow};
+
+/// 登録車両の取得列（車種名はマスターから取得）
+const VEHICLE_SELECT: &str = r#"
+    SELECT
+        v.vehicle_id,
+        v.user_id,
+        v.vehicle_name,
+        v.vehicle_type_id,
+        vt.vehicle_type AS vehicle_type_name,
+        v.license_plate,
+        v.length_cm,
+        v.width_cm,
+        v.height_cm,
+        v.weight_kg,
+        v.is_default,
+        v.created_datetime,
+        v.updated_datetime
+    FROM t_user_vehicles v
+    LEFT JOIN m_parking_vehicle_types vt ON vt.vehicle_type_id = v.vehicle_type_id
+"#;
+
+/// 登録車両のリポジトリ
+#[derive(Debug, Clone)]
+pub struct VehicleRepository {
+    db: PostgresDatabase,
+}
+
+impl VehicleRepository {
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { db }
+    }
+
+    async fn begin_transaction(&self) -> Result<Transaction<'static, Postgres>, DatabaseError> {
+        self.db.pool().begin().await.map_err(|e| {
+            error!("トランザクション開始に失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクション開始に失敗: {}", e))
+        })
+    }
+
+    async fn commit(tx: Transaction<'static, Postgres>) -> Result<(), DatabaseError> {
+        tx.commit().await.map_err(|e| {
+            error!("トランザクションコミットに失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクションコミットに失敗: {}", e))
+        })
+    }
+
+    /// ログインIDから利用者のユーザーIDを取得（オーナーの場合はNone）
+    pub async fn find_user_id(&self, login_id: Uuid) -> Result<Option<String>, DatabaseError> {
+        let sql = r#"
+            SELECT u.user_id
+            FROM m_users u
+            INNER JOIN m_login l ON l.login_id = u.login_id
+            WHERE u.login_id = $1 AND l.is_user_owner = '0'
+        "#;
+
+        let params = vec![SqlParam::String(login_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, String>(sql)
+            .bind(login_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(user_id) => Ok(user_id),
+            Err(e) => {
+                error!("ユーザーID取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("ユーザーID取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 登録車両一覧（デフォルト車両を先頭に登録順）
+    pub async fn list_vehicles(&self, user_id: &str) -> Result<Vec<UserVehicleRow>, DatabaseError> {
+        let sql = format!(
+            "{} WHERE v.user_id = $1 ORDER BY v.is_default DESC, v.created_datetime",
+            VEHICLE_SELECT
+        );
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, UserVehicleRow>(&sql)
+            .bind(user_id)
+            .fetch_all(self.db.pool())
+            .await
+        {
+            Ok(rows) => Ok(rows),
+            Err(e) => {
+                error!("登録車両一覧の取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("登録車両一覧の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 登録車両を取得（本人の車両のみ）
+    pub async fn find_vehicle(
+        &self,
+        user_id: &str,
+        vehicle_id: Uuid,
+    ) -> Result<Option<UserVehicleRow>, DatabaseError> {
+        let sql = format!("{} WHERE v.user_id = $1 AND v.vehicle_id = $2", VEHICLE_SELECT);
+
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(vehicle_id.to_string()),
+        ];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, UserVehicleRow>(&sql)
+            .bind(user_id)
+            .bind(vehicle_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("登録車両の取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("登録車両の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// デフォルト車両を取得
+    pub async fn find_default_vehicle(&self, user_id: &str) -> Result<Option<UserVehicleRow>, DatabaseError> {
+        let sql = format!("{} WHERE v.user_id = $1 AND v.is_default", VEHICLE_SELECT);
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(&sql, &params, None);
+
+        match sqlx::query_as::<_, UserVehicleRow>(&sql)
+            .bind(user_id)
+            .fetch_optional(self.db.pool())
+            .await
+        {
+            Ok(row) => Ok(row),
+            Err(e) => {
+                error!("デフォルト車両の取得に失敗: {}", e);
+                log_sql_error(&sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("デフォルト車両の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 登録車両数
+    pub async fn count_vehicles(&self, user_id: &str) -> Result<i64, DatabaseError> {
+        let sql = "SELECT COUNT(*) FROM t_user_vehicles WHERE user_id = $1";
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, i64>(sql)
+            .bind(user_id)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(count) => Ok(count),
+            Err(e) => {
+                error!("登録車両数の取得に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("登録車両数の取得に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 車種IDがマスターに存在するか
+    pub async fn vehicle_type_exists(&self, vehicle_type_id: &str) -> Result<bool, DatabaseError> {
+        let sql = "SELECT EXISTS (SELECT 1 FROM m_parking_vehicle_types WHERE vehicle_type_id = $1)";
+
+        let params = vec![SqlParam::String(vehicle_type_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, bool>(sql)
+            .bind(vehicle_type_id)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(exists) => Ok(exists),
+            Err(e) => {
+                error!("車種マスターの確認に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("車種マスターの確認に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 同じナンバーの車両が登録済みか（更新時は自身を除く）
+    pub async fn license_plate_in_use(
+        &self,
+        user_id: &str,
+        license_plate: &str,
+        exclude_vehicle_id: Option<Uuid>,
+    ) -> Result<bool, DatabaseError> {
+        let sql = r#"
+            SELECT EXISTS (
+                SELECT 1 FROM t_user_vehicles
+                WHERE user_id = $1 AND license_plate = $2
+                  AND ($3::uuid IS NULL OR vehicle_id <> $3)
+            )
+        "#;
+
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(license_plate.to_string()),
+            SqlParam::OptionString(exclude_vehicle_id.map(|id| id.to_string())),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query_scalar::<_, bool>(sql)
+            .bind(user_id)
+            .bind(license_plate)
+            .bind(exclude_vehicle_id)
+            .fetch_one(self.db.pool())
+            .await
+        {
+            Ok(exists) => Ok(exists),
+            Err(e) => {
+                error!("ナンバーの重複確認に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("ナンバーの重複確認に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 車両を登録し、車両IDを返す
+    pub async fn insert_vehicle(
+        &self,
+        user_id: &str,
+        req: &UserVehicleRequest,
+        is_default: bool,
+    ) -> Result<Uuid, DatabaseError> {
+        let mut tx = self.begin_transaction().await?;
+        if is_default {
+            Self::clear_default(&mut tx, user_id).await?;
+        }
+
+        let sql = r#"
+            INSERT INTO t_user_vehicles (
+                user_id, vehicle_name, vehicle_type_id, license_plate,
+                length_cm, width_cm, height_cm, weight_kg, is_default
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING vehicle_id
+        "#;
+
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(req.vehicle_name.clone()),
+            SqlParam::OptionString(req.vehicle_type_id.clone()),
+            SqlParam::String(req.license_plate.clone()),
+            SqlParam::OptionI32(req.length_cm),
+            SqlParam::OptionI32(req.width_cm),
+            SqlParam::OptionI32(req.height_cm),
+            SqlParam::OptionI32(req.weight_kg),
+            SqlParam::Boolean(is_default),
+        ];
+        log_sql_query(sql, &params, None);
+
+        let vehicle_id = match sqlx::query_scalar::<_, Uuid>(sql)
+            .bind(user_id)
+            .bind(&req.vehicle_name)
+            .bind(&req.vehicle_type_id)
+            .bind(&req.license_plate)
+            .bind(req.length_cm)
+            .bind(req.width_cm)
+            .bind(req.height_cm)
+            .bind(req.weight_kg)
+            .bind(is_default)
+            .fetch_one(&mut *tx)
+            .await
+        {
+            Ok(id) => id,
+            Err(e) => {
+                error!("車両の登録に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("車両の登録に失敗: {}", e)));
+            }
+        };
+
+        Self::commit(tx).await?;
+        info!("車両を登録しました: user_id={}, vehicle_id={}", user_id, vehicle_id);
+        Ok(vehicle_id)
+    }
+
+    /// 車両情報を更新（対象が存在しない場合はfalse）
+    pub async fn update_vehicle(
+        &self,
+        user_id: &str,
+        vehicle_id: Uuid,
+        req: &UserVehicleRequest,
+        is_default: bool,
+    ) -> Result<bool, DatabaseError> {
+        let mut tx = self.begin_transaction().await?;
+        if is_default {
+            Self::clear_default(&mut tx, user_id).await?;
+        }
+
+        let sql = r#"
+            UPDATE t_user_vehicles
+            SET vehicle_name = $3, vehicle_type_id = $4, license_plate = $5,
+                length_cm = $6, width_cm = $7, height_cm = $8, weight_kg = $9,
+                is_default = is_default OR $10, updated_datetime = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND vehicle_id = $2
+        "#;
+
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(vehicle_id.to_string()),
+            SqlParam::String(req.vehicle_name.clone()),
+            SqlParam::OptionString(req.vehicle_type_id.clone()),
+            SqlParam::String(req.license_plate.clone()),
+            SqlParam::OptionI32(req.length_cm),
+            SqlParam::OptionI32(req.width_cm),
+            SqlParam::OptionI32(req.height_cm),
+            SqlParam::OptionI32(req.weight_kg),
+            SqlParam::Boolean(is_default),
+        ];
+        log_sql_query(sql, &params, None);
+
+        let updated = match sqlx::query(sql)
+            .bind(user_id)
+            .bind(vehicle_id)
+            .bind(&req.vehicle_name)
+            .bind(&req.vehicle_type_id)
+            .bind(&req.license_plate)
+            .bind(req.length_cm)
+            .bind(req.width_cm)
+            .bind(req.height_cm)
+            .bind(req.weight_kg)
+            .bind(is_default)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(result) => result.rows_affected() > 0,
+            Err(e) => {
+                error!("車両の更新に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("車両の更新に失敗: {}", e)));
+            }
+        };
+
+        // 対象がない場合はデフォルト解除も取り消す
+        if !updated {
+            return Ok(false);
+        }
+        Self::commit(tx).await?;
+        Ok(true)
+    }
+
+    /// デフォルト車両を切り替え（対象が存在しない場合はfalse）
+    pub async fn set_default_vehicle(&self, user_id: &str, vehicle_id: Uuid) -> Result<bool, DatabaseError> {
+        let mut tx = self.begin_transaction().await?;
+        Self::clear_default(&mut tx, user_id).await?;
+
+        let sql = r#"
+            UPDATE t_user_vehicles
+            SET is_default = TRUE, updated_datetime = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND vehicle_id = $2
+        "#;
+
+        let params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(vehicle_id.to_string()),
+        ];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql)
+            .bind(user_id)
+            .bind(vehicle_id)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(result) if result.rows_affected() == 0 => Ok(false),
+            Ok(_) => {
+                Self::commit(tx).await?;
+                Ok(true)
+            }
+            Err(e) => {
+                error!("デフォルト車両の設定に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("デフォルト車両の設定に失敗: {}", e)))
+            }
+        }
+    }
+
+    /// 車両を削除（デフォルト車両の場合は最後に登録した車両をデフォルトにする）
+    pub async fn delete_vehicle(&self, user_id: &str, vehicle_id: Uuid) -> Result<bool, DatabaseError> {
+        let mut tx = self.begin_transaction().await?;
+
+        let delete_sql = r#"
+            DELETE FROM t_user_vehicles
+            WHERE user_id = $1 AND vehicle_id = $2
+            RETURNING is_default
+        "#;
+
+        let delete_params = vec![
+            SqlParam::String(user_id.to_string()),
+            SqlParam::String(vehicle_id.to_string()),
+        ];
+        log_sql_query(delete_sql, &delete_params, None);
+
+        let was_default = match sqlx::query_scalar::<_, bool>(delete_sql)
+            .bind(user_id)
+            .bind(vehicle_id)
+            .fetch_optional(&mut *tx)
+            .await
+        {
+            Ok(Some(was_default)) => was_default,
+            Ok(None) => return Ok(false),
+            Err(e) => {
+                error!("車両の削除に失敗: {}", e);
+                log_sql_error(delete_sql, &delete_params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("車両の削除に失敗: {}", e)));
+            }
+        };
+
+        if was_default {
+            let promote_sql = r#"
+                UPDATE t_user_vehicles
+                SET is_default = TRUE, updated_datetime = CURRENT_TIMESTAMP
+                WHERE vehicle_id = (
+                    SELECT vehicle_id FROM t_user_vehicles
+                    WHERE user_id = $1
+                    ORDER BY created_datetime DESC
+                    LIMIT 1
+                )
+            "#;
+
+            let promote_params = vec![SqlParam::String(user_id.to_string())];
+            log_sql_query(promote_sql, &promote_params, None);
+
+            if let Err(e) = sqlx::query(promote_sql).bind(user_id).execute(&mut *tx).await {
+                error!("デフォルト車両の再設定に失敗: {}", e);
+                log_sql_error(promote_sql, &promote_params, &e.to_string());
+                return Err(DatabaseError::QueryError(format!("デフォルト車両の再設定に失敗: {}", e)));
+            }
+        }
+
+        Self::commit(tx).await?;
+        info!("車両を削除しました: user_id={}, vehicle_id={}", user_id, vehicle_id);
+        Ok(true)
+    }
+
+    async fn clear_default(tx: &mut Transaction<'static, Postgres>, user_id: &str) -> Result<(), DatabaseError> {
+        let sql = r#"
+            UPDATE t_user_vehicles
+            SET is_default = FALSE, updated_datetime = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND is_default
+        "#;
+
+        let params = vec![SqlParam::String(user_id.to_string())];
+        log_sql_query(sql, &params, None);
+
+        match sqlx::query(sql).bind(user_id).execute(&mut **tx).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!("デフォルト車両の解除に失敗: {}", e);
+                log_sql_error(sql, &params, &e.to_string());
+                Err(DatabaseError::QueryError(format!("デフォルト車両の解除に失敗: {}", e)))
+            }
+        }
+    }
+}
@@ -6,19 +6,101 @@ use crate::controllers::health_controller::{health_check, health_details};
 use crate::controllers::auth_signup_controller::{register_user_controller, register_owner_controller};
 use crate::controllers::auth_signin_controller::{
     signin, signout, refresh_token, get_current_user,
-    request_password_reset, verify_password_reset, complete_password_reset
+    request_password_reset, verify_password_reset, complete_password_reset,
+    change_password,
 };
 use crate::controllers::parking_lots_controller::{add_parking_space_controller};
-use crate::controllers::parking_lots_image_controller::upload_parking_lot_images_controller;
+use crate::controllers::parking_lots_image_controller::{
+    upload_parking_lot_images_controller,
+    get_parking_lot_images_controller,
+    delete_parking_lot_image_controller,
+    reorder_parking_lot_images_controller,
+    update_parking_lot_image_controller,
+};
+use crate::controllers::blob_file_controller::get_blob_file_controller;
+use crate::controllers::account_controller::{
+    export_account_data_controller,
+    request_account_deletion_controller,
+    get_account_deletion_controller,
+    cancel_account_deletion_controller,
+};
+use crate::controllers::vehicle_controller::{
+    list_vehicles_controller,
+    create_vehicle_controller,
+    get_default_vehicle_controller,
+    update_vehicle_controller,
+    set_default_vehicle_controller,
+    delete_vehicle_controller,
+};
+use crate::controllers::station_controller::{search_stations_controller, get_station_controller};
+use crate::controllers::saved_search_controller::{
+    list_saved_searches_controller,
+    create_saved_search_controller,
+    get_saved_search_controller,
+    update_saved_search_controller,
+    delete_saved_search_controller,
+};
+use crate::controllers::owner_analytics_controller::{get_owner_analytics_controller, export_owner_analytics_controller};
+use crate::controllers::payment_controller::{
+    get_reservation_payment_controller,
+    authorize_reservation_payment_controller,
+    refund_reservation_payment_controller,
+    void_reservation_payment_controller,
+    payment_webhook_controller,
+    simulate_payment_webhook_controller,
+};
+use crate::controllers::cancellation_policy_controller::{
+    get_parking_lot_cancellation_policy_controller,
+    get_owner_cancellation_policy_controller,
+    update_cancellation_policy_controller,
+    delete_cancellation_policy_controller,
+    get_cancellation_fee_controller,
+    cancel_reservation_controller,
+};
+use crate::controllers::receipt_controller::{
+    issue_receipt_controller,
+    get_receipt_controller,
+    download_receipt_pdf_controller,
+    email_receipt_controller,
+};
+use crate::controllers::check_in_controller::{
+    get_check_in_code_controller,
+    location_check_in_controller,
+    scan_check_in_code_controller,
+};
+use crate::controllers::notification_controller::{
+    list_notifications_controller,
+    mark_all_notifications_read_controller,
+    mark_notification_read_controller,
+};
+use crate::controllers::profile_controller::{
+    get_profile_controller,
+    update_profile_controller,
+    upload_profile_photo_controller,
+    request_contact_change_controller,
+    verify_contact_change_controller,
+};
 use crate::controllers::parking_search_controller::{
     search_parking_lots_controller,
     get_favorite_parking_lots_controller,
     manage_favorite_parking_lot_controller,
+    reorder_favorites_controller,
+    get_favorite_labels_controller,
+    update_favorite_controller,
+    get_search_filters_controller,
+    get_nearby_parking_lots_controller,
+    search_map_viewport_controller,
+    get_parking_stats_controller,
     get_parking_lot_detail_controller,
+    list_search_history_controller,
+    rerun_search_history_controller,
+    delete_search_history_controller,
+    clear_search_history_controller,
 };
 
+use crate::payments::simulated_webhooks_enabled;
 use crate::controllers::user_home_controller::{get_favorites, get_parking_search_history, get_parking_status, update_parking_status};
-use crate::controllers::use_history_controller::{get_parking_use_history, get_parking_use_history_detail, get_parking_features};
+use crate::controllers::use_history_controller::{get_parking_use_history, get_parking_use_history_detail, get_parking_features, list_parking_use_history};
 
 pub fn init_routes(cfg: &mut web::ServiceConfig) {
     // Health check endpoints
@@ -32,6 +114,7 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
             .service(signout)
             .service(refresh_token)
             .service(get_current_user)
+            .service(change_password)
             .service(get_csrf_token)
             .service(get_parking_status)
             .service(update_parking_status)
@@ -59,7 +142,21 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
             .service(search_parking_lots_controller)
             .service(get_favorite_parking_lots_controller)
             .service(manage_favorite_parking_lot_controller)
+            .service(reorder_favorites_controller)
+            .service(get_favorite_labels_controller)
+            .service(update_favorite_controller)
+            .service(get_search_filters_controller)
+            .service(get_nearby_parking_lots_controller)
+            .service(search_map_viewport_controller)
+            .service(get_parking_stats_controller)
             .service(get_parking_lot_detail_controller)
+            .service(list_search_history_controller)
+            .service(rerun_search_history_controller)
+            .service(delete_search_history_controller)
+            .service(clear_search_history_controller)
+            .service(list_parking_use_history)
+            .service(get_parking_lot_images_controller)
+            .service(get_parking_lot_cancellation_policy_controller)
     );
 
     // Parking search routes
@@ -67,6 +164,101 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
         web::scope("/v1/api/owner")
             .service(add_parking_space_controller)
             .service(upload_parking_lot_images_controller)
+            .service(reorder_parking_lot_images_controller)
+            .service(delete_parking_lot_image_controller)
+            .service(update_parking_lot_image_controller)
+            .service(get_owner_analytics_controller)
+            .service(export_owner_analytics_controller)
+            .service(get_owner_cancellation_policy_controller)
+            .service(update_cancellation_policy_controller)
+            .service(delete_cancellation_policy_controller)
+            .service(scan_check_in_code_controller)
+    );
+
+    // プロフィール管理（ユーザー・オーナー共通）
+    cfg.service(
+        web::scope("/v1/api/profile")
+            .service(get_profile_controller)
+            .service(update_profile_controller)
+            .service(upload_profile_photo_controller)
+            .service(request_contact_change_controller)
+            .service(verify_contact_change_controller)
+    );
+
+    // 個人データのエクスポート・アカウント削除
+    cfg.service(
+        web::scope("/v1/api/account")
+            .service(export_account_data_controller)
+            .service(request_account_deletion_controller)
+            .service(get_account_deletion_controller)
+            .service(cancel_account_deletion_controller)
+    );
+
+    // 登録車両管理（利用者のみ）
+    cfg.service(
+        web::scope("/v1/api/vehicles")
+            .service(list_vehicles_controller)
+            .service(create_vehicle_controller)
+            .service(get_default_vehicle_controller)
+            .service(update_vehicle_controller)
+            .service(set_default_vehicle_controller)
+            .service(delete_vehicle_controller)
+    );
+
+    cfg.service(
+        web::scope("/v1/api/stations")
+            .service(search_stations_controller)
+            .service(get_station_controller)
+    );
+
+    // 保存検索と空き・新規登録通知（利用者のみ）
+    cfg.service(
+        web::scope("/v1/api/saved-searches")
+            .service(list_saved_searches_controller)
+            .service(create_saved_search_controller)
+            .service(get_saved_search_controller)
+            .service(update_saved_search_controller)
+            .service(delete_saved_search_controller)
+    );
+
+    // アプリ内通知（利用者のみ）
+    cfg.service(
+        web::scope("/v1/api/notifications")
+            .service(list_notifications_controller)
+            .service(mark_all_notifications_read_controller)
+            .service(mark_notification_read_controller)
+    );
+
+    // 予約の決済（Webhookの模擬送信は開発環境かつテスト用プロバイダーの場合のみ登録）
+    let mut payments_scope = web::scope("/v1/api/payments").service(payment_webhook_controller);
+    if simulated_webhooks_enabled() {
+        payments_scope = payments_scope.service(simulate_payment_webhook_controller);
+    }
+    cfg.service(
+        payments_scope
+            .service(get_reservation_payment_controller)
+            .service(authorize_reservation_payment_controller)
+            .service(refund_reservation_payment_controller)
+            .service(void_reservation_payment_controller)
+    );
+
+    // 予約のキャンセル（キャンセル料の試算・自動返金）・領収書・入出庫
+    cfg.service(
+        web::scope("/v1/api/reservations")
+            .service(get_cancellation_fee_controller)
+            .service(cancel_reservation_controller)
+            .service(issue_receipt_controller)
+            .service(get_receipt_controller)
+            .service(download_receipt_pdf_controller)
+            .service(email_receipt_controller)
+            .service(get_check_in_code_controller)
+            .service(location_check_in_controller)
+    );
+
+    // 署名済みURLによるファイル配信
+    cfg.service(
+        web::scope("/v1/api/files")
+            .service(get_blob_file_controller)
     );
 
     // 示例：带参数的 resource 路由
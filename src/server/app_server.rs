@@ -2,19 +2,24 @@ use actix_web::{App, HttpServer, middleware::Logger, web};
 use crate::{config::{
     logging::{init_logger, LogConfig},
     postgresql_database::{DatabaseConfig, PostgresDatabase},
-}, services::ParkingLotsService};
+}, services::{account_service::spawn_account_deletion_worker, AccountService, ParkingLotsService, ProfileService, VehicleService}};
+use crate::services::{saved_search_service::spawn_saved_search_worker, CancellationPolicyService, CheckInService, NotificationService, OwnerAnalyticsService, PaymentService, ReceiptService, SavedSearchService};
 use crate::middlewares::{
     cors_middleware::configure_cors,
     csrf_middleware::CsrfMiddleware,
     default_headers::DefaultHeadersMiddleware,
     error_handlers::ErrorHandlersMiddleware,
     identity_middleware::JwtAuthMiddleware, // Changed from IdentityMiddleware to JwtAuthMiddleware
-    session_middleware::SessionMiddleware,
+    session_middleware::{create_session_backend, spawn_session_sweeper, SessionConfig, SessionMiddleware},
 };
+use crate::repositories::AuthSigninRepository;
 use crate::routes::route_config;
 use crate::services::auth_signup_service::AuthSignupService;
 use crate::services::auth_signin_service::AuthSigninService;
 use crate::services::parking_search_service::ParkingSearchService;
+use crate::geocoding::{create_geocoder, load_station_master, GeocodingConfig};
+use crate::storage::{create_blob_store, BlobStoreConfig};
+use crate::payments::{create_payment_provider, PaymentConfig};
 
 use std::io::{self, ErrorKind};
 use std::net::TcpListener;
@@ -57,13 +62,98 @@ pub async fn start_server() -> std::io::Result<()> {
     // 認証サインインサービスの初期化（同一のデータベース接続を使用）
     let auth_signin_service = web::Data::new(AuthSigninService::new(database.clone()));
     
+    // ページネーション用カーソルの署名鍵の読み込み
+    crate::utils::cursor::init_cursor_signing_secret();
+
+    // ファイルストレージの初期化（ローカル / S3互換）
+    let blob_config = BlobStoreConfig::from_env();
+    let blob_store = create_blob_store(&blob_config.backend, &blob_config).map_err(|e| {
+        error!("ファイルストレージの初期化に失敗しました: {}", e);
+        io::Error::other(e.to_string())
+    })?;
+    let blob_store_data = web::Data::from(blob_store.clone());
+
+    // ジオコーディングの初期化（郵便番号・町域データセット）
+    let geocoding_config = GeocodingConfig::from_env();
+    let geocoder = create_geocoder(&geocoding_config).map_err(|e| {
+        error!("ジオコーディングの初期化に失敗しました: {}", e);
+        io::Error::other(e.to_string())
+    })?;
+    // 駅マスタの読み込み
+    let station_master = load_station_master(&geocoding_config).map_err(|e| {
+        error!("駅マスタの読み込みに失敗しました: {}", e);
+        io::Error::other(e.to_string())
+    })?;
+    let station_master_data = web::Data::from(station_master.clone());
+
     // 駐車場検索サービスの初期化（同一のデータベース接続を使用）
-    let parking_search_service = web::Data::new(ParkingSearchService::new(database.clone()));
-    
+    let parking_search_service = web::Data::new(ParkingSearchService::new(
+        database.clone(),
+        blob_store.clone(),
+        blob_config.clone(),
+        geocoder.clone(),
+        station_master.clone(),
+    ));
+
     // 駐車場登録サービスの初期化（同一のデータベース接続を使用）
-    let parking_lots_service = web::Data::new(ParkingLotsService::new(database.clone()));
+    let parking_lots_service = web::Data::new(ParkingLotsService::new(
+        database.clone(),
+        blob_store.clone(),
+        blob_config.clone(),
+        geocoder.clone(),
+        station_master.clone(),
+    ));
+    // プロフィールサービスの初期化
+    let profile_service = web::Data::new(ProfileService::new(
+        database.clone(),
+        blob_store.clone(),
+        blob_config.clone(),
+    ));
+    // アカウント削除サービスの初期化と猶予期間経過後の削除バッチ
+    let account_service = web::Data::new(AccountService::new(database.clone(), blob_store.clone()));
+    spawn_account_deletion_worker(
+        account_service.clone().into_inner(),
+        crate::utils::env::parse_env_or("ACCOUNT_DELETION_SWEEP_INTERVAL_SECS", 3600),
+    );
+    let vehicle_service = web::Data::new(VehicleService::new(database.clone()));
+    // 保存検索サービスの初期化と空き・新規登録の通知バッチ
+    let saved_search_service = web::Data::new(SavedSearchService::new(
+        database.clone(),
+        parking_search_service.clone().into_inner(),
+    ));
+    spawn_saved_search_worker(
+        saved_search_service.clone().into_inner(),
+        crate::utils::env::parse_env_or("SAVED_SEARCH_EVAL_INTERVAL_SECS", 60),
+    );
+    let notification_service = web::Data::new(NotificationService::new(database.clone()));
+    let owner_analytics_service = web::Data::new(OwnerAnalyticsService::new(database.clone()));
+    // 決済サービスの初期化（決済プロバイダーは環境変数で切り替え）
+    let payment_config = PaymentConfig::from_env();
+    let payment_provider = create_payment_provider(&payment_config).map_err(|e| {
+        error!("決済プロバイダーの初期化に失敗しました: {}", e);
+        io::Error::other(e.to_string())
+    })?;
+    let payment_service = web::Data::new(PaymentService::new(database.clone(), payment_provider));
+    let cancellation_policy_service = web::Data::new(CancellationPolicyService::new(
+        database.clone(),
+        payment_service.clone().into_inner(),
+    ));
+    let receipt_service = web::Data::new(ReceiptService::new(database.clone()));
+    let check_in_service = web::Data::new(CheckInService::new(
+        database.clone(),
+        payment_service.clone().into_inner(),
+    ));
     info!("認証サービスと駐車場検索サービスが初期化されました");
 
+    // セッションバックエンドの初期化（全ワーカーで共有）と期限切れセッションの定期削除
+    let session_config = SessionConfig::from_env();
+    let session_backend = create_session_backend(&session_config, &database);
+    spawn_session_sweeper(session_backend.clone(), session_config.cleanup_interval_secs);
+    let session_backend_data = web::Data::from(session_backend.clone());
+
+    // JWT認証でパスワード変更日時を確認するためのリポジトリ
+    let auth_repository = AuthSigninRepository::new(database.clone());
+
     // サーバーのホストとポート設定を環境変数から取得
     let host = std::env::var("SERVER_HOST").unwrap_or_else(|_| {
         warn!("SERVER_HOSTが設定されていません。デフォルト値 '0.0.0.0' を使用します");
@@ -127,6 +217,19 @@ pub async fn start_server() -> std::io::Result<()> {
             .app_data(auth_signin_service.clone())
             .app_data(parking_search_service.clone())
             .app_data(parking_lots_service.clone())
+            .app_data(profile_service.clone())
+            .app_data(account_service.clone())
+            .app_data(vehicle_service.clone())
+            .app_data(saved_search_service.clone())
+            .app_data(notification_service.clone())
+            .app_data(owner_analytics_service.clone())
+            .app_data(payment_service.clone())
+            .app_data(cancellation_policy_service.clone())
+            .app_data(receipt_service.clone())
+            .app_data(check_in_service.clone())
+            .app_data(blob_store_data.clone())
+            .app_data(station_master_data.clone())
+            .app_data(session_backend_data.clone())
 
             // ミドルウェアの適用（適用順序が重要）
             // 1. エラーハンドリング（最外層）
@@ -142,10 +245,10 @@ pub async fn start_server() -> std::io::Result<()> {
             .wrap(configure_cors())
             
             // 5. セッション管理（認証の前提）
-            .wrap(SessionMiddleware::new())
+            .wrap(SessionMiddleware::new(session_backend.clone(), session_config.clone()))
             
             // 6. JWT認証管理（ユーザー認証）
-            .wrap(JwtAuthMiddleware::new()) // Changed from IdentityMiddleware to JwtAuthMiddleware
+            .wrap(JwtAuthMiddleware::new(auth_repository.clone()))
             
             // 7. CSRF保護（認証後のセキュリティ層）
             // 認証が不要なエンドポイントを除外
@@ -165,6 +268,7 @@ pub async fn start_server() -> std::io::Result<()> {
                 .exempt("/api/parking/stats")               // 駐車場統計情報
                 .exempt("/v1/api/owner/add-parking-space")               // 駐車場追加情報
                 .exempt("/v1/api/owner/parking-lot-images/upload")               // 駐車場画像アップロード
+                .exempt("/v1/api/payments/webhook")         // 決済Webhook（署名で検証）
             )
 
             // ルート設定の適用
@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::postgresql_database::PostgresDatabase;
+use crate::controllers::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::account_model::{
+    deletion_scheduled_at, AccountDeletionRequest, AccountDeletionResponse, AccountExport, AccountRow,
+};
+use crate::repositories::{AccountRepository, AuthSigninRepository};
+use crate::storage::{normalize_blob_key, BlobStore};
+use crate::utils::env::parse_env_or;
+use crate::utils::password::verify_password_blocking;
+
+/// 1回のバッチで処理する削除リクエスト数
+const DELETION_BATCH_SIZE: i64 = 50;
+
+/// 個人データのエクスポート・アカウント削除サービス
+pub struct AccountService {
+    repository: AccountRepository,
+    auth_repository: AuthSigninRepository,
+    blob_store: Arc<dyn BlobStore>,
+    cool_off_days: i64,
+}
+
+impl AccountService {
+    /// 新しいサービスインスタンスを作成
+    pub fn new(db: PostgresDatabase, blob_store: Arc<dyn BlobStore>) -> Self {
+        Self {
+            repository: AccountRepository::new(db.clone()),
+            auth_repository: AuthSigninRepository::new(db),
+            blob_store,
+            cool_off_days: parse_env_or("ACCOUNT_DELETION_COOL_OFF_DAYS", 14),
+        }
+    }
+
+    /// 個人データをJSONでエクスポート
+    pub async fn export_data(&self, identity: &UserIdentity) -> Result<AccountExport, ApiError> {
+        let account = self.load_account(identity).await?;
+        let export = self.repository.export_account(&account).await?;
+        info!("個人データをエクスポート: login_id={}", account.login_id);
+        Ok(export)
+    }
+
+    /// アカウント削除を申請（パスワードで再認証し、猶予期間後に削除）
+    ///
+    /// 再認証の失敗はログイン失敗として記録し、ログインと同じくロックの対象にする
+    pub async fn request_deletion(
+        &self,
+        identity: &UserIdentity,
+        req: &AccountDeletionRequest,
+    ) -> Result<AccountDeletionResponse, ApiError> {
+        let account = self.load_account(identity).await?;
+
+        let login = self.auth_repository.get_login_by_id(&account.login_id).await?;
+        if login.is_account_locked() {
+            warn!("ロック中のアカウントの削除申請: login_id={}", account.login_id);
+            return Err(ApiError::AccountLocked(
+                "アカウントがロックされています。しばらく経ってからもう一度試してください".to_string(),
+            ));
+        }
+        if req.password.is_empty() || !verify_password_blocking(&req.password, &account.pass_word).await? {
+            warn!("アカウント削除の再認証に失敗: login_id={}", account.login_id);
+            if let Err(e) = self
+                .auth_repository
+                .record_failed_login_attempt(&account.login_id, "Invalid password for account deletion")
+                .await
+            {
+                error!("ログイン失敗の記録に失敗: {}", e);
+            }
+            return Err(ApiError::AuthenticationError("パスワードが正しくありません".to_string()));
+        }
+
+        let login_id = parse_login_id(identity)?;
+        if self.repository.find_pending_deletion(login_id).await?.is_some() {
+            return Err(ApiError::DuplicateError("既にアカウント削除を受け付けています".to_string()));
+        }
+
+        if self.repository.has_active_reservations(&account).await? {
+            return Err(ApiError::ValidationError(
+                "利用中または利用予定の予約があるため削除できません".to_string(),
+            ));
+        }
+
+        let scheduled_at = deletion_scheduled_at(Utc::now(), self.cool_off_days);
+        let row = self.repository.create_deletion_request(login_id, scheduled_at).await?;
+        Ok(row.into())
+    }
+
+    /// 削除申請の状況を取得
+    pub async fn get_deletion_status(&self, identity: &UserIdentity) -> Result<AccountDeletionResponse, ApiError> {
+        let login_id = parse_login_id(identity)?;
+        self.repository
+            .find_pending_deletion(login_id)
+            .await?
+            .map(AccountDeletionResponse::from)
+            .ok_or_else(|| ApiError::NotFoundError("アカウント削除の申請はありません".to_string()))
+    }
+
+    /// 猶予期間中の削除申請を取り消し
+    pub async fn cancel_deletion(&self, identity: &UserIdentity) -> Result<(), ApiError> {
+        let login_id = parse_login_id(identity)?;
+        if !self.repository.cancel_deletion_request(login_id).await? {
+            return Err(ApiError::NotFoundError("取り消し可能なアカウント削除の申請はありません".to_string()));
+        }
+        info!("アカウント削除を取り消し: login_id={}", login_id);
+        Ok(())
+    }
+
+    /// 猶予期間を過ぎた削除申請を実行し、削除したアカウント数を返す
+    ///
+    /// 利用中の予約がある場合は削除を見送り、次回以降に再試行する
+    pub async fn process_due_deletions(&self) -> Result<usize, ApiError> {
+        let due = self.repository.find_due_deletions(DELETION_BATCH_SIZE).await?;
+        let mut deleted = 0;
+
+        // DBとアプリの時刻がずれていても猶予期間前に削除しないよう、アプリの時刻でも確認する
+        let now = Utc::now();
+        for request in due.into_iter().filter(|request| request.is_due(now)) {
+            let Some(account) = self.repository.find_account(request.login_id).await? else {
+                warn!("削除対象のアカウントが存在しません: login_id={}", request.login_id);
+                continue;
+            };
+
+            if self.repository.has_active_reservations(&account).await? {
+                warn!("利用中の予約があるため削除を延期: login_id={}", account.login_id);
+                continue;
+            }
+
+            match self.repository.delete_account(request.request_id, &account).await? {
+                Some(photo_paths) => {
+                    for path in photo_paths {
+                        let key = normalize_blob_key(&path);
+                        if let Err(e) = self.blob_store.delete(&key).await {
+                            warn!("プロフィール写真の削除に失敗: {} - {}", key, e);
+                        }
+                    }
+                    deleted += 1;
+                }
+                None => info!("削除リクエストは処理済みまたは取り消し済み: {}", request.request_id),
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    async fn load_account(&self, identity: &UserIdentity) -> Result<AccountRow, ApiError> {
+        let login_id = parse_login_id(identity)?;
+        self.repository
+            .find_account(login_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFoundError("アカウントが見つかりません".to_string()))
+    }
+}
+
+fn parse_login_id(identity: &UserIdentity) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(&identity.user_id)
+        .map_err(|_| ApiError::AuthenticationError("ログイン情報が正しくありません".to_string()))
+}
+
+/// 猶予期間を過ぎたアカウント削除を定期実行するバックグラウンドタスクを起動
+pub fn spawn_account_deletion_worker(
+    service: Arc<AccountService>,
+    interval_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    let period = std::time::Duration::from_secs(interval_secs.max(1));
+    info!("アカウント削除バッチを起動します（間隔: {}秒）", period.as_secs());
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match service.process_due_deletions().await {
+                Ok(0) => {}
+                Ok(count) => info!("アカウントを削除しました: {}件", count),
+                Err(e) => error!("アカウント削除バッチに失敗しました: {}", e),
+            }
+        }
+    })
+}
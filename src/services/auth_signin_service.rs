@@ -10,7 +10,6 @@
 //! - 用户登出和令牌刷新
 //! - 安全性验证和错误处理
 
-use bcrypt::verify;
 use chrono::Utc;
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
@@ -18,12 +17,17 @@ use uuid::Uuid;
 use crate::{
     controllers::api_error::ApiError,
     middlewares::jwt::{generate_jwt, generate_refresh_token, verify_refresh_token},
+    middlewares::session_middleware::SessionBackend,
     models::auth_signin_model::{
         AuthSigninRequest, AuthSigninResponse, CurrentUserResponse,
         PasswordResetCompletion, LoginType, RefreshTokenRequest, RefreshTokenResponse,
+        PasswordChangeRequest, PasswordChangeResponse,
     },
     repositories::AuthSigninRepository,
     config::postgresql_database::PostgresDatabase,
+    utils::password::{
+        hash_password_blocking, needs_rehash, verify_password_blocking, PasswordPolicy,
+    },
 };
 
 /// 认证登录服务
@@ -100,11 +104,7 @@ impl AuthSigninService {
         }
         
         // 验证密码
-        let password_valid = verify(&request.password, &login.pass_word)
-            .map_err(|e| {
-                error!("密码验证错误: {}", e);
-                ApiError::InternalServerError
-            })?;
+        let password_valid = verify_password_blocking(&request.password, &login.pass_word).await?;
             
         if !password_valid {
             // 记录失败的登录尝试
@@ -120,6 +120,20 @@ impl AuthSigninService {
             ));
         }
         
+        // 旧格式(bcrypt)的哈希透明地升级为argon2id
+        if needs_rehash(&login.pass_word) {
+            match hash_password_blocking(&request.password).await {
+                Ok(upgraded) => {
+                    if let Err(e) = self.repository.update_password(&login.login_id, &upgraded).await {
+                        warn!("密码哈希升级失败: {}", e);
+                    } else {
+                        info!("密码哈希已升级为argon2id: {}", login.login_id);
+                    }
+                }
+                Err(e) => warn!("密码哈希升级失败: {}", e),
+            }
+        }
+        
         // 重置之前的失败登录尝试计数
         if login.login_failed_count > 0 {
             if let Err(e) = self.repository.reset_failed_login_attempts(&login.login_id).await {
@@ -150,6 +164,7 @@ impl AuthSigninService {
                 is_owner: true,
                 token: access_token,
                 refresh_token,
+                login_id: login.login_id.clone(),
             }
         } else {
             let user = self.repository.get_user_by_login_id(&login.login_id).await?
@@ -163,6 +178,7 @@ impl AuthSigninService {
                 is_owner: false,
                 token: access_token,
                 refresh_token,
+                login_id: login.login_id.clone(),
             }
         };
         
@@ -297,15 +313,14 @@ impl AuthSigninService {
         // 获取用户信息
         let login = self.repository.get_login_by_email(&request.email).await?;
         
+        // 与修改密码相同的密码策略和重复使用检查
+        let history_count = self.validate_new_password(&login.login_id, &request.new_password).await?;
+        
         // 哈希新密码
-        let hashed_password = bcrypt::hash(&request.new_password, bcrypt::DEFAULT_COST)
-            .map_err(|e| {
-                error!("密码哈希错误: {}", e);
-                ApiError::InternalServerError
-            })?;
+        let hashed_password = hash_password_blocking(&request.new_password).await?;
         
-        // 更新密码
-        self.repository.update_password(&login.login_id, &hashed_password).await?;
+        // 更新密码（记录历史，使已签发的刷新令牌失效）
+        self.repository.change_password(&login.login_id, &hashed_password, history_count).await?;
         
         // 删除使用过的验证码
         if let Err(e) = self.repository.delete_password_reset_code(&request.email).await {
@@ -436,6 +451,13 @@ impl AuthSigninService {
         // 获取用户信息以确认用户仍然存在
         let login = self.repository.get_login_by_id(&login_id_str).await?;
         
+        // 密码修改前签发的刷新令牌无效
+        let password_changed = self.repository.get_password_changed_datetime(&login_id_str).await?;
+        if password_changed.is_some_and(|changed| (claims.iat as i64) < changed.timestamp()) {
+            warn!("密码修改前签发的刷新令牌: {}", claims.sub);
+            return Err(ApiError::TokenError("トークンが無効です。再度ログインしてください".to_string()));
+        }
+        
         // 生成新的令牌
         let user_type = if login.is_owner() { "owner" } else { "user" };
         let new_access_token = generate_jwt(&login.login_id.to_string(), user_type)?;
@@ -450,6 +472,108 @@ impl AuthSigninService {
         })
     }
 
+    /// 修改密码
+    /// 
+    /// 验证当前密码，检查密码策略和最近使用过的密码，
+    /// 更新后返回新的令牌对（旧的刷新令牌全部失效）
+    /// 
+    /// # 参数
+    /// * `login_id` - 用户登录ID
+    /// * `request` - 密码修改请求
+    /// 
+    /// # 错误
+    /// - `ApiError::ValidationError` - 输入验证失败或不符合密码策略
+    /// - `ApiError::AuthenticationError` - 当前密码错误
+    /// - `ApiError::AccountLocked` - 账户被锁定
+    #[instrument(skip(self, request, sessions))]
+    pub async fn change_password(
+        &self,
+        login_id: &str,
+        request: &PasswordChangeRequest,
+        sessions: &dyn SessionBackend,
+        current_session_id: Option<&str>,
+    ) -> Result<PasswordChangeResponse, ApiError> {
+        info!("修改密码: {}", login_id);
+        
+        request.validate().map_err(ApiError::ValidationError)?;
+        
+        let login_id_str = Uuid::parse_str(login_id)
+            .map_err(|_| ApiError::ValidationError("無効なユーザーIDです".to_string()))?
+            .to_string();
+        let login = self.repository.get_login_by_id(&login_id_str).await?;
+        
+        if login.is_account_locked() {
+            warn!("被锁定的账户尝试修改密码: {}", login_id);
+            return Err(ApiError::AccountLocked(
+                "アカウントがロックされています。しばらく経ってからもう一度試してください".to_string()
+            ));
+        }
+        
+        // 验证当前密码（失败计入登录失败次数）
+        if !verify_password_blocking(&request.current_password, &login.pass_word).await? {
+            if let Err(e) = self.repository.record_failed_login_attempt(
+                &login.login_id,
+                "Invalid current password"
+            ).await {
+                error!("记录登录失败尝试时出错: {}", e);
+            }
+            return Err(ApiError::AuthenticationError("現在のパスワードが正しくありません".to_string()));
+        }
+        
+        let history_count = self.validate_new_password(&login.login_id, &request.new_password).await?;
+        
+        let hashed_password = hash_password_blocking(&request.new_password).await?;
+        self.repository.change_password(&login.login_id, &hashed_password, history_count).await?;
+        
+        // 生成新的令牌
+        let user_type = if login.is_owner() { "owner" } else { "user" };
+        let access_token = generate_jwt(&login.login_id, user_type)?;
+        let refresh_token = generate_refresh_token(&login.login_id)?;
+        if let Err(e) = self.repository.record_successful_login(&login.login_id, &access_token).await {
+            error!("记录新令牌时出错: {}", e);
+        }
+        
+        // 当前会话以外的会话失效（失败时密码修改仍然有效）
+        let revoked_sessions = match sessions.remove_by_user(login_id, current_session_id).await {
+            Ok(revoked) => {
+                info!("已使其他会话失效: login_id={}, count={}", login_id, revoked);
+                revoked
+            }
+            Err(e) => {
+                error!("使其他会话失效时出错: {}", e);
+                0
+            }
+        };
+        
+        info!("密码修改成功: {}", login_id);
+        Ok(PasswordChangeResponse {
+            access_token,
+            refresh_token,
+            expires_in: 24 * 60 * 60,
+            revoked_sessions,
+        })
+    }
+
+    /// 新密码的检查（密码策略、禁止重复使用最近的密码）
+    /// 
+    /// 当前密码也包含在比较对象内。返回密码历史的保留条数
+    async fn validate_new_password(&self, login_id: &str, new_password: &str) -> Result<i64, ApiError> {
+        let policy = PasswordPolicy::from_env();
+        policy.validate(new_password).map_err(ApiError::ValidationError)?;
+        
+        let history_count = (policy.history_count - 1).max(0);
+        let recent_hashes = self.repository.get_recent_password_hashes(login_id, history_count).await?;
+        for recent in &recent_hashes {
+            if verify_password_blocking(new_password, recent).await? {
+                return Err(ApiError::ValidationError(format!(
+                    "直近{}回以内に使用したパスワードは使用できません",
+                    policy.history_count
+                )));
+            }
+        }
+        Ok(history_count)
+    }
+
     // ================================
     // 辅助方法
     // ================================
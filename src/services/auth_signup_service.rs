@@ -1,4 +1,3 @@
-use bcrypt::{hash, DEFAULT_COST};
 use tracing::{error, instrument, info};
 
 use crate::{
@@ -140,10 +139,7 @@ impl AuthSignupService {
 
     /// パスワードのハッシュ化
     fn hash_password(&self, password: &str) -> Result<String, ApiError> {
-        hash(password, DEFAULT_COST).map_err(|e| {
-            error!("パスワードのハッシュ化に失敗しました: {}", e);
-            ApiError::InternalServerError
-        })
+        crate::utils::password::hash_password(password)
     }
 
     /// JWTトークンの生成
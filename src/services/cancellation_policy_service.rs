@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::{error, info, warn};
+
+use crate::config::postgresql_database::PostgresDatabase;
+use crate::controllers::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::cancellation_policy_model::{
+    cancellation_fee, CancelReservationRequest, CancellationPolicy, CancellationPolicyResponse, CancellationQuote,
+    CancellationReservationRow, CancellationResult,
+};
+use crate::repositories::CancellationPolicyRepository;
+use crate::services::PaymentService;
+
+/// キャンセルできる予約ステータス（1: 予約中, 2: 承認済み）
+const CANCELLABLE_RESERVATION_STATUSES: [&str; 2] = ["1", "2"];
+/// キャンセル済みの予約ステータス
+const CANCELLED_RESERVATION_STATUS: &str = "3";
+
+/// キャンセルポリシーサービス
+///
+/// キャンセル料は予約時点ではなくキャンセル時点のポリシーで算出し、適用したポリシーを予約に保存する
+pub struct CancellationPolicyService {
+    repository: CancellationPolicyRepository,
+    payment_service: Arc<PaymentService>,
+}
+
+impl CancellationPolicyService {
+    /// 新しいサービスインスタンスを作成
+    pub fn new(db: PostgresDatabase, payment_service: Arc<PaymentService>) -> Self {
+        Self {
+            repository: CancellationPolicyRepository::new(db),
+            payment_service,
+        }
+    }
+
+    /// 駐車場のキャンセルポリシー（未設定の場合は利用開始まで無料）
+    pub async fn get_policy(&self, parking_lot_id: &str) -> Result<CancellationPolicyResponse, ApiError> {
+        let row = self.repository.find_policy(parking_lot_id).await?;
+        if row.is_none() && !self.repository.parking_lot_exists(parking_lot_id).await? {
+            return Err(ApiError::NotFoundError("指定された駐車場が存在しません".to_string()));
+        }
+        Ok(CancellationPolicyResponse::new(parking_lot_id, row.as_ref()))
+    }
+
+    /// キャンセルポリシーを登録・更新（駐車場のオーナー確認は呼び出し側で行う）
+    pub async fn update_policy(
+        &self,
+        parking_lot_id: &str,
+        policy: CancellationPolicy,
+    ) -> Result<CancellationPolicyResponse, ApiError> {
+        let policy = policy.normalize().map_err(ApiError::ValidationError)?;
+        let rules = serde_json::to_value(&policy.rules).map_err(|e| {
+            error!("キャンセルポリシーの変換に失敗: {}", e);
+            ApiError::InternalServerError
+        })?;
+        let row = self.repository.upsert_policy(parking_lot_id, &rules).await?;
+        info!("キャンセルポリシーを更新しました: parking_lot_id={}, 規定数={}", parking_lot_id, policy.rules.len());
+        Ok(CancellationPolicyResponse::new(parking_lot_id, Some(&row)))
+    }
+
+    /// キャンセルポリシーを削除し、利用開始まで無料に戻す
+    pub async fn delete_policy(&self, parking_lot_id: &str) -> Result<CancellationPolicyResponse, ApiError> {
+        if self.repository.delete_policy(parking_lot_id).await? {
+            info!("キャンセルポリシーを削除しました: parking_lot_id={}", parking_lot_id);
+        }
+        Ok(CancellationPolicyResponse::new(parking_lot_id, None))
+    }
+
+    /// 現時点でキャンセルした場合のキャンセル料の試算
+    pub async fn quote(&self, identity: &UserIdentity, reservation_id: &str) -> Result<CancellationQuote, ApiError> {
+        let reservation = self.load_reservation(identity, reservation_id).await?;
+        if !CANCELLABLE_RESERVATION_STATUSES.contains(&reservation.status.as_str()) {
+            return Err(ApiError::ValidationError("この予約はキャンセルできません".to_string()));
+        }
+
+        let row = self.repository.find_policy(&reservation.parking_lot_id).await?;
+        let policy = row.as_ref().map(|row| row.policy()).unwrap_or_default();
+        let now = Utc::now();
+        let fee_percent = policy.fee_percent_at(reservation.start_datetime, now);
+        let fee = cancellation_fee(reservation.amount, fee_percent);
+
+        Ok(CancellationQuote {
+            reservation_id: reservation.reservation_id,
+            start_datetime: reservation.start_datetime,
+            quoted_at: now,
+            reservation_amount: reservation.amount,
+            fee_percent,
+            cancellation_fee: fee,
+            refund_amount: reservation.amount - fee,
+            fee_changes_at: policy.fee_changes_at(reservation.start_datetime, now),
+            policy: CancellationPolicyResponse::new(&reservation.parking_lot_id, row.as_ref()),
+            parking_lot_id: reservation.parking_lot_id,
+        })
+    }
+
+    /// 予約をキャンセルし、ポリシーに従ってキャンセル料を差し引いて返金する
+    ///
+    /// キャンセル済みの予約への再送は、記録済みのキャンセル料で精算をやり直して同じ結果を返す
+    pub async fn cancel_reservation(
+        &self,
+        identity: &UserIdentity,
+        reservation_id: &str,
+        req: CancelReservationRequest,
+    ) -> Result<CancellationResult, ApiError> {
+        let cancel_reason = req.normalize().map_err(ApiError::ValidationError)?;
+        let reservation = self.load_reservation(identity, reservation_id).await?;
+
+        if reservation.status == CANCELLED_RESERVATION_STATUS {
+            return self.settle(reservation).await;
+        }
+        if !CANCELLABLE_RESERVATION_STATUSES.contains(&reservation.status.as_str()) {
+            return Err(ApiError::ValidationError("この予約はキャンセルできません".to_string()));
+        }
+
+        let policy = self
+            .repository
+            .find_policy(&reservation.parking_lot_id)
+            .await?
+            .map(|row| row.policy())
+            .unwrap_or_default();
+        let fee_percent = policy.fee_percent_at(reservation.start_datetime, Utc::now());
+        let fee = cancellation_fee(reservation.amount, fee_percent);
+        let snapshot = serde_json::to_value(&policy).map_err(|e| {
+            error!("キャンセルポリシーの変換に失敗: {}", e);
+            ApiError::InternalServerError
+        })?;
+
+        if self
+            .repository
+            .cancel_reservation(reservation_id, cancel_reason.as_deref(), fee_percent, fee, &snapshot)
+            .await?
+        {
+            info!(
+                "予約をキャンセルしました: reservation_id={}, キャンセル料率={}%, キャンセル料={}",
+                reservation_id, fee_percent, fee
+            );
+        } else {
+            // 同時に別のリクエストでキャンセル・ステータス変更された場合は、その結果に従う
+            warn!("予約のキャンセルが競合しました: reservation_id={}", reservation_id);
+        }
+
+        let reservation = self.load_reservation(identity, reservation_id).await?;
+        if reservation.status != CANCELLED_RESERVATION_STATUS {
+            return Err(ApiError::ValidationError("この予約はキャンセルできません".to_string()));
+        }
+        self.settle(reservation).await
+    }
+
+    /// 記録済みのキャンセル料で決済を精算し、実際に返金された額を記録
+    ///
+    /// 返金額は決済の返金済み額（キャンセル前の一部返金を含む）。与信のみの決済は返金せず
+    /// キャンセル料の売上確定か与信の取り消しになるため0
+    async fn settle(&self, reservation: CancellationReservationRow) -> Result<CancellationResult, ApiError> {
+        let (Some(fee_percent), Some(fee)) = (reservation.cancellation_fee_percent, reservation.cancellation_fee) else {
+            // キャンセルポリシー導入前にキャンセルされた予約
+            return Err(ApiError::ValidationError("この予約はキャンセル済みです".to_string()));
+        };
+
+        let payment = self
+            .payment_service
+            .settle_cancellation(&reservation.reservation_id, fee)
+            .await?;
+        let refund_amount = payment.as_ref().map_or(0, |payment| payment.refunded_amount);
+        self.repository
+            .record_refund_amount(&reservation.reservation_id, refund_amount)
+            .await?;
+        info!(
+            "キャンセルを精算しました: reservation_id={}, 返金額={}",
+            reservation.reservation_id, refund_amount
+        );
+
+        Ok(CancellationResult {
+            reservation_id: reservation.reservation_id,
+            cancelled_datetime: reservation.cancelled_datetime,
+            cancel_reason: reservation.cancel_reason,
+            fee_percent,
+            cancellation_fee: fee,
+            refund_amount,
+            payment,
+        })
+    }
+
+    /// 予約を取得し、予約した本人（管理者は全予約）であることを確認
+    async fn load_reservation(
+        &self,
+        identity: &UserIdentity,
+        reservation_id: &str,
+    ) -> Result<CancellationReservationRow, ApiError> {
+        let reservation = self
+            .repository
+            .find_reservation(reservation_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFoundError("指定された予約が存在しません".to_string()))?;
+
+        if !identity.is_admin() && reservation.user_login_id.as_deref() != Some(identity.user_id.as_str()) {
+            warn!(
+                "他ユーザーの予約のキャンセルを拒否: reservation_id={}, user_id={}",
+                reservation_id, identity.user_id
+            );
+            return Err(ApiError::AuthorizationError("予約した本人のみキャンセルできます".to_string()));
+        }
+        Ok(reservation)
+    }
+}
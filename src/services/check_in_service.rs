@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+
+use crate::config::postgresql_database::PostgresDatabase;
+use crate::controllers::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::check_in_model::{
+    CheckInAction, CheckInCodeClaims, CheckInCodeResponse, CheckInMethod, CheckInRecordRow, CheckInRejection,
+    CheckInResponse, CheckInSettings, CheckInTargetRow, LocationCheckInRequest, ScanCheckInRequest,
+};
+use crate::repositories::CheckInRepository;
+use crate::services::PaymentService;
+use crate::utils::env::{parse_env_or, require_signing_secret};
+
+/// 入出庫サービス
+///
+/// 入出庫は駐車場のスキャナーで署名付きQRコードを読み取るか、利用者の位置情報が駐車場の範囲内の場合だけ記録する。
+/// 受け付けなかった場合もエラーではなく理由付きの結果を返す
+pub struct CheckInService {
+    repository: CheckInRepository,
+    payment_service: Arc<PaymentService>,
+    settings: CheckInSettings,
+}
+
+impl CheckInService {
+    /// 新しいサービスインスタンスを作成
+    pub fn new(db: PostgresDatabase, payment_service: Arc<PaymentService>) -> Self {
+        Self {
+            repository: CheckInRepository::new(db),
+            payment_service,
+            settings: CheckInSettings {
+                signing_secret: require_signing_secret("CHECK_IN_CODE_SIGNING_SECRET"),
+                code_ttl_seconds: parse_env_or("CHECK_IN_CODE_TTL_SECS", 300),
+                early_check_in_minutes: parse_env_or("CHECK_IN_EARLY_MINUTES", 15),
+                geofence_radius_meters: parse_env_or("CHECK_IN_GEOFENCE_RADIUS_METERS", 150.0),
+                max_location_accuracy_meters: parse_env_or("CHECK_IN_MAX_LOCATION_ACCURACY_METERS", 100.0),
+            },
+        }
+    }
+
+    /// 入出庫用QRコードの発行（予約した本人のみ）
+    pub async fn issue_code(&self, identity: &UserIdentity, reservation_id: &str) -> Result<CheckInCodeResponse, ApiError> {
+        let target = self.load_target(reservation_id).await?;
+        if !is_reservation_user(identity, &target) {
+            return Err(ApiError::AuthorizationError("予約した本人のみQRコードを表示できます".to_string()));
+        }
+        if let Err(reason) = target.evaluate(None, Utc::now(), self.settings.early_check_in_minutes) {
+            // 入庫時間前でもQRコードは表示できる（読み取り時に入庫時間を確認する）
+            if reason != CheckInRejection::TooEarly {
+                return Err(ApiError::ValidationError(reason.message().to_string()));
+            }
+        }
+
+        let expires_at = Utc::now() + Duration::seconds(self.settings.code_ttl_seconds);
+        let claims = CheckInCodeClaims::new(&target.reservation_id, &target.parking_lot_id, expires_at);
+        Ok(CheckInCodeResponse {
+            reservation_id: target.reservation_id,
+            parking_lot_id: target.parking_lot_id,
+            code: claims.encode(&self.settings.signing_secret),
+            expires_at,
+        })
+    }
+
+    /// 駐車場のスキャナーでのQRコード読み取り（駐車場のオーナー確認は呼び出し側で行う）
+    pub async fn scan(&self, parking_lot_id: &str, req: ScanCheckInRequest) -> Result<CheckInResponse, ApiError> {
+        let method = CheckInMethod::QrScan;
+        let now = Utc::now();
+        let claims = match CheckInCodeClaims::decode(&req.code, &self.settings.signing_secret, now) {
+            Ok(claims) => claims,
+            Err(reason) => return Ok(self.reject(None, method, req.action, reason, None)),
+        };
+        if claims.parking_lot_id != parking_lot_id {
+            return Ok(self.reject(None, method, req.action, CheckInRejection::WrongParkingLot, None));
+        }
+        let Some(target) = self.repository.find_target(&claims.reservation_id).await? else {
+            return Ok(self.reject(None, method, req.action, CheckInRejection::InvalidCode, None));
+        };
+        if target.parking_lot_id != parking_lot_id {
+            return Ok(self.reject(Some(&target), method, req.action, CheckInRejection::WrongParkingLot, None));
+        }
+
+        let action = match target.evaluate(req.action, now, self.settings.early_check_in_minutes) {
+            Ok(action) => action,
+            Err(reason) => return Ok(self.reject(Some(&target), method, req.action, reason, None)),
+        };
+        if action == CheckInAction::CheckOut && target.entry_code_id.as_deref() == Some(claims.code_id.as_str()) {
+            return Ok(self.reject(Some(&target), method, Some(action), CheckInRejection::CodeAlreadyUsed, None));
+        }
+        self.record(&target, method, action, Some(&claims.code_id), None).await
+    }
+
+    /// 位置情報による入出庫（予約した本人のみ）
+    ///
+    /// status_idを指定した場合は予約の入出庫状況と一致するか確認する
+    pub async fn check_by_location(
+        &self,
+        identity: &UserIdentity,
+        reservation_id: &str,
+        req: LocationCheckInRequest,
+        status_id: Option<&str>,
+    ) -> Result<CheckInResponse, ApiError> {
+        let method = CheckInMethod::Geofence;
+        let location = req.location().map_err(ApiError::ValidationError)?;
+        let target = self.load_target(reservation_id).await?;
+        if !is_reservation_user(identity, &target) {
+            return Err(ApiError::AuthorizationError("予約した本人のみ入出庫できます".to_string()));
+        }
+        if status_id.is_some_and(|status_id| target.status_id.as_deref() != Some(status_id)) {
+            return Err(ApiError::ValidationError("入出庫状況IDが予約と一致しません".to_string()));
+        }
+
+        let action = match target.evaluate(req.action, Utc::now(), self.settings.early_check_in_minutes) {
+            Ok(action) => action,
+            Err(reason) => return Ok(self.reject(Some(&target), method, req.action, reason, None)),
+        };
+        let Some((latitude, longitude)) = location else {
+            return Ok(self.reject(Some(&target), method, Some(action), CheckInRejection::LocationRequired, None));
+        };
+        if req
+            .accuracy_meters
+            .is_some_and(|accuracy| accuracy > self.settings.max_location_accuracy_meters)
+        {
+            return Ok(self.reject(Some(&target), method, Some(action), CheckInRejection::LocationInaccurate, None));
+        }
+        let Some(distance) = target.distance_meters(latitude, longitude) else {
+            return Ok(self.reject(Some(&target), method, Some(action), CheckInRejection::LocationUnavailable, None));
+        };
+        if distance > self.settings.geofence_radius_meters {
+            let reason = CheckInRejection::OutsideGeofence;
+            return Ok(self.reject(Some(&target), method, Some(action), reason, Some(distance)));
+        }
+        self.record(&target, method, action, None, Some(distance)).await
+    }
+
+    /// 入出庫の記録（出庫時は売上を確定し、確定後に予約を完了にする）
+    async fn record(
+        &self,
+        target: &CheckInTargetRow,
+        method: CheckInMethod,
+        action: CheckInAction,
+        code_id: Option<&str>,
+        distance: Option<f64>,
+    ) -> Result<CheckInResponse, ApiError> {
+        let record = match action {
+            CheckInAction::CheckIn => self.repository.record_check_in(target, method, code_id).await?,
+            // 出庫済みで予約が完了になっていない場合は売上確定から再試行する
+            CheckInAction::CheckOut if target.is_checked_out() => {
+                target.status_id.clone().map(|status_id| CheckInRecordRow {
+                    status_id,
+                    recorded_datetime: target.exit_datetime,
+                })
+            }
+            CheckInAction::CheckOut => match target.status_id.as_deref() {
+                Some(status_id) => self.repository.record_check_out(status_id, method, code_id).await?,
+                None => None,
+            },
+        };
+        // 同時に入出庫された場合は先に記録された方を優先する
+        let Some(record) = record else {
+            let reason = match action {
+                CheckInAction::CheckIn => CheckInRejection::AlreadyCheckedIn,
+                CheckInAction::CheckOut => CheckInRejection::AlreadyCheckedOut,
+            };
+            return Ok(self.reject(Some(target), method, Some(action), reason, distance));
+        };
+
+        if action == CheckInAction::CheckOut {
+            // 売上確定に失敗した場合は予約を完了にしない（再度の出庫処理で再試行する）
+            self.payment_service.capture_for_checkout(&target.reservation_id).await?;
+            self.repository.complete_reservation(&target.reservation_id).await?;
+        }
+        info!(
+            "入出庫を記録しました: reservation_id={}, action={:?}, method={}, status_id={}",
+            target.reservation_id,
+            action,
+            method.as_str(),
+            record.status_id
+        );
+        Ok(CheckInResponse::accepted(target, method, action, record, distance))
+    }
+
+    fn reject(
+        &self,
+        target: Option<&CheckInTargetRow>,
+        method: CheckInMethod,
+        action: Option<CheckInAction>,
+        reason: CheckInRejection,
+        distance: Option<f64>,
+    ) -> CheckInResponse {
+        warn!(
+            "入出庫を受け付けませんでした: reservation_id={}, method={}, reason={:?}",
+            target.map(|t| t.reservation_id.as_str()).unwrap_or("-"),
+            method.as_str(),
+            reason
+        );
+        CheckInResponse::rejected(target, method, action, reason, distance)
+    }
+
+    async fn load_target(&self, reservation_id: &str) -> Result<CheckInTargetRow, ApiError> {
+        self.repository
+            .find_target(reservation_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFoundError("指定された予約が存在しません".to_string()))
+    }
+}
+
+fn is_reservation_user(identity: &UserIdentity, target: &CheckInTargetRow) -> bool {
+    target.user_login_id.as_deref() == Some(identity.user_id.as_str())
+}
@@ -1,10 +1,13 @@
 use std::env;
 use lettre::{
+    message::{header::ContentType, Attachment, MultiPart, SinglePart},
     transport::smtp::authentication::Credentials,
     Message, SmtpTransport, Transport,
 };
 use tracing::{debug, error, info};
 use crate::controllers::api_error::ApiError;
+use crate::models::receipt_model::ReceiptResponse;
+use crate::models::saved_search_model::SavedSearchMatch;
 
 /// メール送信サービス
 pub struct EmailService {
@@ -157,6 +160,86 @@ impl EmailService {
         self.send_email(to_email, subject, &body).await
     }
 
+    /// 保存検索に一致する駐車場の通知メールを送信する
+    pub async fn send_saved_search_alert(
+        &self,
+        to_email: &str,
+        search_name: &str,
+        matches: &[SavedSearchMatch],
+    ) -> Result<(), ApiError> {
+        debug!("Sending saved search alert to: {}", to_email);
+
+        if !self.is_configured() {
+            debug!("SMTP not configured, skipping actual email sending");
+            println!("Saved search alert for {}: {} ({}件)", to_email, search_name, matches.len());
+            return Ok(());
+        }
+
+        let subject = format!("パーキングアプリ - 「{}」の条件に合う駐車場が見つかりました", search_name);
+        let lots = matches
+            .iter()
+            .map(|m| format!("・{}（{}）", m.parking_lot_name, m.address))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let body = format!(
+            "こんにちは、\n\n\
+            保存した検索条件「{}」に合う駐車場が空いた、または新しく登録されました：\n\n\
+            {}\n\n\
+            アプリから詳細の確認・予約ができます。\n\n\
+            通知が不要な場合は、アプリの保存検索の設定から停止できます。\n\n\
+            よろしくお願いいたします。\n\
+            パーキングアプリチーム",
+            search_name, lots
+        );
+
+        self.send_email(to_email, &subject, &body).await
+    }
+
+    /// 領収書のPDFを添付して送信する
+    pub async fn send_receipt(
+        &self,
+        to_email: &str,
+        receipt: &ReceiptResponse,
+        pdf: Vec<u8>,
+    ) -> Result<(), ApiError> {
+        debug!("Sending receipt {} to: {}", receipt.receipt_number, to_email);
+
+        if !self.is_configured() {
+            debug!("SMTP not configured, skipping actual email sending");
+            println!("Receipt {} for {}: {} bytes", receipt.receipt_number, to_email, pdf.len());
+            return Ok(());
+        }
+
+        let subject = format!("パーキングアプリ - 領収書（{}）", receipt.receipt_number);
+        let body = format!(
+            "こんにちは、\n\n\
+            ご利用いただいた駐車場の領収書をお送りします。\n\n\
+            領収書番号：{}\n\
+            駐車場：{}\n\
+            金額：{}円（税込）\n\n\
+            領収書は添付のPDFをご確認ください。アプリからも再度ダウンロードできます。\n\n\
+            よろしくお願いいたします。\n\
+            パーキングアプリチーム",
+            receipt.receipt_number, receipt.parking_lot_name, receipt.total_amount
+        );
+        let content_type = ContentType::parse("application/pdf").map_err(|e| {
+            error!("Failed to parse content type: {}", e);
+            ApiError::InternalServerError
+        })?;
+        let multipart = MultiPart::mixed()
+            .singlepart(SinglePart::plain(body))
+            .singlepart(Attachment::new(receipt.pdf_filename()).body(pdf, content_type));
+
+        let email = self
+            .message_builder(to_email, &subject)?
+            .multipart(multipart)
+            .map_err(|e| {
+                error!("Failed to build email: {}", e);
+                ApiError::InternalServerError
+            })?;
+        self.deliver(to_email, &email)
+    }
+
     /// 基本的なメール送信メソッド
     async fn send_email(
         &self, 
@@ -164,7 +247,19 @@ impl EmailService {
         subject: &str, 
         body: &str
     ) -> Result<(), ApiError> {
-        let email = Message::builder()
+        let email = self
+            .message_builder(to_email, subject)?
+            .body(body.to_string())
+            .map_err(|e| {
+                error!("Failed to build email: {}", e);
+                ApiError::InternalServerError
+            })?;
+        self.deliver(to_email, &email)
+    }
+
+    /// 送信元・宛先・件名を設定したメッセージ
+    fn message_builder(&self, to_email: &str, subject: &str) -> Result<lettre::message::MessageBuilder, ApiError> {
+        Ok(Message::builder()
             .from(format!("{} <{}>", self.from_name, self.from_email).parse().map_err(|e| {
                 error!("Failed to parse from address: {}", e);
                 ApiError::InternalServerError
@@ -173,13 +268,11 @@ impl EmailService {
                 error!("Failed to parse to address: {}", e);
                 ApiError::ValidationError(format!("無効なメールアドレス: {}", e))
             })?)
-            .subject(subject)
-            .body(body.to_string())
-            .map_err(|e| {
-                error!("Failed to build email: {}", e);
-                ApiError::InternalServerError
-            })?;
+            .subject(subject))
+    }
 
+    /// SMTPでメッセージを送信する
+    fn deliver(&self, to_email: &str, email: &Message) -> Result<(), ApiError> {
         // SMTP トランスポートの設定
         let creds = Credentials::new(
             self.smtp_username.clone(),
@@ -197,7 +290,7 @@ impl EmailService {
             .build();
 
         // メールを送信
-        match mailer.send(&email) {
+        match mailer.send(email) {
             Ok(_) => {
                 info!("Email sent successfully to: {}", to_email);
                 Ok(())
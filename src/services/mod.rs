@@ -34,6 +34,26 @@ pub mod user_home_service;
 pub use user_home_service::UserHomeService;
 pub mod use_history_service;
 pub use use_history_service::UseHistoryService;
+pub mod profile_service;
+pub use profile_service::ProfileService;
+pub mod account_service;
+pub use account_service::AccountService;
+pub mod vehicle_service;
+pub use vehicle_service::VehicleService;
+pub mod saved_search_service;
+pub use saved_search_service::SavedSearchService;
+pub mod notification_service;
+pub use notification_service::NotificationService;
+pub mod owner_analytics_service;
+pub use owner_analytics_service::OwnerAnalyticsService;
+pub mod payment_service;
+pub use payment_service::PaymentService;
+pub mod cancellation_policy_service;
+pub use cancellation_policy_service::CancellationPolicyService;
+pub mod receipt_service;
+pub use receipt_service::ReceiptService;
+pub mod check_in_service;
+pub use check_in_service::CheckInService;
 
 /// サービス層の初期化関数
 pub fn init() {
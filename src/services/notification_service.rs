@@ -0,0 +1,81 @@
+use uuid::Uuid;
+
+use crate::config::postgresql_database::PostgresDatabase;
+use crate::controllers::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::notification_model::{NotificationListQuery, NotificationListResponse, NotificationResponse};
+use crate::repositories::{NotificationRepository, SavedSearchRepository};
+
+/// アプリ内通知サービス
+pub struct NotificationService {
+    repository: NotificationRepository,
+    user_repository: SavedSearchRepository,
+}
+
+impl NotificationService {
+    /// 新しいサービスインスタンスを作成
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self {
+            repository: NotificationRepository::new(db.clone()),
+            user_repository: SavedSearchRepository::new(db),
+        }
+    }
+
+    /// 通知一覧（新しい順）と未読件数
+    pub async fn list_notifications(
+        &self,
+        identity: &UserIdentity,
+        query: NotificationListQuery,
+    ) -> Result<NotificationListResponse, ApiError> {
+        let page = query.page.unwrap_or(1);
+        let page_size = query.page_size.unwrap_or(20);
+        if page < 1 || !(1..=100).contains(&page_size) {
+            return Err(ApiError::ValidationError(
+                "pageは1以上、page_sizeは1以上100以下で指定してください".to_string(),
+            ));
+        }
+
+        let user_id = self.load_user_id(identity).await?;
+        let rows = self
+            .repository
+            .list_notifications(
+                &user_id,
+                query.unread_only.unwrap_or(false),
+                page_size.into(),
+                ((page - 1) as i64) * page_size as i64,
+            )
+            .await?;
+        let unread_count = self.repository.count_unread(&user_id).await?;
+
+        Ok(NotificationListResponse {
+            notifications: rows.into_iter().map(NotificationResponse::from).collect(),
+            unread_count,
+            page,
+            page_size,
+        })
+    }
+
+    /// 通知を既読にする
+    pub async fn mark_read(&self, identity: &UserIdentity, notification_id: Uuid) -> Result<(), ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        if !self.repository.mark_read(&user_id, notification_id).await? {
+            return Err(ApiError::NotFoundError("通知が見つかりません".to_string()));
+        }
+        Ok(())
+    }
+
+    /// すべての通知を既読にする（更新件数を返す）
+    pub async fn mark_all_read(&self, identity: &UserIdentity) -> Result<u64, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        Ok(self.repository.mark_all_read(&user_id).await?)
+    }
+
+    async fn load_user_id(&self, identity: &UserIdentity) -> Result<String, ApiError> {
+        let login_id = Uuid::parse_str(&identity.user_id)
+            .map_err(|_| ApiError::AuthenticationError("ログイン情報が正しくありません".to_string()))?;
+        self.user_repository
+            .find_user_id(login_id)
+            .await?
+            .ok_or_else(|| ApiError::AuthorizationError("通知は利用者アカウントのみ利用できます".to_string()))
+    }
+}
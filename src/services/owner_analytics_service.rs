@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+use tracing::{info, warn};
+
+use crate::config::postgresql_database::PostgresDatabase;
+use crate::controllers::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::owner_analytics_model::{
+    AnalyticsBucketRow, AnalyticsPeriod, AnalyticsSummary, OwnerAnalyticsQuery, OwnerAnalyticsResponse,
+    ParkingLotAnalytics,
+};
+use crate::repositories::OwnerAnalyticsRepository;
+
+const MINUTES_PER_DAY: f64 = 24.0 * 60.0;
+
+/// オーナー向け売上・稼働分析サービス
+pub struct OwnerAnalyticsService {
+    repository: OwnerAnalyticsRepository,
+}
+
+impl OwnerAnalyticsService {
+    /// 新しいサービスインスタンスを作成
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self { repository: OwnerAnalyticsRepository::new(db) }
+    }
+
+    /// 駐車場ごとの売上・稼働分析（管理者は全オーナーの駐車場が対象）
+    pub async fn get_analytics(
+        &self,
+        identity: &UserIdentity,
+        query: &OwnerAnalyticsQuery,
+    ) -> Result<OwnerAnalyticsResponse, ApiError> {
+        if !identity.is_owner() && !identity.is_admin() {
+            warn!("オーナー以外による売上分析の参照: user_id={}", identity.user_id);
+            return Err(ApiError::AuthorizationError("オーナーのみ利用できます".to_string()));
+        }
+
+        let jst = FixedOffset::east_opt(9 * 3600).expect("日本時間のオフセット");
+        let today = Utc::now().with_timezone(&jst).date_naive();
+        let (from, to) = query.date_range(today).map_err(ApiError::ValidationError)?;
+        let granularity = query.granularity.unwrap_or_default();
+        let owner_login_id = (!identity.is_admin()).then_some(identity.user_id.as_str());
+        let parking_lot_id = query.parking_lot_id();
+
+        let lots = self.repository.list_lots(owner_login_id, parking_lot_id).await?;
+        if parking_lot_id.is_some() && lots.is_empty() {
+            return Err(ApiError::NotFoundError("指定された駐車場が存在しません".to_string()));
+        }
+
+        // 日本時間の開始日0時から終了日翌日0時まで
+        let start_of = |date: NaiveDate| {
+            jst.from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+                .single()
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_default()
+        };
+        let range_start = start_of(from);
+        let range_end = start_of(to.succ_opt().unwrap_or(to));
+
+        let rows = self
+            .repository
+            .aggregate_buckets(owner_login_id, parking_lot_id, range_start, range_end, granularity)
+            .await?;
+        let mut buckets: HashMap<&str, HashMap<NaiveDate, &AnalyticsBucketRow>> = HashMap::new();
+        for row in &rows {
+            buckets.entry(row.parking_lot_id.as_str()).or_default().insert(row.period_start, row);
+        }
+        let mut peak_hours: HashMap<String, Vec<Vec<i64>>> = HashMap::new();
+        for row in self
+            .repository
+            .aggregate_peak_hours(owner_login_id, parking_lot_id, range_start, range_end)
+            .await?
+        {
+            let grid = peak_hours.entry(row.parking_lot_id).or_insert_with(|| vec![vec![0; 24]; 7]);
+            if let Some(cell) = grid.get_mut(row.weekday as usize).and_then(|hours| hours.get_mut(row.hour as usize)) {
+                *cell += row.entry_count;
+            }
+        }
+
+        // 集計期間を集計単位で区切り、予約のない期間も0件として返す
+        let mut periods = Vec::new();
+        let mut period_start = granularity.truncate(from);
+        while period_start <= to {
+            let next_start = granularity.next_start(period_start);
+            let clipped = (period_start.max(from), next_start.pred_opt().unwrap_or(next_start).min(to));
+            periods.push((period_start, clipped));
+            period_start = next_start;
+        }
+        let range_days = ((to - from).num_days() + 1) as f64;
+
+        // 全駐車場の合計（稼働率は全駐車場の収容台数に対する割合）
+        let total_capacity: f64 = lots.iter().map(|lot| f64::from(lot.capacity.max(0))).sum();
+        let summary = AnalyticsSummary::from_rows(&rows, total_capacity * range_days * MINUTES_PER_DAY);
+
+        let lots: Vec<ParkingLotAnalytics> = lots
+            .into_iter()
+            .map(|lot| {
+                let lot_buckets = buckets.remove(lot.parking_lot_id.as_str()).unwrap_or_default();
+                let capacity = f64::from(lot.capacity.max(0));
+                let lot_periods = periods
+                    .iter()
+                    .map(|(key, (start, end))| {
+                        let days = ((*end - *start).num_days() + 1) as f64;
+                        AnalyticsPeriod {
+                            period_start: *start,
+                            period_end: *end,
+                            summary: AnalyticsSummary::from_rows(
+                                lot_buckets.get(key).copied(),
+                                capacity * days * MINUTES_PER_DAY,
+                            ),
+                        }
+                    })
+                    .collect();
+                ParkingLotAnalytics {
+                    summary: AnalyticsSummary::from_rows(
+                        lot_buckets.values().copied(),
+                        capacity * range_days * MINUTES_PER_DAY,
+                    ),
+                    periods: lot_periods,
+                    peak_hours: peak_hours
+                        .remove(&lot.parking_lot_id)
+                        .unwrap_or_else(|| vec![vec![0; 24]; 7]),
+                    parking_lot_id: lot.parking_lot_id,
+                    parking_lot_name: lot.parking_lot_name,
+                    capacity: lot.capacity,
+                }
+            })
+            .collect();
+
+        info!(
+            "売上・稼働分析を作成しました: user_id={}, 駐車場数={}, 期間={}〜{}",
+            identity.user_id,
+            lots.len(),
+            from,
+            to
+        );
+        Ok(OwnerAnalyticsResponse { from, to, granularity, summary, lots })
+    }
+}
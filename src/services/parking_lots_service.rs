@@ -1,20 +1,432 @@
 
 use chrono::Utc;
+use std::collections::HashSet;
+use std::sync::Arc;
+use sqlx::{Postgres, Transaction};
 use crate::controllers::ApiError;
-use crate::models::parking_lots_model::{ParkingImageRequest, ParkingLotRequest, ParkingLotRow};
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::parking_lots_model::{
+    ParkingImageOrderRequest, ParkingImageRequest, ParkingImageResponse, ParkingImageRow,
+    ParkingImageUpdateRequest, ParkingImageUploadResponse, ParkingLotOwnerRow, ParkingLotRequest, ParkingLotRow,
+};
 use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::geocoding::{describe_candidates, normalize_address, normalize_postal_code, Geocoder, StationMaster, StationMatch};
 use crate::repositories::ParkingLotsRepository;
-use tracing::error;
+use crate::storage::{normalize_blob_key, signed_url_for_stored, BlobStore, BlobStoreConfig};
+use crate::utils::image_processing::{process_image, ImageUploadConfig, ProcessedImage};
+use tracing::{error, info, warn};
 
 pub struct ParkingLotsService {
     repository: ParkingLotsRepository,
+    image_config: ImageUploadConfig,
+    blob_store: Arc<dyn BlobStore>,
+    blob_config: BlobStoreConfig,
+    geocoder: Arc<dyn Geocoder>,
+    stations: Arc<StationMaster>,
 }
 
 impl ParkingLotsService {
     /// 新しいサービスインスタンスを作成
-    pub fn new(db: PostgresDatabase) -> Self {
+    pub fn new(
+        db: PostgresDatabase,
+        blob_store: Arc<dyn BlobStore>,
+        blob_config: BlobStoreConfig,
+        geocoder: Arc<dyn Geocoder>,
+        stations: Arc<StationMaster>,
+    ) -> Self {
         let repository = ParkingLotsRepository::new(db);
-        Self { repository }
+        Self {
+            repository,
+            image_config: ImageUploadConfig::from_env(),
+            blob_store,
+            blob_config,
+            geocoder,
+            stations,
+        }
+    }
+
+    /// 最寄り駅を駅マスタに紐付ける
+    ///
+    /// nearest_station_id を優先し、未指定の場合は nearest_station の駅名から特定する。
+    /// nearest_station には駅マスタの表示名（例: 新宿駅）を保存する
+    pub fn link_nearest_station(&self, req: &mut ParkingLotRequest) -> Result<(), ApiError> {
+        let station_id = req.nearest_station_id.as_deref().map(str::trim).filter(|id| !id.is_empty());
+        let station_name = req.nearest_station.as_deref().map(str::trim).filter(|name| !name.is_empty());
+
+        let station = match (station_id, station_name) {
+            (Some(station_id), _) => self
+                .stations
+                .find_by_id(station_id)
+                .ok_or_else(|| ApiError::ValidationError("指定された最寄り駅が存在しません".to_string()))?,
+            (None, Some(name)) => match self.stations.resolve_name(name) {
+                StationMatch::Found(station) => station,
+                StationMatch::Ambiguous(candidates) => {
+                    return Err(ApiError::ValidationError(format!(
+                        "同名の駅が複数あります。nearest_station_idを指定してください（{}）",
+                        describe_candidates(&candidates)
+                    )));
+                }
+                StationMatch::NotFound => {
+                    return Err(ApiError::ValidationError(format!("最寄り駅が駅マスタに存在しません: {}", name)));
+                }
+            },
+            (None, None) => {
+                req.nearest_station = None;
+                req.nearest_station_id = None;
+                return Ok(());
+            }
+        };
+
+        req.nearest_station_id = Some(station.station_id.clone());
+        req.nearest_station = Some(station.display_name());
+        Ok(())
+    }
+
+    /// 郵便番号から都道府県・市区町村を検証・補完し、未入力の緯度経度を住所から補完
+    ///
+    /// データセットにない郵便番号の場合は補完せず、入力値のまま検証に回す
+    pub async fn fill_address(&self, req: &mut ParkingLotRequest) -> Result<(), ApiError> {
+        let Some(postal_code) = normalize_postal_code(&req.postal_code) else {
+            return Ok(());
+        };
+        req.postal_code = postal_code;
+        req.prefecture = req.prefecture.trim().to_string();
+        req.city = req.city.trim().to_string();
+
+        let located = self.geocoder.lookup_postal_code(&req.postal_code).await?;
+        if let Some(located) = &located {
+            if req.prefecture.is_empty() {
+                req.prefecture = located.prefecture.clone();
+            } else if req.prefecture != located.prefecture {
+                return Err(ApiError::ValidationError(format!(
+                    "郵便番号と都道府県が一致しません（〒{}: {}）",
+                    req.postal_code, located.prefecture
+                )));
+            }
+
+            let city = normalize_address(&req.city);
+            if req.city.is_empty() {
+                req.city = located.city.clone();
+            } else if !located.city.starts_with(&city) && !city.starts_with(&located.city) {
+                return Err(ApiError::ValidationError(format!(
+                    "郵便番号と市区町村が一致しません（〒{}: {}）",
+                    req.postal_code, located.city
+                )));
+            }
+        }
+
+        if req.latitude.trim().is_empty() || req.longitude.trim().is_empty() {
+            let query = format!("{}{}{}{}", req.postal_code, req.prefecture, req.city, req.address_detail);
+            if let Some(point) = self.geocoder.geocode(&query).await?.or(located) {
+                info!(
+                    "住所から緯度経度を補完しました: 〒{} -> ({:.6}, {:.6})",
+                    req.postal_code, point.latitude, point.longitude
+                );
+                req.latitude = format!("{:.6}", point.latitude);
+                req.longitude = format!("{:.6}", point.longitude);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 画像アップロード設定
+    pub fn image_config(&self) -> &ImageUploadConfig {
+        &self.image_config
+    }
+
+    /// 駐車場の存在と、リクエストユーザーが所有オーナーであることを確認
+    pub async fn authorize_parking_lot_owner(
+        &self,
+        parking_lot_id: &str,
+        identity: &UserIdentity,
+    ) -> Result<ParkingLotOwnerRow, ApiError> {
+        if !identity.is_owner() && !identity.is_admin() {
+            warn!("オーナー以外による駐車場操作: user_id={}", identity.user_id);
+            return Err(ApiError::AuthorizationError("オーナーのみ操作できます".to_string()));
+        }
+
+        let lot = self
+            .repository
+            .find_parking_lot_owner(parking_lot_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFoundError("指定された駐車場が存在しません".to_string()))?;
+
+        if !identity.is_admin() && lot.owner_login_id.as_deref() != Some(identity.user_id.as_str()) {
+            warn!(
+                "他オーナーの駐車場への操作を拒否: parking_lot_id={}, user_id={}",
+                parking_lot_id, identity.user_id
+            );
+            return Err(ApiError::AuthorizationError("この駐車場を操作する権限がありません".to_string()));
+        }
+
+        Ok(lot)
+    }
+
+    /// アップロード画像を検証・変換して保存し、t_parking_imagesへ登録
+    ///
+    /// 登録済みと同一内容（ハッシュ一致）の画像はスキップする
+    pub async fn store_parking_lot_images(
+        &self,
+        parking_lot_id: &str,
+        uploads: Vec<Vec<u8>>,
+    ) -> Result<ParkingImageUploadResponse, ApiError> {
+        if uploads.is_empty() {
+            return Err(ApiError::BadRequest("画像ファイルがありません".to_string()));
+        }
+
+        let existing_hashes = self.repository.find_parking_image_hashes(parking_lot_id).await?;
+        let existing_count = existing_hashes.len() as i64;
+        let mut known_hashes: HashSet<String> = existing_hashes.into_iter().flatten().collect();
+
+        // 画像のデコード・リサイズはCPU負荷が高いためブロッキングスレッドで実行
+        let config = self.image_config.clone();
+        let processed = tokio::task::spawn_blocking(move || {
+            uploads
+                .iter()
+                .map(|bytes| process_image(bytes, &config))
+                .collect::<Result<Vec<ProcessedImage>, ApiError>>()
+        })
+        .await
+        .map_err(|e| {
+            error!("画像処理タスクの実行に失敗: {}", e);
+            ApiError::InternalServerError
+        })??;
+
+        let total = processed.len();
+        let new_images: Vec<ProcessedImage> = processed
+            .into_iter()
+            .filter(|image| known_hashes.insert(image.content_hash.clone()))
+            .collect();
+
+        if existing_count + new_images.len() as i64 > self.image_config.max_images_per_parking_lot {
+            return Err(ApiError::ValidationError(format!(
+                "1つの駐車場に登録できる画像は{}枚までです（登録済み: {}枚）",
+                self.image_config.max_images_per_parking_lot, existing_count
+            )));
+        }
+
+        let mut written: Vec<String> = Vec::new();
+        let mut records: Vec<ParkingImageRequest> = Vec::with_capacity(new_images.len());
+
+        for image in &new_images {
+            let key_of = |filename: String| format!("{}/{}/{}", self.image_config.key_prefix, parking_lot_id, filename);
+            let files = [
+                (key_of(image.original_filename()), &image.original),
+                (key_of(image.medium_filename()), &image.medium),
+                (key_of(image.thumbnail_filename()), &image.thumbnail),
+            ];
+            for (key, bytes) in &files {
+                if let Err(e) = self.blob_store.put(key, bytes.to_vec(), "image/jpeg").await {
+                    error!("画像ファイルの保存に失敗: {} - {}", key, e);
+                    self.delete_unreferenced_blobs(&written).await;
+                    return Err(e.into());
+                }
+                written.push(key.clone());
+            }
+
+            let [(image_url, _), (medium_url, _), (thumbnail_url, _)] = files;
+            records.push(ParkingImageRequest {
+                parking_lot_id: parking_lot_id.to_string(),
+                image_url,
+                medium_url: Some(medium_url),
+                thumbnail_url: Some(thumbnail_url),
+                content_hash: Some(image.content_hash.clone()),
+                width: Some(image.width as i32),
+                height: Some(image.height as i32),
+                file_size: Some(image.original.len() as i32),
+            });
+        }
+
+        let records = if records.is_empty() {
+            records
+        } else {
+            match self.upload_parking_lot_images(parking_lot_id, records).await {
+                Ok(inserted) => inserted,
+                Err(e) => {
+                    self.delete_unreferenced_blobs(&written).await;
+                    return Err(e);
+                }
+            }
+        };
+        // 並行アップロードで先に登録された画像は重複としてスキップ扱い
+        let skipped_duplicates = total - records.len();
+
+        info!(
+            "駐車場画像を登録しました: parking_lot_id={}, 登録={}, 重複スキップ={}",
+            parking_lot_id,
+            records.len(),
+            skipped_duplicates
+        );
+
+        Ok(ParkingImageUploadResponse {
+            uploaded: records.len(),
+            skipped_duplicates,
+            images: records
+                .iter()
+                .map(|r| self.signed_url(&r.image_url))
+                .collect::<Result<Vec<_>, _>>()?,
+            details: records,
+        })
+    }
+
+    /// 駐車場の画像一覧を表示順で取得（URLは署名済み）
+    pub async fn list_parking_lot_images(&self, parking_lot_id: &str) -> Result<Vec<ParkingImageResponse>, ApiError> {
+        if self.repository.find_parking_lot_owner(parking_lot_id).await?.is_none() {
+            return Err(ApiError::NotFoundError("指定された駐車場が存在しません".to_string()));
+        }
+
+        let rows = self.repository.find_parking_images(parking_lot_id).await?;
+        self.to_image_responses(rows)
+    }
+
+    /// 駐車場画像を削除し、保存済みファイル（全サイズ）も削除する
+    ///
+    /// カバー画像を削除した場合は表示順の先頭画像を新しいカバーにする
+    pub async fn delete_parking_lot_image(
+        &self,
+        parking_lot_id: &str,
+        image_id: i32,
+    ) -> Result<Vec<ParkingImageResponse>, ApiError> {
+        let mut tx = self.repository.begin_transaction().await?;
+        let now = Utc::now();
+
+        let deleted = self
+            .repository
+            .delete_parking_image(&mut tx, parking_lot_id, image_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFoundError("指定された画像が存在しません".to_string()))?;
+        if deleted.is_cover {
+            self.repository.ensure_parking_image_cover(&mut tx, parking_lot_id, now).await?;
+        }
+        commit_transaction(tx).await?;
+
+        // DB削除の確定後にファイルを削除（失敗してもDBとの不整合は参照されないファイルが残るのみ）
+        let keys: Vec<String> = [Some(deleted.image_url), deleted.medium_url, deleted.thumbnail_url]
+            .into_iter()
+            .flatten()
+            .map(|key| normalize_blob_key(&key))
+            .collect();
+        self.delete_blobs(&keys).await;
+
+        info!("駐車場画像を削除しました: parking_lot_id={}, image_id={}", parking_lot_id, image_id);
+        self.list_parking_lot_images(parking_lot_id).await
+    }
+
+    /// 駐車場画像の表示順を変更
+    ///
+    /// 登録済みの全画像IDを新しい順序で指定する必要がある
+    pub async fn reorder_parking_lot_images(
+        &self,
+        parking_lot_id: &str,
+        req: &ParkingImageOrderRequest,
+    ) -> Result<Vec<ParkingImageResponse>, ApiError> {
+        let current: HashSet<i32> = self
+            .repository
+            .find_parking_images(parking_lot_id)
+            .await?
+            .iter()
+            .map(|row| row.id)
+            .collect();
+        let requested: HashSet<i32> = req.image_ids.iter().copied().collect();
+
+        if requested.len() != req.image_ids.len() {
+            return Err(ApiError::ValidationError("画像IDが重複しています".to_string()));
+        }
+        if requested != current {
+            return Err(ApiError::ValidationError(
+                "登録済みの全画像IDを指定してください".to_string(),
+            ));
+        }
+
+        let mut tx = self.repository.begin_transaction().await?;
+        self.repository
+            .update_parking_image_sort_orders(&mut tx, parking_lot_id, &req.image_ids, Utc::now())
+            .await?;
+        commit_transaction(tx).await?;
+
+        self.list_parking_lot_images(parking_lot_id).await
+    }
+
+    /// 駐車場画像の説明文・カバー設定を更新
+    pub async fn update_parking_lot_image(
+        &self,
+        parking_lot_id: &str,
+        image_id: i32,
+        req: &ParkingImageUpdateRequest,
+    ) -> Result<ParkingImageResponse, ApiError> {
+        req.validate().map_err(ApiError::ValidationError)?;
+
+        let exists = self
+            .repository
+            .find_parking_images(parking_lot_id)
+            .await?
+            .iter()
+            .any(|row| row.id == image_id);
+        if !exists {
+            return Err(ApiError::NotFoundError("指定された画像が存在しません".to_string()));
+        }
+
+        let mut tx = self.repository.begin_transaction().await?;
+        let now = Utc::now();
+
+        if let Some(caption) = &req.caption {
+            let caption = Some(caption.trim()).filter(|c| !c.is_empty());
+            self.repository
+                .update_parking_image_caption(&mut tx, parking_lot_id, image_id, caption, now)
+                .await?;
+        }
+        if req.is_cover == Some(true) {
+            self.repository
+                .set_parking_image_cover(&mut tx, parking_lot_id, image_id, now)
+                .await?;
+        }
+        commit_transaction(tx).await?;
+
+        self.list_parking_lot_images(parking_lot_id)
+            .await?
+            .into_iter()
+            .find(|image| image.image_id == image_id)
+            .ok_or_else(|| ApiError::NotFoundError("指定された画像が存在しません".to_string()))
+    }
+
+    /// 画像行を署名済みURL付きのレスポンスに変換
+    fn to_image_responses(&self, rows: Vec<ParkingImageRow>) -> Result<Vec<ParkingImageResponse>, ApiError> {
+        rows.into_iter()
+            .map(|row| row.into_response(|key| self.signed_url(key)))
+            .collect()
+    }
+
+    /// 保存キーから期限付きの読み取りURLを生成
+    pub fn signed_url(&self, stored_key: &str) -> Result<String, ApiError> {
+        Ok(signed_url_for_stored(self.blob_store.as_ref(), stored_key, self.blob_config.signed_url_ttl())?)
+    }
+
+    /// 保存済みファイルを削除（ロールバック用）
+    /// 保存済みファイルのうち、どの画像レコードからも参照されていないものだけを削除
+    ///
+    /// キーは内容ハッシュから決まるため、並行アップロードで登録されたレコードが同じファイルを参照している場合がある
+    async fn delete_unreferenced_blobs(&self, keys: &[String]) {
+        if keys.is_empty() {
+            return;
+        }
+        let referenced: HashSet<String> = match self.repository.find_referenced_image_keys(keys).await {
+            Ok(referenced) => referenced.into_iter().collect(),
+            Err(e) => {
+                warn!("画像ファイルの参照確認に失敗したため削除を見送ります: {}", e);
+                return;
+            }
+        };
+        let orphaned: Vec<String> = keys.iter().filter(|k| !referenced.contains(*k)).cloned().collect();
+        self.delete_blobs(&orphaned).await;
+    }
+
+    async fn delete_blobs(&self, keys: &[String]) {
+        for key in keys {
+            if let Err(e) = self.blob_store.delete(key).await {
+                warn!("画像ファイルの削除に失敗: {} - {}", key, e);
+            }
+        }
     }
 
     pub async fn register_parking_lot(&self, req: ParkingLotRequest) -> Result<ParkingLotRow, ApiError> {
@@ -45,10 +457,14 @@ impl ParkingLotsService {
         Ok(parking_lot_row)
     }
 
+    /// 駐車場行をロックした上で画像を登録し、実際に登録したレコードを返す
+    ///
+    /// 枚数上限と重複判定は挿入と同じトランザクション内で行い、並行アップロードでも上限を超えないようにする
     pub async fn upload_parking_lot_images(
         &self,
-        req_list: &Vec<ParkingImageRequest>,
-    ) -> Result<(), ApiError> {
+        parking_lot_id: &str,
+        req_list: Vec<ParkingImageRequest>,
+    ) -> Result<Vec<ParkingImageRequest>, ApiError> {
         if req_list.is_empty() {
             return Err(ApiError::BadRequest("画像リストが空です".to_string()));
         }
@@ -56,12 +472,36 @@ impl ParkingLotsService {
         // 开启事务
         let mut tx = self.repository.begin_transaction().await?;
 
-        let now = Utc::now();
+        // 锁定停车场行，串行化同一停车场的图片登记
+        let existing_hashes = self
+            .repository
+            .lock_parking_image_hashes(&mut tx, parking_lot_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFoundError("指定された駐車場が存在しません".to_string()))?;
+        let existing_count = existing_hashes.len() as i64;
+        let mut known_hashes: HashSet<String> = existing_hashes.into_iter().flatten().collect();
 
-        // 插入多张图片
-        self.repository
-            .insert_parking_images(&mut tx, &req_list, now)
-            .await?;
+        // 跳过其他请求已登记的同一图片
+        let new_list: Vec<ParkingImageRequest> = req_list
+            .into_iter()
+            .filter(|r| r.content_hash.as_ref().is_none_or(|h| known_hashes.insert(h.clone())))
+            .collect();
+
+        if existing_count + new_list.len() as i64 > self.image_config.max_images_per_parking_lot {
+            return Err(ApiError::ValidationError(format!(
+                "1つの駐車場に登録できる画像は{}枚までです（登録済み: {}枚）",
+                self.image_config.max_images_per_parking_lot, existing_count
+            )));
+        }
+
+        if !new_list.is_empty() {
+            let now = Utc::now();
+
+            // 插入多张图片
+            self.repository
+                .insert_parking_images(&mut tx, &new_list, now)
+                .await?;
+        }
 
         // 提交事务
         tx.commit().await.map_err(|e| {
@@ -69,7 +509,14 @@ impl ParkingLotsService {
             DatabaseError::TransactionError(format!("トランザクションコミットに失敗: {}", e))
         })?;
 
-        Ok(())
+        Ok(new_list)
     }
+}
 
+/// トランザクションをコミット
+async fn commit_transaction(tx: Transaction<'static, Postgres>) -> Result<(), ApiError> {
+    tx.commit().await.map_err(|e| {
+        error!("トランザクションコミットに失敗: {}", e);
+        DatabaseError::TransactionError(format!("トランザクションコミットに失敗: {}", e)).into()
+    })
 }
@@ -1,24 +1,101 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
 
 use crate::{
     config::postgresql_database::{DatabaseError, PostgresDatabase},
     controllers::api_error::ApiError,
+    middlewares::identity_middleware::UserIdentity,
     models::parking_search_model::{
         ParkingSearchRequest, ParkingSearchResponse, ParkingSearchResult,
         PaginationInfo, SearchInfo, SearchStats, DistanceInfo, AvailabilityInfo,
         PricingInfo, FavoriteInfo, RatingInfo, FavoriteOperationRequest, FavoriteOperationResponse,
-        SortInfo, PriceRange,
+        SortInfo, PriceRange, VehicleCompatibility, VehicleDimensions, SearchSortBy, RecommendationScore, ScoreComponent,
+        FavoriteListCriteria, FavoriteUpdateRequest, FavoriteOrderRequest, FavoriteLabelCount,
+        normalize_favorite_labels, SearchFilterCriteria, PriceRangeBucket, PRICE_RANGE_BUCKETS,
+        SearchFilterCatalogue, SearchFilterCatalogueQuery, FilterOption, FeatureFilterGroup,
+        NearbyParkingQuery, NearbyParkingResponse, ParkingStatsQuery, ParkingStatsResponse,
+        MapViewportQuery, MapSearchResponse, MAP_CLUSTER_MAX_ZOOM, MAP_MAX_PINS,
+        validate_paging, PageQuery, PagedResponse, SearchPageCriteria,
+        SCORE_FULL_USAGE_COUNT, SCORE_WEIGHT_DISTANCE, SCORE_WEIGHT_PRICE, SCORE_WEIGHT_USAGE,
+    },
+    repositories::{
+        parking_search_repository::{
+            favorite_cursor_keys, search_bounds, search_cursor_keys, ParkingLotWithRelations, ParkingSearchRepository,
+            SearchLotMetrics,
+        },
+        search_history_repository::search_history_cursor_keys,
+        SearchHistoryRepository,
     },
-    repositories::parking_search_repository::ParkingSearchRepository,
+    models::parking_search_history_model::{ParkingSearchHistoryResponse, SearchHistoryCriteria},
+    utils::env::parse_env_or,
     models::{
         t_parking_lots_model::{TParkingLotsModel, ParkingLotResponse},
         t_parking_google_maps_model::{TParkingGoogleMapsModel, GoogleMapsResponse},
         t_parking_rental_types_model::{TParkingRentalTypesModel, RentalTypeResponse},
         m_parking_vehicle_types_model::{MParkingVehicleTypesModel, VehicleTypeResponse},
         m_parking_features_model::{MParkingFeaturesModel, ParkingFeatureResponse},
+        parking_lots_model::{ParkingImageResponse, ParkingLimitModel},
+    },
+    geocoding::{describe_candidates, GeocodeResult, Geocoder, Station, StationMaster, StationMatch},
+    models::parking_search_model::{SearchLocation, StationWalkInfo},
+    storage::{signed_url_for_stored, BlobStore, BlobStoreConfig},
+    utils::cursor::{
+        query_hash, PageCursor, CURSOR_SCOPE_FAVORITES, CURSOR_SCOPE_SEARCH,
+        CURSOR_SCOPE_SEARCH_HISTORY,
     },
 };
 
+/// 利用予定時間の推定料金（料金が数値で登録されている駐車場のみ）
+///
+/// 料金は貸出単位（時間単位は最短の貸出値、日間単位は最短の日数）あたりとみなし、
+/// 利用時間を貸出単位に切り上げて掛ける。利用時間が未指定の場合は1単位分
+fn estimate_charge(
+    lot: &TParkingLotsModel,
+    rental_types: &[TParkingRentalTypesModel],
+    duration_minutes: Option<i32>,
+) -> Option<i32> {
+    let charge = lot.charge.trim().parse::<i32>().ok().filter(|c| *c >= 0)?;
+    let daily = match lot.rental_type.as_deref() {
+        Some(rental_type) => rental_type == "日間単位",
+        None => !rental_types.is_empty() && rental_types.iter().all(TParkingRentalTypesModel::is_daily_rental),
+    };
+    let unit_minutes = if daily {
+        rental_types.iter().filter_map(TParkingRentalTypesModel::get_days).min().unwrap_or(1) * 24 * 60
+    } else {
+        rental_types.iter().filter_map(TParkingRentalTypesModel::get_minutes).min().unwrap_or(60)
+    };
+    let units = duration_minutes.map_or(1, |minutes| (minutes + unit_minutes - 1) / unit_minutes).max(1);
+    Some(charge.saturating_mul(units))
+}
+
+/// おすすめ順のスコア
+///
+/// 距離は検索半径、料金は検索結果内の最安・最高料金を基準に正規化する（並び順はSQLで同じ式で計算）
+fn recommendation_score(metrics: &SearchLotMetrics, radius_km: f64) -> RecommendationScore {
+    let distance = metrics.distance_km.map_or(0.0, |d| 1.0 - d / radius_km);
+    let price = match (metrics.estimated_cost, metrics.min_cost.zip(metrics.max_cost)) {
+        (Some(cost), Some((min, max))) if max > min => f64::from(max - cost) / f64::from(max - min),
+        (Some(_), Some(_)) => 1.0,
+        _ => 0.0,
+    };
+    let usage = metrics.usage_count.min(SCORE_FULL_USAGE_COUNT) as f64 / SCORE_FULL_USAGE_COUNT as f64;
+
+    let distance = ScoreComponent::new(metrics.distance_km, distance, SCORE_WEIGHT_DISTANCE);
+    let price = ScoreComponent::new(metrics.estimated_cost.map(f64::from), price, SCORE_WEIGHT_PRICE);
+    let usage = ScoreComponent::new(Some(metrics.usage_count as f64), usage, SCORE_WEIGHT_USAGE);
+    let total = distance.points + price.points + usage.points;
+
+    RecommendationScore {
+        total_score: (total * 100.0).round() / 100.0,
+        distance,
+        price,
+        usage,
+    }
+}
+
 /// 検索条件構造体
 #[derive(Debug, Clone)]
 pub struct SearchCriteria {
@@ -39,11 +116,71 @@ pub struct SearchCriteria {
 pub struct ParkingSearchFilters {
     pub page: Option<i32>,
     pub page_size: Option<i32>,
+    /// 次ページ取得用のカーソル（pageの代わりに指定）
+    pub cursor: Option<String>,
     pub vehicle_type_id: Option<String>,
-    pub feature_ids: Option<Vec<String>>,
+    /// 特徴ID・種別（カンマ区切り）
+    pub feature_ids: Option<String>,
     pub min_rating: Option<f64>,
     pub max_hourly_rate: Option<i32>,
     pub max_daily_rate: Option<i32>,
+    /// 貸出タイプ
+    pub rental_type: Option<String>,
+    /// 料金帯（検索フィルター一覧のprice_rangesの値）
+    pub price_range: Option<String>,
+    /// お気に入りラベル
+    pub label: Option<String>,
+    /// 駐車場名・住所・最寄り駅・メモの部分一致
+    pub keyword: Option<String>,
+    /// custom（ユーザー指定順）, added_at（登録日時順）, name（名前順）
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+}
+
+impl ParkingSearchFilters {
+    /// お気に入り一覧のSQL条件に変換
+    fn favorite_criteria(&self) -> Result<FavoriteListCriteria, ApiError> {
+        let sort_by = self.sort_by.clone().unwrap_or_else(|| "custom".to_string());
+        if !["custom", "added_at", "name"].contains(&sort_by.as_str()) {
+            return Err(ApiError::ValidationError(
+                "sort_byはcustom、added_at、nameのいずれかで指定してください".to_string()
+            ));
+        }
+        let sort_order = match self.sort_order.as_deref() {
+            Some(order) if order == "asc" || order == "desc" => order.to_string(),
+            Some(_) => return Err(ApiError::ValidationError(
+                "sort_orderはascまたはdescで指定してください".to_string()
+            )),
+            None if sort_by == "added_at" => "desc".to_string(),
+            None => "asc".to_string(),
+        };
+        let non_empty = |v: &Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+        let price_range = match non_empty(&self.price_range) {
+            Some(key) => Some(PriceRangeBucket::find(&key).ok_or_else(|| ApiError::ValidationError(
+                "料金帯は検索フィルター一覧のprice_rangesの値で指定してください".to_string()
+            ))?),
+            None => None,
+        };
+
+        Ok(FavoriteListCriteria {
+            label: non_empty(&self.label),
+            keyword: non_empty(&self.keyword),
+            filters: SearchFilterCriteria {
+                vehicle_type_id: non_empty(&self.vehicle_type_id),
+                feature_ids: self.feature_ids.as_deref().unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                rental_type: non_empty(&self.rental_type),
+                min_charge: price_range.and_then(|r| r.min_charge),
+                max_charge: price_range.and_then(|r| r.max_charge),
+            },
+            sort_by,
+            sort_order,
+        })
+    }
 }
 
 /// Default implementations
@@ -53,11 +190,15 @@ impl Default for ParkingSearchRequest {
             latitude: None,
             longitude: None,
             address: None,
+            station_id: None,
+            station_name: None,
             radius_km: Some(5.0),
             usage_start_datetime: None,
             usage_end_datetime: None,
             vehicle_type_id: None,
             feature_ids: None,
+            rental_type: None,
+            price_range: None,
             min_rating: None,
             max_hourly_rate: None,
             max_daily_rate: None,
@@ -65,8 +206,13 @@ impl Default for ParkingSearchRequest {
             sort_order: Some("asc".to_string()),
             page: Some(1),
             page_size: Some(20),
+            cursor: None,
             favorites_only: None,
             user_id: None,
+            vehicle_id: None,
+            use_default_vehicle: None,
+            vehicle_dimensions: None,
+            include_incompatible: None,
         }
     }
 }
@@ -90,16 +236,59 @@ impl Default for SearchInfo {
 /// 駐車場検索サービス
 /// 検索ロジック、フィルタリング、ソート、お気に入り管理などのビジネスロジックを処理
 /// auth_signup_serviceのパターンに従った実装
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ParkingSearchService {
     repository: ParkingSearchRepository,
+    history_repository: SearchHistoryRepository,
+    /// ユーザーごとの検索履歴の保存上限
+    max_history_entries: i64,
+    blob_store: Arc<dyn BlobStore>,
+    blob_config: BlobStoreConfig,
+    geocoder: Arc<dyn Geocoder>,
+    stations: Arc<StationMaster>,
+}
+
+/// 住所・駅から解決した検索地点
+enum SearchOrigin {
+    Address(GeocodeResult),
+    Station(Station),
+}
+
+impl SearchOrigin {
+    fn display_name(&self) -> String {
+        match self {
+            SearchOrigin::Address(result) => result.display_name(),
+            SearchOrigin::Station(station) => station.display_name(),
+        }
+    }
+
+    fn station(&self) -> Option<&Station> {
+        match self {
+            SearchOrigin::Station(station) => Some(station),
+            SearchOrigin::Address(_) => None,
+        }
+    }
 }
 
 impl ParkingSearchService {
     /// 新しいサービスインスタンスを作成
-    pub fn new(db: PostgresDatabase) -> Self {
-        let repository = ParkingSearchRepository::new(db);
-        Self { repository }
+    pub fn new(
+        db: PostgresDatabase,
+        blob_store: Arc<dyn BlobStore>,
+        blob_config: BlobStoreConfig,
+        geocoder: Arc<dyn Geocoder>,
+        stations: Arc<StationMaster>,
+    ) -> Self {
+        let repository = ParkingSearchRepository::new(db.clone());
+        Self {
+            repository,
+            history_repository: SearchHistoryRepository::new(db),
+            max_history_entries: parse_env_or("SEARCH_HISTORY_MAX_ENTRIES", 50),
+            blob_store,
+            blob_config,
+            geocoder,
+            stations,
+        }
     }
 
     /// 駐車場検索メイン処理
@@ -111,71 +300,118 @@ impl ParkingSearchService {
         &self,
         request: ParkingSearchRequest,
         user_id: Option<String>,
+    ) -> Result<ParkingSearchResponse, ApiError> {
+        // 検索履歴は1ページ目の検索のみ保存する（次ページの取得は同じ検索の続き）
+        let history_criteria = (request.is_first_page() && !request.is_favorites_search())
+            .then(|| SearchHistoryCriteria::from_request(&request));
+        let response = self.execute_search(request, user_id.clone()).await?;
+
+        // 認証済みユーザーの検索条件を履歴に保存（失敗しても検索結果は返す）
+        if let (Some(uid), Some(criteria)) = (user_id.as_deref(), history_criteria) {
+            let saved = self
+                .history_repository
+                .upsert_search_history(uid, &criteria, self.max_history_entries)
+                .await;
+            if let Err(e) = saved {
+                warn!("検索履歴の保存に失敗しました: user_id={}, {}", uid, e);
+            }
+        }
+        Ok(response)
+    }
+
+    /// 駐車場検索（検索履歴は保存しない）
+    async fn execute_search(
+        &self,
+        mut request: ParkingSearchRequest,
+        user_id: Option<String>,
     ) -> Result<ParkingSearchResponse, ApiError> {
         info!("駐車場検索を開始します");
 
         // モデルの検証メソッドを使用した入力データの検証
         request.validate().map_err(ApiError::ValidationError)?;
+
+        // 検索履歴用に、補完前の検索条件を保持
+        let history_criteria = SearchHistoryCriteria::from_request(&request);
+
+        // 駅、または住所・郵便番号で指定された検索地点を座標に変換
+        let origin = self.resolve_search_location(&mut request).await?;
+
+        // 登録車両（use_default_vehicle指定時はデフォルト車両）の車種・寸法を検索条件に反映
+        self.apply_saved_vehicle(&mut request, user_id.as_deref()).await?;
         
         let start_time = std::time::Instant::now();
 
-        // 基本的な駐車場データ取得
-        let parking_lots = if request.is_favorites_search() {
-            if let Some(uid) = &user_id {
-                self.repository.get_favorite_parking_lots(
-                    uid, 
-                    request.page.unwrap_or(1), 
-                    request.page_size.unwrap_or(20)
-                ).await
-                    .map_err(|e| self.handle_database_error(e))?
-            } else {
-                return Err(ApiError::AuthenticationError(
-                    "お気に入り検索には認証が必要です".to_string()
-                ));
-            }
-        } else {
-            self.repository.search_parking_lots_by_location(&request).await
-                .map_err(|e| self.handle_database_error(e))?
+        if request.is_favorites_search() && user_id.is_none() {
+            return Err(ApiError::AuthenticationError(
+                "お気に入り検索には認証が必要です".to_string()
+            ));
+        }
+
+        // 絞り込み・並べ替え・ページネーションはSQLで適用（カーソル指定時は直前ページ末尾より後ろから取得）
+        let criteria = SearchPageCriteria::from_request(&request, user_id.as_deref());
+        let page_size = request.page_size.unwrap_or(20);
+        let cursor_hash = query_hash(&(&history_criteria, &user_id, request.favorites_only));
+        let (page, cursor) = match request.cursor.as_deref().filter(|c| !c.is_empty()) {
+            Some(token) => (0, Some(PageCursor::decode(token, CURSOR_SCOPE_SEARCH, &cursor_hash)?)),
+            None => (request.page.unwrap_or(1), None),
         };
+        let offset = if cursor.is_some() { 0 } else { i64::from((page - 1) * page_size) };
 
-        // フィルタリング適用
-        let filtered_lots = self.apply_search_filters(parking_lots, &request).await?;
+        let mut page_lots = self.repository
+            .search_parking_lots_by_location(&criteria, cursor.as_ref(), i64::from(page_size) + 1, offset)
+            .await
+            .map_err(|e| self.handle_database_error(e))?;
+        let totals = self.repository.count_search_parking_lots(&criteria).await
+            .map_err(|e| self.handle_database_error(e))?;
 
-        // 距離計算（位置情報検索の場合）
-        let lots_with_distance = if let (Some(lat), Some(lng)) = (request.latitude, request.longitude) {
-            self.calculate_distances(filtered_lots, lat, lng).await?
-        } else {
-            filtered_lots.into_iter()
-                .map(|(lot, rental, vehicle, features, maps)| (lot, None, rental, vehicle, features, maps))
-                .collect()
-        };
+        let has_next = page_lots.len() > page_size as usize;
+        page_lots.truncate(page_size as usize);
+        let next_cursor = page_lots
+            .last()
+            .filter(|_| has_next)
+            .map(|((lot, ..), metrics)| {
+                let keys = search_cursor_keys(&lot.parking_lot_id, metrics);
+                PageCursor::new(CURSOR_SCOPE_SEARCH, &cursor_hash, keys).encode()
+            });
 
-        // ソート処理
-        let sorted_lots = self.sort_parking_lots(lots_with_distance, &request).await?;
+        // 表示対象の駐車場の寸法制限の照合結果（除外はSQLで適用済み）
+        let (lots, metrics): (Vec<_>, Vec<_>) = page_lots.into_iter().unzip();
+        let (lots, mut compatibility_by_lot) = self.apply_vehicle_limits(lots, &request).await?;
 
-        // ページネーション適用
-        let total_count = sorted_lots.len() as i64;
-        let page = request.page.unwrap_or(1);
-        let page_size = request.page_size.unwrap_or(20);
-        let offset = ((page - 1) * page_size) as usize;
-        let limit = page_size as usize;
-        
-        let paginated_lots = sorted_lots
+        let station = origin.as_ref().and_then(SearchOrigin::station);
+        let paginated_lots = lots
             .into_iter()
-            .skip(offset)
-            .take(limit)
+            .zip(metrics)
+            .map(|((lot, rental, vehicle, features, maps), metrics)| {
+                let distance_info = metrics.distance_km.map(|distance_km| {
+                    let mut distance_info = DistanceInfo::new(distance_km);
+                    distance_info.station_walk = station
+                        .map(|s| StationWalkInfo::new(&s.station_id, &s.display_name(), distance_km));
+                    distance_info
+                });
+                let score = (criteria.sort_by == SearchSortBy::Recommended)
+                    .then(|| recommendation_score(&metrics, criteria.radius_km));
+                (lot, distance_info, rental, vehicle, features, maps, score)
+            })
             .collect::<Vec<_>>();
 
+        // 表示対象の駐車場画像を一括取得
+        let lot_ids: Vec<String> = paginated_lots.iter().map(|(lot, ..)| lot.parking_lot_id.clone()).collect();
+        let mut images_by_lot = self.get_images_by_parking_lot(&lot_ids).await?;
+
+        // 表示対象のお気に入り情報を一括取得
+        let mut favorites_by_lot = match &user_id {
+            Some(uid) => self.repository.get_favorite_details(uid, &lot_ids).await
+                .map_err(|e| self.handle_database_error(e))?,
+            None => HashMap::new(),
+        };
+
         // 詳細情報を含む検索結果を構築
         let mut search_results = Vec::new();
-        for (parking_lot, distance_info, rental_types, vehicle_types, features, maps_info) in paginated_lots {
+        for (parking_lot, distance_info, rental_types, vehicle_types, features, maps_info, score) in paginated_lots {
             let availability_info = self.get_availability_info(&parking_lot, &request).await?;
             let pricing_info = self.get_pricing_info(&parking_lot, &rental_types, &request).await?;
-            let favorite_info = if let Some(uid) = &user_id {
-                self.get_favorite_info(&parking_lot.parking_lot_id, uid).await?
-            } else {
-                None
-            };
+            let favorite_info = favorites_by_lot.remove(&parking_lot.parking_lot_id);
             let rating_info = self.get_rating_info(&parking_lot.parking_lot_id).await?;
 
             let search_result = ParkingSearchResult {
@@ -184,24 +420,27 @@ impl ParkingSearchService {
                 rental_types: rental_types.into_iter().map(RentalTypeResponse::from).collect(),
                 vehicle_types: vehicle_types.into_iter().map(VehicleTypeResponse::from).collect(),
                 features: features.into_iter().map(ParkingFeatureResponse::from).collect(),
+                images: images_by_lot.remove(&parking_lot.parking_lot_id).unwrap_or_default(),
                 distance_info,
                 availability_info,
                 pricing_info,
                 favorite_info,
                 rating_info,
+                vehicle_compatibility: compatibility_by_lot.remove(&parking_lot.parking_lot_id),
+                recommendation_score: score,
             };
 
             search_results.push(search_result);
         }
 
         // レスポンス構築
-        let pagination = PaginationInfo::new(page, page_size, total_count);
-        let search_info = self.build_search_info(&request).await?;
+        let pagination = PaginationInfo::with_cursor(page, page_size, totals.total_count, next_cursor);
+        let search_info = self.build_search_info(&request, origin.as_ref()).await?;
         let execution_time = start_time.elapsed();
         let search_stats = SearchStats {
             execution_time_ms: execution_time.as_millis() as u64,
-            total_lots_in_radius: total_count,
-            filtered_lots_count: total_count,
+            total_lots_in_radius: totals.lots_in_radius,
+            filtered_lots_count: totals.total_count,
             available_lots_count: search_results.iter()
                 .filter(|r| r.availability_info.is_available)
                 .count() as i64,
@@ -217,10 +456,146 @@ impl ParkingSearchService {
         })
     }
 
+    /// 検索履歴の条件で再検索
+    pub async fn rerun_search_history(
+        &self,
+        user_id: &str,
+        search_id: &str,
+        page: Option<i32>,
+        page_size: Option<i32>,
+        cursor: Option<String>,
+    ) -> Result<ParkingSearchResponse, ApiError> {
+        let condition = self
+            .history_repository
+            .find_search_criteria(user_id, search_id)
+            .await
+            .map_err(|e| self.handle_database_error(e))?
+            .ok_or_else(|| ApiError::NotFoundError("検索履歴が見つかりません".to_string()))?
+            .ok_or_else(|| ApiError::ValidationError("この検索履歴は再検索に対応していません".to_string()))?;
+
+        let criteria: SearchHistoryCriteria = serde_json::from_value(condition).map_err(|e| {
+            error!("検索履歴の条件の復元に失敗: search_id={}, {}", search_id, e);
+            ApiError::InternalServerError
+        })?;
+
+        info!("検索履歴から再検索します: search_id={}", search_id);
+        let request = ParkingSearchRequest {
+            cursor,
+            ..criteria.into_request(user_id, page, page_size)
+        };
+        let first_page = request.is_first_page();
+        let response = self.execute_search(request, Some(user_id.to_string())).await?;
+
+        // 再検索は新しい履歴を作らず、元の履歴の検索日時だけ更新する
+        if first_page
+            && let Err(e) = self.history_repository.touch_search_history(user_id, search_id).await
+        {
+            warn!("検索履歴の検索日時の更新に失敗しました: search_id={}, {}", search_id, e);
+        }
+        Ok(response)
+    }
+
+    /// 検索履歴一覧（新しい順、ページ番号またはカーソルで取得）
+    pub async fn list_search_history(
+        &self,
+        user_id: &str,
+        query: PageQuery,
+    ) -> Result<PagedResponse<ParkingSearchHistoryResponse>, ApiError> {
+        let (page, page_size) = query.validate().map_err(ApiError::ValidationError)?;
+        let cursor_hash = query_hash(&user_id);
+        let cursor = query.cursor.as_deref()
+            .filter(|c| !c.is_empty())
+            .map(|token| PageCursor::decode(token, CURSOR_SCOPE_SEARCH_HISTORY, &cursor_hash))
+            .transpose()?;
+
+        let mut items = self
+            .history_repository
+            .list_search_history(
+                user_id,
+                cursor.as_ref(),
+                page_size as i64 + 1,
+                (page.max(1) as i64 - 1) * page_size as i64,
+            )
+            .await
+            .map_err(|e| self.handle_database_error(e))?;
+        let total_count = self
+            .history_repository
+            .count_search_history(user_id)
+            .await
+            .map_err(|e| self.handle_database_error(e))?;
+
+        let has_next = items.len() > page_size as usize;
+        items.truncate(page_size as usize);
+        let next_cursor = items
+            .last()
+            .filter(|_| has_next)
+            .map(|row| PageCursor::new(CURSOR_SCOPE_SEARCH_HISTORY, &cursor_hash, search_history_cursor_keys(row)).encode());
+
+        Ok(PagedResponse {
+            items,
+            pagination: PaginationInfo::with_cursor(page, page_size, total_count, next_cursor),
+        })
+    }
+
+    /// 検索履歴を1件削除
+    pub async fn delete_search_history(&self, user_id: &str, search_id: &str) -> Result<(), ApiError> {
+        let deleted = self
+            .history_repository
+            .delete_search_history(user_id, search_id)
+            .await
+            .map_err(|e| self.handle_database_error(e))?;
+        if !deleted {
+            return Err(ApiError::NotFoundError("検索履歴が見つかりません".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 検索履歴を全件削除（削除件数を返す）
+    pub async fn clear_search_history(&self, user_id: &str) -> Result<u64, ApiError> {
+        self.history_repository
+            .clear_search_history(user_id)
+            .await
+            .map_err(|e| self.handle_database_error(e))
+    }
+
+    /// 指定の駐車場のうち、検索条件に一致するもののIDを返す（保存検索の通知判定用）
+    ///
+    /// 検索と同じ地点解決・フィルター・車両寸法・検索半径の判定を行う。検索履歴は保存しない
+    pub async fn find_matching_lot_ids(
+        &self,
+        mut request: ParkingSearchRequest,
+        user_id: &str,
+        candidate_lot_ids: &[String],
+    ) -> Result<Vec<String>, ApiError> {
+        request.validate().map_err(ApiError::ValidationError)?;
+        let origin = self.resolve_search_location(&mut request).await?;
+        self.apply_saved_vehicle(&mut request, Some(user_id)).await?;
+        request.include_incompatible = Some(false);
+
+        let filtered_lot_ids = self.repository
+            .filter_parking_lot_ids(candidate_lot_ids, &SearchFilterCriteria::from_request(&request)).await
+            .map_err(|e| self.handle_database_error(e))?;
+
+        let lots = self.repository.get_parking_lot_details(&filtered_lot_ids).await
+            .map_err(|e| self.handle_database_error(e))?;
+
+        let (lots, _) = self.apply_vehicle_limits(lots, &request).await?;
+        let lot_ids = if let (Some(lat), Some(lng)) = (request.latitude, request.longitude) {
+            let station = origin.as_ref().and_then(SearchOrigin::station);
+            self.calculate_distances(lots, lat, lng, request.radius_km, station).await?
+                .into_iter()
+                .map(|(lot, ..)| lot.parking_lot_id)
+                .collect()
+        } else {
+            lots.into_iter().map(|(lot, ..)| lot.parking_lot_id).collect()
+        };
+        Ok(lot_ids)
+    }
+
     /// お気に入り駐車場検索
     /// 
     /// 認証されたユーザーのお気に入り駐車場を検索します。
-    /// 追加のフィルタリング条件も適用可能です。
+    /// ラベル・キーワード・車種・特徴での絞り込み、並べ替え、件数取得はSQLで行います。
     #[instrument(skip(self), fields(user_id = %user_id))]
     pub async fn get_favorite_parking_lots(
         &self,
@@ -232,29 +607,47 @@ impl ParkingSearchService {
         // ユーザー認証状態の確認
         self.validate_user_access(&user_id).await?;
 
+        let (page, page_size) = validate_paging(filters.page, filters.page_size, filters.cursor.as_deref())
+            .map_err(ApiError::ValidationError)?;
+        let criteria = filters.favorite_criteria()?;
+        let cursor_hash = query_hash(&(&user_id, &criteria));
+        let cursor = filters.cursor.as_deref()
+            .filter(|c| !c.is_empty())
+            .map(|token| PageCursor::decode(token, CURSOR_SCOPE_FAVORITES, &cursor_hash))
+            .transpose()?;
+
         let start_time = std::time::Instant::now();
 
-        // お気に入り駐車場データの取得
-        let favorite_lots = self.repository.get_favorite_parking_lots(
-            &user_id, 
-            filters.page.unwrap_or(1), 
-            filters.page_size.unwrap_or(20)
+        // お気に入り駐車場データと絞り込み後の総件数の取得（次ページの有無を判定するため1件多く取得）
+        let mut favorite_lots = self.repository.get_favorite_parking_lots(
+            &user_id,
+            &criteria,
+            cursor.as_ref(),
+            Some(page_size as i64 + 1),
+            (page.max(1) as i64 - 1) * page_size as i64,
         ).await
             .map_err(|e| self.handle_database_error(e))?;
+        let has_next = favorite_lots.len() > page_size as usize;
+        favorite_lots.truncate(page_size as usize);
+        let next_cursor = favorite_lots
+            .last()
+            .filter(|_| has_next)
+            .map(|(lot, .., favorite)| {
+                PageCursor::new(CURSOR_SCOPE_FAVORITES, &cursor_hash, favorite_cursor_keys(&criteria, lot, favorite)).encode()
+            });
+        let total_count = self.repository.count_favorite_parking_lots(&user_id, &criteria).await
+            .map_err(|e| self.handle_database_error(e))?;
+        let total_favorites = self.repository.get_user_favorites_count(&user_id).await
+            .map_err(|e| self.handle_database_error(e))?;
 
-        // フィルタリング適用
-        let filtered_lots = self.apply_favorite_filters(favorite_lots, &filters).await?;
+        let lot_ids: Vec<String> = favorite_lots.iter().map(|(lot, ..)| lot.parking_lot_id.clone()).collect();
+        let mut images_by_lot = self.get_images_by_parking_lot(&lot_ids).await?;
 
         // 詳細情報構築
         let mut search_results = Vec::new();
-        for (parking_lot, rental_types, vehicle_types, features, maps_info) in filtered_lots {
+        for (parking_lot, rental_types, vehicle_types, features, maps_info, favorite_info) in favorite_lots {
             let availability_info = self.get_availability_info(&parking_lot, &ParkingSearchRequest::default()).await?;
             let pricing_info = self.get_pricing_info(&parking_lot, &rental_types, &ParkingSearchRequest::default()).await?;
-            let favorite_info = Some(FavoriteInfo {
-                is_favorite: true,
-                favorite_added_at: Some(chrono::Utc::now()),
-                favorite_memo: None,
-            });
             let rating_info = self.get_rating_info(&parking_lot.parking_lot_id).await?;
 
             let search_result = ParkingSearchResult {
@@ -263,43 +656,113 @@ impl ParkingSearchService {
                 rental_types: rental_types.into_iter().map(RentalTypeResponse::from).collect(),
                 vehicle_types: vehicle_types.into_iter().map(VehicleTypeResponse::from).collect(),
                 features: features.into_iter().map(ParkingFeatureResponse::from).collect(),
+                images: images_by_lot.remove(&parking_lot.parking_lot_id).unwrap_or_default(),
                 distance_info: None,
                 availability_info,
                 pricing_info,
-                favorite_info,
+                favorite_info: Some(favorite_info),
                 rating_info,
+                vehicle_compatibility: None,
+                recommendation_score: None,
             };
 
             search_results.push(search_result);
         }
 
-        let total_count = search_results.len() as i64;
-        let pagination = PaginationInfo::new(
-            filters.page.unwrap_or(1), 
-            filters.page_size.unwrap_or(20), 
-            total_count
-        );
+        let pagination = PaginationInfo::with_cursor(page, page_size, total_count, next_cursor);
 
         let execution_time = start_time.elapsed();
         let search_stats = SearchStats {
             execution_time_ms: execution_time.as_millis() as u64,
-            total_lots_in_radius: total_count,
+            total_lots_in_radius: total_favorites,
             filtered_lots_count: total_count,
             available_lots_count: search_results.iter()
                 .filter(|r| r.availability_info.is_available)
                 .count() as i64,
         };
 
-        info!("お気に入り駐車場検索が正常に完了しました - 結果数: {}", search_results.len());
+        info!("お気に入り駐車場検索が正常に完了しました - 結果数: {}, 総件数: {}", search_results.len(), total_count);
 
         Ok(ParkingSearchResponse {
             parking_lots: search_results,
             pagination,
-            search_info: SearchInfo::default(),
+            search_info: SearchInfo {
+                sort_info: SortInfo {
+                    sort_display_name: match criteria.sort_by.as_str() {
+                        "added_at" => "登録日時順",
+                        "name" => "名前順",
+                        _ => "表示順",
+                    }.to_string(),
+                    sort_by: criteria.sort_by,
+                    sort_order: criteria.sort_order,
+                },
+                ..SearchInfo::default()
+            },
             search_stats,
         })
     }
 
+    /// お気に入りのメモ・ラベル更新
+    #[instrument(skip(self, request), fields(user_id = %user_id, parking_lot_id = %parking_lot_id))]
+    pub async fn update_favorite(
+        &self,
+        user_id: &str,
+        parking_lot_id: &str,
+        request: FavoriteUpdateRequest,
+    ) -> Result<FavoriteInfo, ApiError> {
+        request.validate().map_err(ApiError::ValidationError)?;
+        self.validate_user_access(user_id).await?;
+
+        let labels = request.labels.as_deref()
+            .map(normalize_favorite_labels)
+            .transpose()
+            .map_err(ApiError::ValidationError)?;
+        let memo = request.memo.as_deref()
+            .map(|m| Some(m.trim()).filter(|m| !m.is_empty()));
+
+        self.repository.update_favorite(user_id, parking_lot_id, memo, labels.as_deref()).await
+            .map_err(|e| self.handle_database_error(e))?
+            .ok_or_else(|| ApiError::NotFoundError(
+                "指定された駐車場はお気に入りに登録されていません".to_string()
+            ))
+    }
+
+    /// お気に入りの並べ替え
+    /// 
+    /// お気に入り全件の駐車場IDを表示順に受け取り、その順序で保存します。
+    #[instrument(skip(self, request), fields(user_id = %user_id))]
+    pub async fn reorder_favorites(
+        &self,
+        user_id: &str,
+        request: FavoriteOrderRequest,
+    ) -> Result<Vec<String>, ApiError> {
+        request.validate().map_err(ApiError::ValidationError)?;
+        self.validate_user_access(user_id).await?;
+
+        let mut current = self.repository.get_user_favorites(user_id).await
+            .map_err(|e| self.handle_database_error(e))?;
+        let mut requested = request.parking_lot_ids.clone();
+        current.sort();
+        requested.sort();
+        if current != requested {
+            return Err(ApiError::ValidationError(
+                "お気に入りに登録されているすべての駐車場IDを指定してください".to_string()
+            ));
+        }
+
+        self.repository.reorder_favorites(user_id, &request.parking_lot_ids).await
+            .map_err(|e| self.handle_database_error(e))?;
+        info!("お気に入りの並べ替えが完了しました - ユーザー: {}, 件数: {}", user_id, request.parking_lot_ids.len());
+        Ok(request.parking_lot_ids)
+    }
+
+    /// お気に入りラベル一覧（件数付き）
+    pub async fn get_favorite_labels(&self, user_id: &str) -> Result<Vec<FavoriteLabelCount>, ApiError> {
+        self.validate_user_access(user_id).await?;
+        self.repository.get_favorite_labels(user_id).await
+            .map_err(|e| self.handle_database_error(e))
+    }
+
     /// お気に入り操作管理
     /// 
     /// 駐車場のお気に入り追加・削除を管理します。
@@ -329,9 +792,15 @@ impl ParkingSearchService {
                     ));
                 }
 
+                let labels = normalize_favorite_labels(request.labels.as_deref().unwrap_or_default())
+                    .map_err(ApiError::ValidationError)?;
+                let memo = request.normalized_memo();
+
                 let result = self.repository.add_to_favorites(
                     &request.user_id, 
-                    &request.parking_lot_id
+                    &request.parking_lot_id,
+                    memo.as_deref(),
+                    &labels,
                 ).await
                     .map_err(|e| self.handle_database_error(e))?;
                 
@@ -387,6 +856,151 @@ impl ParkingSearchService {
         }
     }
 
+    /// 検索フィルター一覧
+    ///
+    /// 車種・設備・貸出タイプ・料金帯の選択肢と、それぞれに該当する駐車場数を返します。
+    /// 地点を指定した場合は検索半径内（矩形で近似）の駐車場で集計します。
+    #[instrument(skip(self))]
+    pub async fn get_filter_catalogue(
+        &self,
+        query: SearchFilterCatalogueQuery,
+    ) -> Result<SearchFilterCatalogue, ApiError> {
+        let bounds = match (query.latitude, query.longitude) {
+            (Some(lat), Some(lng)) => {
+                let radius_km = query.radius_km.unwrap_or(5.0);
+                if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+                    return Err(ApiError::ValidationError("緯度・経度の値が正しくありません".to_string()));
+                }
+                if radius_km <= 0.0 || radius_km > 100.0 {
+                    return Err(ApiError::ValidationError("検索半径は0.1km以上100km以下で指定してください".to_string()));
+                }
+                Some(search_bounds(lat, lng, radius_km))
+            }
+            (None, None) => None,
+            _ => return Err(ApiError::ValidationError("緯度と経度は両方指定してください".to_string())),
+        };
+
+        let rows = self.repository.get_filter_counts(bounds).await
+            .map_err(|e| self.handle_database_error(e))?;
+
+        let mut catalogue = SearchFilterCatalogue {
+            total_count: 0,
+            vehicle_types: Vec::new(),
+            features: Vec::new(),
+            rental_types: Vec::new(),
+            price_ranges: Vec::new(),
+        };
+        let mut price_counts = [0_i64; PRICE_RANGE_BUCKETS.len()];
+        let option = |value: String, count: i64| FilterOption { display_name: value.clone(), value, count };
+
+        for row in rows {
+            match (row.filter_kind.as_str(), row.filter_value) {
+                ("total", _) => catalogue.total_count = row.count,
+                ("vehicle_type", Some(value)) => catalogue.vehicle_types.push(option(value, row.count)),
+                ("rental_type", Some(value)) => catalogue.rental_types.push(option(value, row.count)),
+                ("feature", Some(value)) => {
+                    let feature_type = row.filter_group.unwrap_or_default();
+                    let filter_option = FilterOption {
+                        value: format!("{}:{}", feature_type, value),
+                        display_name: value,
+                        count: row.count,
+                    };
+                    match catalogue.features.iter_mut().find(|g| g.feature_type == feature_type) {
+                        Some(group) => group.options.push(filter_option),
+                        None => catalogue.features.push(FeatureFilterGroup { feature_type, options: vec![filter_option] }),
+                    }
+                }
+                ("charge", Some(value)) => {
+                    let charge = value.parse::<i32>().ok();
+                    if let Some(index) = charge.and_then(|c| PRICE_RANGE_BUCKETS.iter().position(|r| r.contains(c))) {
+                        price_counts[index] += row.count;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let by_count = |a: &FilterOption, b: &FilterOption| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value));
+        catalogue.vehicle_types.sort_by(by_count);
+        catalogue.rental_types.sort_by(by_count);
+        catalogue.features.sort_by(|a, b| a.feature_type.cmp(&b.feature_type));
+        for group in &mut catalogue.features {
+            group.options.sort_by(by_count);
+        }
+        catalogue.price_ranges = PRICE_RANGE_BUCKETS.iter()
+            .zip(price_counts)
+            .map(|(range, count)| FilterOption {
+                value: range.key.to_string(),
+                display_name: range.display_name.to_string(),
+                count,
+            })
+            .collect();
+
+        info!("検索フィルター一覧を取得しました - 対象駐車場数: {}", catalogue.total_count);
+        Ok(catalogue)
+    }
+
+    /// 近隣駐車場検索（地図のピン表示用）
+    ///
+    /// 関連データを読み込まず、距離の計算・並べ替え・件数制限をSQLで行う軽量な検索です。
+    #[instrument(skip(self))]
+    pub async fn get_nearby_parking_lots(&self, query: NearbyParkingQuery) -> Result<NearbyParkingResponse, ApiError> {
+        query.validate().map_err(ApiError::ValidationError)?;
+        let radius_km = query.radius_km.unwrap_or(1.0);
+
+        let parking_lots = self.repository
+            .get_nearby_parking_lots(query.latitude, query.longitude, radius_km, query.limit.unwrap_or(50))
+            .await
+            .map_err(|e| self.handle_database_error(e))?;
+
+        Ok(NearbyParkingResponse {
+            latitude: query.latitude,
+            longitude: query.longitude,
+            radius_km,
+            parking_lots,
+        })
+    }
+
+    /// 地図の表示範囲による検索
+    ///
+    /// 拡大時は個々の駐車場のピンを、縮小時（またはピンが多すぎる場合）は格子単位のクラスタを返します。
+    #[instrument(skip(self))]
+    pub async fn search_map_viewport(&self, query: MapViewportQuery) -> Result<MapSearchResponse, ApiError> {
+        query.validate().map_err(ApiError::ValidationError)?;
+
+        if query.zoom > MAP_CLUSTER_MAX_ZOOM {
+            let pins = self.repository.get_map_pins(&query, MAP_MAX_PINS + 1).await
+                .map_err(|e| self.handle_database_error(e))?;
+            if pins.len() as i64 <= MAP_MAX_PINS {
+                info!("地図検索（ピン）完了 - 件数: {}", pins.len());
+                return Ok(MapSearchResponse::pins(query.zoom, pins));
+            }
+            info!("表示範囲内の駐車場が{}件を超えるためクラスタで返します", MAP_MAX_PINS);
+        }
+
+        let clusters = self.repository.get_map_clusters(&query).await
+            .map_err(|e| self.handle_database_error(e))?;
+        Ok(MapSearchResponse::clusters(query.zoom, clusters))
+    }
+
+    /// 都道府県・市区町村別の駐車場統計
+    #[instrument(skip(self))]
+    pub async fn get_parking_stats(&self, query: ParkingStatsQuery) -> Result<ParkingStatsResponse, ApiError> {
+        let group_by = query.group_by.unwrap_or_else(|| "prefecture".to_string());
+        if group_by != "prefecture" && group_by != "city" {
+            return Err(ApiError::ValidationError(
+                "group_byはprefectureまたはcityで指定してください".to_string()
+            ));
+        }
+        let prefecture = query.prefecture.as_deref().map(str::trim).filter(|p| !p.is_empty());
+
+        let aggregated_at = chrono::Utc::now();
+        let areas = self.repository.get_area_stats(group_by == "city", prefecture, aggregated_at).await
+            .map_err(|e| self.handle_database_error(e))?;
+
+        Ok(ParkingStatsResponse { group_by, aggregated_at, areas })
+    }
+
     /// 駐車場詳細情報取得
     /// 
     /// 指定された駐車場の詳細情報を取得します。
@@ -399,30 +1013,10 @@ impl ParkingSearchService {
     ) -> Result<Option<ParkingSearchResult>, ApiError> {
         info!("駐車場詳細情報取得を開始します: {}", parking_lot_id);
 
-        // 駐車場の基本情報取得 - 実際には単一の駐車場を取得するロジックを実装
-        let search_request = ParkingSearchRequest {
-            latitude: None,
-            longitude: None,
-            address: None,
-            radius_km: Some(0.0),
-            usage_start_datetime: None,
-            usage_end_datetime: None,
-            vehicle_type_id: None,
-            feature_ids: None,
-            min_rating: None,
-            max_hourly_rate: None,
-            max_daily_rate: None,
-            sort_by: Some("distance".to_string()),
-            sort_order: Some("asc".to_string()),
-            page: Some(1),
-            page_size: Some(1000),
-            favorites_only: Some(false),
-            user_id: user_id.clone(),
-        };
-        let parking_lot_details = self.repository.search_parking_lots_by_location(&search_request).await
+        let parking_lot_detail = self.repository.get_parking_lot_detail(parking_lot_id).await
             .map_err(|e| self.handle_database_error(e))?;
 
-        if let Some((parking_lot, rental_types, vehicle_types, features, maps_info)) = parking_lot_details.into_iter().find(|(lot, _, _, _, _)| lot.parking_lot_id == parking_lot_id) {
+        if let Some((parking_lot, rental_types, vehicle_types, features, maps_info)) = parking_lot_detail {
             // 詳細情報構築
             let availability_info = self.get_availability_info(&parking_lot, &ParkingSearchRequest::default()).await?;
             let pricing_info = self.get_pricing_info(&parking_lot, &rental_types, &ParkingSearchRequest::default()).await?;
@@ -432,6 +1026,11 @@ impl ParkingSearchService {
                 None
             };
             let rating_info = self.get_rating_info(&parking_lot.parking_lot_id).await?;
+            let images = self
+                .get_images_by_parking_lot(std::slice::from_ref(&parking_lot.parking_lot_id))
+                .await?
+                .remove(&parking_lot.parking_lot_id)
+                .unwrap_or_default();
 
             let detail_result = ParkingSearchResult {
                 parking_lot: ParkingLotResponse::from(parking_lot.clone()),
@@ -439,11 +1038,14 @@ impl ParkingSearchService {
                 rental_types: rental_types.into_iter().map(RentalTypeResponse::from).collect(),
                 vehicle_types: vehicle_types.into_iter().map(VehicleTypeResponse::from).collect(),
                 features: features.into_iter().map(ParkingFeatureResponse::from).collect(),
+                images,
                 distance_info: None,
                 availability_info,
                 pricing_info,
                 favorite_info,
                 rating_info,
+                vehicle_compatibility: None,
+                recommendation_score: None,
             };
 
             info!("駐車場詳細情報取得が完了しました: {}", parking_lot_id);
@@ -458,6 +1060,38 @@ impl ParkingSearchService {
 
 
 
+    /// 駐車場ごとの画像一覧（署名済みURL）を取得
+    async fn get_images_by_parking_lot(
+        &self,
+        parking_lot_ids: &[String],
+    ) -> Result<HashMap<String, Vec<ParkingImageResponse>>, ApiError> {
+        let rows = self.repository.get_images_by_parking_lot_ids(parking_lot_ids).await
+            .map_err(|e| self.handle_database_error(e))?;
+
+        let ttl = self.blob_config.signed_url_ttl();
+        let mut images_by_lot: HashMap<String, Vec<ParkingImageResponse>> = HashMap::new();
+        for row in rows {
+            let parking_lot_id = row.parking_lot_id.clone();
+            let image = row.into_response(|key| signed_url_for_stored(self.blob_store.as_ref(), key, ttl))?;
+            images_by_lot.entry(parking_lot_id).or_default().push(image);
+        }
+        Ok(images_by_lot)
+    }
+
+    /// 認証情報から利用者のユーザーIDを取得（オーナーの場合はNone）
+    pub async fn find_user_id(&self, identity: &UserIdentity) -> Result<Option<String>, ApiError> {
+        let login_id = Uuid::parse_str(&identity.user_id)
+            .map_err(|_| ApiError::AuthenticationError("ログイン情報が正しくありません".to_string()))?;
+        self.repository.find_user_id(login_id).await
+            .map_err(|e| self.handle_database_error(e))
+    }
+
+    /// 認証情報から利用者のユーザーIDを取得（利用者以外はエラー）
+    pub async fn load_user_id(&self, identity: &UserIdentity) -> Result<String, ApiError> {
+        self.find_user_id(identity).await?
+            .ok_or_else(|| ApiError::AuthorizationError("利用者アカウントのみ利用できます".to_string()))
+    }
+
     /// ユーザーアクセス権限の検証
     async fn validate_user_access(&self, user_id: &str) -> Result<(), ApiError> {
         if user_id.is_empty() {
@@ -525,45 +1159,105 @@ impl ParkingSearchService {
 
     // 仮実装メソッド（実装が必要な場合は拡張）
 
-    async fn apply_search_filters(
-        &self,
-        lots: Vec<(TParkingLotsModel, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>,
-        _request: &ParkingSearchRequest,
-    ) -> Result<Vec<(TParkingLotsModel, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>, ApiError> {
-        // フィルタリングロジックの実装
-        Ok(lots)
+    /// 登録車両の車種・寸法を検索条件に反映
+    ///
+    /// 車両ID・車種・寸法のいずれも指定がなく、use_default_vehicle=trueの場合はデフォルト車両を使用する
+    async fn apply_saved_vehicle(&self, request: &mut ParkingSearchRequest, user_id: Option<&str>) -> Result<(), ApiError> {
+        if request.vehicle_id.is_none() && !request.use_default_vehicle.unwrap_or(false) {
+            return Ok(());
+        }
+        let Some(uid) = user_id else {
+            return Err(ApiError::AuthenticationError("登録車両での検索には認証が必要です".to_string()));
+        };
+        if request.vehicle_id.is_none() && (request.vehicle_type_id.is_some() || request.vehicle_dimensions.is_some()) {
+            return Ok(());
+        }
+
+        let vehicle = self.repository.get_search_vehicle(uid, request.vehicle_id).await
+            .map_err(|e| self.handle_database_error(e))?;
+        let Some(vehicle) = vehicle else {
+            if request.vehicle_id.is_some() {
+                return Err(ApiError::ValidationError("指定された登録車両が見つかりません".to_string()));
+            }
+            return Ok(());
+        };
+
+        if request.vehicle_type_id.is_none() {
+            request.vehicle_type_id = vehicle.vehicle_type_id;
+        }
+        if request.vehicle_dimensions.is_none() {
+            // 登録車両はcm単位、寸法制限はmm単位
+            request.vehicle_dimensions = Some(VehicleDimensions {
+                length_mm: vehicle.length_cm.map(|v| v * 10),
+                width_mm: vehicle.width_cm.map(|v| v * 10),
+                height_mm: vehicle.height_cm.map(|v| v * 10),
+                weight_kg: vehicle.weight_kg,
+            });
+        }
+        Ok(())
     }
 
-    async fn apply_favorite_filters(
+    /// 車両寸法と駐車場の寸法制限を照合し、適合しない駐車場を除外（include_incompatible指定時は残す）
+    async fn apply_vehicle_limits(
         &self,
-        lots: Vec<(TParkingLotsModel, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>,
-        _filters: &ParkingSearchFilters,
-    ) -> Result<Vec<(TParkingLotsModel, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>, ApiError> {
-        // お気に入りフィルタリングロジックの実装
-        Ok(lots)
+        lots: Vec<ParkingLotWithRelations>,
+        request: &ParkingSearchRequest,
+    ) -> Result<(Vec<ParkingLotWithRelations>, HashMap<String, VehicleCompatibility>), ApiError> {
+        let Some(dimensions) = request.vehicle_dimensions.as_ref().filter(|d| !d.is_empty()) else {
+            return Ok((lots, HashMap::new()));
+        };
+
+        let lot_ids: Vec<String> = lots.iter().map(|(lot, ..)| lot.parking_lot_id.clone()).collect();
+        let limits: HashMap<String, ParkingLimitModel> = self.repository.get_limits_by_parking_lot_ids(&lot_ids).await
+            .map_err(|e| self.handle_database_error(e))?
+            .into_iter()
+            .map(|limit| (limit.parking_lot_id.clone(), limit))
+            .collect();
+
+        let compatibility_by_lot: HashMap<String, VehicleCompatibility> = lot_ids
+            .into_iter()
+            .map(|id| {
+                let compatibility = dimensions.check_limits(limits.get(&id));
+                (id, compatibility)
+            })
+            .collect();
+
+        let include_incompatible = request.include_incompatible.unwrap_or(false);
+        let lots: Vec<_> = lots
+            .into_iter()
+            .filter(|(lot, ..)| {
+                include_incompatible
+                    || compatibility_by_lot.get(&lot.parking_lot_id).is_none_or(|c| c.is_compatible)
+            })
+            .collect();
+
+        Ok((lots, compatibility_by_lot))
     }
 
     async fn calculate_distances(
         &self,
         lots: Vec<(TParkingLotsModel, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>,
-        _lat: f64,
-        _lng: f64,
+        lat: f64,
+        lng: f64,
+        radius_km: Option<f64>,
+        station: Option<&Station>,
     ) -> Result<Vec<(TParkingLotsModel, Option<DistanceInfo>, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>, ApiError> {
-        // 距離計算ロジックの実装
+        // 座標のない駐車場と検索半径外の駐車場は除外
+        let radius_km = radius_km.unwrap_or(5.0);
         Ok(lots.into_iter()
-            .map(|(lot, rental, vehicle, features, maps)| (lot, None, rental, vehicle, features, maps))
+            .filter_map(|(lot, rental, vehicle, features, maps)| {
+                let distance_km = maps.as_ref()?.calculate_distance_to(lat, lng)?;
+                if distance_km > radius_km {
+                    return None;
+                }
+                let mut distance_info = DistanceInfo::new(distance_km);
+                distance_info.station_walk = station
+                    .map(|s| StationWalkInfo::new(&s.station_id, &s.display_name(), distance_km));
+                Some((lot, Some(distance_info), rental, vehicle, features, maps))
+            })
             .collect())
     }
 
-    async fn sort_parking_lots(
-        &self,
-        lots: Vec<(TParkingLotsModel, Option<DistanceInfo>, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>,
-        _request: &ParkingSearchRequest,
-    ) -> Result<Vec<(TParkingLotsModel, Option<DistanceInfo>, Vec<TParkingRentalTypesModel>, Vec<MParkingVehicleTypesModel>, Vec<MParkingFeaturesModel>, Option<TParkingGoogleMapsModel>)>, ApiError> {
-        // ソートロジックの実装
-        Ok(lots)
-    }
-
     async fn get_availability_info(&self, _parking_lot: &TParkingLotsModel, _request: &ParkingSearchRequest) -> Result<AvailabilityInfo, ApiError> {
         // 利用可能性情報の取得
         Ok(AvailabilityInfo {
@@ -576,10 +1270,10 @@ impl ParkingSearchService {
         })
     }
 
-    async fn get_pricing_info(&self, _parking_lot: &TParkingLotsModel, _rental_types: &[TParkingRentalTypesModel], _request: &ParkingSearchRequest) -> Result<PricingInfo, ApiError> {
+    async fn get_pricing_info(&self, parking_lot: &TParkingLotsModel, rental_types: &[TParkingRentalTypesModel], request: &ParkingSearchRequest) -> Result<PricingInfo, ApiError> {
         // 料金情報の取得
         Ok(PricingInfo {
-            estimated_cost: Some(800),
+            estimated_cost: estimate_charge(parking_lot, rental_types, request.get_duration_minutes()),
             cheapest_rental_type: None,
             hourly_rate_range: Some(PriceRange {
                 min_price: 200,
@@ -596,17 +1290,9 @@ impl ParkingSearchService {
     }
 
     async fn get_favorite_info(&self, parking_lot_id: &str, user_id: &str) -> Result<Option<FavoriteInfo>, ApiError> {
-        // お気に入り情報の取得
-        let is_favorite = self.check_favorite_exists(user_id, parking_lot_id).await?;
-        if is_favorite {
-            Ok(Some(FavoriteInfo {
-                is_favorite: true,
-                favorite_added_at: Some(chrono::Utc::now()),
-                favorite_memo: None,
-            }))
-        } else {
-            Ok(None)
-        }
+        // お気に入り情報の取得（登録日時・メモ・表示順・ラベル）
+        self.repository.get_favorite_detail(user_id, parking_lot_id).await
+            .map_err(|e| self.handle_database_error(e))
     }
 
     async fn get_rating_info(&self, _parking_lot_id: &str) -> Result<Option<RatingInfo>, ApiError> {
@@ -619,18 +1305,151 @@ impl ParkingSearchService {
         }))
     }
 
-    async fn build_search_info(&self, request: &ParkingSearchRequest) -> Result<SearchInfo, ApiError> {
+    /// 検索地点を解決
+    ///
+    /// 駅が指定された場合は駅の座標、緯度経度が未指定で住所がある場合はジオコーディング結果を検索地点にする
+    async fn resolve_search_location(
+        &self,
+        request: &mut ParkingSearchRequest,
+    ) -> Result<Option<SearchOrigin>, ApiError> {
+        if request.is_station_search() {
+            let station = self.resolve_station(request)?;
+            request.latitude = Some(station.latitude);
+            request.longitude = Some(station.longitude);
+            return Ok(Some(SearchOrigin::Station(station)));
+        }
+        if request.latitude.is_some() && request.longitude.is_some() {
+            return Ok(None);
+        }
+        let Some(address) = request.address.as_deref().map(str::trim).filter(|a| !a.is_empty()) else {
+            return Err(ApiError::ValidationError("検索地点の指定が必要です（緯度経度、住所または駅）".to_string()));
+        };
+
+        let result = self.geocoder.geocode(address).await?.ok_or_else(|| {
+            ApiError::ValidationError("住所から位置を特定できませんでした".to_string())
+        })?;
+        info!(
+            "住所を座標に変換しました: {} -> ({}, {}) [{}]",
+            address, result.latitude, result.longitude, self.geocoder.backend_name()
+        );
+
+        request.latitude = Some(result.latitude);
+        request.longitude = Some(result.longitude);
+        Ok(Some(SearchOrigin::Address(result)))
+    }
+
+    /// 駅ID、または駅名から検索駅を特定
+    fn resolve_station(&self, request: &ParkingSearchRequest) -> Result<Station, ApiError> {
+        if let Some(station_id) = request.station_id.as_deref().filter(|id| !id.trim().is_empty()) {
+            return self
+                .stations
+                .find_by_id(station_id)
+                .cloned()
+                .ok_or_else(|| ApiError::ValidationError("指定された駅が存在しません".to_string()));
+        }
+
+        let name = request.station_name.as_deref().unwrap_or_default();
+        match self.stations.resolve_name(name) {
+            StationMatch::Found(station) => Ok(station.clone()),
+            StationMatch::Ambiguous(candidates) => Err(ApiError::ValidationError(format!(
+                "同名の駅が複数あります。station_idを指定してください（{}）",
+                describe_candidates(&candidates)
+            ))),
+            StationMatch::NotFound => Err(ApiError::ValidationError(format!("駅が見つかりません: {}", name))),
+        }
+    }
+
+    async fn build_search_info(
+        &self,
+        request: &ParkingSearchRequest,
+        origin: Option<&SearchOrigin>,
+    ) -> Result<SearchInfo, ApiError> {
+        let search_location = match (request.latitude, request.longitude) {
+            (Some(latitude), Some(longitude)) => Some(SearchLocation {
+                latitude,
+                longitude,
+                address: request.address.clone(),
+                location_name: origin.map(SearchOrigin::display_name),
+            }),
+            _ => None,
+        };
+
         // 検索情報の構築
+        let (sort_by, descending) = request.sort();
         Ok(SearchInfo {
-            search_location: None,
+            search_location,
             search_radius_km: request.radius_km,
             search_period: None,
-            applied_filters: vec![],
+            applied_filters: request.applied_filters(),
             sort_info: SortInfo {
-                sort_by: request.sort_by.clone().unwrap_or_else(|| "distance".to_string()),
-                sort_order: request.sort_order.clone().unwrap_or_else(|| "asc".to_string()),
-                sort_display_name: "距離順".to_string(),
+                sort_by: sort_by.as_str().to_string(),
+                sort_order: if descending { "desc" } else { "asc" }.to_string(),
+                sort_display_name: sort_by.display_name(descending).to_string(),
             },
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{logging::count_sql_queries, postgresql_database::DatabaseConfig};
+    use crate::geocoding::OfflineGeocoder;
+    use crate::storage::LocalBlobStore;
+
+    /// テスト用データベース（DB_*環境変数）に接続したサービス
+    async fn test_service() -> ParkingSearchService {
+        let config = DatabaseConfig::from_env().expect("テスト用データベースの接続設定（DB_*環境変数）が必要です");
+        let db = PostgresDatabase::connect(&config).await.expect("テスト用データベースに接続できません");
+        let blob_config = BlobStoreConfig {
+            backend: "local".to_string(),
+            local_root: std::env::temp_dir().to_string_lossy().to_string(),
+            public_base_url: String::new(),
+            signing_secret: "test-secret".to_string(),
+            signed_url_ttl_secs: 3600,
+        };
+        let blob_store = Arc::new(LocalBlobStore::new(&blob_config.local_root, "", &blob_config.signing_secret));
+        ParkingSearchService::new(
+            db,
+            blob_store,
+            blob_config,
+            Arc::new(OfflineGeocoder::default()),
+            Arc::new(StationMaster::default()),
+        )
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DB_* test database"]
+    async fn test_search_parking_lots_query_count_is_constant() {
+        let service = test_service().await;
+        let request = |page_size: i32, cursor: Option<String>| ParkingSearchRequest {
+            latitude: Some(35.681236),
+            longitude: Some(139.767125),
+            radius_km: Some(50.0),
+            sort_by: Some("recommended".to_string()),
+            page_size: Some(page_size),
+            cursor,
+            ..Default::default()
+        };
+
+        let (small, small_count) = count_sql_queries(service.search_parking_lots(request(1, None), None)).await;
+        let small = small.unwrap();
+        assert!(small.pagination.total_count >= 2, "検索範囲内に駐車場が2件以上あるテストデータが必要です");
+
+        // ページの件数によらずクエリ数は一定（検索・件数・関連テーブル・画像）
+        let (large, large_count) = count_sql_queries(service.search_parking_lots(request(20, None), None)).await;
+        let large = large.unwrap();
+        assert!(large.parking_lots.len() > small.parking_lots.len());
+        assert_eq!(large_count, small_count);
+
+        // カーソル指定時も同じクエリ数で、続きの駐車場を返す
+        let cursor = small.pagination.next_cursor.clone();
+        let (next, next_count) = count_sql_queries(service.search_parking_lots(request(1, cursor), None)).await;
+        let next = next.unwrap();
+        assert_eq!(next_count, small_count);
+        assert_eq!(
+            next.parking_lots[0].parking_lot.parking_lot_id,
+            large.parking_lots[1].parking_lot.parking_lot_id
+        );
+    }
+}
@@ -0,0 +1,572 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::postgresql_database::PostgresDatabase;
+use crate::controllers::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::payment_model::{
+    AuthorizePaymentRequest, PaymentChanges, PaymentEventType, PaymentReservationRow, PaymentResponse, PaymentRow,
+    PaymentStatus, PaymentWebhookEvent, PaymentWebhookResult, RefundPaymentRequest, SimulatePaymentWebhookRequest,
+};
+use crate::payments::{simulated_webhooks_enabled, AuthorizeParams, PaymentError, PaymentProvider, DEFAULT_CURRENCY};
+use crate::repositories::PaymentRepository;
+
+/// 決済できる予約ステータス（1: 予約中, 2: 承認済み）
+const PAYABLE_RESERVATION_STATUSES: [&str; 2] = ["1", "2"];
+
+/// 決済サービス
+///
+/// t_paymentsの状態遷移は現在のステータスを条件にした更新で行い、
+/// 同じ操作の再送・Webhookとの競合では先に反映された結果を返す
+pub struct PaymentService {
+    repository: PaymentRepository,
+    provider: Arc<dyn PaymentProvider>,
+}
+
+impl PaymentService {
+    /// 新しいサービスインスタンスを作成
+    pub fn new(db: PostgresDatabase, provider: Arc<dyn PaymentProvider>) -> Self {
+        Self {
+            repository: PaymentRepository::new(db),
+            provider,
+        }
+    }
+
+    /// 予約の決済情報
+    pub async fn get_payment(&self, identity: &UserIdentity, reservation_id: &str) -> Result<PaymentResponse, ApiError> {
+        let reservation = self.load_reservation(reservation_id).await?;
+        ensure_access(identity, &reservation, true)?;
+        self.repository
+            .find_by_reservation(reservation_id)
+            .await?
+            .map(PaymentResponse::from)
+            .ok_or_else(payment_not_found)
+    }
+
+    /// 予約の与信（与信済みの場合は既存の決済を返す）
+    pub async fn authorize_payment(
+        &self,
+        identity: &UserIdentity,
+        reservation_id: &str,
+        req: &AuthorizePaymentRequest,
+    ) -> Result<PaymentResponse, ApiError> {
+        let reservation = self.load_reservation(reservation_id).await?;
+        if !is_reservation_user(identity, &reservation) {
+            return Err(ApiError::AuthorizationError("予約した本人のみ支払いできます".to_string()));
+        }
+        if !PAYABLE_RESERVATION_STATUSES.contains(&reservation.status.as_str()) {
+            return Err(ApiError::ValidationError("この予約は支払いできません".to_string()));
+        }
+
+        let payment = self.authorize(&reservation, req.payment_method.as_deref()).await?;
+        Ok(payment.into())
+    }
+
+    /// 出庫時の売上確定（与信がない場合は登録済みの支払い方法で与信してから確定する）
+    ///
+    /// 売上確定済みの場合は何もしないため、出庫処理の再試行で二重請求にならない
+    pub async fn capture_for_checkout(&self, reservation_id: &str) -> Result<PaymentResponse, ApiError> {
+        let reservation = self.load_reservation(reservation_id).await?;
+        let mut payment = match self.repository.find_by_reservation(reservation_id).await? {
+            Some(payment) if payment.status() == PaymentStatus::Authorized => payment,
+            Some(payment) if matches!(payment.status(), PaymentStatus::Captured | PaymentStatus::Refunded) => {
+                return Ok(payment.into());
+            }
+            Some(payment) if payment.status() == PaymentStatus::Voided => {
+                return Err(ApiError::ValidationError("取消済みの決済は売上確定できません".to_string()));
+            }
+            _ => self.authorize(&reservation, None).await?,
+        };
+
+        if payment.status() == PaymentStatus::Authorized {
+            let provider_payment_id = provider_payment_id(&payment)?;
+            if reservation.amount > payment.amount {
+                warn!(
+                    "予約金額が与信金額を超えているため与信金額で売上確定します: reservation_id={}, 予約金額={}, 与信金額={}",
+                    reservation_id, reservation.amount, payment.amount
+                );
+            }
+            let amount = reservation.amount.min(payment.amount);
+            let idempotency_key = format!("{}:capture", payment.payment_id);
+
+            let changes = match self.provider.capture(&provider_payment_id, amount, &idempotency_key).await {
+                Ok(capture) => PaymentChanges {
+                    captured_amount: Some(capture.amount),
+                    ..Default::default()
+                },
+                Err(PaymentError::Declined(reason)) => {
+                    self.mark_failed(&payment, &reason).await?;
+                    return Err(PaymentError::Declined(reason).into());
+                }
+                Err(e) => return Err(e.into()),
+            };
+            payment = self
+                .apply(&payment, &[PaymentStatus::Authorized], PaymentStatus::Captured, &changes)
+                .await?;
+            info!(
+                "売上を確定しました: reservation_id={}, payment_id={}, 金額={}",
+                reservation_id, payment.payment_id, payment.captured_amount
+            );
+        }
+        Ok(payment.into())
+    }
+
+    /// 返金（駐車場のオーナー・管理者のみ、金額未指定の場合は返金可能な全額）
+    pub async fn refund_payment(
+        &self,
+        identity: &UserIdentity,
+        reservation_id: &str,
+        req: &RefundPaymentRequest,
+    ) -> Result<PaymentResponse, ApiError> {
+        let reservation = self.load_reservation(reservation_id).await?;
+        ensure_access(identity, &reservation, false)?;
+        let payment = self
+            .repository
+            .find_by_reservation(reservation_id)
+            .await?
+            .ok_or_else(payment_not_found)?;
+
+        match payment.status() {
+            PaymentStatus::Captured => {}
+            PaymentStatus::Refunded if req.amount.is_none() => return Ok(payment.into()),
+            _ => return Err(ApiError::ValidationError("売上確定済みの決済のみ返金できます".to_string())),
+        }
+        let refundable = payment.refundable_amount();
+        let amount = req.amount.unwrap_or(refundable);
+        if amount <= 0 || amount > refundable {
+            return Err(ApiError::ValidationError(format!(
+                "返金額は1円以上{}円以下で指定してください",
+                refundable
+            )));
+        }
+
+        let payment = self.refund(&payment, amount, req.reason.as_deref()).await?;
+        Ok(payment.into())
+    }
+
+    /// 与信の取消（売上確定前のみ）
+    pub async fn void_payment(&self, identity: &UserIdentity, reservation_id: &str) -> Result<PaymentResponse, ApiError> {
+        let reservation = self.load_reservation(reservation_id).await?;
+        ensure_access(identity, &reservation, true)?;
+        let payment = self
+            .repository
+            .find_by_reservation(reservation_id)
+            .await?
+            .ok_or_else(payment_not_found)?;
+
+        let payment = match payment.status() {
+            PaymentStatus::Voided => payment,
+            PaymentStatus::Pending | PaymentStatus::Failed => {
+                self.apply(
+                    &payment,
+                    &[PaymentStatus::Pending, PaymentStatus::Failed],
+                    PaymentStatus::Voided,
+                    &PaymentChanges::default(),
+                )
+                .await?
+            }
+            PaymentStatus::Authorized => {
+                let provider_payment_id = provider_payment_id(&payment)?;
+                let idempotency_key = format!("{}:void", payment.payment_id);
+                self.provider.void(&provider_payment_id, &idempotency_key).await?;
+                self.apply(
+                    &payment,
+                    &[PaymentStatus::Authorized],
+                    PaymentStatus::Voided,
+                    &PaymentChanges::default(),
+                )
+                .await?
+            }
+            PaymentStatus::Captured | PaymentStatus::Refunded => {
+                return Err(ApiError::ValidationError(
+                    "売上確定済みの決済は取消できません。返金を行ってください".to_string(),
+                ));
+            }
+        };
+        info!("与信を取消しました: reservation_id={}, payment_id={}", reservation_id, payment.payment_id);
+        Ok(payment.into())
+    }
+
+    /// 予約キャンセル時の精算
+    ///
+    /// 売上確定済みの場合はキャンセル料を差し引いた額を返金し、与信のみの場合はキャンセル料だけを売上確定する
+    /// （キャンセル料が0円の場合は与信を取消）。精算済みの決済は変更しないため再実行できる
+    pub async fn settle_cancellation(
+        &self,
+        reservation_id: &str,
+        cancellation_fee: i64,
+    ) -> Result<Option<PaymentResponse>, ApiError> {
+        let Some(payment) = self.repository.find_by_reservation(reservation_id).await? else {
+            return Ok(None);
+        };
+
+        let payment = match payment.status() {
+            PaymentStatus::Captured => {
+                let amount = (payment.captured_amount - cancellation_fee).max(0) - payment.refunded_amount;
+                if amount > 0 {
+                    self.refund(&payment, amount, Some("予約のキャンセル")).await?
+                } else {
+                    payment
+                }
+            }
+            PaymentStatus::Authorized if cancellation_fee > 0 => {
+                let provider_payment_id = provider_payment_id(&payment)?;
+                let amount = cancellation_fee.min(payment.amount);
+                let idempotency_key = format!("{}:capture", payment.payment_id);
+                let capture = self.provider.capture(&provider_payment_id, amount, &idempotency_key).await?;
+                let changes = PaymentChanges {
+                    captured_amount: Some(capture.amount),
+                    ..Default::default()
+                };
+                info!(
+                    "キャンセル料を売上確定しました: reservation_id={}, 金額={}",
+                    reservation_id, capture.amount
+                );
+                self.apply(&payment, &[PaymentStatus::Authorized], PaymentStatus::Captured, &changes)
+                    .await?
+            }
+            PaymentStatus::Authorized => {
+                let provider_payment_id = provider_payment_id(&payment)?;
+                let idempotency_key = format!("{}:void", payment.payment_id);
+                self.provider.void(&provider_payment_id, &idempotency_key).await?;
+                self.apply(
+                    &payment,
+                    &[PaymentStatus::Authorized],
+                    PaymentStatus::Voided,
+                    &PaymentChanges::default(),
+                )
+                .await?
+            }
+            PaymentStatus::Pending | PaymentStatus::Failed => {
+                if cancellation_fee > 0 {
+                    warn!(
+                        "与信がないためキャンセル料を請求できません: reservation_id={}, キャンセル料={}",
+                        reservation_id, cancellation_fee
+                    );
+                }
+                self.apply(
+                    &payment,
+                    &[PaymentStatus::Pending, PaymentStatus::Failed],
+                    PaymentStatus::Voided,
+                    &PaymentChanges::default(),
+                )
+                .await?
+            }
+            PaymentStatus::Refunded | PaymentStatus::Voided => payment,
+        };
+        Ok(Some(payment.into()))
+    }
+
+    /// 決済プロバイダーからのWebhookを処理
+    ///
+    /// 処理済みのイベントと、既に反映済み・遷移できない状態のイベントは決済状態を変更しない。
+    /// 反映前に失敗したイベントは再送時に再処理する（状態遷移は現在のステータスを条件にするため重複して反映されない）
+    pub async fn handle_webhook(&self, body: &[u8], signature: &str) -> Result<PaymentWebhookResult, ApiError> {
+        let event = self.provider.parse_webhook(body, signature).map_err(|e| {
+            warn!("Webhookの検証に失敗: {}", e);
+            ApiError::from(e)
+        })?;
+        let provider = self.provider.provider_name();
+        let payload = serde_json::to_value(&event).map_err(|e| ApiError::ValidationError(e.to_string()))?;
+
+        let payment = self
+            .repository
+            .find_by_provider_payment_id(provider, &event.provider_payment_id)
+            .await?;
+        let mut result = PaymentWebhookResult {
+            event_id: event.event_id.clone(),
+            duplicate: false,
+            applied: false,
+            payment_status: payment.as_ref().map(PaymentRow::status),
+        };
+
+        if !self.repository.insert_event(provider, &event, &payload).await? {
+            info!("処理済みのWebhookイベントです: event_id={}", event.event_id);
+            result.duplicate = true;
+            return Ok(result);
+        }
+
+        match payment {
+            Some(payment) => {
+                if let Some(updated) = self.apply_event(&payment, &event).await? {
+                    result.applied = true;
+                    result.payment_status = Some(updated.status());
+                }
+            }
+            None => warn!(
+                "Webhookの対象の決済が見つかりません: event_id={}, provider_payment_id={}",
+                event.event_id, event.provider_payment_id
+            ),
+        }
+        self.repository.mark_event_processed(provider, &event.event_id).await?;
+        info!(
+            "Webhookイベントを処理しました: event_id={}, event_type={}, applied={}",
+            event.event_id,
+            event.event_type.as_str(),
+            result.applied
+        );
+        Ok(result)
+    }
+
+    /// Webhookの模擬送信（開発環境のテスト用プロバイダーでのローカル動作確認用、管理者のみ）
+    pub async fn simulate_webhook(
+        &self,
+        identity: &UserIdentity,
+        req: &SimulatePaymentWebhookRequest,
+    ) -> Result<PaymentWebhookResult, ApiError> {
+        if !simulated_webhooks_enabled() {
+            return Err(ApiError::NotFoundError("Webhookの模擬送信は利用できません".to_string()));
+        }
+        if !identity.is_admin() {
+            return Err(ApiError::AuthorizationError("Webhookの模擬送信は管理者のみ実行できます".to_string()));
+        }
+        self.load_reservation(&req.reservation_id).await?;
+        let payment = self
+            .repository
+            .find_by_reservation(&req.reservation_id)
+            .await?
+            .ok_or_else(payment_not_found)?;
+
+        let event = PaymentWebhookEvent {
+            event_id: format!("evt_sim_{}", Uuid::new_v4().simple()),
+            event_type: req.event_type,
+            provider_payment_id: provider_payment_id(&payment)?,
+            amount: req.amount,
+            failure_reason: req.failure_reason.clone(),
+            occurred_at: Utc::now(),
+        };
+        let (body, signature) = self.provider.simulate_webhook(&event)?;
+        self.handle_webhook(&body, &signature).await
+    }
+
+    /// 返金（売上確定済みの決済のみ、返金済み金額が変わっていない場合のみ反映）
+    async fn refund(&self, payment: &PaymentRow, amount: i64, reason: Option<&str>) -> Result<PaymentRow, ApiError> {
+        let provider_payment_id = provider_payment_id(payment)?;
+        // 返金前の返金済み金額を含めることで、同じ返金の再送はプロバイダー側でも同じ結果になる
+        let idempotency_key = format!("{}:refund:{}:{}", payment.payment_id, payment.refunded_amount, amount);
+        let refund = self.provider.refund(&provider_payment_id, amount, &idempotency_key).await?;
+
+        let refunded_amount = payment.refunded_amount + refund.amount;
+        let to = if refunded_amount >= payment.captured_amount {
+            PaymentStatus::Refunded
+        } else {
+            PaymentStatus::Captured
+        };
+        let changes = PaymentChanges {
+            refunded_amount: Some(refunded_amount),
+            expected_refunded_amount: Some(payment.refunded_amount),
+            ..Default::default()
+        };
+        let updated = self.apply(payment, &[PaymentStatus::Captured], to, &changes).await?;
+        info!(
+            "返金しました: reservation_id={}, refund_id={}, 金額={}, 理由={}",
+            payment.reservation_id,
+            refund.refund_id,
+            refund.amount,
+            reason.unwrap_or("-")
+        );
+        Ok(updated)
+    }
+
+    /// 与信（与信待ち・失敗の決済のみプロバイダーに送信する）
+    async fn authorize(
+        &self,
+        reservation: &PaymentReservationRow,
+        payment_method: Option<&str>,
+    ) -> Result<PaymentRow, ApiError> {
+        if reservation.amount <= 0 {
+            return Err(ApiError::ValidationError("予約金額が設定されていません".to_string()));
+        }
+
+        let mut payment = self
+            .repository
+            .ensure_payment(reservation, self.provider.provider_name(), DEFAULT_CURRENCY)
+            .await?;
+        match payment.status() {
+            PaymentStatus::Pending => {}
+            PaymentStatus::Failed => {
+                payment = match self.repository.reset_failed_payment(payment.payment_id, reservation.amount).await? {
+                    Some(payment) => payment,
+                    None => self.reload(&payment).await?,
+                };
+                if payment.status() != PaymentStatus::Pending {
+                    return Ok(payment);
+                }
+            }
+            PaymentStatus::Voided => {
+                return Err(ApiError::ValidationError("取消済みの決済です".to_string()));
+            }
+            PaymentStatus::Authorized | PaymentStatus::Captured | PaymentStatus::Refunded => return Ok(payment),
+        }
+
+        let params = AuthorizeParams {
+            reference: reservation.reservation_id.clone(),
+            amount: payment.amount,
+            currency: payment.currency.clone(),
+            payment_method: payment_method.map(str::to_string),
+            idempotency_key: format!("{}:authorize:{}", payment.payment_id, payment.attempt_count),
+        };
+        let changes = match self.provider.authorize(&params).await {
+            Ok(authorization) => PaymentChanges {
+                provider_payment_id: Some(authorization.provider_payment_id),
+                ..Default::default()
+            },
+            Err(PaymentError::Declined(reason)) => {
+                self.mark_failed(&payment, &reason).await?;
+                return Err(PaymentError::Declined(reason).into());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let payment = self
+            .apply(&payment, &[PaymentStatus::Pending], PaymentStatus::Authorized, &changes)
+            .await?;
+        info!(
+            "与信しました: reservation_id={}, payment_id={}, 金額={}",
+            reservation.reservation_id, payment.payment_id, payment.amount
+        );
+        Ok(payment)
+    }
+
+    /// Webhookイベントを決済状態に反映（反映した場合は更新後の決済）
+    async fn apply_event(
+        &self,
+        payment: &PaymentRow,
+        event: &PaymentWebhookEvent,
+    ) -> Result<Option<PaymentRow>, ApiError> {
+        let current = payment.status();
+        let (from, to, changes) = match event.event_type {
+            PaymentEventType::Refunded => {
+                // 返金は累計返金額で判定する（一部返金はcapturedのまま）
+                let refunded_amount = event
+                    .amount
+                    .unwrap_or(payment.captured_amount)
+                    .min(payment.captured_amount);
+                if current != PaymentStatus::Captured || refunded_amount <= payment.refunded_amount {
+                    return Ok(None);
+                }
+                let to = if refunded_amount >= payment.captured_amount {
+                    PaymentStatus::Refunded
+                } else {
+                    PaymentStatus::Captured
+                };
+                let changes = PaymentChanges {
+                    refunded_amount: Some(refunded_amount),
+                    expected_refunded_amount: Some(payment.refunded_amount),
+                    ..Default::default()
+                };
+                (PaymentStatus::Captured, to, changes)
+            }
+            event_type => {
+                let to = event_type.target_status();
+                if !current.can_transition_to(to) {
+                    if current != to {
+                        warn!(
+                            "現在の決済ステータスから遷移できないWebhookイベントを無視します: event_id={}, status={}, event_type={}",
+                            event.event_id,
+                            current.as_str(),
+                            event_type.as_str()
+                        );
+                    }
+                    return Ok(None);
+                }
+                let changes = PaymentChanges {
+                    captured_amount: (to == PaymentStatus::Captured)
+                        .then(|| event.amount.unwrap_or(payment.amount).min(payment.amount)),
+                    failure_reason: event.failure_reason.clone().or_else(|| {
+                        (to == PaymentStatus::Failed).then(|| "決済プロバイダーから失敗が通知されました".to_string())
+                    }),
+                    ..Default::default()
+                };
+                (current, to, changes)
+            }
+        };
+
+        Ok(self.repository.transition(payment.payment_id, &[from], to, &changes).await?)
+    }
+
+    /// ステータスを遷移（他の処理で先に遷移済みの場合は最新の決済を返す）
+    async fn apply(
+        &self,
+        payment: &PaymentRow,
+        from: &[PaymentStatus],
+        to: PaymentStatus,
+        changes: &PaymentChanges,
+    ) -> Result<PaymentRow, ApiError> {
+        match self.repository.transition(payment.payment_id, from, to, changes).await? {
+            Some(updated) => Ok(updated),
+            None => {
+                info!(
+                    "決済は他の処理で更新済みです: payment_id={}, 遷移先={}",
+                    payment.payment_id,
+                    to.as_str()
+                );
+                self.reload(payment).await
+            }
+        }
+    }
+
+    async fn mark_failed(&self, payment: &PaymentRow, reason: &str) -> Result<(), ApiError> {
+        warn!("決済に失敗しました: payment_id={}, 理由={}", payment.payment_id, reason);
+        let changes = PaymentChanges {
+            failure_reason: Some(reason.to_string()),
+            ..Default::default()
+        };
+        self.repository
+            .transition(
+                payment.payment_id,
+                &[PaymentStatus::Pending, PaymentStatus::Authorized],
+                PaymentStatus::Failed,
+                &changes,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn reload(&self, payment: &PaymentRow) -> Result<PaymentRow, ApiError> {
+        self.repository
+            .find_by_reservation(&payment.reservation_id)
+            .await?
+            .ok_or_else(payment_not_found)
+    }
+
+    async fn load_reservation(&self, reservation_id: &str) -> Result<PaymentReservationRow, ApiError> {
+        self.repository
+            .find_reservation(reservation_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFoundError("予約が見つかりません".to_string()))
+    }
+}
+
+fn is_reservation_user(identity: &UserIdentity, reservation: &PaymentReservationRow) -> bool {
+    reservation.user_login_id.as_deref() == Some(identity.user_id.as_str())
+}
+
+fn is_lot_owner(identity: &UserIdentity, reservation: &PaymentReservationRow) -> bool {
+    identity.is_owner() && reservation.owner_login_id.as_deref() == Some(identity.user_id.as_str())
+}
+
+/// 予約した本人（allow_user）・駐車場のオーナー・管理者のみ許可
+fn ensure_access(identity: &UserIdentity, reservation: &PaymentReservationRow, allow_user: bool) -> Result<(), ApiError> {
+    if identity.is_admin() || is_lot_owner(identity, reservation) || (allow_user && is_reservation_user(identity, reservation)) {
+        return Ok(());
+    }
+    warn!(
+        "予約の決済へのアクセスを拒否しました: user_id={}, reservation_id={}",
+        identity.user_id, reservation.reservation_id
+    );
+    Err(ApiError::AuthorizationError("この予約の決済を操作する権限がありません".to_string()))
+}
+
+fn provider_payment_id(payment: &PaymentRow) -> Result<String, ApiError> {
+    payment
+        .provider_payment_id
+        .clone()
+        .ok_or_else(|| ApiError::ValidationError("与信されていない決済です".to_string()))
+}
+
+fn payment_not_found() -> ApiError {
+    ApiError::NotFoundError("決済情報が見つかりません".to_string())
+}
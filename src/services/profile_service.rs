@@ -0,0 +1,332 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use rand::Rng;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::postgresql_database::PostgresDatabase;
+use crate::controllers::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::profile_model::{
+    ContactChangeRequest, ContactChangeResponse, ContactChangeVerifyRequest, ProfileResponse, ProfileRow,
+    ProfileUpdateRequest, CONTACT_CHANGE_EMAIL, CONTACT_CHANGE_PHONE,
+};
+use crate::models::receipt_model::normalize_invoice_registration_number;
+use crate::repositories::ProfileRepository;
+use crate::services::{EmailService, SmsService};
+use crate::storage::{hmac_sha256, normalize_blob_key, signed_url_for_stored, to_hex, BlobStore, BlobStoreConfig};
+use crate::utils::env::{parse_env_or, require_signing_secret};
+use crate::utils::image_processing::{content_hash, process_image, ImageUploadConfig};
+
+/// プロフィール写真の保存先キーの接頭辞
+const PROFILE_PHOTO_PREFIX: &str = "profile_photos";
+
+pub struct ProfileService {
+    repository: ProfileRepository,
+    image_config: ImageUploadConfig,
+    blob_store: Arc<dyn BlobStore>,
+    blob_config: BlobStoreConfig,
+    code_ttl_minutes: i64,
+    max_attempts: i32,
+    code_secret: String,
+}
+
+impl ProfileService {
+    /// 新しいサービスインスタンスを作成
+    pub fn new(db: PostgresDatabase, blob_store: Arc<dyn BlobStore>, blob_config: BlobStoreConfig) -> Self {
+        Self {
+            repository: ProfileRepository::new(db),
+            image_config: ImageUploadConfig::from_env(),
+            blob_store,
+            blob_config,
+            code_ttl_minutes: parse_env_or("CONTACT_CHANGE_CODE_TTL_MINUTES", 10),
+            max_attempts: parse_env_or("CONTACT_CHANGE_MAX_ATTEMPTS", 5),
+            code_secret: require_signing_secret("CONTACT_CHANGE_CODE_SECRET"),
+        }
+    }
+
+    /// 画像アップロード設定
+    pub fn image_config(&self) -> &ImageUploadConfig {
+        &self.image_config
+    }
+
+    /// ログイン中アカウントのプロフィールを取得
+    pub async fn get_profile(&self, identity: &UserIdentity) -> Result<ProfileResponse, ApiError> {
+        let row = self.load_profile(identity).await?;
+        self.to_response(row)
+    }
+
+    /// プロフィールを更新し、更新後のプロフィールを返す
+    pub async fn update_profile(
+        &self,
+        identity: &UserIdentity,
+        req: ProfileUpdateRequest,
+    ) -> Result<ProfileResponse, ApiError> {
+        let req = trim_update_request(req);
+        req.validate().map_err(ApiError::ValidationError)?;
+
+        let row = self.load_profile(identity).await?;
+        if !row.is_owner && req.invoice_registration_number.is_some() {
+            return Err(ApiError::ValidationError("登録番号はオーナーのみ登録できます".to_string()));
+        }
+        let login_id = parse_login_id(identity)?;
+        self.repository
+            .update_profile(login_id, row.is_owner, &req, Utc::now())
+            .await?;
+
+        info!("プロフィールを更新しました: login_id={}", login_id);
+        self.get_profile(identity).await
+    }
+
+    /// プロフィール写真を登録・差し替え
+    ///
+    /// メタデータを除去した中サイズ画像のみ保存し、差し替え前の写真は削除する
+    pub async fn upload_profile_photo(
+        &self,
+        identity: &UserIdentity,
+        bytes: Vec<u8>,
+    ) -> Result<ProfileResponse, ApiError> {
+        let row = self.load_profile(identity).await?;
+        let account_id = row
+            .account_id
+            .clone()
+            .ok_or_else(|| ApiError::NotFoundError("プロフィールが登録されていません".to_string()))?;
+
+        let config = self.image_config.clone();
+        let image = tokio::task::spawn_blocking(move || process_image(&bytes, &config))
+            .await
+            .map_err(|e| {
+                error!("画像処理タスクの実行に失敗: {}", e);
+                ApiError::InternalServerError
+            })??;
+
+        let key = format!(
+            "{}/{}/{}.jpg",
+            PROFILE_PHOTO_PREFIX,
+            account_id,
+            content_hash(&image.medium)
+        );
+        self.blob_store.put(&key, image.medium, "image/jpeg").await?;
+
+        let mut tx = self.repository.begin_transaction().await?;
+        let previous = match self
+            .repository
+            .upsert_profile_photo(&mut tx, row.is_owner, &account_id, &key, Utc::now())
+            .await
+        {
+            Ok(previous) => previous,
+            Err(e) => {
+                self.delete_blob(&key).await;
+                return Err(e.into());
+            }
+        };
+        if let Err(e) = tx.commit().await {
+            error!("トランザクションコミットに失敗: {}", e);
+            self.delete_blob(&key).await;
+            return Err(ApiError::DatabaseError("プロフィール写真の保存に失敗しました".to_string()));
+        }
+
+        if let Some(previous) = previous.map(|p| normalize_blob_key(&p)).filter(|p| *p != key) {
+            self.delete_blob(&previous).await;
+        }
+
+        info!("プロフィール写真を更新しました: account_id={}", account_id);
+        self.get_profile(identity).await
+    }
+
+    /// 新しい連絡先に認証コードを送信
+    pub async fn request_contact_change(
+        &self,
+        identity: &UserIdentity,
+        req: ContactChangeRequest,
+    ) -> Result<ContactChangeResponse, ApiError> {
+        let req = ContactChangeRequest {
+            change_type: req.change_type.trim().to_string(),
+            new_value: req.new_value.trim().to_string(),
+        };
+        req.validate().map_err(ApiError::ValidationError)?;
+
+        let row = self.load_profile(identity).await?;
+        let current = if req.change_type == CONTACT_CHANGE_EMAIL { &row.email } else { &row.phone_number };
+        if current.eq_ignore_ascii_case(&req.new_value) {
+            return Err(ApiError::ValidationError("現在と同じ連絡先です".to_string()));
+        }
+        if self.repository.contact_in_use(&req.change_type, &req.new_value).await? {
+            return Err(ApiError::DuplicateError("この連絡先は既に使用されています".to_string()));
+        }
+
+        let login_id = parse_login_id(identity)?;
+        let code = format!("{:06}", rand::rng().random_range(100000..1000000));
+        let expires_at = Utc::now() + Duration::minutes(self.code_ttl_minutes);
+        self.repository
+            .replace_contact_change(
+                login_id,
+                &req.change_type,
+                &req.new_value,
+                &self.code_hash(login_id, &req.change_type, &code),
+                expires_at,
+            )
+            .await?;
+
+        if req.change_type == CONTACT_CHANGE_EMAIL {
+            EmailService::from_env().send_verification_code(&req.new_value, &code).await?;
+        } else {
+            SmsService::from_env().send_verification_code(&req.new_value, &code).await?;
+        }
+
+        info!("連絡先変更の認証コードを送信: login_id={}, change_type={}", login_id, req.change_type);
+        Ok(ContactChangeResponse {
+            destination: mask_contact(&req.change_type, &req.new_value),
+            change_type: req.change_type,
+            expires_at,
+        })
+    }
+
+    /// 認証コードを確認して連絡先を変更
+    pub async fn verify_contact_change(
+        &self,
+        identity: &UserIdentity,
+        req: ContactChangeVerifyRequest,
+    ) -> Result<ProfileResponse, ApiError> {
+        if req.change_type != CONTACT_CHANGE_EMAIL && req.change_type != CONTACT_CHANGE_PHONE {
+            return Err(ApiError::ValidationError(
+                "change_type は 'email' または 'phone' を指定してください".to_string(),
+            ));
+        }
+
+        let login_id = parse_login_id(identity)?;
+        let pending = self
+            .repository
+            .find_pending_contact_change(login_id, &req.change_type)
+            .await?
+            .ok_or_else(|| ApiError::NotFoundError("連絡先変更のリクエストがありません".to_string()))?;
+
+        if pending.expires_at < Utc::now() {
+            return Err(ApiError::ValidationError("認証コードの有効期限が切れています".to_string()));
+        }
+        // 照合前に試行回数を消費する（同時に照合されても上限を超えない）
+        if !self
+            .repository
+            .consume_contact_change_attempt(pending.request_id, self.max_attempts)
+            .await?
+        {
+            warn!("連絡先変更の試行回数超過: login_id={}", login_id);
+            return Err(ApiError::RateLimitError(
+                "認証コードの試行回数が上限を超えました。再度コードを取得してください".to_string(),
+            ));
+        }
+
+        if self.code_hash(login_id, &req.change_type, req.verification_code.trim()) != pending.code_hash {
+            return Err(ApiError::ValidationError("認証コードが正しくありません".to_string()));
+        }
+
+        // コード送信後に他アカウントが登録した場合に備えて再確認
+        if self.repository.contact_in_use(&req.change_type, &pending.new_value).await? {
+            return Err(ApiError::DuplicateError("この連絡先は既に使用されています".to_string()));
+        }
+
+        let row = self.load_profile(identity).await?;
+        self.repository
+            .apply_contact_change(login_id, row.is_owner, &req.change_type, &pending.new_value, pending.request_id, Utc::now())
+            .await?;
+
+        self.get_profile(identity).await
+    }
+
+    /// 認証コードのハッシュ（6桁のコードは総当たりできるため、サーバーの秘密鍵でHMACを取る）
+    fn code_hash(&self, login_id: Uuid, change_type: &str, code: &str) -> String {
+        let message = format!("{}:{}:{}", login_id, change_type, code);
+        to_hex(&hmac_sha256(self.code_secret.as_bytes(), message.as_bytes()))
+    }
+
+    async fn load_profile(&self, identity: &UserIdentity) -> Result<ProfileRow, ApiError> {
+        let login_id = parse_login_id(identity)?;
+        self.repository
+            .find_profile(login_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFoundError("プロフィールが見つかりません".to_string()))
+    }
+
+    fn to_response(&self, row: ProfileRow) -> Result<ProfileResponse, ApiError> {
+        let photo_url = match row.photo_path.as_deref() {
+            Some(path) => Some(signed_url_for_stored(
+                self.blob_store.as_ref(),
+                path,
+                self.blob_config.signed_url_ttl(),
+            )?),
+            None => None,
+        };
+
+        Ok(ProfileResponse {
+            user_type: if row.is_owner { "owner" } else { "user" }.to_string(),
+            account_id: row.account_id.unwrap_or_default(),
+            login_id: row.login_id,
+            email: row.email,
+            phone_number: row.phone_number,
+            full_name: row.full_name.unwrap_or_default(),
+            full_name_kana: row.full_name_kana,
+            birthday: row.birthday,
+            gender: row.gender,
+            postal_code: row.postal_code,
+            address: row.address.unwrap_or_default(),
+            registrant_type: row.registrant_type,
+            invoice_registration_number: row.invoice_registration_number,
+            photo_url,
+            photo_description: row.photo_description,
+        })
+    }
+
+    async fn delete_blob(&self, key: &str) {
+        if let Err(e) = self.blob_store.delete(key).await {
+            warn!("プロフィール写真の削除に失敗: {} - {}", key, e);
+        }
+    }
+}
+
+fn parse_login_id(identity: &UserIdentity) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(&identity.user_id)
+        .map_err(|_| ApiError::AuthenticationError("ログイン情報が正しくありません".to_string()))
+}
+
+/// 入力値の前後の空白を除去
+fn trim_update_request(req: ProfileUpdateRequest) -> ProfileUpdateRequest {
+    let trim = |v: Option<String>| v.map(|s| s.trim().to_string());
+    ProfileUpdateRequest {
+        full_name: trim(req.full_name),
+        full_name_kana: trim(req.full_name_kana),
+        birthday: trim(req.birthday),
+        gender: trim(req.gender),
+        postal_code: trim(req.postal_code),
+        address: trim(req.address),
+        // 検証済みの登録番号は表記を揃えて保存する（例: t7000-0120-50002 → T7000012050002）
+        invoice_registration_number: trim(req.invoice_registration_number)
+            .map(|n| normalize_invoice_registration_number(&n).unwrap_or(n)),
+    }
+}
+
+/// 送信先を一部マスク（例: ta***@example.com、*******5678）
+fn mask_contact(change_type: &str, value: &str) -> String {
+    if change_type == CONTACT_CHANGE_EMAIL {
+        let (local, domain) = value.split_once('@').unwrap_or((value, ""));
+        let visible: String = local.chars().take(2).collect();
+        format!("{}***@{}", visible, domain)
+    } else {
+        let digits: Vec<char> = value.chars().collect();
+        let keep = digits.len().min(4);
+        let masked = "*".repeat(digits.len() - keep);
+        format!("{}{}", masked, digits[digits.len() - keep..].iter().collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_contact() {
+        assert_eq!(mask_contact(CONTACT_CHANGE_EMAIL, "taro@example.com"), "ta***@example.com");
+        assert_eq!(mask_contact(CONTACT_CHANGE_PHONE, "090-1234-5678"), "*********5678");
+        assert_eq!(mask_contact(CONTACT_CHANGE_PHONE, "12"), "12");
+    }
+}
@@ -0,0 +1,165 @@
+use chrono::{Datelike, FixedOffset, Utc};
+use tracing::{info, warn};
+
+use crate::config::postgresql_database::PostgresDatabase;
+use crate::controllers::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::receipt_model::{
+    included_tax, IssueReceiptRequest, NewReceipt, ReceiptEmailResponse, ReceiptResponse, ReceiptSourceRow,
+    STANDARD_TAX_RATE,
+};
+use crate::repositories::ReceiptRepository;
+use crate::services::EmailService;
+
+/// 領収書を発行できる予約ステータス（4: 利用完了）
+const COMPLETED_RESERVATION_STATUS: &str = "4";
+
+/// 領収書サービス
+///
+/// 領収書は1予約につき1件だけ発行し、発行時点の発行者・金額を保存する。
+/// 再出力・メール送信は保存した内容から行うため、番号や金額は変わらない
+pub struct ReceiptService {
+    repository: ReceiptRepository,
+    email_service: EmailService,
+}
+
+impl ReceiptService {
+    /// 新しいサービスインスタンスを作成
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self {
+            repository: ReceiptRepository::new(db),
+            email_service: EmailService::from_env(),
+        }
+    }
+
+    /// 領収書の発行（発行済みの場合は既存の領収書を返す）
+    pub async fn issue_receipt(
+        &self,
+        identity: &UserIdentity,
+        reservation_id: &str,
+        req: IssueReceiptRequest,
+    ) -> Result<ReceiptResponse, ApiError> {
+        let recipient_name = req.normalize().map_err(ApiError::ValidationError)?;
+        let source = self.load_source(reservation_id).await?;
+        if !is_reservation_user(identity, &source) && !identity.is_admin() {
+            return Err(ApiError::AuthorizationError("予約した本人のみ領収書を発行できます".to_string()));
+        }
+
+        if let Some(receipt) = self.repository.find_by_reservation(reservation_id).await? {
+            return Ok(receipt.into());
+        }
+        if source.status != COMPLETED_RESERVATION_STATUS {
+            return Err(ApiError::ValidationError("利用完了した予約のみ領収書を発行できます".to_string()));
+        }
+        let Some(issuer_name) = source.owner_name.clone() else {
+            return Err(ApiError::ValidationError("発行者の情報がないため領収書を発行できません".to_string()));
+        };
+        if source.amount <= 0 {
+            return Err(ApiError::ValidationError("請求金額がないため領収書を発行できません".to_string()));
+        }
+
+        let receipt = NewReceipt {
+            reservation_id: source.reservation_id.clone(),
+            user_id: source.user_id.clone(),
+            owner_id: source.owner_id.clone(),
+            issuer_name,
+            invoice_registration_number: source.invoice_registration_number.clone(),
+            recipient_name: recipient_name.or_else(|| source.user_full_name.clone()),
+            parking_lot_name: source.parking_lot_name.clone(),
+            usage_start_datetime: source.entry_datetime.unwrap_or(source.start_datetime),
+            usage_end_datetime: source.exit_datetime.unwrap_or(source.end_datetime),
+            total_amount: source.amount,
+            tax_rate: STANDARD_TAX_RATE,
+            tax_amount: included_tax(source.amount, STANDARD_TAX_RATE),
+        };
+        let jst = FixedOffset::east_opt(9 * 3600).expect("日本時間のオフセット");
+        let issue_year = Utc::now().with_timezone(&jst).year();
+
+        let row = match self.repository.insert_receipt(&receipt, issue_year).await? {
+            Some(row) => {
+                info!(
+                    "領収書を発行しました: reservation_id={}, receipt_number={}, 金額={}, 適格簡易請求書={}",
+                    reservation_id,
+                    row.receipt_number,
+                    row.total_amount,
+                    row.invoice_registration_number.is_some()
+                );
+                row
+            }
+            // 同時に発行された場合は先に登録された領収書を返す
+            None => self
+                .repository
+                .find_by_reservation(reservation_id)
+                .await?
+                .ok_or_else(receipt_not_found)?,
+        };
+        Ok(row.into())
+    }
+
+    /// 発行済みの領収書（予約した本人・駐車場のオーナー・管理者）
+    pub async fn get_receipt(&self, identity: &UserIdentity, reservation_id: &str) -> Result<ReceiptResponse, ApiError> {
+        let source = self.load_source(reservation_id).await?;
+        if !is_reservation_user(identity, &source) && !is_lot_owner(identity, &source) && !identity.is_admin() {
+            warn!(
+                "他ユーザーの領収書の参照を拒否: reservation_id={}, user_id={}",
+                reservation_id, identity.user_id
+            );
+            return Err(ApiError::AuthorizationError("この領収書を参照する権限がありません".to_string()));
+        }
+        self.repository
+            .find_by_reservation(reservation_id)
+            .await?
+            .map(ReceiptResponse::from)
+            .ok_or_else(receipt_not_found)
+    }
+
+    /// 発行済みの領収書をアカウントのメールアドレスにPDF添付で送信
+    pub async fn email_receipt(
+        &self,
+        identity: &UserIdentity,
+        reservation_id: &str,
+    ) -> Result<ReceiptEmailResponse, ApiError> {
+        let source = self.load_source(reservation_id).await?;
+        if !is_reservation_user(identity, &source) && !identity.is_admin() {
+            return Err(ApiError::AuthorizationError("予約した本人のみ領収書を送信できます".to_string()));
+        }
+        let Some(email) = source.user_email else {
+            return Err(ApiError::ValidationError("送信先のメールアドレスがありません".to_string()));
+        };
+        let receipt: ReceiptResponse = self
+            .repository
+            .find_by_reservation(reservation_id)
+            .await?
+            .ok_or_else(receipt_not_found)?
+            .into();
+
+        self.email_service.send_receipt(&email, &receipt, receipt.to_pdf()).await?;
+        info!(
+            "領収書をメールで送信しました: reservation_id={}, receipt_number={}",
+            reservation_id, receipt.receipt_number
+        );
+        Ok(ReceiptEmailResponse {
+            receipt_number: receipt.receipt_number,
+            sent_to: email,
+        })
+    }
+
+    async fn load_source(&self, reservation_id: &str) -> Result<ReceiptSourceRow, ApiError> {
+        self.repository
+            .find_source(reservation_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFoundError("指定された予約が存在しません".to_string()))
+    }
+}
+
+fn is_reservation_user(identity: &UserIdentity, source: &ReceiptSourceRow) -> bool {
+    source.user_login_id.as_deref() == Some(identity.user_id.as_str())
+}
+
+fn is_lot_owner(identity: &UserIdentity, source: &ReceiptSourceRow) -> bool {
+    identity.is_owner() && source.owner_login_id.as_deref() == Some(identity.user_id.as_str())
+}
+
+fn receipt_not_found() -> ApiError {
+    ApiError::NotFoundError("領収書が発行されていません".to_string())
+}
@@ -0,0 +1,352 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde_json::json;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::postgresql_database::{DatabaseError, PostgresDatabase};
+use crate::controllers::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::notification_model::NOTIFICATION_TYPE_SAVED_SEARCH_MATCH;
+use crate::models::saved_search_model::{SavedSearchMatch, SavedSearchRequest, SavedSearchResponse, SavedSearchRow};
+use crate::repositories::saved_search_repository::SavedSearchValues;
+use crate::repositories::{NotificationRepository, SavedSearchRepository};
+use crate::services::{EmailService, ParkingSearchService};
+use crate::utils::env::parse_env_or;
+
+/// 処理中のまま再取得するまでの時間（秒、処理中に停止したイベントの再処理用）
+const EVENT_CLAIM_TIMEOUT_SECS: i64 = 600;
+
+/// 保存検索と空き・新規登録通知のサービス
+pub struct SavedSearchService {
+    repository: SavedSearchRepository,
+    notification_repository: NotificationRepository,
+    search_service: Arc<ParkingSearchService>,
+    email_service: EmailService,
+    max_saved_searches: i64,
+    default_quiet_minutes: i32,
+    /// 1回の評価で処理するイベント数の上限
+    event_batch_size: i64,
+}
+
+impl SavedSearchService {
+    /// 新しいサービスインスタンスを作成
+    pub fn new(db: PostgresDatabase, search_service: Arc<ParkingSearchService>) -> Self {
+        Self {
+            repository: SavedSearchRepository::new(db.clone()),
+            notification_repository: NotificationRepository::new(db),
+            search_service,
+            email_service: EmailService::from_env(),
+            max_saved_searches: parse_env_or("SAVED_SEARCH_MAX_COUNT", 10),
+            default_quiet_minutes: parse_env_or("SAVED_SEARCH_DEFAULT_QUIET_MINUTES", 60),
+            event_batch_size: parse_env_or("SAVED_SEARCH_EVENT_BATCH_SIZE", 200),
+        }
+    }
+
+    /// 保存検索一覧
+    pub async fn list_saved_searches(&self, identity: &UserIdentity) -> Result<Vec<SavedSearchResponse>, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        let rows = self.repository.list_saved_searches(&user_id).await?;
+        Ok(rows.into_iter().map(SavedSearchResponse::from).collect())
+    }
+
+    /// 保存検索を1件取得
+    pub async fn get_saved_search(
+        &self,
+        identity: &UserIdentity,
+        saved_search_id: Uuid,
+    ) -> Result<SavedSearchResponse, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        self.load_saved_search(&user_id, saved_search_id).await.map(SavedSearchResponse::from)
+    }
+
+    /// 保存検索を登録
+    pub async fn create_saved_search(
+        &self,
+        identity: &UserIdentity,
+        req: SavedSearchRequest,
+    ) -> Result<SavedSearchResponse, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        let values = self.validate_request(&user_id, req, None).await?;
+
+        if self.repository.count_saved_searches(&user_id).await? >= self.max_saved_searches {
+            return Err(ApiError::ValidationError(format!(
+                "保存できる検索条件は{}件までです",
+                self.max_saved_searches
+            )));
+        }
+
+        let saved_search_id = self.repository.insert_saved_search(&user_id, &values).await?;
+        self.get_saved_search(identity, saved_search_id).await
+    }
+
+    /// 保存検索を更新（検索条件を変更した場合は通知済みの駐車場もあらためて通知する）
+    pub async fn update_saved_search(
+        &self,
+        identity: &UserIdentity,
+        saved_search_id: Uuid,
+        req: SavedSearchRequest,
+    ) -> Result<SavedSearchResponse, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        let current = self.load_saved_search(&user_id, saved_search_id).await?;
+        let values = self.validate_request(&user_id, req, Some(saved_search_id)).await?;
+
+        let condition_changed = current.condition_json != values.condition_json;
+        if !self
+            .repository
+            .update_saved_search(&user_id, saved_search_id, &values, condition_changed)
+            .await?
+        {
+            return Err(saved_search_not_found());
+        }
+        self.load_saved_search(&user_id, saved_search_id).await.map(SavedSearchResponse::from)
+    }
+
+    /// 保存検索を削除
+    pub async fn delete_saved_search(&self, identity: &UserIdentity, saved_search_id: Uuid) -> Result<(), ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        if !self.repository.delete_saved_search(&user_id, saved_search_id).await? {
+            return Err(saved_search_not_found());
+        }
+        info!("保存検索を削除しました: user_id={}, saved_search_id={}", user_id, saved_search_id);
+        Ok(())
+    }
+
+    /// 未処理のイベント（駐車場の新規登録・予約のキャンセル）で保存検索を再評価し、通知する
+    ///
+    /// 通知休止期間中の保存検索は対象の駐車場を保留し、休止明けに評価する。
+    /// 通知した保存検索の件数を返す。保存検索ごとの失敗はログに記録して処理を続ける
+    pub async fn process_pending_events(&self) -> Result<usize, ApiError> {
+        let events = self
+            .repository
+            .claim_pending_events(self.event_batch_size, EVENT_CLAIM_TIMEOUT_SECS)
+            .await?;
+        if events.is_empty() && !self.repository.has_pending_lots().await? {
+            return Ok(0);
+        }
+
+        let mut seen = HashSet::new();
+        let lot_ids: Vec<String> = events
+            .iter()
+            .filter(|event| seen.insert(event.parking_lot_id.clone()))
+            .map(|event| event.parking_lot_id.clone())
+            .collect();
+        info!("保存検索を再評価します: イベント{}件, 駐車場{}件", events.len(), lot_ids.len());
+
+        let now = Utc::now();
+        let (quiet, active): (Vec<SavedSearchRow>, Vec<SavedSearchRow>) = self
+            .repository
+            .list_active_saved_searches()
+            .await?
+            .into_iter()
+            .partition(|saved_search| saved_search.is_quiet_at(now));
+
+        let quiet_ids: Vec<Uuid> = quiet.iter().map(|saved_search| saved_search.saved_search_id).collect();
+        self.repository.hold_pending_lots(&quiet_ids, &lot_ids).await?;
+
+        let active_ids: Vec<Uuid> = active.iter().map(|saved_search| saved_search.saved_search_id).collect();
+        let mut held: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for (saved_search_id, lot_id) in self.repository.take_pending_lots(&active_ids).await? {
+            held.entry(saved_search_id).or_default().push(lot_id);
+        }
+
+        let mut notified = 0;
+        for saved_search in active {
+            let held_lot_ids = held.remove(&saved_search.saved_search_id).unwrap_or_default();
+            let mut candidates = lot_ids.clone();
+            candidates.extend(held_lot_ids.iter().filter(|id| !seen.contains(*id)).cloned());
+            if candidates.is_empty() {
+                continue;
+            }
+            match self.evaluate_saved_search(&saved_search, &candidates).await {
+                Ok(true) => notified += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    warn!(
+                        "保存検索の評価に失敗しました: saved_search_id={}, {}",
+                        saved_search.saved_search_id, e
+                    );
+                    // 保留していた駐車場は次回に再評価する
+                    if let Err(e) = self
+                        .repository
+                        .hold_pending_lots(&[saved_search.saved_search_id], &held_lot_ids)
+                        .await
+                    {
+                        error!("保留する駐車場の再記録に失敗: saved_search_id={}, {}", saved_search.saved_search_id, e);
+                    }
+                }
+            }
+        }
+
+        let event_ids: Vec<i64> = events.iter().map(|event| event.event_id).collect();
+        self.repository.mark_events_processed(&event_ids).await?;
+        Ok(notified)
+    }
+
+    /// 保存検索に新たに一致する駐車場があれば通知する（通知した場合はtrue）
+    async fn evaluate_saved_search(&self, saved_search: &SavedSearchRow, lot_ids: &[String]) -> Result<bool, ApiError> {
+        let criteria = saved_search.criteria().map_err(|e| {
+            error!("保存検索の条件の復元に失敗: saved_search_id={}, {}", saved_search.saved_search_id, e);
+            ApiError::InternalServerError
+        })?;
+        let usage_period = criteria.usage_start_datetime.zip(criteria.usage_end_datetime);
+        let request = criteria.into_request(&saved_search.user_id, None, None);
+
+        let matched = self
+            .search_service
+            .find_matching_lot_ids(request, &saved_search.user_id, lot_ids)
+            .await?;
+        if matched.is_empty() {
+            return Ok(false);
+        }
+
+        // 通知済みの駐車場と、利用期間が既存の予約と重なる駐車場は除外
+        let mut excluded: HashSet<String> = self
+            .repository
+            .find_notified_lot_ids(saved_search.saved_search_id, &matched)
+            .await?
+            .into_iter()
+            .collect();
+        if let Some((start, end)) = usage_period {
+            excluded.extend(self.repository.find_reserved_lot_ids(&matched, start, end).await?);
+        }
+        let new_lot_ids: Vec<String> = matched.into_iter().filter(|id| !excluded.contains(id)).collect();
+        if new_lot_ids.is_empty() {
+            return Ok(false);
+        }
+
+        let matches = self.repository.find_match_summaries(&new_lot_ids).await?;
+        self.notify(saved_search, &new_lot_ids, &matches).await?;
+        Ok(true)
+    }
+
+    /// アプリ内通知の作成と通知済みの記録を行い、メールを送信する
+    async fn notify(
+        &self,
+        saved_search: &SavedSearchRow,
+        lot_ids: &[String],
+        matches: &[SavedSearchMatch],
+    ) -> Result<(), ApiError> {
+        let mut tx = self.repository.begin_transaction().await?;
+        self.repository
+            .record_matches(&mut tx, saved_search.saved_search_id, lot_ids)
+            .await?;
+
+        if saved_search.notify_in_app {
+            let title = format!("「{}」の条件に合う駐車場があります", saved_search.search_name);
+            let body = match matches {
+                [single] => format!("{}（{}）が利用できるようになりました", single.parking_lot_name, single.address),
+                _ => format!("{}件の駐車場が利用できるようになりました", lot_ids.len()),
+            };
+            let payload = json!({
+                "saved_search_id": saved_search.saved_search_id,
+                "parking_lot_ids": lot_ids,
+            });
+            self.notification_repository
+                .insert_notification(
+                    &mut tx,
+                    &saved_search.user_id,
+                    NOTIFICATION_TYPE_SAVED_SEARCH_MATCH,
+                    &title,
+                    &body,
+                    &payload,
+                )
+                .await?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            error!("トランザクションコミットに失敗: {}", e);
+            DatabaseError::TransactionError(format!("トランザクションコミットに失敗: {}", e))
+        })?;
+        info!(
+            "保存検索の通知を作成しました: saved_search_id={}, 駐車場{}件",
+            saved_search.saved_search_id,
+            lot_ids.len()
+        );
+
+        // メール送信の失敗は通知済みの記録を取り消さない
+        if saved_search.notify_email {
+            match self.repository.find_notification_email(&saved_search.user_id).await? {
+                Some(email) => {
+                    let sent = self
+                        .email_service
+                        .send_saved_search_alert(&email, &saved_search.search_name, matches)
+                        .await;
+                    if let Err(e) = sent {
+                        warn!("保存検索の通知メール送信に失敗しました: user_id={}, {}", saved_search.user_id, e);
+                    }
+                }
+                None => info!("サービスメール未許可のためメール通知を省略: user_id={}", saved_search.user_id),
+            }
+        }
+        Ok(())
+    }
+
+    async fn validate_request(
+        &self,
+        user_id: &str,
+        req: SavedSearchRequest,
+        exclude_id: Option<Uuid>,
+    ) -> Result<SavedSearchValues, ApiError> {
+        let req = req.normalize().map_err(ApiError::ValidationError)?;
+        if self.repository.name_exists(user_id, &req.search_name, exclude_id).await? {
+            return Err(ApiError::DuplicateError("同じ名前の保存検索が既にあります".to_string()));
+        }
+
+        let condition_json = serde_json::to_value(req.criteria()).map_err(|e| {
+            error!("検索条件の変換に失敗: {}", e);
+            ApiError::InternalServerError
+        })?;
+        Ok(SavedSearchValues {
+            search_name: req.search_name,
+            condition_json,
+            notify_email: req.notify_email.unwrap_or(true),
+            notify_in_app: req.notify_in_app.unwrap_or(true),
+            quiet_period_minutes: req.quiet_period_minutes.unwrap_or(self.default_quiet_minutes),
+            is_active: req.is_active.unwrap_or(true),
+        })
+    }
+
+    async fn load_saved_search(&self, user_id: &str, saved_search_id: Uuid) -> Result<SavedSearchRow, ApiError> {
+        self.repository
+            .find_saved_search(user_id, saved_search_id)
+            .await?
+            .ok_or_else(saved_search_not_found)
+    }
+
+    async fn load_user_id(&self, identity: &UserIdentity) -> Result<String, ApiError> {
+        let login_id = Uuid::parse_str(&identity.user_id)
+            .map_err(|_| ApiError::AuthenticationError("ログイン情報が正しくありません".to_string()))?;
+        self.repository
+            .find_user_id(login_id)
+            .await?
+            .ok_or_else(|| ApiError::AuthorizationError("検索条件の保存は利用者アカウントのみ可能です".to_string()))
+    }
+}
+
+fn saved_search_not_found() -> ApiError {
+    ApiError::NotFoundError("保存検索が見つかりません".to_string())
+}
+
+/// 保存検索の再評価バッチをバックグラウンドで起動
+pub fn spawn_saved_search_worker(
+    service: Arc<SavedSearchService>,
+    interval_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    let period = std::time::Duration::from_secs(interval_secs.max(1));
+    info!("保存検索の通知バッチを起動します（間隔: {}秒）", period.as_secs());
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match service.process_pending_events().await {
+                Ok(0) => {}
+                Ok(count) => info!("保存検索の通知を送信しました: {}件", count),
+                Err(e) => error!("保存検索の通知バッチに失敗しました: {}", e),
+            }
+        }
+    })
+}
@@ -2,10 +2,14 @@ use crate::models::{
    parking_use_history_model::{ParkingUseHistoryRequest, ParkingUseHistoryDetailRequest, ParkingUseHistoryResponse, ParkingUseHistoryDetailResponse},
    parking_feature_model::{ParkingFeatureRequest, ParkingFeatureResponse},
 };
-use crate::repositories::UseHistoryRepository;
+use crate::models::parking_search_model::{PageQuery, PagedResponse, PaginationInfo};
+use crate::repositories::{UseHistoryRepository, use_history_repository::use_history_cursor_keys};
+use crate::utils::cursor::{query_hash, PageCursor, CURSOR_SCOPE_USE_HISTORY};
 use crate::controllers::api_error::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
 use sqlx::PgPool;
 use tracing::{debug, error, info};
+use uuid::Uuid;
 
 /// 駐車場利用履歴用サービス
 pub struct UseHistoryService {
@@ -32,6 +36,60 @@ impl UseHistoryService {
         Ok(response)
     }
 
+    /// 駐車場利用履歴一覧（新しい順、ページ番号またはカーソルで取得）
+    pub async fn list_parking_use_history(&self, identity: &UserIdentity, query: PageQuery) -> Result<PagedResponse<ParkingUseHistoryResponse>, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        let user_id = user_id.as_str();
+        debug!("list_parking_use_history user_id: {}", user_id);
+
+        let (page, page_size) = query.validate().map_err(ApiError::ValidationError)?;
+        let cursor_hash = query_hash(&user_id);
+        let cursor = query.cursor.as_deref()
+            .filter(|c| !c.is_empty())
+            .map(|token| PageCursor::decode(token, CURSOR_SCOPE_USE_HISTORY, &cursor_hash))
+            .transpose()?;
+
+        let mut items = self.repo.list_parking_use_history(
+            user_id,
+            cursor.as_ref(),
+            page_size as i64 + 1,
+            (page.max(1) as i64 - 1) * page_size as i64,
+        ).await
+            .map_err(|e| {
+                error!("Failed to list_parking_use_history information: {}", e);
+                ApiError::InternalServerError
+            })?;
+        let total_count = self.repo.count_parking_use_history(user_id).await
+            .map_err(|e| {
+                error!("Failed to count_parking_use_history information: {}", e);
+                ApiError::InternalServerError
+            })?;
+
+        let has_next = items.len() > page_size as usize;
+        items.truncate(page_size as usize);
+        let next_cursor = items
+            .last()
+            .filter(|_| has_next)
+            .map(|row| PageCursor::new(CURSOR_SCOPE_USE_HISTORY, &cursor_hash, use_history_cursor_keys(row)).encode());
+
+        info!("list_parking_use_history successfully: user_id: {}", user_id);
+        Ok(PagedResponse {
+            items,
+            pagination: PaginationInfo::with_cursor(page, page_size, total_count, next_cursor),
+        })
+    }
+
+    async fn load_user_id(&self, identity: &UserIdentity) -> Result<String, ApiError> {
+        let login_id = Uuid::parse_str(&identity.user_id)
+            .map_err(|_| ApiError::AuthenticationError("ログイン情報が正しくありません".to_string()))?;
+        self.repo.find_user_id(login_id).await
+            .map_err(|e| {
+                error!("Failed to find_user_id information: {}", e);
+                ApiError::InternalServerError
+            })?
+            .ok_or_else(|| ApiError::AuthorizationError("利用履歴の参照は利用者アカウントのみ可能です".to_string()))
+    }
+
     /// 駐車場利用履歴詳細の取得
     pub async fn get_parking_use_history_detail(&self, request: &ParkingUseHistoryDetailRequest) -> Result<ParkingUseHistoryDetailResponse, ApiError> {
         debug!("get_parking_use_history_detail reservation_id: {}", request.reservation_id);
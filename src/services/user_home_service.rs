@@ -1,5 +1,5 @@
 use crate::models::{
-    favorites_model:: {FavoriteResponse, FavoritesRequest}, parking_status_model::{ParkingStatusRequest, ParkingStatusResponse, UpdateParkingStatusRequest}, ParkingSearchHistoryRequest, ParkingSearchHistoryResponse
+    favorites_model:: {FavoriteResponse, FavoritesRequest}, parking_status_model::{ParkingStatusRequest, ParkingStatusResponse}, ParkingSearchHistoryRequest, ParkingSearchHistoryResponse
 };
 use crate::repositories::UserHomeRepository;
 use crate::controllers::api_error::ApiError;
@@ -31,41 +31,6 @@ impl UserHomeService {
         Ok(response)
     }
 
-    /// 入庫状況の更新
-    pub async fn update_parking_status_checkin(&self, request: &UpdateParkingStatusRequest) -> Result<(), ApiError> {
-         debug!("update_parking_status_checkin status_id: {}", request.status_id);
-
-        self.repo.update_parking_status_checkin(&request.status_id).await
-            .map_err(|e| {
-                error!("Failed to update_parking_status_checkin: {}", e);
-                ApiError::InternalServerError
-            })?;
-
-           info!("update_parking_status_checkin successfully status_id: {}", request.status_id);
-        Ok(())
-    }
-
-    /// 出庫状況の更新
-    pub async fn update_parking_status_checkout(&self, request: &UpdateParkingStatusRequest) -> Result<(), ApiError> {
-        debug!("update_parking_status_checkout status_id: {} reservation_id: {}", request.status_id, request.reservation_id);
-
-        self.repo.update_parking_status_checkout(&request.status_id).await
-            .map_err(|e| {
-                error!("Failed to pdate_parking_status_checkout: {}", e);
-                ApiError::InternalServerError
-            })?;
-
-        self.repo.update_reservation_status(&request.reservation_id).await
-            .map_err(|e| {
-                error!("Failed to update_reservation_status: {}", e);
-                ApiError::InternalServerError
-            })?;
-
-        info!("update_parking_status_checkout successfully status_id: {} reservation_id: {}", request.status_id, request.reservation_id);
-
-        Ok(())
-    }
-
     /// 駐車場検索履歴の取得
     pub async fn get_parking_search_history(&self, request: &ParkingSearchHistoryRequest) -> Result<Vec<ParkingSearchHistoryResponse>, ApiError> {
         debug!("get_favorites user_id: {}", request.user_id);
@@ -0,0 +1,153 @@
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::postgresql_database::PostgresDatabase;
+use crate::controllers::ApiError;
+use crate::middlewares::identity_middleware::UserIdentity;
+use crate::models::vehicle_model::{DefaultVehicleResponse, UserVehicleRequest, UserVehicleResponse};
+use crate::repositories::VehicleRepository;
+use crate::utils::env::parse_env_or;
+
+/// 登録車両管理サービス
+pub struct VehicleService {
+    repository: VehicleRepository,
+    max_vehicles: i64,
+}
+
+impl VehicleService {
+    /// 新しいサービスインスタンスを作成
+    pub fn new(db: PostgresDatabase) -> Self {
+        Self {
+            repository: VehicleRepository::new(db),
+            max_vehicles: parse_env_or("USER_VEHICLE_MAX_COUNT", 10),
+        }
+    }
+
+    /// 登録車両一覧
+    pub async fn list_vehicles(&self, identity: &UserIdentity) -> Result<Vec<UserVehicleResponse>, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        let rows = self.repository.list_vehicles(&user_id).await?;
+        Ok(rows.into_iter().map(UserVehicleResponse::from).collect())
+    }
+
+    /// 車両を登録（最初の1台は自動的にデフォルト車両になる）
+    pub async fn create_vehicle(
+        &self,
+        identity: &UserIdentity,
+        req: UserVehicleRequest,
+    ) -> Result<UserVehicleResponse, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        let req = self.validate_request(&user_id, req, None).await?;
+
+        let count = self.repository.count_vehicles(&user_id).await?;
+        if count >= self.max_vehicles {
+            return Err(ApiError::ValidationError(format!(
+                "登録できる車両は{}台までです",
+                self.max_vehicles
+            )));
+        }
+
+        let is_default = count == 0 || req.is_default.unwrap_or(false);
+        let vehicle_id = self.repository.insert_vehicle(&user_id, &req, is_default).await?;
+        self.load_vehicle(&user_id, vehicle_id).await
+    }
+
+    /// 車両情報を更新
+    ///
+    /// is_default=false を指定してもデフォルトは解除しない（別の車両をデフォルトにする）
+    pub async fn update_vehicle(
+        &self,
+        identity: &UserIdentity,
+        vehicle_id: Uuid,
+        req: UserVehicleRequest,
+    ) -> Result<UserVehicleResponse, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        let req = self.validate_request(&user_id, req, Some(vehicle_id)).await?;
+
+        let is_default = req.is_default.unwrap_or(false);
+        if !self.repository.update_vehicle(&user_id, vehicle_id, &req, is_default).await? {
+            return Err(vehicle_not_found());
+        }
+        self.load_vehicle(&user_id, vehicle_id).await
+    }
+
+    /// デフォルト車両に設定
+    pub async fn set_default_vehicle(
+        &self,
+        identity: &UserIdentity,
+        vehicle_id: Uuid,
+    ) -> Result<UserVehicleResponse, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        if !self.repository.set_default_vehicle(&user_id, vehicle_id).await? {
+            return Err(vehicle_not_found());
+        }
+        info!("デフォルト車両を変更しました: user_id={}, vehicle_id={}", user_id, vehicle_id);
+        self.load_vehicle(&user_id, vehicle_id).await
+    }
+
+    /// 車両を削除
+    pub async fn delete_vehicle(&self, identity: &UserIdentity, vehicle_id: Uuid) -> Result<(), ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        if !self.repository.delete_vehicle(&user_id, vehicle_id).await? {
+            return Err(vehicle_not_found());
+        }
+        Ok(())
+    }
+
+    /// デフォルト車両と、予約の利用情報・検索条件の初期値を取得
+    pub async fn get_default_vehicle(&self, identity: &UserIdentity) -> Result<DefaultVehicleResponse, ApiError> {
+        let user_id = self.load_user_id(identity).await?;
+        self.repository
+            .find_default_vehicle(&user_id)
+            .await?
+            .map(DefaultVehicleResponse::from)
+            .ok_or_else(|| ApiError::NotFoundError("デフォルト車両が登録されていません".to_string()))
+    }
+
+    async fn validate_request(
+        &self,
+        user_id: &str,
+        req: UserVehicleRequest,
+        exclude_vehicle_id: Option<Uuid>,
+    ) -> Result<UserVehicleRequest, ApiError> {
+        let req = req.normalize().map_err(ApiError::ValidationError)?;
+
+        let unknown_type = match &req.vehicle_type_id {
+            Some(vehicle_type_id) => !self.repository.vehicle_type_exists(vehicle_type_id).await?,
+            None => false,
+        };
+        if unknown_type {
+            return Err(ApiError::ValidationError("指定された車種が存在しません".to_string()));
+        }
+        if self
+            .repository
+            .license_plate_in_use(user_id, &req.license_plate, exclude_vehicle_id)
+            .await?
+        {
+            return Err(ApiError::DuplicateError("同じナンバーの車両が既に登録されています".to_string()));
+        }
+
+        Ok(req)
+    }
+
+    async fn load_vehicle(&self, user_id: &str, vehicle_id: Uuid) -> Result<UserVehicleResponse, ApiError> {
+        self.repository
+            .find_vehicle(user_id, vehicle_id)
+            .await?
+            .map(UserVehicleResponse::from)
+            .ok_or_else(vehicle_not_found)
+    }
+
+    async fn load_user_id(&self, identity: &UserIdentity) -> Result<String, ApiError> {
+        let login_id = Uuid::parse_str(&identity.user_id)
+            .map_err(|_| ApiError::AuthenticationError("ログイン情報が正しくありません".to_string()))?;
+        self.repository
+            .find_user_id(login_id)
+            .await?
+            .ok_or_else(|| ApiError::AuthorizationError("車両の登録は利用者アカウントのみ可能です".to_string()))
+    }
+}
+
+fn vehicle_not_found() -> ApiError {
+    ApiError::NotFoundError("車両が見つかりません".to_string())
+}
@@ -0,0 +1,192 @@
+// src/storage/local_blob_store.rs
+//! ローカルファイルシステムのストレージ実装
+//!
+//! 読み取りはAPI（`/v1/api/files/{key}`）経由で配信し、HMAC署名と有効期限で保護する。
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use tracing::{debug, error};
+
+use super::{hmac_sha256, signing_window, to_hex, validate_blob_key, BlobStore, StorageError};
+
+/// ファイル配信APIのパス
+pub const FILES_API_PATH: &str = "/v1/api/files";
+
+#[derive(Debug, Clone)]
+pub struct LocalBlobStore {
+    root: PathBuf,
+    public_base_url: String,
+    signing_secret: String,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: &str, public_base_url: &str, signing_secret: &str) -> Self {
+        Self {
+            root: PathBuf::from(root),
+            public_base_url: public_base_url.to_string(),
+            signing_secret: signing_secret.to_string(),
+        }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, StorageError> {
+        validate_blob_key(key)?;
+        Ok(self.root.join(key))
+    }
+
+    fn sign(&self, key: &str, expires: i64) -> String {
+        let message = format!("{}\n{}", key, expires);
+        to_hex(&hmac_sha256(self.signing_secret.as_bytes(), message.as_bytes()))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    fn backend_name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>, _content_type: &str) -> Result<(), StorageError> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                error!("ディレクトリ作成に失敗: {} - {}", parent.display(), e);
+                StorageError::Io(e.to_string())
+            })?;
+        }
+        tokio::fs::write(&path, bytes).await.map_err(|e| {
+            error!("ファイル書き込みに失敗: {} - {}", path.display(), e);
+            StorageError::Io(e.to_string())
+        })
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let path = self.path_for(key)?;
+        tokio::fs::read(&path).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => StorageError::NotFound(key.to_string()),
+            _ => StorageError::Io(e.to_string()),
+        })
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                debug!("削除対象のファイルが存在しません: {}", path.display());
+                Ok(())
+            }
+            Err(e) => Err(StorageError::Io(e.to_string())),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        let path = self.path_for(key)?;
+        tokio::fs::try_exists(&path)
+            .await
+            .map_err(|e| StorageError::Io(e.to_string()))
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        let mut keys = Vec::new();
+        let mut pending: Vec<PathBuf> = vec![self.root.clone()];
+
+        while let Some(dir) = pending.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(StorageError::Io(e.to_string())),
+            };
+
+            while let Some(entry) = entries
+                .next_entry()
+                .await
+                .map_err(|e| StorageError::Io(e.to_string()))?
+            {
+                let path = entry.path();
+                let file_type = entry
+                    .file_type()
+                    .await
+                    .map_err(|e| StorageError::Io(e.to_string()))?;
+
+                if file_type.is_dir() {
+                    pending.push(path);
+                } else if let Some(key) = relative_key(&self.root, &path)
+                    .filter(|key| key.starts_with(prefix))
+                {
+                    keys.push(key);
+                }
+            }
+        }
+
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn signed_url(&self, key: &str, expires_in: Duration) -> Result<String, StorageError> {
+        validate_blob_key(key)?;
+        let (signed_at, valid_secs) = signing_window(expires_in);
+        let expires = signed_at.timestamp() + valid_secs;
+        Ok(format!(
+            "{}{}/{}?expires={}&signature={}",
+            self.public_base_url,
+            FILES_API_PATH,
+            key,
+            expires,
+            self.sign(key, expires)
+        ))
+    }
+
+    fn verify_signature(&self, key: &str, expires: i64, signature: &str) -> bool {
+        if expires < Utc::now().timestamp() {
+            debug!("署名済みURLの有効期限切れ: {}", key);
+            return false;
+        }
+        constant_time_eq(self.sign(key, expires).as_bytes(), signature.as_bytes())
+    }
+}
+
+/// ルートからの相対パスを`/`区切りのキーに変換
+fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let segments: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_str().map(str::to_string))
+        .collect::<Option<Vec<_>>>()?;
+    Some(segments.join("/"))
+}
+
+/// タイミング攻撃を避けるための定数時間比較
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_store_roundtrip_and_signed_url() {
+        let root = std::env::temp_dir().join(format!("blob_store_test_{}", uuid::Uuid::new_v4()));
+        let store = LocalBlobStore::new(root.to_str().unwrap(), "", "secret");
+
+        store.put("images/p1/a.jpg", vec![1, 2, 3], "image/jpeg").await.unwrap();
+        assert_eq!(store.get("images/p1/a.jpg").await.unwrap(), vec![1, 2, 3]);
+        assert_eq!(store.list("images/").await.unwrap(), vec!["images/p1/a.jpg".to_string()]);
+
+        let url = store.signed_url("images/p1/a.jpg", Duration::minutes(5)).unwrap();
+        let query = url.split_once('?').unwrap().1;
+        let params: std::collections::HashMap<_, _> =
+            query.split('&').filter_map(|p| p.split_once('=')).collect();
+        let expires: i64 = params["expires"].parse().unwrap();
+        assert!(store.verify_signature("images/p1/a.jpg", expires, params["signature"]));
+        assert!(!store.verify_signature("images/p1/b.jpg", expires, params["signature"]));
+
+        assert!(store.get("images/../../etc/passwd").await.is_err());
+
+        store.delete("images/p1/a.jpg").await.unwrap();
+        assert!(!store.exists("images/p1/a.jpg").await.unwrap());
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
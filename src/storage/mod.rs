@@ -0,0 +1,243 @@
+// src/storage/mod.rs
+//! アップロードファイルの保存先（オブジェクトストレージ）
+//!
+//! `BlobStore`トレイトで保存先を抽象化し、ローカルファイルシステムと
+//! S3互換ストレージ（MinIO等）の実装を提供する。
+//! DBにはファイルパスではなく保存先に依存しないキー（例: `parking_lot_images/{id}/{hash}.jpg`）を保存する。
+
+use std::env;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::controllers::ApiError;
+use crate::utils::env::{parse_env_or, require_signing_secret};
+
+pub mod local_blob_store;
+pub mod s3_blob_store;
+
+pub use local_blob_store::LocalBlobStore;
+pub use s3_blob_store::{S3BlobStore, S3Config};
+
+/// 旧形式（ローカルパス）で保存されたimage_urlの接頭辞
+const LEGACY_LOCAL_PREFIXES: [&str; 2] = ["./uploadfolder/", "uploadfolder/"];
+
+/// 署名済みURLの署名時刻を丸める単位（秒）
+///
+/// 同じ単位内では同じURLになり、ブラウザやCDNのキャッシュが効く
+const SIGNED_URL_BUCKET_SECS: i64 = 15 * 60;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("オブジェクトが見つかりません: {0}")]
+    NotFound(String),
+
+    #[error("無効なキーです: {0}")]
+    InvalidKey(String),
+
+    #[error("ストレージ入出力エラー: {0}")]
+    Io(String),
+
+    #[error("ストレージバックエンドエラー: {0}")]
+    Backend(String),
+
+    #[error("ストレージ設定エラー: {0}")]
+    Config(String),
+}
+
+impl From<StorageError> for ApiError {
+    fn from(err: StorageError) -> Self {
+        match err {
+            StorageError::NotFound(msg) => ApiError::NotFoundError(msg),
+            StorageError::InvalidKey(msg) => ApiError::ValidationError(msg),
+            StorageError::Io(msg) | StorageError::Backend(msg) => {
+                tracing::error!("ストレージエラー: {}", msg);
+                ApiError::InternalServerError
+            }
+            StorageError::Config(msg) => ApiError::ServiceUnavailableError(msg),
+        }
+    }
+}
+
+/// ファイル保存先
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// バックエンド名（ログ・移行コマンド用）
+    fn backend_name(&self) -> &'static str;
+
+    /// オブジェクトを保存（既存の場合は上書き）
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), StorageError>;
+
+    /// オブジェクトを取得
+    async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+
+    /// オブジェクトを削除（存在しない場合も成功）
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// オブジェクトの存在確認
+    async fn exists(&self, key: &str) -> Result<bool, StorageError>;
+
+    /// 接頭辞に一致するキー一覧
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, StorageError>;
+
+    /// 期限付きの署名済み読み取りURLを生成
+    fn signed_url(&self, key: &str, expires_in: Duration) -> Result<String, StorageError>;
+
+    /// 署名済みURLの検証（APIでファイルを配信するバックエンドのみ実装）
+    fn verify_signature(&self, _key: &str, _expires: i64, _signature: &str) -> bool {
+        false
+    }
+}
+
+/// ストレージ設定
+#[derive(Debug, Clone)]
+pub struct BlobStoreConfig {
+    /// バックエンド種別 (local / s3)
+    pub backend: String,
+    /// ローカル保存先のルートディレクトリ
+    pub local_root: String,
+    /// 署名済みURLの基点（例: https://api.parking-app.com）。空の場合は相対URL
+    pub public_base_url: String,
+    /// URL署名用の秘密鍵
+    pub signing_secret: String,
+    /// 署名済みURLの有効期間（秒）
+    pub signed_url_ttl_secs: i64,
+}
+
+impl BlobStoreConfig {
+    /// 環境変数からストレージ設定を読み込む
+    pub fn from_env() -> Self {
+        Self {
+            backend: env::var("BLOB_STORE_BACKEND")
+                .unwrap_or_else(|_| "local".to_string())
+                .to_lowercase(),
+            local_root: env::var("BLOB_LOCAL_ROOT").unwrap_or_else(|_| "uploadfolder".to_string()),
+            public_base_url: env::var("BLOB_PUBLIC_BASE_URL")
+                .unwrap_or_default()
+                .trim_end_matches('/')
+                .to_string(),
+            signing_secret: require_signing_secret("BLOB_URL_SIGNING_SECRET"),
+            signed_url_ttl_secs: parse_env_or("BLOB_SIGNED_URL_TTL_SECS", 3600),
+        }
+    }
+
+    /// 署名済みURLの有効期間
+    pub fn signed_url_ttl(&self) -> Duration {
+        Duration::seconds(self.signed_url_ttl_secs)
+    }
+}
+
+/// 指定バックエンドのストレージを生成
+pub fn create_blob_store(backend: &str, config: &BlobStoreConfig) -> Result<Arc<dyn BlobStore>, StorageError> {
+    match backend {
+        "local" => {
+            info!("ストレージバックエンド: ローカル ({})", config.local_root);
+            Ok(Arc::new(LocalBlobStore::new(
+                &config.local_root,
+                &config.public_base_url,
+                &config.signing_secret,
+            )))
+        }
+        "s3" | "minio" => {
+            let s3_config = S3Config::from_env()?;
+            info!("ストレージバックエンド: S3互換 ({} / {})", s3_config.endpoint, s3_config.bucket);
+            Ok(Arc::new(S3BlobStore::new(s3_config)?))
+        }
+        other => Err(StorageError::Config(format!("不明なストレージバックエンドです: {}", other))),
+    }
+}
+
+/// DBに保存された値からストレージキーを取得
+///
+/// 旧形式の`uploadfolder/...`パスはローカルルートからの相対キーに変換する
+pub fn normalize_blob_key(stored: &str) -> String {
+    LEGACY_LOCAL_PREFIXES
+        .iter()
+        .find_map(|prefix| stored.strip_prefix(prefix))
+        .unwrap_or(stored)
+        .trim_start_matches('/')
+        .to_string()
+}
+
+/// DBに保存された値から期限付きの読み取りURLを生成
+pub fn signed_url_for_stored(
+    store: &dyn BlobStore,
+    stored: &str,
+    expires_in: Duration,
+) -> Result<String, StorageError> {
+    store.signed_url(&normalize_blob_key(stored), expires_in)
+}
+
+/// キーの妥当性を検証（パストラバーサル対策）
+pub fn validate_blob_key(key: &str) -> Result<(), StorageError> {
+    let invalid = key.is_empty()
+        || key.starts_with('/')
+        || key.contains('\\')
+        || key.contains('\0')
+        || key.split('/').any(|segment| segment.is_empty() || segment == "." || segment == "..");
+
+    if invalid {
+        warn!("無効なストレージキー: {}", key);
+        return Err(StorageError::InvalidKey(key.to_string()));
+    }
+    Ok(())
+}
+
+/// 拡張子からContent-Typeを推定
+pub fn content_type_for_key(key: &str) -> &'static str {
+    match key.rsplit('.').next().map(|ext| ext.to_lowercase()).as_deref() {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 署名済みURLの署名時刻と有効期間（秒）
+///
+/// 署名時刻は丸め単位の開始時刻に切り捨て、切り捨てた分だけ有効期間を延ばす
+/// （発行から最低でも指定の有効期間は使える）
+pub(crate) fn signing_window(expires_in: Duration) -> (DateTime<Utc>, i64) {
+    signing_window_at(Utc::now(), expires_in)
+}
+
+fn signing_window_at(now: DateTime<Utc>, expires_in: Duration) -> (DateTime<Utc>, i64) {
+    let timestamp = now.timestamp();
+    let signed_at = DateTime::from_timestamp(timestamp - timestamp.rem_euclid(SIGNED_URL_BUCKET_SECS), 0).unwrap_or(now);
+    (signed_at, expires_in.num_seconds() + SIGNED_URL_BUCKET_SECS)
+}
+
+/// HMAC-SHA256
+pub(crate) fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMACは任意長の鍵を受け付ける");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// バイト列を16進数文字列に変換
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_window_is_stable_within_bucket() {
+        let start = DateTime::from_timestamp(1_700_000_100, 0).unwrap();
+        let ttl = Duration::hours(1);
+        let (signed_at, valid_secs) = signing_window_at(start, ttl);
+        assert_eq!(signing_window_at(start + Duration::seconds(SIGNED_URL_BUCKET_SECS - 1), ttl).0, signed_at);
+        assert_ne!(signing_window_at(start + Duration::seconds(SIGNED_URL_BUCKET_SECS), ttl).0, signed_at);
+        // 丸めても発行時点から指定の有効期間は使える
+        assert!(signed_at.timestamp() + valid_secs >= (start + ttl).timestamp());
+    }
+}
@@ -0,0 +1,231 @@
+// filepath: /src/utils/cursor.rs
+//! カーソル方式（キーセット）ページネーション
+//!
+//! 直前ページ末尾の並べ替えキーを署名付きの不透明なトークンにして返し、
+//! 次のページはそのキーより後ろから取得する。件数が変わってもページがずれず、
+//! OFFSETのように深いページほど重くなることもない。
+//! トークンには一覧の種類と検索条件のハッシュを含め、別の一覧・条件では使えないようにする。
+
+use std::cmp::Ordering;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{postgres::PgArguments, query::QueryAs, Postgres};
+
+use crate::config::logging::SqlParam;
+use crate::controllers::ApiError;
+use crate::utils::env::require_signing_secret;
+
+/// 駐車場検索
+pub const CURSOR_SCOPE_SEARCH: &str = "search";
+/// お気に入り一覧
+pub const CURSOR_SCOPE_FAVORITES: &str = "favorites";
+/// 検索履歴
+pub const CURSOR_SCOPE_SEARCH_HISTORY: &str = "search_history";
+/// 利用履歴
+pub const CURSOR_SCOPE_USE_HISTORY: &str = "use_history";
+
+lazy_static! {
+    static ref CURSOR_SIGNING_SECRET: String = require_signing_secret("CURSOR_SIGNING_SECRET");
+}
+
+/// カーソル署名用の秘密鍵を読み込む（本番環境で未設定の場合は起動時に中止する）
+pub fn init_cursor_signing_secret() {
+    lazy_static::initialize(&CURSOR_SIGNING_SECRET);
+}
+
+/// 並べ替えキーの値
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "t", content = "v", rename_all = "snake_case")]
+pub enum CursorKey {
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Time(DateTime<Utc>),
+}
+
+impl CursorKey {
+    fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Int(a), Self::Int(b)) => a.cmp(b),
+            (Self::Float(a), Self::Float(b)) => a.total_cmp(b),
+            (Self::Text(a), Self::Text(b)) => a.cmp(b),
+            (Self::Time(a), Self::Time(b)) => a.cmp(b),
+            _ => Ordering::Equal,
+        }
+    }
+
+    fn to_sql_param(&self) -> SqlParam {
+        match self {
+            Self::Int(v) => SqlParam::Integer(*v),
+            Self::Float(v) => SqlParam::Float(*v),
+            Self::Text(v) => SqlParam::String(v.clone()),
+            Self::Time(v) => SqlParam::String(v.to_rfc3339()),
+        }
+    }
+}
+
+/// 直前ページ末尾の位置（並べ替えキーを優先順に並べ、最後は一意キー）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageCursor {
+    #[serde(rename = "s")]
+    pub scope: String,
+    #[serde(rename = "q")]
+    pub query_hash: String,
+    #[serde(rename = "k")]
+    pub keys: Vec<CursorKey>,
+}
+
+impl PageCursor {
+    pub fn new(scope: &str, query_hash: &str, keys: Vec<CursorKey>) -> Self {
+        Self {
+            scope: scope.to_string(),
+            query_hash: query_hash.to_string(),
+            keys,
+        }
+    }
+
+    /// 署名付きトークンに変換
+    pub fn encode(&self) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default());
+        let signature = URL_SAFE_NO_PAD.encode(sign(payload.as_bytes()).finalize().into_bytes());
+        format!("{}.{}", payload, signature)
+    }
+
+    /// トークンを検証して復元（署名・一覧の種類・検索条件が一致しない場合はエラー）
+    pub fn decode(token: &str, scope: &str, query_hash: &str) -> Result<Self, ApiError> {
+        let invalid = || ApiError::ValidationError(
+            "cursorが無効です。一覧の先頭から取得し直してください".to_string()
+        );
+
+        let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+        sign(payload.as_bytes()).verify_slice(&signature).map_err(|_| invalid())?;
+
+        let json = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?;
+        let cursor: Self = serde_json::from_slice(&json).map_err(|_| invalid())?;
+        if cursor.scope != scope || cursor.query_hash != query_hash || cursor.keys.is_empty() {
+            return Err(invalid());
+        }
+        Ok(cursor)
+    }
+
+    /// 並べ替えキーがこのカーソルより後ろか（descendingはキーごとの降順指定）
+    pub fn precedes(&self, keys: &[CursorKey], descending: &[bool]) -> bool {
+        compare_keys(keys, &self.keys, descending) == Ordering::Greater
+    }
+
+    /// ログ出力用のパラメータ
+    pub fn sql_params(&self) -> Vec<SqlParam> {
+        self.keys.iter().map(CursorKey::to_sql_param).collect()
+    }
+}
+
+/// 並べ替えキーの比較（descendingはキーごとの降順指定）
+///
+/// メモリ上で並べ替える一覧は、この比較で並べるとカーソルの位置と順序が一致する
+pub fn compare_keys(a: &[CursorKey], b: &[CursorKey], descending: &[bool]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .zip(descending)
+        .map(|((a, b), desc)| if *desc { a.compare(b).reverse() } else { a.compare(b) })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn sign(message: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(CURSOR_SIGNING_SECRET.as_bytes())
+        .expect("HMACは任意長の鍵を受け付ける");
+    mac.update(message);
+    mac
+}
+
+/// 検索条件のハッシュ（カーソルと条件の対応確認用）
+pub fn query_hash<T: Serialize>(value: &T) -> String {
+    let json = serde_json::to_vec(value).unwrap_or_default();
+    Sha256::digest(&json)[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// カーソルより後ろの行に絞り込むWHERE条件
+///
+/// columnsは並べ替え順の(SQL式, 降順か)で、カーソルのキーと同じ順・同じ数にする。
+/// 各キーは $first_param から順にバインドする
+pub fn keyset_sql(columns: &[(&str, bool)], first_param: usize) -> String {
+    columns
+        .iter()
+        .enumerate()
+        .rev()
+        .fold(String::new(), |inner, (i, (column, desc))| {
+            let op = if *desc { "<" } else { ">" };
+            let param = first_param + i;
+            if inner.is_empty() {
+                format!("{} {} ${}", column, op, param)
+            } else {
+                format!("({} {} ${} OR ({} = ${} AND {}))", column, op, param, column, param, inner)
+            }
+        })
+}
+
+/// カーソルのキーをバインド
+pub fn bind_cursor<'q, O>(
+    mut query: QueryAs<'q, Postgres, O, PgArguments>,
+    cursor: &'q PageCursor,
+) -> QueryAs<'q, Postgres, O, PgArguments> {
+    for key in &cursor.keys {
+        query = match key {
+            CursorKey::Int(v) => query.bind(*v),
+            CursorKey::Float(v) => query.bind(*v),
+            CursorKey::Text(v) => query.bind(v.as_str()),
+            CursorKey::Time(v) => query.bind(*v),
+        };
+    }
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip_and_tampering() {
+        let cursor = PageCursor::new(
+            CURSOR_SCOPE_FAVORITES,
+            "hash",
+            vec![CursorKey::Int(3), CursorKey::Text("P-000001".to_string())],
+        );
+        let token = cursor.encode();
+        assert_eq!(PageCursor::decode(&token, CURSOR_SCOPE_FAVORITES, "hash").unwrap(), cursor);
+
+        // 別の一覧・条件では使えない
+        assert!(PageCursor::decode(&token, CURSOR_SCOPE_SEARCH, "hash").is_err());
+        assert!(PageCursor::decode(&token, CURSOR_SCOPE_FAVORITES, "other").is_err());
+
+        // 改ざんされたトークンは署名検証で弾く
+        let forged = PageCursor::new(CURSOR_SCOPE_FAVORITES, "hash", vec![CursorKey::Int(0)]).encode();
+        let (forged_payload, _) = forged.split_once('.').unwrap();
+        let (_, signature) = token.split_once('.').unwrap();
+        assert!(PageCursor::decode(&format!("{}.{}", forged_payload, signature), CURSOR_SCOPE_FAVORITES, "hash").is_err());
+        assert!(PageCursor::decode("invalid", CURSOR_SCOPE_FAVORITES, "hash").is_err());
+    }
+
+    #[test]
+    fn test_keyset() {
+        assert_eq!(keyset_sql(&[("id", false)], 3), "id > $3");
+        assert_eq!(
+            keyset_sql(&[("created", true), ("id", false)], 2),
+            "(created < $2 OR (created = $2 AND id > $3))"
+        );
+
+        let cursor = PageCursor::new(CURSOR_SCOPE_SEARCH, "hash", vec![CursorKey::Float(1.5), CursorKey::Text("B".to_string())]);
+        let key = |d: f64, id: &str| vec![CursorKey::Float(d), CursorKey::Text(id.to_string())];
+        assert!(cursor.precedes(&key(2.0, "A"), &[false, false]));
+        assert!(cursor.precedes(&key(1.5, "C"), &[false, false]));
+        assert!(!cursor.precedes(&key(1.5, "B"), &[false, false]));
+        assert!(!cursor.precedes(&key(1.0, "Z"), &[false, false]));
+        assert!(cursor.precedes(&key(1.0, "A"), &[true, false]));
+    }
+}
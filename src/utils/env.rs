@@ -0,0 +1,48 @@
+// filepath: /src/utils/env.rs
+use std::env;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use tracing::warn;
+
+/// 環境変数を値として読み込む（未設定・不正値の場合はデフォルト値）
+pub fn parse_env_or<T: FromStr + Display>(key: &str, default: T) -> T {
+    match env::var(key) {
+        Ok(value) => value.parse().unwrap_or_else(|_| {
+            warn!("{}の値が無効です: {}。デフォルト値 '{}' を使用します", key, value, default);
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+/// 環境変数を真偽値として読み込む（"true"の場合のみtrue）
+pub fn env_flag_or(key: &str, default: bool) -> bool {
+    env::var(key)
+        .map(|v| v.to_lowercase() == "true")
+        .unwrap_or(default)
+}
+
+/// 署名用の秘密鍵を読み込む
+///
+/// 本番環境（ENVIRONMENT=development以外）で未設定の場合は起動を中止する。
+/// 開発環境では鍵ごとの固定値を使用し、JWT_SECRETなど他の鍵は流用しない
+pub fn require_signing_secret(key: &str) -> String {
+    match env::var(key) {
+        Ok(secret) if !secret.is_empty() => secret,
+        _ => {
+            if !is_development() {
+                panic!("本番環境では{}環境変数が必要です！", key);
+            }
+            warn!("{}が設定されていません。開発用の固定値を使用します", key);
+            format!("development-{}", key.to_lowercase().replace('_', "-"))
+        }
+    }
+}
+
+/// 開発環境（ENVIRONMENT=development）かどうかを判定
+pub fn is_development() -> bool {
+    env::var("ENVIRONMENT")
+        .unwrap_or_else(|_| "development".to_string())
+        .to_lowercase() == "development"
+}
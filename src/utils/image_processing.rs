@@ -0,0 +1,233 @@
+// filepath: /src/utils/image_processing.rs
+//! 画像アップロード処理
+//!
+//! マジックバイトによる形式判定、再エンコードによるEXIF（GPS含む）メタデータの除去、
+//! リサイズ画像・サムネイルの生成、コンテンツハッシュの算出を行う。
+
+use std::env;
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::controllers::ApiError;
+use crate::utils::env::parse_env_or;
+
+/// 保存する画像の拡張子（全てJPEGに正規化する）
+pub const STORED_IMAGE_EXTENSION: &str = "jpg";
+
+/// 画像アップロード設定
+#[derive(Debug, Clone)]
+pub struct ImageUploadConfig {
+    /// 1ファイルあたりの最大サイズ（バイト）
+    pub max_file_size: usize,
+    /// 1リクエストあたりの最大ファイル数
+    pub max_files_per_request: usize,
+    /// 1駐車場あたりの最大画像数
+    pub max_images_per_parking_lot: i64,
+    /// 入力画像の最大ピクセル幅・高さ（デコード爆弾対策）
+    pub max_input_dimension: u32,
+    /// 保存する元画像の長辺上限（ピクセル）
+    pub original_max_dimension: u32,
+    /// 中サイズ画像の長辺（ピクセル）
+    pub medium_dimension: u32,
+    /// サムネイルの長辺（ピクセル）
+    pub thumbnail_dimension: u32,
+    /// JPEG品質（1-100）
+    pub jpeg_quality: u8,
+    /// 保存先キーの接頭辞
+    pub key_prefix: String,
+}
+
+impl ImageUploadConfig {
+    /// 環境変数から画像アップロード設定を読み込む
+    pub fn from_env() -> Self {
+        Self {
+            max_file_size: parse_env_or("IMAGE_UPLOAD_MAX_FILE_SIZE", 5 * 1024 * 1024),
+            max_files_per_request: parse_env_or("IMAGE_UPLOAD_MAX_FILES", 10),
+            max_images_per_parking_lot: parse_env_or("IMAGE_MAX_PER_PARKING_LOT", 20),
+            max_input_dimension: parse_env_or("IMAGE_MAX_INPUT_DIMENSION", 10_000),
+            original_max_dimension: parse_env_or("IMAGE_ORIGINAL_MAX_DIMENSION", 1920),
+            medium_dimension: parse_env_or("IMAGE_MEDIUM_DIMENSION", 800),
+            thumbnail_dimension: parse_env_or("IMAGE_THUMBNAIL_DIMENSION", 240),
+            jpeg_quality: parse_env_or::<u8>("IMAGE_JPEG_QUALITY", 85).clamp(1, 100),
+            key_prefix: env::var("IMAGE_KEY_PREFIX")
+                .unwrap_or_else(|_| "parking_lot_images".to_string()),
+        }
+    }
+}
+
+impl Default for ImageUploadConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// 処理済み画像（元画像・中サイズ・サムネイル）
+#[derive(Debug, Clone)]
+pub struct ProcessedImage {
+    /// 保存する元画像のSHA-256（16進数）
+    pub content_hash: String,
+    /// 元画像の幅
+    pub width: u32,
+    /// 元画像の高さ
+    pub height: u32,
+    /// 元画像（メタデータ除去・長辺制限済みJPEG）
+    pub original: Vec<u8>,
+    /// 中サイズ画像（JPEG）
+    pub medium: Vec<u8>,
+    /// サムネイル（JPEG）
+    pub thumbnail: Vec<u8>,
+}
+
+impl ProcessedImage {
+    /// 元画像のファイル名
+    pub fn original_filename(&self) -> String {
+        format!("{}.{}", self.content_hash, STORED_IMAGE_EXTENSION)
+    }
+
+    /// 中サイズ画像のファイル名
+    pub fn medium_filename(&self) -> String {
+        format!("{}_medium.{}", self.content_hash, STORED_IMAGE_EXTENSION)
+    }
+
+    /// サムネイルのファイル名
+    pub fn thumbnail_filename(&self) -> String {
+        format!("{}_thumb.{}", self.content_hash, STORED_IMAGE_EXTENSION)
+    }
+}
+
+/// マジックバイトから画像形式を判定（JPEG / PNG / WebPのみ許可）
+pub fn sniff_image_format(bytes: &[u8]) -> Option<ImageFormat> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(ImageFormat::Jpeg)
+    } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        Some(ImageFormat::Png)
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some(ImageFormat::WebP)
+    } else {
+        None
+    }
+}
+
+/// アップロード画像を検証・正規化し、各サイズの画像を生成する
+///
+/// デコード後に再エンコードするため、EXIF（GPS位置情報を含む）等のメタデータは全て除去される。
+/// EXIFの回転情報は除去前に画素へ適用する。
+pub fn process_image(bytes: &[u8], config: &ImageUploadConfig) -> Result<ProcessedImage, ApiError> {
+    let format = sniff_image_format(bytes).ok_or_else(|| {
+        ApiError::UnsupportedMediaType("JPEG・PNG・WebP形式の画像のみアップロードできます".to_string())
+    })?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(config.max_input_dimension);
+    limits.max_image_height = Some(config.max_input_dimension);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(|e| {
+        warn!("画像デコーダーの初期化に失敗: {}", e);
+        ApiError::ValidationError("画像ファイルが破損しているか、サイズが大きすぎます".to_string())
+    })?;
+    let orientation = decoder.orientation().ok();
+
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| {
+        warn!("画像のデコードに失敗: {}", e);
+        ApiError::ValidationError("画像ファイルが破損しているか、サイズが大きすぎます".to_string())
+    })?;
+    if let Some(orientation) = orientation {
+        image.apply_orientation(orientation);
+    }
+
+    let original_image = shrink_to_fit(image, config.original_max_dimension);
+    let medium_image = shrink_to_fit(original_image.clone(), config.medium_dimension);
+    let thumbnail_image = shrink_to_fit(medium_image.clone(), config.thumbnail_dimension);
+
+    let original = encode_jpeg(&original_image, config.jpeg_quality)?;
+    let medium = encode_jpeg(&medium_image, config.jpeg_quality)?;
+    let thumbnail = encode_jpeg(&thumbnail_image, config.jpeg_quality)?;
+
+    Ok(ProcessedImage {
+        content_hash: content_hash(&original),
+        width: original_image.width(),
+        height: original_image.height(),
+        original,
+        medium,
+        thumbnail,
+    })
+}
+
+/// バイト列のSHA-256ハッシュ（16進数）
+pub fn content_hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 長辺が指定サイズを超える場合のみ縮小する
+fn shrink_to_fit(image: DynamicImage, max_dimension: u32) -> DynamicImage {
+    if image.width() <= max_dimension && image.height() <= max_dimension {
+        image
+    } else {
+        image.resize(max_dimension, max_dimension, FilterType::Lanczos3)
+    }
+}
+
+/// JPEGにエンコード（透過はRGBへ変換）
+fn encode_jpeg(image: &DynamicImage, quality: u8) -> Result<Vec<u8>, ApiError> {
+    let mut buffer = Vec::new();
+    let encoder = JpegEncoder::new_with_quality(&mut buffer, quality);
+    image.to_rgb8().write_with_encoder(encoder).map_err(|e| {
+        tracing::error!("画像のエンコードに失敗: {}", e);
+        ApiError::InternalServerError
+    })?;
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn test_config() -> ImageUploadConfig {
+        ImageUploadConfig {
+            max_file_size: 1024 * 1024,
+            max_files_per_request: 5,
+            max_images_per_parking_lot: 20,
+            max_input_dimension: 4000,
+            original_max_dimension: 400,
+            medium_dimension: 200,
+            thumbnail_dimension: 50,
+            jpeg_quality: 80,
+            key_prefix: "test_images".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_process_image_resizes_and_normalizes_to_jpeg() {
+        let source = RgbaImage::from_pixel(800, 400, Rgba([10, 120, 200, 255]));
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(source)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let processed = process_image(&png, &test_config()).unwrap();
+
+        assert_eq!((processed.width, processed.height), (400, 200));
+        assert_eq!(sniff_image_format(&processed.original), Some(ImageFormat::Jpeg));
+        assert_eq!(sniff_image_format(&processed.thumbnail), Some(ImageFormat::Jpeg));
+        assert_eq!(processed.content_hash, content_hash(&processed.original));
+        assert_eq!(processed.thumbnail_filename(), format!("{}_thumb.jpg", processed.content_hash));
+    }
+
+    #[test]
+    fn test_process_image_rejects_non_image() {
+        let result = process_image(b"<?php echo 'x'; ?>", &test_config());
+        assert!(matches!(result, Err(ApiError::UnsupportedMediaType(_))));
+    }
+}
@@ -1 +1,6 @@
 pub mod validation;
+pub mod env;
+pub mod image_processing;
+pub mod password;
+pub mod cursor;
+pub mod pdf;
@@ -0,0 +1,170 @@
+// filepath: /src/utils/password.rs
+//! パスワードのハッシュ化・検証とパスワードポリシー
+//!
+//! 新規ハッシュはargon2idで生成する。既存のbcryptハッシュも検証でき、
+//! サインイン時に`needs_rehash`で判定してargon2idへ移行する。
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand::RngCore;
+use tracing::error;
+
+use crate::controllers::ApiError;
+use crate::utils::env::parse_env_or;
+
+/// argon2idハッシュの接頭辞
+const ARGON2ID_PREFIX: &str = "$argon2id$";
+
+/// パスワードをargon2idでハッシュ化
+pub fn hash_password(password: &str) -> Result<String, ApiError> {
+    let mut salt = [0u8; 16];
+    rand::rng().fill_bytes(&mut salt);
+    let salt = SaltString::encode_b64(&salt).map_err(|e| {
+        error!("ソルト生成に失敗: {}", e);
+        ApiError::InternalServerError
+    })?;
+
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| {
+            error!("パスワードのハッシュ化に失敗: {}", e);
+            ApiError::InternalServerError
+        })
+}
+
+/// パスワードを検証（argon2 / bcrypt 両形式に対応）
+pub fn verify_password(password: &str, stored_hash: &str) -> Result<bool, ApiError> {
+    if stored_hash.starts_with("$argon2") {
+        let parsed = PasswordHash::new(stored_hash).map_err(|e| {
+            error!("パスワードハッシュの形式が不正: {}", e);
+            ApiError::InternalServerError
+        })?;
+        return Ok(Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok());
+    }
+
+    bcrypt::verify(password, stored_hash).map_err(|e| {
+        error!("パスワード検証に失敗: {}", e);
+        ApiError::InternalServerError
+    })
+}
+
+/// パスワードをargon2idでハッシュ化（計算が重いためブロッキング用スレッドで実行）
+pub async fn hash_password_blocking(password: &str) -> Result<String, ApiError> {
+    let password = password.to_string();
+    run_blocking(move || hash_password(&password)).await
+}
+
+/// パスワードを検証（計算が重いためブロッキング用スレッドで実行）
+pub async fn verify_password_blocking(password: &str, stored_hash: &str) -> Result<bool, ApiError> {
+    let (password, stored_hash) = (password.to_string(), stored_hash.to_string());
+    run_blocking(move || verify_password(&password, &stored_hash)).await
+}
+
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, ApiError> + Send + 'static,
+) -> Result<T, ApiError> {
+    tokio::task::spawn_blocking(f).await.map_err(|e| {
+        error!("パスワード処理のタスクが異常終了: {}", e);
+        ApiError::InternalServerError
+    })?
+}
+
+/// argon2id以外（bcrypt等）のハッシュは再ハッシュ対象
+pub fn needs_rehash(stored_hash: &str) -> bool {
+    !stored_hash.starts_with(ARGON2ID_PREFIX)
+}
+
+/// パスワードポリシー
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    /// 最小文字数
+    pub min_length: usize,
+    /// 最大文字数
+    pub max_length: usize,
+    /// 必要な文字種の数（英大文字・英小文字・数字・記号のうち）
+    pub min_char_classes: usize,
+    /// 再利用を禁止する直近のパスワード数
+    pub history_count: i64,
+}
+
+impl PasswordPolicy {
+    /// 環境変数からパスワードポリシーを読み込む
+    pub fn from_env() -> Self {
+        Self {
+            min_length: parse_env_or("PASSWORD_MIN_LENGTH", 8),
+            max_length: parse_env_or("PASSWORD_MAX_LENGTH", 128),
+            min_char_classes: parse_env_or::<usize>("PASSWORD_MIN_CHAR_CLASSES", 3).clamp(1, 4),
+            history_count: parse_env_or("PASSWORD_HISTORY_COUNT", 5),
+        }
+    }
+
+    /// パスワードがポリシーを満たしているか検証
+    pub fn validate(&self, password: &str) -> Result<(), String> {
+        let length = password.chars().count();
+        if length < self.min_length {
+            return Err(format!("パスワードは{}文字以上で入力してください", self.min_length));
+        }
+        if length > self.max_length {
+            return Err(format!("パスワードは{}文字以内で入力してください", self.max_length));
+        }
+        if password.chars().any(char::is_whitespace) {
+            return Err("パスワードに空白は使用できません".to_string());
+        }
+
+        let classes = [
+            password.chars().any(|c| c.is_ascii_uppercase()),
+            password.chars().any(|c| c.is_ascii_lowercase()),
+            password.chars().any(|c| c.is_ascii_digit()),
+            password.chars().any(|c| !c.is_ascii_alphanumeric()),
+        ]
+        .iter()
+        .filter(|&&present| present)
+        .count();
+
+        if classes < self.min_char_classes {
+            return Err(format!(
+                "パスワードには英大文字・英小文字・数字・記号のうち{}種類以上を含めてください",
+                self.min_char_classes
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify_with_rehash_detection() {
+        let argon = hash_password("Secret123!").unwrap();
+        assert!(argon.starts_with(ARGON2ID_PREFIX));
+        assert!(verify_password("Secret123!", &argon).unwrap());
+        assert!(!verify_password("secret123!", &argon).unwrap());
+        assert!(!needs_rehash(&argon));
+
+        let legacy = bcrypt::hash("Secret123!", 4).unwrap();
+        assert!(verify_password("Secret123!", &legacy).unwrap());
+        assert!(needs_rehash(&legacy));
+    }
+
+    #[test]
+    fn test_password_policy() {
+        let policy = PasswordPolicy { min_length: 8, max_length: 64, min_char_classes: 3, history_count: 5 };
+        assert!(policy.validate("Abcdef12").is_ok());
+        assert!(policy.validate("Abc12").is_err());
+        assert!(policy.validate("abcdefgh12").is_err());
+        assert!(policy.validate("Abcd ef12").is_err());
+        assert!(policy.validate("abcdef1!").is_ok());
+    }
+}
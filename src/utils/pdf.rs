@@ -0,0 +1,179 @@
+//! 帳票出力用の最小限のPDF生成
+//!
+//! 1ページのテキストと罫線のみを扱う。日本語はPDFビューアが標準で持つ
+//! 平成角ゴシック（HeiseiKakuGo-W5, Adobe-Japan1）を参照し、フォントは埋め込まない
+
+/// A4縦（ポイント）
+pub const A4_WIDTH: f32 = 595.0;
+pub const A4_HEIGHT: f32 = 842.0;
+
+const FONT_NAME: &str = "HeiseiKakuGo-W5";
+
+/// 1ページのPDF文書
+#[derive(Debug, Default)]
+pub struct PdfDocument {
+    content: String,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 左下を原点とした位置(x, y)にテキストを描画
+    pub fn text(&mut self, x: f32, y: f32, size: f32, text: &str) {
+        self.content.push_str(&format!(
+            "BT /F1 {} Tf {} {} Td <{}> Tj ET\n",
+            number(size),
+            number(x),
+            number(y),
+            encode_text(text)
+        ));
+    }
+
+    /// 右端をxに揃えてテキストを描画
+    pub fn text_right(&mut self, x: f32, y: f32, size: f32, text: &str) {
+        self.text(x - text_width(text, size), y, size, text);
+    }
+
+    /// 中央をxに揃えてテキストを描画
+    pub fn text_center(&mut self, x: f32, y: f32, size: f32, text: &str) {
+        self.text(x - text_width(text, size) / 2.0, y, size, text);
+    }
+
+    /// 罫線を描画
+    pub fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, width: f32) {
+        self.content.push_str(&format!(
+            "{} w {} {} m {} {} l S\n",
+            number(width),
+            number(x1),
+            number(y1),
+            number(x2),
+            number(y2)
+        ));
+    }
+
+    /// 枠を描画
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, line_width: f32) {
+        self.content.push_str(&format!(
+            "{} w {} {} {} {} re S\n",
+            number(line_width),
+            number(x),
+            number(y),
+            number(width),
+            number(height)
+        ));
+    }
+
+    /// PDFのバイト列を出力
+    pub fn finish(self) -> Vec<u8> {
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>",
+                number(A4_WIDTH),
+                number(A4_HEIGHT)
+            ),
+            format!("<< /Length {} >>\nstream\n{}endstream", self.content.len(), self.content),
+            format!(
+                "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /UniJIS-UCS2-H /DescendantFonts [6 0 R] >>",
+                FONT_NAME
+            ),
+            // 半角英数字（CID 1〜95, 231〜632）は全角の半分の幅
+            format!(
+                "<< /Type /Font /Subtype /CIDFontType0 /BaseFont /{} /CIDSystemInfo << /Registry (Adobe) /Ordering (Japan1) /Supplement 2 >> /FontDescriptor 7 0 R /DW 1000 /W [1 95 500 231 632 500] >>",
+                FONT_NAME
+            ),
+            format!(
+                "<< /Type /FontDescriptor /FontName /{} /Flags 4 /FontBBox [-92 -250 1010 922] /ItalicAngle 0 /Ascent 752 /Descent -221 /CapHeight 737 /StemV 114 >>",
+                FONT_NAME
+            ),
+        ];
+
+        let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", index + 1, object).as_bytes());
+        }
+
+        let xref_offset = pdf.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            trailer.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        trailer.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        ));
+        pdf.extend_from_slice(trailer.as_bytes());
+        pdf
+    }
+}
+
+/// 描画幅の目安（半角は全角の半分）
+pub fn text_width(text: &str, size: f32) -> f32 {
+    text.chars()
+        .map(|c| if c.is_ascii() || ('\u{FF61}'..='\u{FF9F}').contains(&c) { 0.5 } else { 1.0 })
+        .sum::<f32>()
+        * size
+}
+
+/// UCS-2（UTF-16BE）の16進文字列に変換（基本多言語面以外の文字は〓に置き換える）
+fn encode_text(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            let code = u16::try_from(u32::from(c)).unwrap_or(0x3013);
+            format!("{:04X}", code)
+        })
+        .collect()
+}
+
+fn number(value: f32) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{}", rounded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_structure() {
+        let mut doc = PdfDocument::new();
+        doc.text(50.0, 800.0, 12.0, "領収書 No.1");
+        doc.line(50.0, 790.0, 545.0, 790.0, 0.5);
+        let pdf = doc.finish();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("<981853CE66F80020004E006F002E0031> Tj"));
+
+        // xrefの各オフセットがオブジェクトの開始位置を指している
+        let xref_at: usize = text.split("startxref\n").nth(1).unwrap().lines().next().unwrap().parse().unwrap();
+        assert!(pdf[xref_at..].starts_with(b"xref"));
+        let entries: Vec<usize> = text[text.find("0000000000 65535 f").unwrap()..]
+            .lines()
+            .skip(1)
+            .take(7)
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        for (index, offset) in entries.iter().enumerate() {
+            assert!(pdf[*offset..].starts_with(format!("{} 0 obj", index + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_text_width() {
+        assert_eq!(text_width("AB", 10.0), 10.0);
+        assert_eq!(text_width("領収", 10.0), 20.0);
+        assert_eq!(encode_text("😀"), "3013");
+    }
+}